@@ -0,0 +1,558 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, Semaphore};
+use uuid::Uuid;
+
+use crate::domain::{
+    copy_rows, create_table_sql, foreign_key_cycles, foreign_key_sql, function_sql, index_sql, insert_statements, is_destructive,
+    merged_foreign_keys, qualified_identifier, sequence_sql, split_dump, standalone_indexes, table_order, trigger_sql, view_order,
+    view_sql, CellValue, Connection, ConstraintType, DomainError, DomainEvent, DumpDataFormat, DumpOptions, DumpSummary, EventBus,
+    ExecutionOptions, FunctionInfo, RestoreError, RestoreOptions, RestoreSummary, RowBatch, ScriptMode, SequenceInfo, SqlDialect,
+    SqlDriver, StreamOptions, TableInfo, TransactionOptions, ViewInfo, DEFAULT_EXPORT_BATCH, DEFAULT_ROWS_PER_INSERT, MAX_RESTORE_ERRORS,
+};
+use crate::infrastructure::DumpFileWriter;
+use super::query_use_case::STREAM_CHANNEL_CAPACITY;
+use super::{ConnectionUseCase, QueryUseCase};
+
+/// Caracteres de una sentencia que se guardan en los errores de una restauración
+const RESTORE_SQL_PREVIEW: usize = 200;
+
+/// Objetos de un volcado, leídos del catálogo antes de escribir nada
+#[derive(Default)]
+struct DumpObjects {
+    tables: Vec<TableInfo>,
+    views: Vec<ViewInfo>,
+    functions: Vec<FunctionInfo>,
+    sequences: Vec<SequenceInfo>,
+}
+
+/// Volcado en curso: lo que comparten todas sus fases
+struct DumpJob<'a> {
+    connection_id: Uuid,
+    dump_id: Uuid,
+    driver: &'a dyn SqlDriver,
+    conn: &'a Connection,
+    dialect: SqlDialect,
+    options: &'a DumpOptions,
+    window: &'a Semaphore,
+}
+
+/// Caso de uso para volcar bases a scripts SQL y restaurarlos
+pub struct DumpUseCase {
+    connection_use_case: Arc<ConnectionUseCase>,
+    /// Seguimiento, confirmaciones, cancelación y transacciones de las ejecuciones, compartidos con las queries
+    queries: Arc<QueryUseCase>,
+    event_bus: Arc<dyn EventBus>,
+}
+
+impl DumpUseCase {
+    pub fn new(connection_use_case: Arc<ConnectionUseCase>, queries: Arc<QueryUseCase>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self { connection_use_case, queries, event_bus }
+    }
+
+    /// Vuelca la base (o un esquema) a un script SQL del mismo motor, reconstruido con la
+    /// introspección: DDL en orden de dependencias y datos como `INSERT` o `COPY`. El avance
+    /// se emite como `DumpProgress`; `cancel_query` con el id del volcado lo detiene y
+    /// descarta el fichero a medias
+    pub async fn dump_database(
+        &self,
+        connection_id: Uuid,
+        path: PathBuf,
+        options: DumpOptions,
+        dump_id: Option<Uuid>,
+    ) -> Result<DumpSummary, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        let dialect = SqlDialect::from_engine(&conn.engine);
+        if !matches!(dialect, SqlDialect::Postgres | SqlDialect::MySql | SqlDialect::Sqlite) {
+            return Err(DomainError::validation("Dumps are only supported for PostgreSQL, MySQL, MariaDB and SQLite"));
+        }
+        if options.data_format == DumpDataFormat::Copy && dialect != SqlDialect::Postgres {
+            return Err(DomainError::validation("COPY data is only supported in PostgreSQL dumps"));
+        }
+        if !options.include_schema && !options.include_data {
+            return Err(DomainError::validation("Nothing to dump: include the schema, the data or both"));
+        }
+        let driver = self.queries.execution_driver(connection_id).await?;
+        let dump_id = dump_id.unwrap_or_else(Uuid::new_v4);
+
+        // Solo PostgreSQL califica los nombres; un volcado de MySQL o SQLite se restaura en
+        // la base de la conexión que lo ejecute
+        let schemas: Vec<Option<String>> = match (dialect, &options.schema) {
+            (SqlDialect::Postgres, None) => driver
+                .list_schemas(conn.database.as_deref().unwrap_or_default())
+                .await?
+                .into_iter()
+                .filter(|s| !s.is_system)
+                .map(|s| Some(s.name))
+                .collect(),
+            (SqlDialect::Sqlite, _) => vec![None],
+            (_, schema) => vec![schema.clone()],
+        };
+        let objects = Self::dump_objects(driver.as_ref(), &schemas, options.include_schema).await?;
+
+        let window = self.queries.open_window(dump_id, 0).await;
+        self.queries.start_tracking(connection_id, dump_id, &format!("-- Dump to {}", path.display())).await;
+        let started = Instant::now();
+
+        let result = async {
+            let mut file = DumpFileWriter::create(&path).await?;
+            let mut warnings = Vec::new();
+            let job = DumpJob {
+                connection_id,
+                dump_id,
+                driver: driver.as_ref(),
+                conn: &conn,
+                dialect,
+                options: &options,
+                window: &window,
+            };
+            let written = self.write_dump(&job, &objects, &mut file, &mut warnings).await;
+            let row_count = match written {
+                Ok(row_count) => row_count,
+                Err(e) => {
+                    file.abort().await;
+                    return Err(e);
+                }
+            };
+            let bytes_written = file.finish().await?;
+            Ok(DumpSummary {
+                dump_id,
+                path: path.display().to_string(),
+                schemas: schemas.iter().flatten().cloned().collect(),
+                table_count: objects.tables.len(),
+                view_count: objects.views.len(),
+                function_count: objects.functions.len(),
+                sequence_count: objects.sequences.len(),
+                trigger_count: objects.tables.iter().map(|t| t.triggers.len()).sum(),
+                row_count,
+                bytes_written,
+                warnings,
+                execution_time_ms: started.elapsed().as_millis() as u64,
+            })
+        }
+        .await;
+
+        self.queries.close_window(dump_id).await;
+        self.queries.finish_tracking(connection_id, dump_id, result.as_ref().map(|s| s.row_count as usize)).await;
+        result
+    }
+
+    async fn dump_objects(driver: &dyn SqlDriver, schemas: &[Option<String>], include_schema: bool) -> Result<DumpObjects, DomainError> {
+        let mut objects = DumpObjects::default();
+        for schema in schemas {
+            let schema = schema.as_deref();
+            for table in driver.list_tables(schema).await? {
+                objects.tables.push(driver.get_table_info(&table.name, schema).await?);
+            }
+            if include_schema {
+                objects.views.extend(driver.list_views(schema).await?);
+                objects.functions.extend(driver.list_functions(schema).await?);
+                objects.sequences.extend(driver.list_sequences(schema).await?);
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Escribe el volcado y devuelve las filas volcadas. Orden: secuencias, funciones
+    /// procedurales, tablas, datos, valores de las secuencias, índices, claves foráneas,
+    /// funciones SQL (que PostgreSQL valida al crearlas), vistas y triggers, que así no se
+    /// disparan al cargar los datos
+    async fn write_dump(
+        &self,
+        job: &DumpJob<'_>,
+        objects: &DumpObjects,
+        file: &mut DumpFileWriter,
+        warnings: &mut Vec<String>,
+    ) -> Result<u64, DomainError> {
+        let &DumpJob { dump_id, driver, conn, dialect, options, .. } = job;
+        let qualify = dialect == SqlDialect::Postgres;
+        let target = |schema: &Option<String>| if qualify { schema.clone() } else { None };
+        let table_order = table_order(&objects.tables);
+        let view_order = view_order(&objects.views);
+        let ordered_tables = || table_order.iter().map(|&i| &objects.tables[i]);
+        let ordered_views = || view_order.iter().map(|&i| &objects.views[i]);
+        let (procedural, sql_functions): (Vec<&FunctionInfo>, Vec<&FunctionInfo>) = objects
+            .functions
+            .iter()
+            .filter(|f| {
+                let native = matches!(f.language.to_lowercase().as_str(), "c" | "internal");
+                if native {
+                    warnings.push(format!("Function {} is written in {} and was not dumped", f.name, f.language));
+                }
+                !native
+            })
+            .partition(|f| !f.language.eq_ignore_ascii_case("sql"));
+
+        file.write(&format!(
+            "-- Dump of {} ({:?}) generated at {}\n\n",
+            conn.name,
+            conn.engine,
+            chrono::Utc::now().to_rfc3339()
+        ))
+        .await?;
+
+        if options.include_schema {
+            if qualify {
+                let mut created: Vec<&str> = objects.tables.iter().filter_map(|t| t.schema.as_deref()).collect();
+                created.extend(options.schema.as_deref());
+                created.sort_unstable();
+                created.dedup();
+                for schema in created.into_iter().filter(|s| *s != "public") {
+                    file.write(&format!("CREATE SCHEMA IF NOT EXISTS {};\n", dialect.quote_identifier(schema))).await?;
+                }
+            }
+            if options.drop_existing {
+                let cascade = if dialect == SqlDialect::Postgres { " CASCADE" } else { "" };
+                let views: Vec<&ViewInfo> = ordered_views().collect();
+                for view in views.iter().rev() {
+                    let name = qualified_identifier(target(&view.schema).as_deref(), &view.name, dialect);
+                    file.write(&format!("DROP VIEW IF EXISTS {}{};\n", name, cascade)).await?;
+                }
+                let tables: Vec<&TableInfo> = ordered_tables().collect();
+                for table in tables.iter().rev() {
+                    let name = qualified_identifier(target(&table.schema).as_deref(), &table.name, dialect);
+                    file.write(&format!("DROP TABLE IF EXISTS {}{};\n", name, cascade)).await?;
+                }
+                for sequence in &objects.sequences {
+                    let name = qualified_identifier(target(&sequence.schema).as_deref(), &sequence.name, dialect);
+                    file.write(&format!("DROP SEQUENCE IF EXISTS {}{};\n", name, cascade)).await?;
+                }
+            }
+            file.write("\n").await?;
+
+            for sequence in &objects.sequences {
+                file.write(&sequence_sql(sequence, target(&sequence.schema).as_deref(), dialect)).await?;
+            }
+            for function in &procedural {
+                self.write_function(file, function, warnings).await?;
+            }
+            // SQLite no puede añadir claves foráneas después: van en la tabla, que se crea
+            // después de las que referencia
+            let inline_foreign_keys = dialect == SqlDialect::Sqlite;
+            if inline_foreign_keys {
+                for table in foreign_key_cycles(&objects.tables) {
+                    warnings.push(format!("Table {} is part of a foreign key cycle; load its data with foreign keys disabled", table));
+                }
+            }
+            for table in ordered_tables() {
+                for column in table.columns.iter().filter(|c| c.data_type == "USER-DEFINED") {
+                    warnings.push(format!(
+                        "Column {}.{} uses type {}, which the dump does not create",
+                        table.name, column.name, column.native_type
+                    ));
+                }
+                if dialect == SqlDialect::MySql {
+                    for check in table.constraints.iter().filter(|c| matches!(c.constraint_type, ConstraintType::Check)) {
+                        warnings.push(format!("Check constraint {} on {} was not dumped: its definition is not available", check.name, table.name));
+                    }
+                }
+                file.write("\n").await?;
+                file.write(&create_table_sql(table, target(&table.schema).as_deref(), dialect, inline_foreign_keys)).await?;
+            }
+        }
+
+        let mut row_count = 0u64;
+        if options.include_data {
+            let table_count = objects.tables.len();
+            for (done, table) in ordered_tables().enumerate() {
+                row_count += self
+                    .dump_table_data(job, table, target(&table.schema), file, (done, table_count), row_count)
+                    .await?;
+            }
+            if dialect == SqlDialect::Postgres {
+                file.write("\n").await?;
+                for sequence in &objects.sequences {
+                    let name = qualified_identifier(sequence.schema.as_deref(), &sequence.name, dialect);
+                    let value = driver
+                        .execute_query(&format!("SELECT last_value, is_called FROM {}", name), &ExecutionOptions::new(dump_id))
+                        .await?;
+                    if let Some([CellValue::Int(last), CellValue::Bool(called)]) = value.rows.first().map(Vec::as_slice) {
+                        let name = qualified_identifier(target(&sequence.schema).as_deref(), &sequence.name, dialect);
+                        file.write(&format!("SELECT pg_catalog.setval('{}', {}, {});\n", name.replace('\'', "''"), last, called)).await?;
+                    }
+                }
+            }
+        }
+
+        if options.include_schema {
+            file.write("\n").await?;
+            for table in ordered_tables() {
+                let schema = target(&table.schema);
+                for index in standalone_indexes(table, dialect) {
+                    file.write(&index_sql(table, &index, schema.as_deref(), dialect)).await?;
+                }
+            }
+            if dialect != SqlDialect::Sqlite {
+                for table in ordered_tables() {
+                    for fk in merged_foreign_keys(&table.foreign_keys) {
+                        file.write(&foreign_key_sql(table, &fk, target(&table.schema).as_deref(), dialect)).await?;
+                    }
+                }
+            }
+            for function in &sql_functions {
+                self.write_function(file, function, warnings).await?;
+            }
+            for view in ordered_views() {
+                match view_sql(view, target(&view.schema).as_deref(), dialect) {
+                    Some(sql) => file.write(&format!("\n{}", sql)).await?,
+                    None => warnings.push(format!("View {} was not dumped: its definition is not available", view.name)),
+                }
+            }
+            for table in ordered_tables() {
+                for trigger in &table.triggers {
+                    let Some(sql) = trigger_sql(trigger, target(&table.schema).as_deref(), dialect) else {
+                        warnings.push(format!("Trigger {} on {} was not dumped: its definition is not available", trigger.name, table.name));
+                        continue;
+                    };
+                    file.write(&format!("\n{}", sql)).await?;
+                    if !trigger.enabled && dialect == SqlDialect::Postgres {
+                        let name = qualified_identifier(target(&table.schema).as_deref(), &table.name, dialect);
+                        file.write(&format!("ALTER TABLE {} DISABLE TRIGGER {};\n", name, dialect.quote_identifier(&trigger.name))).await?;
+                    }
+                }
+            }
+        }
+        Ok(row_count)
+    }
+
+    async fn write_function(&self, file: &mut DumpFileWriter, function: &FunctionInfo, warnings: &mut Vec<String>) -> Result<(), DomainError> {
+        match function_sql(function) {
+            Some(sql) => file.write(&format!("\n{}", sql)).await,
+            None => {
+                warnings.push(format!("Function {} was not dumped: the catalog does not give its full definition", function.name));
+                Ok(())
+            }
+        }
+    }
+
+    /// Lee la tabla en lotes y escribe sus filas; devuelve cuántas
+    async fn dump_table_data(
+        &self,
+        job: &DumpJob<'_>,
+        table: &TableInfo,
+        target_schema: Option<String>,
+        file: &mut DumpFileWriter,
+        (tables_done, table_count): (usize, usize),
+        rows_before: u64,
+    ) -> Result<u64, DomainError> {
+        let &DumpJob { connection_id, dump_id, driver, dialect, options, window, .. } = job;
+        let columns: Vec<String> = table.columns.iter().map(|c| dialect.quote_identifier(&c.name)).collect();
+        let source = qualified_identifier(table.schema.as_deref(), &table.name, dialect);
+        let target = qualified_identifier(target_schema.as_deref(), &table.name, dialect);
+        let select = format!("SELECT {} FROM {}", columns.join(", "), source);
+        let copy = options.data_format == DumpDataFormat::Copy;
+        let prefix = format!("INSERT INTO {} ({}) VALUES", target, columns.join(", "));
+        let rows_per_insert = options.rows_per_insert.unwrap_or(DEFAULT_ROWS_PER_INSERT);
+        let exec = ExecutionOptions::new(dump_id);
+        // Un valor que no se pueda leer tal cual detiene el volcado: escribirlo como NULL
+        // destruiría el dato al restaurar
+        let stream_options = StreamOptions {
+            batch_size: options.batch_size.unwrap_or(DEFAULT_EXPORT_BATCH).max(1),
+            exact_values: true,
+            ..StreamOptions::default()
+        };
+
+        file.write(&format!("\n-- Data for {}\n", target)).await?;
+        let (sender, receiver) = mpsc::channel::<RowBatch>(STREAM_CHANNEL_CAPACITY);
+        let write = async {
+            let mut receiver = receiver;
+            let mut row_count = 0u64;
+            while let Some(batch) = receiver.recv().await {
+                if window.is_closed() {
+                    return Err(DomainError::Cancelled);
+                }
+                if batch.rows.is_empty() {
+                    continue;
+                }
+                if copy && row_count == 0 {
+                    file.write(&format!("COPY {} ({}) FROM stdin;\n", target, columns.join(", "))).await?;
+                }
+                let text = if copy {
+                    copy_rows(&batch.rows)
+                } else {
+                    insert_statements(&prefix, &batch.rows, dialect, rows_per_insert)
+                };
+                file.write(&text).await?;
+                row_count += batch.rows.len() as u64;
+                self.event_bus
+                    .publish(DomainEvent::DumpProgress {
+                        connection_id,
+                        dump_id,
+                        table: table.name.clone(),
+                        tables_done,
+                        table_count,
+                        row_count: rows_before + row_count,
+                    })
+                    .await
+                    .ok();
+            }
+            if copy && row_count > 0 {
+                file.write("\\.\n").await?;
+            }
+            Ok(row_count)
+        };
+        let (streamed, written) = tokio::join!(driver.execute_query_stream(&select, &exec, &stream_options, sender), write);
+        // Si falló la escritura, el driver solo vio cerrarse el canal
+        let row_count = written?;
+        streamed?;
+        Ok(row_count)
+    }
+
+    /// Restaura un volcado sentencia a sentencia; cada bloque `COPY` se carga de una vez.
+    /// El avance se emite como `RestoreProgress` y `cancel_query` con el id de la restauración
+    /// la detiene. Si se detiene, `resume_at` dice desde qué sentencia retomarla
+    pub async fn restore_dump(
+        &self,
+        connection_id: Uuid,
+        script: &str,
+        options: RestoreOptions,
+        restore_id: Option<Uuid>,
+        confirmation_token: Option<&str>,
+    ) -> Result<RestoreSummary, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        let statements = split_dump(script, SqlDialect::from_engine(&conn.engine));
+        if statements.is_empty() {
+            return Err(DomainError::validation("The dump has no statements"));
+        }
+        let start_at = options.start_at.unwrap_or(0);
+        if start_at >= statements.len() {
+            return Err(DomainError::validation(format!("The dump has only {} statements", statements.len())));
+        }
+        let pending = &statements[start_at..];
+        if conn.read_only && pending.iter().any(|s| is_destructive(&s.sql, SqlDialect::from_engine(&conn.engine))) {
+            return Err(QueryUseCase::read_only_denied());
+        }
+        let sql: Vec<&str> = pending.iter().map(|s| s.sql.as_str()).collect();
+        self.queries.guard_risks(&conn, &sql, confirmation_token).await?;
+        let driver = self.queries.execution_driver(connection_id).await?;
+
+        let restore_id = restore_id.unwrap_or_else(Uuid::new_v4);
+        if options.mode == ScriptMode::Transaction {
+            // A nombre de la restauración: nada más puede usar la transacción mientras dura
+            self.queries.begin_owned_transaction(
+                driver.as_ref(),
+                connection_id,
+                restore_id,
+                &TransactionOptions::default(),
+                "A transaction is already open on this connection: restore in another mode",
+            )
+            .await?;
+        }
+
+        // Sin ventana que limitar: solo deja que `cancel_query` corte entre sentencias
+        let window = self.queries.open_window(restore_id, 0).await;
+        self.queries.start_tracking(connection_id, restore_id, &format!("-- Restore of {} statements", pending.len())).await;
+        let started = Instant::now();
+        let timeout = QueryUseCase::resolve_timeout(None, &conn);
+        let exec = ExecutionOptions::new(restore_id).with_timeout(timeout);
+        let mut summary = RestoreSummary {
+            restore_id,
+            mode: options.mode,
+            statement_count: statements.len(),
+            start_at,
+            executed: 0,
+            succeeded: 0,
+            failed: 0,
+            affected_rows: 0,
+            errors: Vec::new(),
+            stopped: false,
+            resume_at: None,
+            committed: None,
+            execution_time_ms: 0,
+        };
+        let cancelled = DomainError::Cancelled.to_string();
+
+        for (index, statement) in statements.iter().enumerate().skip(start_at) {
+            let outcome = if window.is_closed() {
+                Err(cancelled.clone())
+            } else {
+                match &statement.copy_data {
+                    Some(data) => QueryUseCase::with_timeout(
+                        driver.as_ref(),
+                        restore_id,
+                        timeout,
+                        driver.copy_from_stdin(&statement.sql, data.as_bytes(), &exec),
+                    )
+                    .await
+                    .map_err(|e| e.to_string()),
+                    None => QueryUseCase::with_timeout(
+                        driver.as_ref(),
+                        restore_id,
+                        timeout,
+                        driver.execute_multi_statement(vec![statement.sql.clone()], &exec),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|results| match results.into_iter().next() {
+                        Some(result) if result.success => Ok(result.affected_rows.unwrap_or(0)),
+                        Some(result) => Err(result.error.unwrap_or_default()),
+                        None => Err("Statement was not executed".to_string()),
+                    }),
+                }
+            };
+
+            summary.executed += 1;
+            let error = match outcome {
+                Ok(rows) => {
+                    summary.succeeded += 1;
+                    summary.affected_rows += rows;
+                    None
+                }
+                Err(error) => {
+                    summary.failed += 1;
+                    if summary.errors.len() < MAX_RESTORE_ERRORS {
+                        summary.errors.push(RestoreError {
+                            statement_index: index,
+                            line: statement.line,
+                            sql: statement.sql.chars().take(RESTORE_SQL_PREVIEW).collect(),
+                            error: error.clone(),
+                        });
+                    }
+                    Some(error)
+                }
+            };
+            let stop = error.as_ref().is_some_and(|e| options.mode != ScriptMode::ContinueOnError || *e == cancelled);
+            self.event_bus
+                .publish(DomainEvent::RestoreProgress {
+                    connection_id,
+                    restore_id,
+                    statement_index: index,
+                    statement_count: statements.len(),
+                    success: error.is_none(),
+                    error,
+                })
+                .await
+                .ok();
+            if stop {
+                summary.resume_at = Some(index);
+                break;
+            }
+        }
+        summary.stopped = summary.resume_at.is_some();
+
+        let mut outcome = Ok(summary.succeeded);
+        if options.mode == ScriptMode::Transaction {
+            let commit = summary.failed == 0;
+            let closed = if commit { driver.commit_transaction().await } else { driver.rollback_transaction().await };
+            if let Err(e) = closed {
+                if driver.in_transaction().await {
+                    driver.rollback_transaction().await.ok();
+                }
+                outcome = Err(e);
+            }
+            self.queries.end_owned_transaction(connection_id, restore_id).await;
+            let committed = commit && outcome.is_ok();
+            summary.committed = Some(committed);
+            // Deshecha la transacción, hay que repetir todo lo que se ejecutó en ella
+            if !committed {
+                summary.resume_at = Some(start_at);
+            }
+        }
+        summary.execution_time_ms = started.elapsed().as_millis() as u64;
+
+        self.queries.close_window(restore_id).await;
+        self.queries.finish_tracking(connection_id, restore_id, outcome.as_ref().copied()).await;
+        outcome.map(|_| summary)
+    }
+}
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::domain::{
+    is_destructive, DomainError, DomainEvent, EventBus, ExecutionOptions, ExportOptions, ExportSummary, QueryHistoryEntry, RowBatch,
+    SqlDialect, StreamOptions, DEFAULT_EXPORT_BATCH,
+};
+use crate::infrastructure::create_export_writer;
+use super::query_use_case::STREAM_CHANNEL_CAPACITY;
+use super::{ConnectionUseCase, QueryUseCase};
+
+/// Caso de uso para exportar resultados de queries a ficheros
+pub struct ExportUseCase {
+    connection_use_case: Arc<ConnectionUseCase>,
+    /// Seguimiento, confirmaciones y cancelación de las ejecuciones, compartidos con las queries
+    queries: Arc<QueryUseCase>,
+    event_bus: Arc<dyn EventBus>,
+}
+
+impl ExportUseCase {
+    pub fn new(connection_use_case: Arc<ConnectionUseCase>, queries: Arc<QueryUseCase>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self { connection_use_case, queries, event_bus }
+    }
+
+    /// Ejecuta una query y escribe sus filas en un fichero a medida que llegan, sin pasar
+    /// por el frontend. El avance se emite como `ExportProgress`; `cancel_query` con el id
+    /// de la exportación la detiene y descarta el fichero a medias
+    pub async fn export_query(
+        &self,
+        connection_id: Uuid,
+        query: &str,
+        path: PathBuf,
+        options: ExportOptions,
+        export_id: Option<Uuid>,
+        confirmation_token: Option<&str>,
+    ) -> Result<ExportSummary, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        if conn.read_only && is_destructive(query, SqlDialect::from_engine(&conn.engine)) {
+            return Err(QueryUseCase::read_only_denied());
+        }
+        self.queries.guard_risks(&conn, &[query], confirmation_token).await?;
+        let driver = self.queries.execution_driver(connection_id).await?;
+        let export_id = export_id.unwrap_or_else(Uuid::new_v4);
+        let timeout = QueryUseCase::resolve_timeout(None, &conn);
+        let exec = ExecutionOptions::new(export_id).with_timeout(timeout);
+        let stream_options = StreamOptions {
+            batch_size: options.batch_size.unwrap_or(DEFAULT_EXPORT_BATCH).max(1),
+            max_rows: options.max_rows,
+            exact_values: true,
+            ..StreamOptions::default()
+        };
+        let mut writer = create_export_writer(&path, &options, SqlDialect::from_engine(&conn.engine));
+
+        // El ritmo lo marca el disco, así que la ventana no limita nada: solo permite que
+        // `cancel_query` detenga la exportación aunque el driver esté esperando para enviar
+        let window = self.queries.open_window(export_id, 0).await;
+        self.queries.start_tracking(connection_id, export_id, query).await;
+
+        let started = Instant::now();
+        let (sender, receiver) = mpsc::channel::<RowBatch>(STREAM_CHANNEL_CAPACITY);
+        let write = async {
+            // Al salir se suelta el receptor y el driver deja de leer
+            let mut receiver = receiver;
+            let mut row_count = 0u64;
+            let mut begun = false;
+            while let Some(batch) = receiver.recv().await {
+                if window.is_closed() {
+                    return Err(DomainError::Cancelled);
+                }
+                if let Some(columns) = &batch.columns {
+                    writer.begin(columns).await?;
+                    begun = true;
+                }
+                writer.write_rows(&batch.rows).await?;
+                row_count += batch.rows.len() as u64;
+                self.event_bus
+                    .publish(DomainEvent::ExportProgress { connection_id, export_id, row_count })
+                    .await
+                    .ok();
+            }
+            Ok(begun)
+        };
+        let (streamed, written) = tokio::join!(
+            QueryUseCase::with_timeout(
+                driver.as_ref(),
+                export_id,
+                timeout,
+                driver.execute_query_stream(query, &exec, &stream_options, sender),
+            ),
+            write,
+        );
+
+        let result = async {
+            // Si falló la escritura, el driver solo vio cerrarse el canal
+            let begun = written?;
+            let summary = streamed?;
+            if !begun {
+                writer.begin(&summary.columns).await?;
+            }
+            let bytes_written = writer.finish().await?;
+            Ok(ExportSummary {
+                export_id,
+                path: path.display().to_string(),
+                format: options.format,
+                row_count: summary.row_count,
+                bytes_written,
+                truncated: summary.truncated,
+                execution_time_ms: started.elapsed().as_millis() as u64,
+            })
+        }
+        .await;
+        if result.is_err() {
+            writer.abort().await;
+        }
+
+        self.queries.close_window(export_id).await;
+        self.queries.finish_tracking(connection_id, export_id, result.as_ref().map(|s| s.row_count as usize)).await;
+        let history_repo = self.queries.get_history_repo().await?;
+        let entry = match &result {
+            Ok(summary) => QueryHistoryEntry::success(connection_id, query.to_string(), summary.execution_time_ms, summary.row_count as usize),
+            Err(e) => QueryHistoryEntry::failure(connection_id, query.to_string(), e.to_string()),
+        };
+        history_repo.save(entry).await.ok();
+        result
+    }
+}
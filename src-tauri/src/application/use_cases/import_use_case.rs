@@ -0,0 +1,231 @@
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::domain::{
+    BindValue, DomainError, DomainEvent, EventBus, ExecutionOptions, ImportErrorMode, ImportOptions, ImportPlan, ImportSource,
+    ImportSummary, ImportTableDto, ImportTarget, QueryHistoryEntry, SqlDialect, SqlDriver, TransactionOptions, WriteKind,
+};
+use super::{ConnectionUseCase, QueryUseCase};
+
+/// Registros por lote de una importación si no se indica otro tamaño, y máximo admitido
+const DEFAULT_IMPORT_BATCH: usize = 1_000;
+const MAX_IMPORT_BATCH: usize = 50_000;
+
+/// Savepoints de una importación: el de toda ella, si se une a una transacción manual,
+/// y el de cada lote, para descartar un lote fallido sin perder los anteriores
+const IMPORT_SAVEPOINT: &str = "import";
+const IMPORT_BATCH_SAVEPOINT: &str = "import_batch";
+
+/// Importación ya validada, a punto de escribirse en el driver
+struct ImportJob<'a> {
+    driver: &'a dyn SqlDriver,
+    connection_id: Uuid,
+    plan: ImportPlan,
+    target: ImportTarget,
+    exec: ExecutionOptions,
+}
+
+/// Caso de uso para importar ficheros en tablas
+pub struct ImportUseCase {
+    connection_use_case: Arc<ConnectionUseCase>,
+    /// Seguimiento, confirmaciones y transacciones de las ejecuciones, compartidos con las queries
+    queries: Arc<QueryUseCase>,
+    event_bus: Arc<dyn EventBus>,
+}
+
+impl ImportUseCase {
+    pub fn new(connection_use_case: Arc<ConnectionUseCase>, queries: Arc<QueryUseCase>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self { connection_use_case, queries, event_bus }
+    }
+
+    /// Importa en una tabla los registros leídos de un fichero, por lotes. Con una transacción
+    /// manual abierta se importa dentro de ella sin confirmar; si no, en una transacción propia
+    /// que se deshace si la importación se detiene. El avance se emite como `ImportProgress`
+    pub async fn import_rows(
+        &self,
+        dto: ImportTableDto,
+        source: ImportSource,
+        options: ImportOptions,
+    ) -> Result<ImportSummary, DomainError> {
+        let ImportTableDto { connection_id, schema, table, import_id, confirmation_token } = dto;
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        if conn.read_only {
+            return Err(QueryUseCase::read_only_denied());
+        }
+        if source.rows.is_empty() {
+            return Err(DomainError::validation("The file has no rows to import"));
+        }
+        let target_name = QueryUseCase::row_edit_target(schema.as_deref(), &table);
+        self.queries.guard_write(&conn, WriteKind::Insert, schema.as_deref(), &table, confirmation_token.as_deref()).await?;
+        let driver = self.queries.execution_driver(connection_id).await?;
+
+        let dialect = SqlDialect::from_engine(&conn.engine);
+        let info = driver.get_table_info(&table, schema.as_deref()).await?;
+        let plan = ImportPlan::new(&source, &info.columns, &options.mapping, dialect)?;
+        let target = ImportTarget {
+            schema,
+            table,
+            dialect,
+            columns: plan.columns(),
+            key_columns: info.primary_key.map(|pk| pk.columns).unwrap_or_default(),
+            on_duplicate: options.on_duplicate,
+        };
+        // Una política de duplicados que el motor no admite se rechaza antes de escribir nada
+        target.conflict_clause()?;
+
+        let import_id = import_id.unwrap_or_else(Uuid::new_v4);
+        let exec = ExecutionOptions::new(import_id).with_timeout(QueryUseCase::resolve_timeout(None, &conn));
+        let description = format!("IMPORT INTO {} ({} rows)", target_name, source.rows.len());
+        self.queries.start_tracking(connection_id, import_id, &description).await;
+
+        let job = ImportJob { driver: driver.as_ref(), connection_id, plan, target, exec };
+        let result = self.write_import(&job, &source, &options).await;
+
+        self.queries.finish_tracking(connection_id, import_id, result.as_ref().map(|s| s.written_rows as usize)).await;
+        let history_repo = self.queries.get_history_repo().await?;
+        let entry = match &result {
+            Ok(s) => QueryHistoryEntry::success(connection_id, description, s.execution_time_ms, s.written_rows as usize),
+            Err(e) => QueryHistoryEntry::failure(connection_id, description, e.to_string()),
+        };
+        history_repo.save(entry).await.ok();
+        result
+    }
+
+    /// Convierte y escribe los lotes. Si un lote falla se repite fila a fila para saber
+    /// qué registros lo causan; en motores sin savepoints no se puede repetir sin
+    /// duplicar filas, así que un lote fallido detiene la importación
+    async fn write_import(&self, job: &ImportJob<'_>, source: &ImportSource, options: &ImportOptions) -> Result<ImportSummary, DomainError> {
+        let &ImportJob { driver, connection_id, ref plan, ref target, ref exec } = job;
+        let started = Instant::now();
+        let mut summary = ImportSummary::new(exec.query_id, source.rows.len());
+        let joined = driver.in_transaction().await;
+        // Sin savepoints cada lote queda escrito al terminar (o en la transacción manual abierta)
+        let transactional = driver.supports_transactions();
+        if transactional && joined {
+            driver.create_savepoint(IMPORT_SAVEPOINT).await?;
+        } else if transactional {
+            // La transacción propia queda a nombre de la importación: nada más puede usarla mientras dura
+            self.queries
+                .begin_owned_transaction(
+                    driver,
+                    connection_id,
+                    exec.query_id,
+                    &TransactionOptions::default(),
+                    "A transaction is already open on this connection: wait for it to finish before importing",
+                )
+                .await?;
+        }
+        // Cancelar o perder la conexión detiene la importación sea cual sea el modo
+        let fatal = |e: &DomainError| matches!(e, DomainError::Cancelled) || e.is_retryable();
+        let abort_on_error = options.on_error == ImportErrorMode::Abort;
+        let batch_size = options.batch_size.unwrap_or(DEFAULT_IMPORT_BATCH).clamp(1, MAX_IMPORT_BATCH);
+        let mut failure = None;
+
+        'batches: for (batch, records) in source.rows.chunks(batch_size).enumerate() {
+            let first = batch * batch_size + 1;
+            let mut rows = Vec::with_capacity(records.len());
+            let mut numbers = Vec::with_capacity(records.len());
+            for (offset, record) in records.iter().enumerate() {
+                match plan.convert(record) {
+                    Ok(values) => {
+                        rows.push(values);
+                        numbers.push(first + offset);
+                    }
+                    Err(e) => {
+                        summary.record_failure(first + offset, e);
+                        if abort_on_error {
+                            summary.aborted = true;
+                            break 'batches;
+                        }
+                    }
+                }
+            }
+
+            match Self::write_import_batch(driver, target, &rows, transactional, exec).await {
+                Ok(written) => summary.written_rows += written,
+                Err(e) if fatal(&e) || !transactional => {
+                    failure = Some(e);
+                    break;
+                }
+                Err(_) => {
+                    for (values, number) in rows.iter().zip(numbers) {
+                        match Self::write_import_batch(driver, target, std::slice::from_ref(values), transactional, exec).await {
+                            Ok(written) => summary.written_rows += written,
+                            Err(e) if fatal(&e) => {
+                                failure = Some(e);
+                                break 'batches;
+                            }
+                            Err(e) => {
+                                summary.record_failure(number, e);
+                                if abort_on_error {
+                                    summary.aborted = true;
+                                    break 'batches;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            self.event_bus
+                .publish(DomainEvent::ImportProgress {
+                    connection_id,
+                    import_id: exec.query_id,
+                    processed_rows: first - 1 + records.len(),
+                    total_rows: summary.total_rows,
+                    written_rows: summary.written_rows,
+                    failed_rows: summary.failed_rows,
+                })
+                .await
+                .ok();
+        }
+
+        let stopped = summary.aborted || failure.is_some();
+        if transactional {
+            let closed = match (stopped, joined) {
+                (true, true) => driver.rollback_to_savepoint(IMPORT_SAVEPOINT).await,
+                (true, false) => driver.rollback_transaction().await,
+                (false, true) => Ok(()),
+                (false, false) => driver.commit_transaction().await,
+            };
+            if closed.is_err() && !joined && driver.in_transaction().await {
+                // Un COMMIT fallido no debe dejar abierta la transacción de la importación
+                driver.rollback_transaction().await.ok();
+            }
+            if !joined {
+                self.queries.end_owned_transaction(connection_id, exec.query_id).await;
+            }
+            closed?;
+        }
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        summary.committed = !joined && (!stopped || !transactional);
+        summary.execution_time_ms = started.elapsed().as_millis() as u64;
+        Ok(summary)
+    }
+
+    /// Escribe un lote; dentro de una transacción, protegido por un savepoint para poder
+    /// descartarlo sin perder lo anterior
+    async fn write_import_batch(
+        driver: &dyn SqlDriver,
+        target: &ImportTarget,
+        rows: &[Vec<BindValue>],
+        transactional: bool,
+        exec: &ExecutionOptions,
+    ) -> Result<u64, DomainError> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        if !transactional {
+            return driver.import_rows(target, rows, exec).await;
+        }
+        driver.create_savepoint(IMPORT_BATCH_SAVEPOINT).await?;
+        let written = driver.import_rows(target, rows, exec).await;
+        if written.is_err() {
+            driver.rollback_to_savepoint(IMPORT_BATCH_SAVEPOINT).await?;
+        }
+        written
+    }
+}
@@ -1,10 +1,16 @@
 mod connection_use_case;
+mod dump_use_case;
+mod export_use_case;
+mod import_use_case;
 mod query_use_case;
 mod saved_query_use_case;
 mod schema_use_case;
 mod workspace_use_case;
 
 pub use connection_use_case::ConnectionUseCase;
+pub use dump_use_case::DumpUseCase;
+pub use export_use_case::ExportUseCase;
+pub use import_use_case::ImportUseCase;
 pub use query_use_case::QueryUseCase;
 pub use saved_query_use_case::SavedQueryUseCase;
 pub use schema_use_case::SchemaUseCase;
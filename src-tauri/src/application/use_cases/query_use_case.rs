@@ -1,34 +1,292 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::OnceCell;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, OnceCell, RwLock, Semaphore};
 use uuid::Uuid;
 
 use crate::domain::{
-    DomainError, ExecuteQueryDto, QueryHistoryEntry, QueryHistoryRepository, QueryResult,
+    assess_statements, assess_write, check_dry_runnable, is_destructive, BoundQuery, CellValue, Connection, DatabaseEngine, DomainError, DomainEvent, DryRunStatement, EventBus, ExecuteQueryDto, ExecutionOptions, PageRequest, QueryHistoryEntry,
+    QueryHistoryRepository, MutationScope, QueryParams, QueryPlan, QueryResult, QueryStreamSummary, RiskContext, RowDelete, RowDiffSample, RowEditTarget, RowUpdate, RunningQueryInfo, ScriptMode,
+    ScriptRunSummary, ScriptStatement, SqlDialect, SqlDriver, StatementResult, StatementRisk, StreamOptions, TransactionOptions, WriteKind,
+    TransactionResult, TransactionSession, TransactionWarningReason, split_script,
 };
 use crate::infrastructure::FileQueryHistoryRepository;
 use super::ConnectionUseCase;
 
+/// Lotes que el driver puede adelantar antes de quedar esperando al reenvío de eventos
+pub(super) const STREAM_CHANNEL_CAPACITY: usize = 2;
+
+/// Intervalo tras el que se avisa (y se vuelve a avisar) de una transacción manual abierta
+const TRANSACTION_WARN_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// Margen sobre el límite antes de cancelar desde el cliente: normalmente el servidor corta antes
+const CLIENT_TIMEOUT_GRACE: Duration = Duration::from_millis(500);
+
+/// Espera máxima a que termine una query cancelada por timeout antes de abandonarla
+const TIMEOUT_CANCEL_WAIT: Duration = Duration::from_secs(5);
+
+/// Filas de la muestra antes/después de cada sentencia de una simulación
+const DRY_RUN_SAMPLE_ROWS: usize = 20;
+
+/// Savepoint que protege la transacción de una simulación si falla una lectura de muestra
+const DRY_RUN_SAVEPOINT: &str = "dry_run_sample";
+
+/// Validez de un token de confirmación de sentencias peligrosas
+const CONFIRMATION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Edición por clave ya validada, a punto de ejecutarse en el driver
+struct RowEdit {
+    driver: Arc<dyn SqlDriver>,
+    target: RowEditTarget,
+    options: ExecutionOptions,
+}
+
+/// Confirmación emitida y aún no usada: vale solo para la misma conexión y las mismas sentencias
+struct PendingConfirmation {
+    connection_id: Uuid,
+    fingerprint: u64,
+    expires_at: Instant,
+}
+
 /// Caso de uso para ejecución de queries
 pub struct QueryUseCase {
     connection_use_case: Arc<ConnectionUseCase>,
     data_dir: PathBuf,
     history_repo: OnceCell<Arc<dyn QueryHistoryRepository>>,
+    event_bus: Arc<dyn EventBus>,
+    running_queries: Arc<RwLock<HashMap<Uuid, RunningQueryInfo>>>,
+    /// Ventana de lotes sin confirmar por el frontend de cada stream activo
+    stream_windows: Arc<RwLock<HashMap<Uuid, Arc<Semaphore>>>>,
+    /// Transacciones manuales abiertas, por conexión
+    transactions: Arc<RwLock<HashMap<Uuid, TransactionSession>>>,
+    /// Tokens de confirmación pendientes
+    confirmations: Arc<RwLock<HashMap<String, PendingConfirmation>>>,
 }
 
 impl QueryUseCase {
     pub fn new_lazy(
         connection_use_case: Arc<ConnectionUseCase>,
         data_dir: PathBuf,
+        event_bus: Arc<dyn EventBus>,
     ) -> Self {
         Self {
             connection_use_case,
             data_dir,
             history_repo: OnceCell::new(),
+            event_bus,
+            running_queries: Arc::new(RwLock::new(HashMap::new())),
+            stream_windows: Arc::new(RwLock::new(HashMap::new())),
+            transactions: Arc::new(RwLock::new(HashMap::new())),
+            confirmations: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    async fn get_history_repo(&self) -> Result<&Arc<dyn QueryHistoryRepository>, DomainError> {
+    /// Ventana de lotes de un stream; `cancel_query` la cierra para detenerlo aunque el
+    /// driver esté esperando para enviar
+    pub(super) async fn open_window(&self, query_id: Uuid, permits: usize) -> Arc<Semaphore> {
+        let window = Arc::new(Semaphore::new(permits));
+        self.stream_windows.write().await.insert(query_id, window.clone());
+        window
+    }
+
+    pub(super) async fn close_window(&self, query_id: Uuid) {
+        self.stream_windows.write().await.remove(&query_id);
+    }
+
+    /// Registra una ejecución en curso y publica QueryStarted
+    pub(super) async fn start_tracking(&self, connection_id: Uuid, query_id: Uuid, query: &str) {
+        self.running_queries.write().await.insert(
+            query_id,
+            RunningQueryInfo::new(query_id, connection_id, query.to_string()),
+        );
+        self.event_bus
+            .publish(DomainEvent::QueryStarted { connection_id, query_id })
+            .await
+            .ok();
+    }
+
+    /// Quita la ejecución del registro y publica su evento de cierre (completada, fallida o cancelada)
+    pub(super) async fn finish_tracking(&self, connection_id: Uuid, query_id: Uuid, outcome: Result<usize, &DomainError>) {
+        self.running_queries.write().await.remove(&query_id);
+        let event = match outcome {
+            Ok(row_count) => DomainEvent::QueryCompleted { connection_id, query_id, row_count },
+            Err(DomainError::Cancelled) => DomainEvent::QueryCancelled { connection_id, query_id },
+            Err(e) => DomainEvent::QueryFailed { connection_id, query_id, error: e.to_string() },
+        };
+        self.event_bus.publish(event).await.ok();
+    }
+
+    /// Límite efectivo de una ejecución: el indicado en la llamada o, si no, el de la conexión.
+    /// 0 en cualquiera de los dos significa sin límite
+    pub(super) fn resolve_timeout(timeout_ms: Option<u64>, conn: &Connection) -> Option<Duration> {
+        timeout_ms
+            .or(conn.statement_timeout_ms)
+            .filter(|ms| *ms > 0)
+            .map(Duration::from_millis)
+    }
+
+    /// Espera a `operation` como mucho el límite indicado (más un margen). Si el servidor no la
+    /// ha cortado para entonces, se cancela la query y se espera un tiempo acotado a que termine
+    pub(super) async fn with_timeout<T>(
+        driver: &dyn SqlDriver,
+        query_id: Uuid,
+        timeout: Option<Duration>,
+        operation: impl Future<Output = Result<T, DomainError>>,
+    ) -> Result<T, DomainError> {
+        let Some(limit) = timeout else {
+            return operation.await;
+        };
+        let started = Instant::now();
+        tokio::pin!(operation);
+
+        let result = match tokio::time::timeout(limit + CLIENT_TIMEOUT_GRACE, &mut operation).await {
+            Ok(result) => result,
+            Err(_) => {
+                driver.cancel_query(query_id).await.ok();
+                tokio::time::timeout(TIMEOUT_CANCEL_WAIT, &mut operation)
+                    .await
+                    .unwrap_or(Err(DomainError::Cancelled))
+            }
+        };
+        match result {
+            // Una interrupción pasado el límite es la del timeout (progress handler, cancelación
+            // desde el cliente), no una cancelación del usuario
+            Err(DomainError::Cancelled) if started.elapsed() >= limit => Err(DomainError::Timeout {
+                operation: format!("query exceeded the {} ms limit", limit.as_millis()),
+            }),
+            other => other,
+        }
+    }
+
+    /// Esquema y base en los que caen los nombres sin calificar de la conexión activa
+    async fn risk_context(&self, conn: &Connection) -> RiskContext {
+        let (database, schema) = self.connection_use_case
+            .get_active_context(conn.id)
+            .await
+            .unwrap_or_default();
+        let (schema, database) = match conn.engine {
+            // En MySQL y ClickHouse el esquema es la base
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB | DatabaseEngine::ClickHouse => (database, None),
+            DatabaseEngine::PostgreSQL => (schema.or_else(|| Some("public".to_string())), database),
+            DatabaseEngine::SqlServer => (schema.or_else(|| Some("dbo".to_string())), database),
+            DatabaseEngine::SQLite | DatabaseEngine::DuckDB => (schema.or_else(|| Some("main".to_string())), None),
+        };
+        RiskContext { schema, database, production: conn.production }
+    }
+
+    /// Exige confirmación si alguna sentencia es peligrosa. Sin token, o con uno que no
+    /// corresponde a esta conexión y estas sentencias, se emite uno nuevo de un solo uso
+    pub(super) async fn guard_risks<S: AsRef<str>>(
+        &self,
+        conn: &Connection,
+        statements: &[S],
+        confirmation_token: Option<&str>,
+    ) -> Result<(), DomainError> {
+        let context = self.risk_context(conn).await;
+        let risks: Vec<StatementRisk> = assess_statements(statements, SqlDialect::from_engine(&conn.engine), &context);
+        self.confirm_risks(conn, risks, statements, confirmation_token).await
+    }
+
+    /// Como `guard_risks`, para las escrituras que genera la aplicación sobre una tabla
+    pub(super) async fn guard_write(
+        &self,
+        conn: &Connection,
+        kind: WriteKind,
+        schema: Option<&str>,
+        table: &str,
+        confirmation_token: Option<&str>,
+    ) -> Result<(), DomainError> {
+        let context = self.risk_context(conn).await;
+        let target: Vec<String> = schema.into_iter().chain([table]).map(str::to_string).collect();
+        // Las ediciones por clave siempre filtran por la clave primaria
+        let risks = assess_write(kind, &target, kind != WriteKind::Insert, &context);
+        let subject: Vec<String> = risks.iter().map(|r| r.sql.clone()).collect();
+        self.confirm_risks(conn, risks, &subject, confirmation_token).await
+    }
+
+    /// Deja pasar si no hay riesgos o si el token confirma estos mismos sobre `subject`
+    /// (las sentencias evaluadas); si no, emite un token nuevo
+    async fn confirm_risks<S: AsRef<str>>(
+        &self,
+        conn: &Connection,
+        risks: Vec<StatementRisk>,
+        subject: &[S],
+        confirmation_token: Option<&str>,
+    ) -> Result<(), DomainError> {
+        if risks.is_empty() {
+            return Ok(());
+        }
+
+        let mut hasher = DefaultHasher::new();
+        conn.id.hash(&mut hasher);
+        for statement in subject {
+            statement.as_ref().hash(&mut hasher);
+        }
+        let fingerprint = hasher.finish();
+
+        let now = Instant::now();
+        let mut pending = self.confirmations.write().await;
+        pending.retain(|_, p| p.expires_at > now);
+        if let Some(confirmed) = confirmation_token.and_then(|t| pending.remove(t)) {
+            if confirmed.connection_id == conn.id && confirmed.fingerprint == fingerprint {
+                return Ok(());
+            }
+        }
+
+        let token = Uuid::new_v4().to_string();
+        pending.insert(token.clone(), PendingConfirmation {
+            connection_id: conn.id,
+            fingerprint,
+            expires_at: now + CONFIRMATION_TTL,
+        });
+        Err(DomainError::ConfirmationRequired { token, risks })
+    }
+
+    /// Driver de la conexión para una ejecución nueva. Falla si la conexión tiene abierta la
+    /// transacción de otra ejecución: las sentencias irían a parar dentro de ella
+    pub(super) async fn execution_driver(&self, connection_id: Uuid) -> Result<Arc<dyn SqlDriver>, DomainError> {
+        if let Some(owner) = self.transactions.read().await.get(&connection_id).and_then(|s| s.owner) {
+            return Err(DomainError::validation(format!(
+                "The connection is busy with the transaction of execution {}: wait for it to finish or cancel it",
+                owner
+            )));
+        }
+        self.connection_use_case.get_active_driver(connection_id).await
+    }
+
+    /// Abre la transacción propia de una ejecución y la registra a su nombre, de modo que hasta
+    /// `end_owned_transaction` el resto de ejecuciones de la conexión se rechazan en vez de unirse a ella.
+    /// `conflict` es el error si ya hay otra transacción abierta
+    pub(super) async fn begin_owned_transaction(
+        &self,
+        driver: &dyn SqlDriver,
+        connection_id: Uuid,
+        owner: Uuid,
+        options: &TransactionOptions,
+        conflict: &str,
+    ) -> Result<(), DomainError> {
+        let mut transactions = self.transactions.write().await;
+        if transactions.contains_key(&connection_id) {
+            return Err(DomainError::validation(conflict));
+        }
+        driver.begin_transaction(options).await?;
+        transactions.insert(connection_id, TransactionSession::new(connection_id, options).owned_by(owner));
+        Ok(())
+    }
+
+    /// Olvida la transacción de la ejecución una vez confirmada o deshecha
+    pub(super) async fn end_owned_transaction(&self, connection_id: Uuid, owner: Uuid) {
+        let mut transactions = self.transactions.write().await;
+        if transactions.get(&connection_id).is_some_and(|s| s.owner == Some(owner)) {
+            transactions.remove(&connection_id);
+        }
+    }
+
+    pub(super) async fn get_history_repo(&self) -> Result<&Arc<dyn QueryHistoryRepository>, DomainError> {
         self.history_repo
             .get_or_try_init(|| async {
                 let repo = FileQueryHistoryRepository::new(self.data_dir.clone()).await?;
@@ -38,13 +296,39 @@ impl QueryUseCase {
     }
 
     pub async fn execute_query(&self, dto: ExecuteQueryDto) -> Result<QueryResult, DomainError> {
-        let driver = self.connection_use_case.get_active_driver(dto.connection_id).await?;
+        let conn = self.connection_use_case.get_connection(dto.connection_id).await?;
+        if conn.read_only && is_destructive(&dto.query, SqlDialect::from_engine(&conn.engine)) {
+            return Err(Self::read_only_denied());
+        }
+        self.guard_risks(&conn, &[&dto.query], dto.confirmation_token.as_deref()).await?;
+        let driver = self.execution_driver(dto.connection_id).await?;
+        let query_id = dto.query_id.unwrap_or_else(Uuid::new_v4);
+        // Los marcadores se traducen a la sintaxis del driver; el historial guarda el texto original
+        let bound = BoundQuery::bind(&dto.query, dto.params.as_ref(), |i, v| driver.placeholder(i, v))?;
+        let timeout = Self::resolve_timeout(dto.timeout_ms, &conn);
+        let options = ExecutionOptions::new(query_id)
+            .with_params(bound.values)
+            .with_timeout(timeout);
+        self.start_tracking(dto.connection_id, query_id, &dto.query).await;
 
-        let result = if let (Some(page), Some(page_size)) = (dto.page, dto.page_size) {
-            driver.execute_query_paginated(&dto.query, page, page_size).await
-        } else {
-            driver.execute_query(&dto.query).await
+        let execution = async {
+            if let (Some(page), Some(page_size)) = (dto.page, dto.page_size) {
+                let mut request = PageRequest::new(page, page_size);
+                request.cursor = dto.cursor.clone();
+                request.count_mode = dto.count_mode.unwrap_or_default();
+                driver.execute_query_paginated(&bound.sql, &request, &options).await
+            } else {
+                driver.execute_query(&bound.sql, &options).await
+            }
         };
+        let result = Self::with_timeout(driver.as_ref(), query_id, timeout, execution)
+            .await
+            .map(|r| r.with_id(query_id));
+        self.finish_tracking(
+            dto.connection_id,
+            query_id,
+            result.as_ref().map(|r| r.row_count),
+        ).await;
 
         // Guardar en historial
         let history_repo = self.get_history_repo().await?;
@@ -71,13 +355,121 @@ impl QueryUseCase {
         result
     }
 
-    pub async fn execute_statement(&self, connection_id: Uuid, statement: &str) -> Result<u64, DomainError> {
+    /// Ejecuta una query enviando las filas al frontend en lotes (eventos QueryStreamBatch).
+    /// Cada lote enviado consume un hueco de la ventana; el frontend lo devuelve con
+    /// `ack_stream_batch`. Con la ventana llena se deja de leer del servidor.
+    pub async fn execute_query_stream(
+        &self,
+        connection_id: Uuid,
+        query: &str,
+        query_id: Option<Uuid>,
+        stream_options: StreamOptions,
+        confirmation_token: Option<&str>,
+    ) -> Result<QueryStreamSummary, DomainError> {
         let conn = self.connection_use_case.get_connection(connection_id).await?;
-        if conn.read_only && Self::is_destructive(statement) {
-            return Err(DomainError::validation("Conexión en modo solo lectura: operación bloqueada"));
+        if conn.read_only && is_destructive(query, SqlDialect::from_engine(&conn.engine)) {
+            return Err(Self::read_only_denied());
         }
-        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
-        let result = driver.execute_statement(statement).await;
+        self.guard_risks(&conn, &[query], confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+        let query_id = query_id.unwrap_or_else(Uuid::new_v4);
+        let timeout = Self::resolve_timeout(None, &conn);
+        let options = ExecutionOptions::new(query_id).with_timeout(timeout);
+
+        let window = self.open_window(query_id, stream_options.max_pending_batches.max(1)).await;
+        self.start_tracking(connection_id, query_id, query).await;
+
+        let (sender, mut receiver) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+        let forward = async {
+            while let Some(batch) = receiver.recv().await {
+                // Si la ventana se cierra (cancelación) se suelta el receptor y el driver se detiene
+                match window.acquire().await {
+                    Ok(permit) => permit.forget(),
+                    Err(_) => break,
+                }
+                self.event_bus
+                    .publish(DomainEvent::QueryStreamBatch { connection_id, query_id, batch })
+                    .await
+                    .ok();
+            }
+        };
+        let (result, _) = tokio::join!(
+            Self::with_timeout(
+                driver.as_ref(),
+                query_id,
+                timeout,
+                driver.execute_query_stream(query, &options, &stream_options, sender),
+            ),
+            forward,
+        );
+
+        self.close_window(query_id).await;
+        self.finish_tracking(
+            connection_id,
+            query_id,
+            result.as_ref().map(|s| s.row_count as usize),
+        ).await;
+
+        let history_repo = self.get_history_repo().await?;
+        match &result {
+            Ok(summary) => {
+                let entry = QueryHistoryEntry::success(
+                    connection_id,
+                    query.to_string(),
+                    summary.execution_time_ms,
+                    summary.row_count as usize,
+                );
+                history_repo.save(entry).await.ok();
+            }
+            Err(e) => {
+                let entry = QueryHistoryEntry::failure(connection_id, query.to_string(), e.to_string());
+                history_repo.save(entry).await.ok();
+            }
+        }
+
+        result
+    }
+
+    /// El frontend confirma que procesó un lote del stream
+    pub async fn ack_stream_batch(&self, query_id: Uuid) {
+        if let Some(window) = self.stream_windows.read().await.get(&query_id) {
+            window.add_permits(1);
+        }
+    }
+
+    pub async fn execute_statement(
+        &self,
+        connection_id: Uuid,
+        statement: &str,
+        params: Option<&QueryParams>,
+        query_id: Option<Uuid>,
+        confirmation_token: Option<&str>,
+    ) -> Result<u64, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        if conn.read_only && is_destructive(statement, SqlDialect::from_engine(&conn.engine)) {
+            return Err(Self::read_only_denied());
+        }
+        self.guard_risks(&conn, &[statement], confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+        let query_id = query_id.unwrap_or_else(Uuid::new_v4);
+        let bound = BoundQuery::bind(statement, params, |i, v| driver.placeholder(i, v))?;
+        self.start_tracking(connection_id, query_id, statement).await;
+
+        let timeout = Self::resolve_timeout(None, &conn);
+        let options = ExecutionOptions::new(query_id)
+            .with_params(bound.values)
+            .with_timeout(timeout);
+        let result = Self::with_timeout(
+            driver.as_ref(),
+            query_id,
+            timeout,
+            driver.execute_statement(&bound.sql, &options),
+        ).await;
+        self.finish_tracking(
+            connection_id,
+            query_id,
+            result.as_ref().map(|affected| *affected as usize),
+        ).await;
 
         let history_repo = self.get_history_repo().await?;
         match &result {
@@ -99,13 +491,15 @@ impl QueryUseCase {
         connection_id: Uuid, 
         schema: Option<String>, 
         table: String, 
-        values: std::collections::HashMap<String, serde_json::Value>
+        values: std::collections::HashMap<String, serde_json::Value>,
+        confirmation_token: Option<&str>,
     ) -> Result<QueryResult, DomainError> {
         let conn = self.connection_use_case.get_connection(connection_id).await?;
         if conn.read_only {
-            return Err(DomainError::validation("Conexión en modo solo lectura: operación bloqueada"));
+            return Err(Self::read_only_denied());
         }
-        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
+        self.guard_write(&conn, WriteKind::Insert, schema.as_deref(), &table, confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
         
         let result = driver.insert_row(schema.as_deref(), &table, values).await;
         
@@ -133,37 +527,535 @@ impl QueryUseCase {
         result
     }
 
-    pub async fn execute_multi_statement(&self, connection_id: Uuid, statements: Vec<String>) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
+    /// Modifica filas por clave primaria en una sola transacción
+    pub async fn update_rows(
+        &self,
+        connection_id: Uuid,
+        schema: Option<String>,
+        table: String,
+        rows: Vec<RowUpdate>,
+        confirmation_token: Option<&str>,
+    ) -> Result<TransactionResult, DomainError> {
+        let target = Self::row_edit_target(schema.as_deref(), &table);
+        let edit = self.prepare_row_edit(connection_id, schema.as_deref(), &table, rows.len(), WriteKind::Update, confirmation_token).await?;
+        let apply = edit.driver.update_rows(&edit.target, &rows, &edit.options);
+        let result = self.apply_row_edit(connection_id, &edit, apply).await;
+        self.finish_row_edit(connection_id, &edit, format!("UPDATE {} ({} rows)", target, rows.len()), result).await
+    }
+
+    /// Borra filas por clave primaria en una sola transacción
+    pub async fn delete_rows(
+        &self,
+        connection_id: Uuid,
+        schema: Option<String>,
+        table: String,
+        rows: Vec<RowDelete>,
+        confirmation_token: Option<&str>,
+    ) -> Result<TransactionResult, DomainError> {
+        let target = Self::row_edit_target(schema.as_deref(), &table);
+        let edit = self.prepare_row_edit(connection_id, schema.as_deref(), &table, rows.len(), WriteKind::Delete, confirmation_token).await?;
+        let apply = edit.driver.delete_rows(&edit.target, &rows, &edit.options);
+        let result = self.apply_row_edit(connection_id, &edit, apply).await;
+        self.finish_row_edit(connection_id, &edit, format!("DELETE FROM {} ({} rows)", target, rows.len()), result).await
+    }
+
+    /// Comprobaciones comunes de las ediciones por clave: solo lectura, riesgos y clave primaria.
+    async fn prepare_row_edit(
+        &self,
+        connection_id: Uuid,
+        schema: Option<&str>,
+        table: &str,
+        row_count: usize,
+        kind: WriteKind,
+        confirmation_token: Option<&str>,
+    ) -> Result<RowEdit, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        if conn.read_only {
+            return Err(Self::read_only_denied());
+        }
+        if row_count == 0 {
+            return Err(DomainError::validation("There are no rows to edit"));
+        }
+        self.guard_write(&conn, kind, schema, table, confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+
+        let info = driver.get_table_info(table, schema).await?;
+        let key_columns = info
+            .primary_key
+            .map(|pk| pk.columns)
+            .filter(|columns| !columns.is_empty())
+            .ok_or_else(|| DomainError::validation(format!("{} has no primary key: its rows cannot be edited by key", table)))?;
+
+        let query_id = Uuid::new_v4();
+        // El límite se aplica en el servidor a cada sentencia; cortar desde el cliente
+        // dejaría la transacción del lote a medias
+        let options = ExecutionOptions::new(query_id).with_timeout(Self::resolve_timeout(None, &conn));
+        self.start_tracking(connection_id, query_id, &format!("Editing {} rows of {}", row_count, table)).await;
+        let target = RowEditTarget {
+            schema: schema.map(str::to_string),
+            table: table.to_string(),
+            dialect: SqlDialect::from_engine(&conn.engine),
+            columns: info.columns,
+            key_columns,
+        };
+        Ok(RowEdit { driver, target, options })
+    }
+
+    /// Ejecuta las sentencias de la edición todo o nada, en una transacción registrada a su
+    /// nombre. Con una transacción manual abierta se ejecutan dentro de ella, protegidas por
+    /// un savepoint, y no se confirman
+    async fn apply_row_edit(
+        &self,
+        connection_id: Uuid,
+        edit: &RowEdit,
+        apply: impl Future<Output = Result<u64, DomainError>>,
+    ) -> Result<TransactionResult, DomainError> {
+        const SAVEPOINT: &str = "row_edits";
+        let driver = edit.driver.as_ref();
+        let owner = edit.options.query_id;
+        let started = Instant::now();
+        let joined = driver.in_transaction().await;
+        if joined {
+            driver.create_savepoint(SAVEPOINT).await?;
+        } else {
+            self.begin_owned_transaction(
+                driver,
+                connection_id,
+                owner,
+                &TransactionOptions::default(),
+                "A transaction is already open on this connection: wait for it to finish before editing rows",
+            )
+            .await?;
+        }
+
+        let applied = apply.await;
+        let closed = match (&applied, joined) {
+            (Err(_), true) => driver.rollback_to_savepoint(SAVEPOINT).await,
+            (Err(_), false) => driver.rollback_transaction().await,
+            (Ok(_), true) => Ok(()),
+            (Ok(_), false) => driver.commit_transaction().await,
+        };
+        if !joined {
+            if closed.is_err() && driver.in_transaction().await {
+                // Un COMMIT fallido no debe dejar abierta la transacción de la edición
+                driver.rollback_transaction().await.ok();
+            }
+            self.end_owned_transaction(connection_id, owner).await;
+        }
+        let affected_rows = applied?;
+        closed?;
+        Ok(TransactionResult {
+            affected_rows,
+            execution_time_ms: started.elapsed().as_millis() as u64,
+            committed: !joined,
+        })
+    }
+
+    async fn finish_row_edit(
+        &self,
+        connection_id: Uuid,
+        edit: &RowEdit,
+        description: String,
+        result: Result<TransactionResult, DomainError>,
+    ) -> Result<TransactionResult, DomainError> {
+        self.finish_tracking(
+            connection_id,
+            edit.options.query_id,
+            result.as_ref().map(|r| r.affected_rows as usize),
+        ).await;
+
+        let history_repo = self.get_history_repo().await?;
+        let entry = match &result {
+            Ok(r) => QueryHistoryEntry::success(connection_id, description, r.execution_time_ms, r.affected_rows as usize),
+            Err(e) => QueryHistoryEntry::failure(connection_id, description, e.to_string()),
+        };
+        history_repo.save(entry).await.ok();
+        result
+    }
+
+    /// Tabla de una edición o importación, tal como se anota en el historial
+    pub(super) fn row_edit_target(schema: Option<&str>, table: &str) -> String {
+        let quote = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+        match schema {
+            Some(schema) => format!("{}.{}", quote(schema), quote(table)),
+            None => quote(table),
+        }
+    }
+
+    pub async fn execute_multi_statement(
+        &self,
+        connection_id: Uuid,
+        statements: Vec<String>,
+        query_id: Option<Uuid>,
+        confirmation_token: Option<&str>,
+    ) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
         let conn = self.connection_use_case.get_connection(connection_id).await?;
         if conn.read_only {
             for s in &statements {
-                if Self::is_destructive(s) {
-                    return Err(DomainError::validation("Conexión en modo solo lectura: operación bloqueada"));
+                if is_destructive(s, SqlDialect::from_engine(&conn.engine)) {
+                    return Err(Self::read_only_denied());
                 }
             }
         }
-        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
-        driver.execute_multi_statement(statements).await
+        self.guard_risks(&conn, &statements, confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+        let query_id = query_id.unwrap_or_else(Uuid::new_v4);
+        self.start_tracking(connection_id, query_id, &statements.join(";\n")).await;
+
+        let timeout = Self::resolve_timeout(None, &conn);
+        let options = ExecutionOptions::new(query_id).with_timeout(timeout);
+        let result = Self::with_timeout(
+            driver.as_ref(),
+            query_id,
+            timeout,
+            driver.execute_multi_statement(statements, &options),
+        ).await;
+        self.finish_tracking(
+            connection_id,
+            query_id,
+            result.as_ref().map(|r| r.len()),
+        ).await;
+        result
     }
 
-    pub async fn execute_in_transaction(&self, connection_id: Uuid, statement: &str) -> Result<crate::domain::TransactionResult, DomainError> {
+    /// Ejecuta las sentencias en una transacción que siempre se deshace y devuelve, por sentencia,
+    /// las filas afectadas y una muestra de las filas tocadas antes y después.
+    /// Se detiene en la primera que falla
+    pub async fn dry_run(
+        &self,
+        connection_id: Uuid,
+        statements: Vec<String>,
+        params: Option<&QueryParams>,
+        query_id: Option<Uuid>,
+        confirmation_token: Option<&str>,
+    ) -> Result<Vec<DryRunStatement>, DomainError> {
         let conn = self.connection_use_case.get_connection(connection_id).await?;
-        if conn.read_only && Self::is_destructive(statement) {
-            return Err(DomainError::validation("Conexión en modo solo lectura: operación bloqueada"));
+        if statements.is_empty() {
+            return Err(DomainError::validation("There are no statements to dry-run"));
         }
-        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
-        driver.execute_in_transaction(statement).await
+        if params.is_some_and(|p| !p.is_empty()) && statements.len() > 1 {
+            return Err(DomainError::validation("Parameters can only be used when dry-running a single statement"));
+        }
+        let dialect = SqlDialect::from_engine(&conn.engine);
+        if conn.read_only && statements.iter().any(|s| is_destructive(s, dialect)) {
+            return Err(Self::read_only_denied());
+        }
+        for statement in &statements {
+            check_dry_runnable(statement, dialect)?;
+        }
+        self.guard_risks(&conn, &statements, confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+        let query_id = query_id.unwrap_or_else(Uuid::new_v4);
+        // La transacción queda registrada a nombre de la simulación: nada más puede usarla mientras dura
+        self.begin_owned_transaction(
+            driver.as_ref(),
+            connection_id,
+            query_id,
+            &TransactionOptions::default(),
+            "A transaction is already open on this connection: commit or roll it back before a dry run",
+        )
+        .await?;
+
+        self.start_tracking(connection_id, query_id, &statements.join(";\n")).await;
+        let timeout = Self::resolve_timeout(None, &conn);
+        let options = ExecutionOptions::new(query_id).with_timeout(timeout);
+
+        let mut report = Vec::with_capacity(statements.len());
+        for (index, statement) in statements.iter().enumerate() {
+            let scope = MutationScope::parse(statement, dialect);
+            let before = match &scope {
+                Some(scope) => Some(Self::dry_run_before(driver.as_ref(), scope, params, &options, dialect).await),
+                None => None,
+            };
+
+            let started = Instant::now();
+            let outcome = match BoundQuery::bind(statement, params, |i, v| driver.placeholder(i, v)) {
+                Ok(bound) => {
+                    let options = options.clone().with_params(bound.values);
+                    Self::with_timeout(driver.as_ref(), query_id, timeout, driver.execute_statement(&bound.sql, &options))
+                        .await
+                        .map(|affected| (None, Some(affected)))
+                }
+                Err(e) => Err(e),
+            };
+            let result = StatementResult::from_outcome(index, statement, outcome, started.elapsed().as_millis() as u64);
+
+            let (sample, sample_note) = match (scope, before) {
+                _ if !result.success => (None, None),
+                (Some(scope), Some(Ok((key_columns, before)))) => {
+                    match Self::dry_run_after(driver.as_ref(), &scope, &key_columns, &before, &options, dialect).await {
+                        Ok(after) => {
+                            let truncated = result.affected_rows.is_some_and(|n| n > before.row_count as u64);
+                            let table = scope.schema.map_or(scope.table.clone(), |s| format!("{}.{}", s, scope.table));
+                            (Some(RowDiffSample { table, key_columns, before, after, truncated }), None)
+                        }
+                        Err(e) => (None, Some(format!("Could not read the rows after the statement: {}", e))),
+                    }
+                }
+                (Some(_), Some(Err(note))) => (None, Some(note)),
+                _ => (None, Some("Rows are only sampled for single-table UPDATE and DELETE statements".to_string())),
+            };
+
+            let failed = !result.success;
+            report.push(DryRunStatement { result, sample, sample_note });
+            if failed {
+                break;
+            }
+        }
+
+        // La simulación nunca se confirma, ni siquiera si alguna lectura dejó la transacción en mal estado
+        let mut outcome = driver.rollback_transaction().await;
+        if outcome.is_err() && driver.in_transaction().await {
+            outcome = driver.rollback_transaction().await;
+        }
+        self.end_owned_transaction(connection_id, query_id).await;
+        let outcome = outcome.map(|_| report);
+        self.finish_tracking(connection_id, query_id, outcome.as_ref().map(|r| r.len())).await;
+        outcome
     }
 
+    /// Clave primaria de la tabla y filas que va a tocar la sentencia. El error es el motivo,
+    /// para el informe, por el que no hay muestra
+    async fn dry_run_before(
+        driver: &dyn SqlDriver,
+        scope: &MutationScope,
+        params: Option<&QueryParams>,
+        options: &ExecutionOptions,
+        dialect: SqlDialect,
+    ) -> Result<(Vec<String>, QueryResult), String> {
+        // Con `?` o `$n` la posición de cada valor cambia al quitar el SET de la sentencia
+        if matches!(params, Some(QueryParams::Positional(values)) if !values.is_empty()) {
+            return Err("Rows are not sampled for statements with positional parameters".to_string());
+        }
+        let key_columns: Vec<String> = driver
+            .get_columns(&scope.table, scope.schema.as_deref())
+            .await
+            .map_err(|e| format!("Could not read the primary key of {}: {}", scope.table, e))?
+            .into_iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name)
+            .collect();
+        if key_columns.is_empty() {
+            return Err(format!("{} has no primary key to match rows before and after", scope.table));
+        }
+
+        let bound = BoundQuery::bind(&scope.before_query(DRY_RUN_SAMPLE_ROWS, dialect), params, |i, v| driver.placeholder(i, v))
+            .map_err(|e| e.to_string())?;
+        let mut before = Self::dry_run_sample(driver, &bound, options).await.map_err(|e| e.to_string())?;
+        before.rows.truncate(DRY_RUN_SAMPLE_ROWS);
+        before.row_count = before.rows.len();
+        Ok((key_columns, before))
+    }
 
-fn is_destructive(sql: &str) -> bool {
-    let normalized = sql.trim().to_uppercase();
-    normalized.starts_with("UPDATE")
-        || normalized.starts_with("DELETE")
-        || normalized.starts_with("DROP")
-        || normalized.starts_with("TRUNCATE")
-        || normalized.starts_with("ALTER")
-}
+    /// Las filas de la muestra previa, buscadas otra vez por su clave primaria
+    async fn dry_run_after(
+        driver: &dyn SqlDriver,
+        scope: &MutationScope,
+        key_columns: &[String],
+        before: &QueryResult,
+        options: &ExecutionOptions,
+        dialect: SqlDialect,
+    ) -> Result<QueryResult, DomainError> {
+        if before.rows.is_empty() {
+            let mut after = before.clone();
+            after.id = Uuid::new_v4();
+            return Ok(after);
+        }
+        let positions: Vec<usize> = key_columns
+            .iter()
+            .map(|k| before.columns.iter().position(|c| &c.name == k))
+            .collect::<Option<_>>()
+            .ok_or_else(|| DomainError::internal("The sampled rows do not include the primary key"))?;
+        let mut keys: HashMap<String, CellValue> = HashMap::new();
+        for (row, values) in before.rows.iter().enumerate() {
+            for (col, &position) in positions.iter().enumerate() {
+                keys.insert(format!("k{}_{}", row, col), values[position].clone());
+            }
+        }
+
+        let sql = scope.after_query(key_columns, before.rows.len(), dialect);
+        let bound = BoundQuery::bind(&sql, Some(&QueryParams::Named(keys)), |i, v| driver.placeholder(i, v))?;
+        Self::dry_run_sample(driver, &bound, options).await
+    }
+
+    /// Lectura de muestra dentro de un savepoint: en PostgreSQL un error abortaría la transacción
+    /// y con ella la simulación
+    async fn dry_run_sample(driver: &dyn SqlDriver, bound: &BoundQuery, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        driver.create_savepoint(DRY_RUN_SAVEPOINT).await?;
+        let options = options.clone().with_params(bound.values.clone());
+        let result = Self::with_timeout(driver, options.query_id, options.timeout, driver.execute_query(&bound.sql, &options)).await;
+        if result.is_err() {
+            driver.rollback_to_savepoint(DRY_RUN_SAVEPOINT).await?;
+        }
+        result
+    }
+
+    /// Divide un script en sentencias con las reglas del motor de la conexión
+    pub async fn split_script(&self, connection_id: Uuid, script: &str) -> Result<Vec<ScriptStatement>, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        Ok(split_script(script, SqlDialect::from_engine(&conn.engine)))
+    }
+
+    /// Ejecuta un script sentencia a sentencia, publicando el resultado de cada una según termina.
+    /// Cancelar `script_id` detiene la sentencia en curso y las siguientes en cualquier modo.
+    pub async fn run_script(
+        &self,
+        connection_id: Uuid,
+        script: &str,
+        mode: ScriptMode,
+        script_id: Option<Uuid>,
+        confirmation_token: Option<&str>,
+    ) -> Result<ScriptRunSummary, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        let dialect = SqlDialect::from_engine(&conn.engine);
+        let statements = split_script(script, dialect);
+        if statements.is_empty() {
+            return Err(DomainError::validation("The script has no statements"));
+        }
+        if conn.read_only && statements.iter().any(|s| is_destructive(&s.sql, dialect)) {
+            return Err(Self::read_only_denied());
+        }
+        let sql: Vec<&str> = statements.iter().map(|s| s.sql.as_str()).collect();
+        self.guard_risks(&conn, &sql, confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+
+        let script_id = script_id.unwrap_or_else(Uuid::new_v4);
+        if mode == ScriptMode::Transaction {
+            // El script no puede confirmar ni deshacer una transacción manual ajena
+            let options = TransactionOptions { read_only: conn.read_only, ..Default::default() };
+            self.begin_owned_transaction(
+                driver.as_ref(),
+                connection_id,
+                script_id,
+                &options,
+                "A transaction is already open on this connection: run the script in another mode",
+            )
+            .await?;
+        }
+
+        self.start_tracking(connection_id, script_id, script).await;
+        let started = Instant::now();
+        // El límite de la conexión se aplica a cada sentencia, no al script entero
+        let timeout = Self::resolve_timeout(None, &conn);
+        let options = ExecutionOptions::new(script_id).with_timeout(timeout);
+        let mut summary = ScriptRunSummary {
+            script_id,
+            mode,
+            statement_count: statements.len(),
+            executed: 0,
+            succeeded: 0,
+            failed: 0,
+            stopped: false,
+            committed: None,
+            execution_time_ms: 0,
+        };
+        let cancelled = DomainError::Cancelled.to_string();
+
+        for (index, statement) in statements.into_iter().enumerate() {
+            let executed = Self::with_timeout(
+                driver.as_ref(),
+                script_id,
+                timeout,
+                driver.execute_multi_statement(vec![statement.sql.clone()], &options),
+            )
+            .await
+            .map(|results| results.into_iter().next());
+            let result = match executed {
+                Ok(Some(mut result)) => {
+                    result.statement_index = index;
+                    result
+                }
+                other => StatementResult {
+                    statement_index: index,
+                    sql: statement.sql,
+                    success: false,
+                    affected_rows: None,
+                    result: None,
+                    error: Some(match other {
+                        Err(e) => e.to_string(),
+                        _ => "Statement was not executed".to_string(),
+                    }),
+                    execution_time_ms: 0,
+                },
+            };
+
+            summary.executed += 1;
+            if result.success {
+                summary.succeeded += 1;
+            } else {
+                summary.failed += 1;
+            }
+            let stop = !result.success
+                && (mode != ScriptMode::ContinueOnError || result.error.as_deref() == Some(cancelled.as_str()));
+            self.event_bus
+                .publish(DomainEvent::ScriptStatementCompleted { connection_id, script_id, result: Box::new(result) })
+                .await
+                .ok();
+            if stop {
+                break;
+            }
+        }
+        summary.stopped = summary.executed < summary.statement_count;
+
+        let mut outcome = Ok(summary.succeeded);
+        if mode == ScriptMode::Transaction {
+            let commit = summary.failed == 0;
+            let closed = if commit { driver.commit_transaction().await } else { driver.rollback_transaction().await };
+            if let Err(e) = closed {
+                // Un COMMIT fallido no debe dejar la transacción del script abierta
+                if driver.in_transaction().await {
+                    driver.rollback_transaction().await.ok();
+                }
+                outcome = Err(e);
+            }
+            self.end_owned_transaction(connection_id, script_id).await;
+            summary.committed = Some(commit && outcome.is_ok());
+        }
+        summary.execution_time_ms = started.elapsed().as_millis() as u64;
+
+        self.finish_tracking(connection_id, script_id, outcome.as_ref().copied()).await;
+        outcome.map(|_| summary)
+    }
+
+    /// Plan de ejecución de la sentencia; con `analyze` se ejecuta y se deshace
+    pub async fn explain_query(
+        &self,
+        connection_id: Uuid,
+        sql: &str,
+        analyze: bool,
+        confirmation_token: Option<&str>,
+    ) -> Result<QueryPlan, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        if analyze {
+            // ANALYZE ejecuta la sentencia: pasa por las mismas comprobaciones que ejecutarla
+            if conn.read_only && is_destructive(sql, SqlDialect::from_engine(&conn.engine)) {
+                return Err(Self::read_only_denied());
+            }
+            self.guard_risks(&conn, &[sql], confirmation_token).await?;
+        }
+        let driver = self.execution_driver(connection_id).await?;
+        driver.explain(sql, analyze).await
+    }
+
+    pub async fn execute_in_transaction(
+        &self,
+        connection_id: Uuid,
+        statement: &str,
+        confirmation_token: Option<&str>,
+    ) -> Result<crate::domain::TransactionResult, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        if conn.read_only && is_destructive(statement, SqlDialect::from_engine(&conn.engine)) {
+            return Err(Self::read_only_denied());
+        }
+        self.guard_risks(&conn, &[statement], confirmation_token).await?;
+        let driver = self.execution_driver(connection_id).await?;
+        driver.execute_in_transaction(statement).await
+    }
+
+    /// Error de una escritura bloqueada en una conexión de solo lectura
+    pub(super) fn read_only_denied() -> DomainError {
+        DomainError::PermissionDenied { resource: "read-only connection".to_string() }
+    }
 
     pub async fn get_history(&self, connection_id: Uuid, limit: usize) -> Result<Vec<QueryHistoryEntry>, DomainError> {
         let repo = self.get_history_repo().await?;
@@ -180,8 +1072,198 @@ fn is_destructive(sql: &str) -> bool {
         repo.get_frequent(connection_id, limit).await
     }
 
-    pub async fn cancel_query(&self, connection_id: Uuid) -> Result<(), DomainError> {
+    /// Libera un cursor de paginación que el frontend ya no va a usar
+    pub async fn close_query_cursor(&self, connection_id: Uuid, cursor: &str) -> Result<(), DomainError> {
         let driver = self.connection_use_case.get_active_driver(connection_id).await?;
-        driver.cancel_query().await
+        driver.close_pagination_cursor(cursor).await
+    }
+
+    /// Queries en ejecución sobre una conexión
+    pub async fn list_running_queries(&self, connection_id: Uuid) -> Vec<RunningQueryInfo> {
+        let running = self.running_queries.read().await;
+        let mut queries: Vec<RunningQueryInfo> = running
+            .values()
+            .filter(|q| q.connection_id == connection_id)
+            .cloned()
+            .collect();
+        queries.sort_by_key(|q| q.started_at);
+        queries
+    }
+
+    /// Cancela una ejecución concreta, o todas las de la conexión si no se indica query_id
+    pub async fn cancel_query(&self, connection_id: Uuid, query_id: Option<Uuid>) -> Result<(), DomainError> {
+        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
+
+        let targets: Vec<Uuid> = match query_id {
+            Some(id) => {
+                let running = self.running_queries.read().await;
+                match running.get(&id) {
+                    Some(q) if q.connection_id == connection_id => vec![id],
+                    _ => return Err(DomainError::not_found(format!("Running query {}", id))),
+                }
+            }
+            None => self
+                .list_running_queries(connection_id)
+                .await
+                .into_iter()
+                .map(|q| q.query_id)
+                .collect(),
+        };
+
+        for id in targets {
+            // Un stream pausado esperando confirmaciones no está leyendo del servidor
+            if let Some(window) = self.stream_windows.read().await.get(&id) {
+                window.close();
+            }
+            driver.cancel_query(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Abre una transacción manual; las ejecuciones siguientes de la conexión van en ella
+    /// hasta `commit_transaction` o `rollback_transaction`
+    pub async fn begin_transaction(&self, connection_id: Uuid, mut options: TransactionOptions) -> Result<TransactionSession, DomainError> {
+        let conn = self.connection_use_case.get_connection(connection_id).await?;
+        // Una conexión de solo lectura solo admite transacciones de solo lectura
+        options.read_only |= conn.read_only;
+        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
+
+        let mut transactions = self.transactions.write().await;
+        if transactions.contains_key(&connection_id) {
+            return Err(DomainError::validation("A transaction is already open on this connection"));
+        }
+        driver.begin_transaction(&options).await?;
+        let session = TransactionSession::new(connection_id, &options);
+        transactions.insert(connection_id, session.clone());
+        drop(transactions);
+
+        self.watch_transaction(&session);
+        Ok(session)
+    }
+
+    /// Avisa periódicamente mientras la transacción siga abierta
+    fn watch_transaction(&self, session: &TransactionSession) {
+        let transactions = self.transactions.clone();
+        let event_bus = self.event_bus.clone();
+        let connection_id = session.connection_id;
+        let transaction_id = session.transaction_id;
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(TRANSACTION_WARN_AFTER).await;
+                let open_for_secs = match transactions.read().await.get(&connection_id) {
+                    Some(s) if s.transaction_id == transaction_id => s.open_for_secs(),
+                    _ => break,
+                };
+                event_bus
+                    .publish(DomainEvent::TransactionWarning {
+                        connection_id,
+                        transaction_id,
+                        reason: TransactionWarningReason::LongRunning,
+                        open_for_secs,
+                    })
+                    .await
+                    .ok();
+            }
+        });
+    }
+
+    pub async fn commit_transaction(&self, connection_id: Uuid) -> Result<(), DomainError> {
+        let driver = self.execution_driver(connection_id).await?;
+        let result = driver.commit_transaction().await;
+        self.sync_transaction(connection_id, driver.in_transaction().await).await;
+        result
+    }
+
+    pub async fn rollback_transaction(&self, connection_id: Uuid) -> Result<(), DomainError> {
+        let driver = self.execution_driver(connection_id).await?;
+        let result = driver.rollback_transaction().await;
+        self.sync_transaction(connection_id, driver.in_transaction().await).await;
+        result
+    }
+
+    /// Olvida la transacción si el driver ya la cerró (un COMMIT fallido puede dejarla abierta)
+    async fn sync_transaction(&self, connection_id: Uuid, still_open: bool) {
+        if !still_open {
+            self.transactions.write().await.remove(&connection_id);
+        }
+    }
+
+    pub async fn create_savepoint(&self, connection_id: Uuid, name: &str) -> Result<TransactionSession, DomainError> {
+        Self::validate_savepoint_name(name)?;
+        let driver = self.execution_driver(connection_id).await?;
+
+        let mut transactions = self.transactions.write().await;
+        let session = transactions
+            .get_mut(&connection_id)
+            .ok_or_else(|| DomainError::validation("No transaction is open on this connection"))?;
+        driver.create_savepoint(name).await?;
+        // Un savepoint con el mismo nombre sustituye al anterior
+        session.savepoints.retain(|s| s != name);
+        session.savepoints.push(name.to_string());
+        Ok(session.clone())
+    }
+
+    pub async fn rollback_to_savepoint(&self, connection_id: Uuid, name: &str) -> Result<TransactionSession, DomainError> {
+        let driver = self.execution_driver(connection_id).await?;
+
+        let mut transactions = self.transactions.write().await;
+        let session = transactions
+            .get_mut(&connection_id)
+            .ok_or_else(|| DomainError::validation("No transaction is open on this connection"))?;
+        let position = session
+            .savepoints
+            .iter()
+            .position(|s| s == name)
+            .ok_or_else(|| DomainError::not_found(format!("Savepoint {}", name)))?;
+        driver.rollback_to_savepoint(name).await?;
+        // Los savepoints posteriores dejan de existir; el destino se conserva
+        session.savepoints.truncate(position + 1);
+        Ok(session.clone())
+    }
+
+    fn validate_savepoint_name(name: &str) -> Result<(), DomainError> {
+        let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && name.len() <= 63;
+        if valid {
+            Ok(())
+        } else {
+            Err(DomainError::validation("Savepoint names must be identifiers (letters, digits and _)"))
+        }
+    }
+
+    /// Transacción manual abierta en la conexión, si hay una
+    pub async fn get_transaction(&self, connection_id: Uuid) -> Option<TransactionSession> {
+        self.transactions.read().await.get(&connection_id).cloned()
+    }
+
+    /// Comprueba que se puede desconectar. Con una transacción abierta se avisa y, salvo
+    /// que se fuerce, se rechaza; forzando, los cambios pendientes se deshacen.
+    pub async fn release_transaction_for_disconnect(&self, connection_id: Uuid, force: bool) -> Result<(), DomainError> {
+        let Some(session) = self.get_transaction(connection_id).await else {
+            return Ok(());
+        };
+
+        self.event_bus
+            .publish(DomainEvent::TransactionWarning {
+                connection_id,
+                transaction_id: session.transaction_id,
+                reason: TransactionWarningReason::UncommittedOnDisconnect,
+                open_for_secs: session.open_for_secs(),
+            })
+            .await
+            .ok();
+        if !force {
+            return Err(DomainError::validation(
+                "The connection has an open transaction: commit or roll back before disconnecting",
+            ));
+        }
+
+        if let Ok(driver) = self.connection_use_case.get_active_driver(connection_id).await {
+            driver.rollback_transaction().await.ok();
+        }
+        self.transactions.write().await.remove(&connection_id);
+        Ok(())
     }
 }
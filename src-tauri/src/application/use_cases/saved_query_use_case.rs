@@ -1,7 +1,10 @@
-use crate::domain::entities::{CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
+use crate::domain::entities::{
+    detect_parameters, CellValue, CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto,
+};
 use crate::domain::error::DomainError;
 use crate::domain::ports::SavedQueryRepository;
 use crate::infrastructure::repositories::FileSavedQueryRepository;
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -30,6 +33,7 @@ impl SavedQueryUseCase {
         if dto.sql.trim().is_empty() {
             return Err(DomainError::validation("SQL cannot be empty"));
         }
+        detect_parameters(&dto.sql)?;
 
         self.repository.create(dto).await
     }
@@ -45,11 +49,21 @@ impl SavedQueryUseCase {
             if sql.trim().is_empty() {
                 return Err(DomainError::validation("SQL cannot be empty"));
             }
+            detect_parameters(sql)?;
         }
 
         self.repository.update(id, dto).await
     }
 
+    /// Recuerda los valores con los que se ejecutó la consulta, por nombre de parámetro
+    pub async fn record_parameter_values(
+        &self,
+        id: Uuid,
+        values: HashMap<String, CellValue>,
+    ) -> Result<SavedQuery, DomainError> {
+        self.repository.record_parameter_values(id, values).await
+    }
+
     pub async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
         self.repository.delete(id).await
     }
@@ -2,7 +2,7 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::domain::{
-    ColumnSchema, ConstraintInfo, DatabaseInfo, DomainError, FunctionInfo, IndexInfo,
+    ColumnSchema, ConstraintInfo, DatabaseInfo, DdlObject, DdlOptions, DomainError, FunctionInfo, IndexInfo,
     SchemaInfo, SequenceInfo, TableInfo, TriggerInfo, ViewInfo,
 };
 use super::ConnectionUseCase;
@@ -77,6 +77,12 @@ impl SchemaUseCase {
         driver.list_sequences(schema).await
     }
 
+    /// Sentencia `CREATE` de un objeto del schema
+    pub async fn get_object_ddl(&self, connection_id: Uuid, object: &DdlObject, options: &DdlOptions) -> Result<String, DomainError> {
+        let driver = self.connection_use_case.get_active_driver(connection_id).await?;
+        driver.get_object_ddl(object, options).await
+    }
+
     pub async fn get_server_version(&self, connection_id: Uuid) -> Result<String, DomainError> {
         let driver = self.connection_use_case.get_active_driver(connection_id).await?;
         driver.server_version().await
@@ -2,9 +2,11 @@ use tauri::State;
 use uuid::Uuid;
 
 use crate::application::ConnectionUseCase;
-use crate::domain::{Connection, ConnectionStatus, CreateConnectionDto, DomainError, UpdateConnectionDto};
+use crate::domain::{Connection, ConnectionStatus, CreateConnectionDto, DomainError, TlsStatus, UpdateConnectionDto};
 use std::sync::Arc;
 
+use super::query_commands::QueryState;
+
 pub type ConnectionState = Arc<ConnectionUseCase>;
 
 #[tauri::command]
@@ -72,9 +74,13 @@ pub async fn connect(
 #[tauri::command]
 pub async fn disconnect(
     state: State<'_, ConnectionState>,
+    query_state: State<'_, QueryState>,
     id: String,
+    force: Option<bool>,
 ) -> Result<(), DomainError> {
     let uuid = Uuid::parse_str(&id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    // Con una transacción manual abierta hace falta confirmarlo con `force`
+    query_state.release_transaction_for_disconnect(uuid, force.unwrap_or(false)).await?;
     state.disconnect(uuid).await
 }
 
@@ -87,6 +93,15 @@ pub async fn get_connection_status(
     Ok(state.get_connection_status(uuid).await)
 }
 
+#[tauri::command]
+pub async fn get_connection_tls(
+    state: State<'_, ConnectionState>,
+    id: String,
+) -> Result<TlsStatus, DomainError> {
+    let uuid = Uuid::parse_str(&id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.get_connection_tls(uuid).await
+}
+
 #[tauri::command]
 pub async fn change_database(
     state: State<'_, ConnectionState>,
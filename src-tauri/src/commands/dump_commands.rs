@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::application::DumpUseCase;
+use crate::domain::{DomainError, DumpOptions, DumpSummary, RestoreOptions, RestoreSummary};
+
+pub type DumpState = Arc<DumpUseCase>;
+
+fn parse_optional_uuid(id: Option<String>) -> Result<Option<Uuid>, DomainError> {
+    id.map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))
+}
+
+/// Vuelca la base o un esquema a un script SQL en `path`. El avance llega como evento
+/// `DumpProgress`; se cancela con `cancel_query(dump_id)`
+#[tauri::command]
+pub async fn dump_database(
+    state: State<'_, DumpState>,
+    connection_id: String,
+    path: String,
+    options: DumpOptions,
+    dump_id: Option<String>,
+) -> Result<DumpSummary, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state
+        .dump_database(uuid, PathBuf::from(path), options, parse_optional_uuid(dump_id)?)
+        .await
+}
+
+/// Restaura un volcado desde `path`. El avance llega como evento `RestoreProgress`; una
+/// restauración detenida se retoma con `options.start_at = resume_at`
+#[tauri::command]
+pub async fn restore_dump(
+    state: State<'_, DumpState>,
+    connection_id: String,
+    path: String,
+    options: Option<RestoreOptions>,
+    restore_id: Option<String>,
+    confirmation_token: Option<String>,
+) -> Result<RestoreSummary, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    let script = tokio::fs::read_to_string(&path).await?;
+    state
+        .restore_dump(
+            uuid,
+            &script,
+            options.unwrap_or_default(),
+            parse_optional_uuid(restore_id)?,
+            confirmation_token.as_deref(),
+        )
+        .await
+}
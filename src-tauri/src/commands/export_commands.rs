@@ -1,49 +1,77 @@
 use serde_json::Value;
-use umya_spreadsheet::{self as excel, Worksheet};
-
-fn index_to_column_letters(mut col: u32) -> String {
-    let mut s = String::new();
-    while col > 0 {
-        let rem = (col - 1) % 26;
-        s.insert(0, (b'A' + rem as u8) as char);
-        col = (col - 1) / 26;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::application::ExportUseCase;
+use crate::domain::{CellValue, DomainError, ExportOptions, ExportSummary, StatementResult, XlsxSheet};
+use crate::infrastructure::write_xlsx;
+
+pub type ExportState = Arc<ExportUseCase>;
+
+/// Celda tipada a partir del valor JSON que envía el frontend
+fn json_cell(value: Value) -> CellValue {
+    match value {
+        Value::Null => CellValue::Null,
+        Value::Bool(b) => CellValue::Bool(b),
+        Value::Number(n) => n.as_i64().map(CellValue::Int).unwrap_or_else(|| CellValue::Float(n.as_f64().unwrap_or_default())),
+        Value::String(s) => CellValue::String(s),
+        other => CellValue::Json(other),
     }
-    s
 }
 
-fn set_cell_text(sheet: &mut Worksheet, row: u32, col: u32, text: String) {
-    let col_letters = index_to_column_letters(col);
-    let coordinate = format!("{}{}", col_letters, row);
-    sheet.get_cell_mut(coordinate).set_value(text);
+/// El libro se genera en memoria y se comprime al escribirlo: fuera del runtime async
+async fn write_workbook(path: String, sheets: Vec<XlsxSheet>) -> Result<(), DomainError> {
+    tokio::task::spawn_blocking(move || write_xlsx(&PathBuf::from(path), &sheets))
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?
 }
 
 #[tauri::command]
 pub async fn export_results_xlsx(columns: Vec<String>, rows: Vec<Vec<Value>>, path: String) -> Result<(), String> {
-    let mut book = excel::new_file();
-    let sheet_name = "Sheet1";
-    let sheet = book
-        .get_sheet_by_name_mut(sheet_name)
-        .map_err(|e| e.to_string())?;
-
-    for (i, col_name) in columns.iter().enumerate() {
-        set_cell_text(sheet, 1, (i as u32) + 1, col_name.to_string());
-    }
+    let rows = rows.into_iter().map(|row| row.into_iter().map(json_cell).collect()).collect();
+    let sheet = XlsxSheet { name: "Sheet1".to_string(), columns, rows };
+    write_workbook(path, vec![sheet]).await.map_err(|e| e.to_string())
+}
 
-    for (r_idx, row) in rows.iter().enumerate() {
-        let row_num = (r_idx as u32) + 2;
-        for (c_idx, cell) in row.iter().enumerate() {
-            let col_num = (c_idx as u32) + 1;
-            let text = match cell {
-                Value::Null => "".to_string(),
-                Value::Bool(b) => if *b { "TRUE".to_string() } else { "FALSE".to_string() },
-                Value::Number(n) => n.to_string(),
-                Value::String(s) => s.to_string(),
-                Value::Array(a) => serde_json::to_string(a).unwrap_or_default(),
-                Value::Object(o) => serde_json::to_string(o).unwrap_or_default(),
-            };
-            set_cell_text(sheet, row_num, col_num, text);
-        }
-    }
+/// Libro con una hoja por conjunto de resultados, con las celdas tipadas
+#[tauri::command]
+pub async fn export_xlsx_workbook(sheets: Vec<XlsxSheet>, path: String) -> Result<(), DomainError> {
+    write_workbook(path, sheets).await
+}
+
+/// Una hoja por sentencia de un script que devolvió filas, en el orden del script
+#[tauri::command]
+pub async fn export_statement_results_xlsx(results: Vec<StatementResult>, path: String) -> Result<(), DomainError> {
+    let sheets = results
+        .into_iter()
+        .filter_map(|statement| {
+            let name = format!("Result {}", statement.statement_index + 1);
+            statement.result.map(|result| XlsxSheet::from_result(name, result))
+        })
+        .collect();
+    write_workbook(path, sheets).await
+}
 
-    excel::writer::xlsx::write(&book, &path).map_err(|e| e.to_string())
+/// Ejecuta la query en el backend y escribe el resultado directamente en `path`.
+/// El avance llega como evento `ExportProgress`; se cancela con `cancel_query(export_id)`
+#[tauri::command]
+pub async fn export_query(
+    state: State<'_, ExportState>,
+    connection_id: String,
+    query: String,
+    path: String,
+    options: ExportOptions,
+    export_id: Option<String>,
+    confirmation_token: Option<String>,
+) -> Result<ExportSummary, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    let export_uuid = export_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state
+        .export_query(uuid, &query, PathBuf::from(path), options, export_uuid, confirmation_token.as_deref())
+        .await
 }
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::application::ImportUseCase;
+use crate::domain::{DomainError, ImportOptions, ImportPreview, ImportSource, ImportSummary, ImportTableDto};
+use crate::infrastructure::read_import_source;
+
+pub type ImportState = Arc<ImportUseCase>;
+
+/// Filas de la muestra si no se indica otro límite
+const DEFAULT_PREVIEW_ROWS: usize = 50;
+
+/// Lee el fichero fuera del runtime async: un XLSX grande tarda en descomprimirse
+async fn read_source(path: String, options: &ImportOptions) -> Result<ImportSource, DomainError> {
+    let options = options.clone();
+    tokio::task::spawn_blocking(move || read_import_source(&PathBuf::from(path), &options))
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?
+}
+
+/// Columnas y primeras filas del fichero, para decidir el mapeo antes de importar
+#[tauri::command]
+pub async fn preview_import_file(
+    path: String,
+    options: ImportOptions,
+    limit: Option<usize>,
+) -> Result<ImportPreview, DomainError> {
+    let source = read_source(path, &options).await?;
+    Ok(source.preview(limit.unwrap_or(DEFAULT_PREVIEW_ROWS)))
+}
+
+/// Importa un CSV, JSON/NDJSON o XLSX en una tabla; el avance llega como evento `ImportProgress`
+#[tauri::command]
+pub async fn import_table_data(
+    state: State<'_, ImportState>,
+    dto: ImportTableDto,
+    path: String,
+    options: ImportOptions,
+) -> Result<ImportSummary, DomainError> {
+    let source = read_source(path, &options).await?;
+    state.import_rows(dto, source, options).await
+}
@@ -4,6 +4,8 @@ mod saved_query_commands;
 mod schema_commands;
 mod workspace_commands;
 mod export_commands;
+mod import_commands;
+mod dump_commands;
 pub mod app_commands;
 
 pub use connection_commands::*;
@@ -12,4 +14,6 @@ pub use saved_query_commands::*;
 pub use schema_commands::*;
 pub use workspace_commands::*;
 pub use export_commands::*;
+pub use import_commands::*;
+pub use dump_commands::*;
 pub use app_commands::*;
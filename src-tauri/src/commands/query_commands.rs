@@ -3,7 +3,11 @@ use uuid::Uuid;
 use std::sync::Arc;
 
 use crate::application::QueryUseCase;
-use crate::domain::{DomainError, ExecuteQueryDto, QueryHistoryEntry, QueryResult, StatementResult, TransactionResult};
+use crate::domain::{
+    detect_parameters, DomainError, DryRunStatement, ExecuteQueryDto, QueryHistoryEntry, QueryOptions, QueryParameter, QueryParams,
+    QueryPlan, QueryResult, QueryStreamSummary, RowDelete, RowUpdate, RunningQueryInfo, ScriptMode, ScriptRunSummary, ScriptStatement, StatementResult,
+    StreamOptions, TransactionOptions, TransactionResult, TransactionSession,
+};
 
 pub type QueryState = Arc<QueryUseCase>;
 
@@ -12,19 +16,10 @@ pub async fn execute_query(
     state: State<'_, QueryState>,
     connection_id: String,
     query: String,
-    page: Option<u32>,
-    page_size: Option<u32>,
+    options: Option<QueryOptions>,
 ) -> Result<QueryResult, DomainError> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
-    
-    let dto = ExecuteQueryDto {
-        connection_id: uuid,
-        query,
-        page,
-        page_size,
-    };
-    
-    state.execute_query(dto).await
+    state.execute_query(ExecuteQueryDto::new(uuid, query, options.unwrap_or_default())).await
 }
 
 #[tauri::command]
@@ -34,9 +29,38 @@ pub async fn insert_row(
     schema: Option<String>,
     table: String,
     values: std::collections::HashMap<String, serde_json::Value>,
+    confirmation_token: Option<String>,
 ) -> Result<QueryResult, DomainError> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
-    state.insert_row(uuid, schema, table, values).await
+    state.insert_row(uuid, schema, table, values, confirmation_token.as_deref()).await
+}
+
+/// Modifica filas por clave primaria; con `original` falla con RowConflict si otro las cambió
+#[tauri::command]
+pub async fn update_rows(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    schema: Option<String>,
+    table: String,
+    rows: Vec<RowUpdate>,
+    confirmation_token: Option<String>,
+) -> Result<TransactionResult, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.update_rows(uuid, schema, table, rows, confirmation_token.as_deref()).await
+}
+
+/// Borra filas por clave primaria, todas en una transacción
+#[tauri::command]
+pub async fn delete_rows(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    schema: Option<String>,
+    table: String,
+    rows: Vec<RowDelete>,
+    confirmation_token: Option<String>,
+) -> Result<TransactionResult, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.delete_rows(uuid, schema, table, rows, confirmation_token.as_deref()).await
 }
 
 #[tauri::command]
@@ -44,9 +68,75 @@ pub async fn execute_multi_statement(
     state: State<'_, QueryState>,
     connection_id: String,
     statements: Vec<String>,
+    query_id: Option<String>,
+    confirmation_token: Option<String>,
 ) -> Result<Vec<StatementResult>, DomainError> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
-    state.execute_multi_statement(uuid, statements).await
+    let query_uuid = query_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.execute_multi_statement(uuid, statements, query_uuid, confirmation_token.as_deref()).await
+}
+
+/// Simula las sentencias en una transacción que se deshace siempre, con muestras de las filas tocadas
+#[tauri::command]
+pub async fn dry_run_statements(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    statements: Vec<String>,
+    params: Option<QueryParams>,
+    query_id: Option<String>,
+    confirmation_token: Option<String>,
+) -> Result<Vec<DryRunStatement>, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    let query_uuid = query_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.dry_run(uuid, statements, params.as_ref(), query_uuid, confirmation_token.as_deref()).await
+}
+
+/// Sentencias del script según el dialecto de la conexión
+#[tauri::command]
+pub async fn split_sql_script(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    script: String,
+) -> Result<Vec<ScriptStatement>, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.split_script(uuid, &script).await
+}
+
+/// Ejecuta un script completo; cada sentencia emite un evento ScriptStatementCompleted
+#[tauri::command]
+pub async fn run_script(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    script: String,
+    mode: Option<ScriptMode>,
+    script_id: Option<String>,
+    confirmation_token: Option<String>,
+) -> Result<ScriptRunSummary, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    let script_uuid = script_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.run_script(uuid, &script, mode.unwrap_or_default(), script_uuid, confirmation_token.as_deref()).await
+}
+
+/// Plan de ejecución normalizado; con `analyze` la sentencia se ejecuta en una transacción que se deshace
+#[tauri::command]
+pub async fn explain_query(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    sql: String,
+    analyze: Option<bool>,
+    confirmation_token: Option<String>,
+) -> Result<QueryPlan, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.explain_query(uuid, &sql, analyze.unwrap_or(false), confirmation_token.as_deref()).await
 }
 
 #[tauri::command]
@@ -54,9 +144,67 @@ pub async fn execute_in_transaction(
     state: State<'_, QueryState>,
     connection_id: String,
     statement: String,
+    confirmation_token: Option<String>,
 ) -> Result<TransactionResult, DomainError> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
-    state.execute_in_transaction(uuid, &statement).await
+    state.execute_in_transaction(uuid, &statement, confirmation_token.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn begin_transaction(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    options: Option<TransactionOptions>,
+) -> Result<TransactionSession, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.begin_transaction(uuid, options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn commit_transaction(
+    state: State<'_, QueryState>,
+    connection_id: String,
+) -> Result<(), DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.commit_transaction(uuid).await
+}
+
+#[tauri::command]
+pub async fn rollback_transaction(
+    state: State<'_, QueryState>,
+    connection_id: String,
+) -> Result<(), DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.rollback_transaction(uuid).await
+}
+
+#[tauri::command]
+pub async fn create_savepoint(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    name: String,
+) -> Result<TransactionSession, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.create_savepoint(uuid, &name).await
+}
+
+#[tauri::command]
+pub async fn rollback_to_savepoint(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    name: String,
+) -> Result<TransactionSession, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.rollback_to_savepoint(uuid, &name).await
+}
+
+#[tauri::command]
+pub async fn get_transaction_status(
+    state: State<'_, QueryState>,
+    connection_id: String,
+) -> Result<Option<TransactionSession>, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    Ok(state.get_transaction(uuid).await)
 }
 
 #[tauri::command]
@@ -64,9 +212,22 @@ pub async fn execute_statement(
     state: State<'_, QueryState>,
     connection_id: String,
     statement: String,
+    query_id: Option<String>,
+    params: Option<QueryParams>,
+    confirmation_token: Option<String>,
 ) -> Result<u64, DomainError> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
-    state.execute_statement(uuid, &statement).await
+    let query_uuid = query_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.execute_statement(uuid, &statement, params.as_ref(), query_uuid, confirmation_token.as_deref()).await
+}
+
+/// Parámetros que necesita la query, para pedir sus valores antes de ejecutarla
+#[tauri::command]
+pub async fn detect_query_parameters(query: String) -> Result<Vec<QueryParameter>, DomainError> {
+    detect_parameters(&query)
 }
 
 #[tauri::command]
@@ -88,11 +249,62 @@ pub async fn search_query_history(
     state.search_history(&query, limit.unwrap_or(50)).await
 }
 
+#[tauri::command]
+pub async fn execute_query_stream(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    query: String,
+    query_id: Option<String>,
+    options: Option<StreamOptions>,
+    confirmation_token: Option<String>,
+) -> Result<QueryStreamSummary, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    let query_uuid = query_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.execute_query_stream(uuid, &query, query_uuid, options.unwrap_or_default(), confirmation_token.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn ack_query_stream_batch(
+    state: State<'_, QueryState>,
+    query_id: String,
+) -> Result<(), DomainError> {
+    let uuid = Uuid::parse_str(&query_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.ack_stream_batch(uuid).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn close_query_cursor(
+    state: State<'_, QueryState>,
+    connection_id: String,
+    cursor: String,
+) -> Result<(), DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.close_query_cursor(uuid, &cursor).await
+}
+
 #[tauri::command]
 pub async fn cancel_query(
     state: State<'_, QueryState>,
     connection_id: String,
+    query_id: Option<String>,
 ) -> Result<(), DomainError> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
-    state.cancel_query(uuid).await
+    let query_uuid = query_id
+        .map(|id| Uuid::parse_str(&id))
+        .transpose()
+        .map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.cancel_query(uuid, query_uuid).await
+}
+
+#[tauri::command]
+pub async fn list_running_queries(
+    state: State<'_, QueryState>,
+    connection_id: String,
+) -> Result<Vec<RunningQueryInfo>, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    Ok(state.list_running_queries(uuid).await)
 }
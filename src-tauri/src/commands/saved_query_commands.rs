@@ -1,5 +1,6 @@
 use crate::application::use_cases::SavedQueryUseCase;
-use crate::domain::entities::{CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
+use crate::domain::entities::{CellValue, CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
 use uuid::Uuid;
@@ -49,6 +50,19 @@ pub async fn update_saved_query(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn record_saved_query_parameters(
+    id: String,
+    values: HashMap<String, CellValue>,
+    use_case: State<'_, Arc<SavedQueryUseCase>>,
+) -> Result<SavedQuery, String> {
+    let query_id = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+    use_case
+        .record_parameter_values(query_id, values)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn delete_saved_query(
     id: String,
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use crate::application::SchemaUseCase;
 use crate::domain::{
-    ColumnSchema, ConstraintInfo, DatabaseInfo, DomainError, FunctionInfo, IndexInfo,
+    ColumnSchema, ConstraintInfo, DatabaseInfo, DdlObject, DdlOptions, DomainError, FunctionInfo, IndexInfo,
     SchemaInfo, SequenceInfo, TableInfo, TriggerInfo, ViewInfo,
 };
 
@@ -134,6 +134,17 @@ pub async fn list_sequences(
     state.list_sequences(uuid, schema.as_deref()).await
 }
 
+#[tauri::command]
+pub async fn get_object_ddl(
+    state: State<'_, SchemaState>,
+    connection_id: String,
+    object: DdlObject,
+    options: Option<DdlOptions>,
+) -> Result<String, DomainError> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|_| DomainError::validation("Invalid UUID"))?;
+    state.get_object_ddl(uuid, &object, &options.unwrap_or_default()).await
+}
+
 #[tauri::command]
 pub async fn get_server_version(
     state: State<'_, SchemaState>,
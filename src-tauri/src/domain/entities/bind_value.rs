@@ -0,0 +1,572 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{CellValue, ColumnSchema, SqlDialect};
+use crate::domain::error::DomainError;
+
+/// Familia de tipo de una columna, que decide a qué se convierte el valor antes de enlazarlo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnKind {
+    Bool,
+    SmallInt,
+    Int,
+    BigInt,
+    Real,
+    Double,
+    /// Numérico exacto: viaja como texto para no perder precisión
+    Decimal,
+    Text,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Uuid,
+    Json,
+    Bytes,
+    Array(Box<ColumnKind>),
+    /// Tipo propio del motor (enums, geometrías...): el valor se enlaza tal como llega
+    Other,
+}
+
+/// Valor convertido al tipo de su columna, listo para enlazarlo en cualquier motor
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindValue {
+    Null,
+    Bool(bool),
+    SmallInt(i16),
+    Int(i32),
+    BigInt(i64),
+    Real(f32),
+    Double(f64),
+    Decimal(String),
+    Text(String),
+    Date(NaiveDate),
+    Time(NaiveTime),
+    Timestamp(NaiveDateTime),
+    TimestampTz(DateTime<FixedOffset>),
+    Uuid(Uuid),
+    Json(Value),
+    Bytes(Vec<u8>),
+    Array(Vec<BindValue>),
+}
+
+impl ColumnKind {
+    /// PostgreSQL y MySQL describen el tipo exacto en `native_type` (udt_name y COLUMN_TYPE)
+    pub fn of(column: &ColumnSchema, dialect: SqlDialect) -> Self {
+        match dialect {
+            SqlDialect::Postgres | SqlDialect::MySql if !column.native_type.is_empty() => {
+                Self::parse(&column.native_type, dialect)
+            }
+            _ => Self::parse(&column.data_type, dialect),
+        }
+    }
+
+    pub fn parse(type_name: &str, dialect: SqlDialect) -> Self {
+        let name = type_name.trim().to_lowercase();
+        if let Some(element) = name.strip_suffix("[]") {
+            return Self::Array(Box::new(Self::parse(element, dialect)));
+        }
+        if let Some(element) = name.strip_prefix('_').filter(|_| dialect == SqlDialect::Postgres) {
+            return Self::Array(Box::new(Self::parse(element, dialect)));
+        }
+        if let Some(inner) = name.strip_prefix("nullable(").and_then(|n| n.strip_suffix(')')) {
+            return Self::parse(inner, dialect);
+        }
+        if let Some(element) = name.strip_prefix("array(").and_then(|n| n.strip_suffix(')')) {
+            return Self::Array(Box::new(Self::parse(element, dialect)));
+        }
+
+        let unsigned = name.contains("unsigned");
+        let base = name.split(['(', ' ']).next().unwrap_or_default();
+        let kind = match base {
+            // MySQL guarda los booleanos como tinyint(1)
+            "tinyint" if name.starts_with("tinyint(1)") => Self::Bool,
+            "bit" if !name.starts_with("bit(") || name.starts_with("bit(1)") => Self::Bool,
+            "bool" | "boolean" => Self::Bool,
+            // En ClickHouse Int8 es de un byte, no el bigint de PostgreSQL
+            "int8" | "uint8" | "int16" if dialect == SqlDialect::ClickHouse => Self::SmallInt,
+            "smallint" | "tinyint" | "int2" | "smallserial" | "year" if unsigned => Self::Int,
+            "smallint" | "tinyint" | "int2" | "smallserial" | "year" => Self::SmallInt,
+            "int" | "integer" | "int4" | "mediumint" | "serial" | "int32" if unsigned => Self::BigInt,
+            "int" | "integer" | "int4" | "mediumint" | "serial" | "int32" => Self::Int,
+            "bigint" | "int8" | "bigserial" | "int64" | "hugeint" if unsigned => Self::Decimal,
+            "bigint" | "int8" | "bigserial" | "int64" => Self::BigInt,
+            "float" if dialect == SqlDialect::MySql => Self::Real,
+            "real" | "float4" | "float32" if dialect != SqlDialect::Sqlite => Self::Real,
+            "real" | "float4" | "float32" | "float" | "float8" | "float64" | "double" => Self::Double,
+            "numeric" | "decimal" | "dec" | "fixed" | "number" | "hugeint" | "uint64" | "int128" => Self::Decimal,
+            "char" | "varchar" | "character" | "bpchar" | "nchar" | "nvarchar" | "text" | "tinytext"
+            | "mediumtext" | "longtext" | "ntext" | "clob" | "string" | "name" | "citext" | "fixedstring" => Self::Text,
+            "date" | "date32" => Self::Date,
+            "time" if !name.contains("with time zone") => Self::Time,
+            "timestamp" if name.contains("with time zone") => Self::TimestampTz,
+            "timestamptz" | "datetimeoffset" => Self::TimestampTz,
+            "timestamp" | "datetime" | "datetime2" | "smalldatetime" | "datetime64" => Self::Timestamp,
+            "uuid" | "uniqueidentifier" => Self::Uuid,
+            "json" | "jsonb" => Self::Json,
+            "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" | "image" => Self::Bytes,
+            _ => Self::Other,
+        };
+        match (dialect, kind) {
+            // SQLite guarda cualquier entero en 64 bits, sin importar cómo se declaró
+            (SqlDialect::Sqlite, Self::SmallInt | Self::Int) => Self::BigInt,
+            // PostgreSQL convierte desde texto sus tipos propios con el cast del marcador
+            (SqlDialect::Postgres, Self::Other) => Self::Text,
+            (_, kind) => kind,
+        }
+    }
+
+    /// Convierte un valor del editor; el error explica por qué no encaja en la columna
+    pub fn convert_json(&self, value: &Value) -> Result<BindValue, String> {
+        match value {
+            Value::Null => return Ok(BindValue::Null),
+            // Una celda vacía en una columna que no es de texto es NULL
+            Value::String(s) if s.is_empty() && !matches!(self, Self::Text | Self::Other) => {
+                return Ok(BindValue::Null)
+            }
+            _ => {}
+        }
+        match self {
+            Self::Bool => match value {
+                Value::Bool(b) => Ok(BindValue::Bool(*b)),
+                Value::Number(n) if n.as_i64() == Some(0) => Ok(BindValue::Bool(false)),
+                Value::Number(n) if n.as_i64() == Some(1) => Ok(BindValue::Bool(true)),
+                Value::String(s) => parse_bool(s).map(BindValue::Bool),
+                other => Err(format!("{} is not a boolean", other)),
+            },
+            Self::SmallInt => integer(value).and_then(|i| {
+                i16::try_from(i).map(BindValue::SmallInt).map_err(|_| format!("{} is out of range for a small integer", i))
+            }),
+            Self::Int => integer(value).and_then(|i| {
+                i32::try_from(i).map(BindValue::Int).map_err(|_| format!("{} is out of range for an integer", i))
+            }),
+            Self::BigInt => integer(value).map(BindValue::BigInt),
+            Self::Real => float(value).and_then(|f| {
+                if f.is_finite() && f.abs() > f32::MAX as f64 {
+                    Err(format!("{} is out of range for a real", f))
+                } else {
+                    Ok(BindValue::Real(f as f32))
+                }
+            }),
+            Self::Double => float(value).map(BindValue::Double),
+            Self::Decimal => match value {
+                Value::Number(n) => Ok(BindValue::Decimal(n.to_string())),
+                Value::String(s) if is_decimal(s.trim()) => Ok(BindValue::Decimal(s.trim().to_string())),
+                other => Err(format!("{} is not a number", other)),
+            },
+            Self::Text => Ok(BindValue::Text(text(value))),
+            Self::Date => NaiveDate::parse_from_str(string(value)?.trim(), "%Y-%m-%d")
+                .map(BindValue::Date)
+                .map_err(|_| format!("{} is not a date (YYYY-MM-DD)", value)),
+            Self::Time => parse_time(string(value)?.trim())
+                .map(BindValue::Time)
+                .ok_or_else(|| format!("{} is not a time (HH:MM:SS)", value)),
+            Self::Timestamp => {
+                let s = string(value)?.trim();
+                parse_timestamp(s)
+                    .or_else(|| parse_timestamp_tz(s).map(|dt| dt.naive_local()))
+                    .map(BindValue::Timestamp)
+                    .ok_or_else(|| format!("{} is not a timestamp (YYYY-MM-DD HH:MM:SS)", value))
+            }
+            // Sin zona, el servidor la interpreta en la de la sesión
+            Self::TimestampTz => {
+                let s = string(value)?.trim();
+                parse_timestamp_tz(s)
+                    .map(BindValue::TimestampTz)
+                    .or_else(|| parse_timestamp(s).map(BindValue::Timestamp))
+                    .ok_or_else(|| format!("{} is not a timestamp with time zone", value))
+            }
+            Self::Uuid => Uuid::parse_str(string(value)?.trim())
+                .map(BindValue::Uuid)
+                .map_err(|_| format!("{} is not a UUID", value)),
+            Self::Json => match value {
+                Value::String(s) => serde_json::from_str(s)
+                    .map(BindValue::Json)
+                    .map_err(|e| format!("not valid JSON ({})", e)),
+                other => Ok(BindValue::Json(other.clone())),
+            },
+            Self::Bytes => match value {
+                Value::String(s) => Ok(BindValue::Bytes(parse_bytes(s)?)),
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()).ok_or_else(|| format!("{} is not a byte", item)))
+                    .collect::<Result<Vec<u8>, String>>()
+                    .map(BindValue::Bytes),
+                other => Err(format!("{} is not binary data", other)),
+            },
+            Self::Array(element) => {
+                let parsed;
+                let items = match value {
+                    Value::Array(items) => items,
+                    Value::String(s) => match serde_json::from_str(s) {
+                        Ok(Value::Array(items)) => {
+                            parsed = items;
+                            &parsed
+                        }
+                        _ => return Err(format!("{} is not a JSON array", value)),
+                    },
+                    other => return Err(format!("{} is not an array", other)),
+                };
+                items
+                    .iter()
+                    .map(|item| element.convert_json(item))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(BindValue::Array)
+            }
+            Self::Other => Ok(match value {
+                Value::Bool(b) => BindValue::Bool(*b),
+                Value::Number(n) => match n.as_i64() {
+                    Some(i) => BindValue::BigInt(i),
+                    None => BindValue::Double(n.as_f64().unwrap_or_default()),
+                },
+                other => BindValue::Text(text(other)),
+            }),
+        }
+    }
+
+    /// Como `convert_json`, conservando los binarios y arrays ya tipados
+    pub fn convert_cell(&self, value: &CellValue) -> Result<BindValue, String> {
+        match (self, value) {
+            (Self::Bytes | Self::Other, CellValue::Bytes(bytes)) => Ok(BindValue::Bytes(bytes.clone())),
+            (_, CellValue::Bytes(_)) => Err("binary data only fits binary columns".to_string()),
+            (Self::Array(element), CellValue::Array(items)) => items
+                .iter()
+                .map(|item| element.convert_cell(item))
+                .collect::<Result<Vec<_>, _>>()
+                .map(BindValue::Array),
+            (_, CellValue::Json(json)) => self.convert_json(json),
+            (_, other) => self.convert_json(&cell_json(other)),
+        }
+    }
+}
+
+impl BindValue {
+    /// Convierte el valor del editor para `column`; si no encaja, el error indica la columna
+    pub fn from_json(value: &Value, column: &ColumnSchema, dialect: SqlDialect) -> Result<Self, DomainError> {
+        ColumnKind::of(column, dialect)
+            .convert_json(value)
+            .map_err(|reason| Self::invalid(column, reason))
+    }
+
+    pub fn from_cell(value: &CellValue, column: &ColumnSchema, dialect: SqlDialect) -> Result<Self, DomainError> {
+        ColumnKind::of(column, dialect)
+            .convert_cell(value)
+            .map_err(|reason| Self::invalid(column, reason))
+    }
+
+    fn invalid(column: &ColumnSchema, reason: String) -> DomainError {
+        DomainError::invalid_column(
+            &column.name,
+            format!("Invalid value for column {} ({}): {}", column.name, column.data_type, reason),
+        )
+    }
+
+    /// Texto canónico del valor, que cualquier motor sabe convertir a su tipo
+    pub fn as_text(&self) -> Option<String> {
+        Some(match self {
+            BindValue::Null => return None,
+            BindValue::Bool(b) => b.to_string(),
+            BindValue::SmallInt(i) => i.to_string(),
+            BindValue::Int(i) => i.to_string(),
+            BindValue::BigInt(i) => i.to_string(),
+            BindValue::Real(f) => f.to_string(),
+            BindValue::Double(f) => f.to_string(),
+            BindValue::Decimal(s) | BindValue::Text(s) => s.clone(),
+            BindValue::Date(d) => d.format("%Y-%m-%d").to_string(),
+            BindValue::Time(t) => t.format("%H:%M:%S%.f").to_string(),
+            BindValue::Timestamp(dt) => dt.format("%Y-%m-%d %H:%M:%S%.f").to_string(),
+            BindValue::TimestampTz(dt) => dt.to_rfc3339(),
+            BindValue::Uuid(u) => u.to_string(),
+            BindValue::Json(v) => v.to_string(),
+            BindValue::Bytes(bytes) => format!("\\x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+            BindValue::Array(_) => self.to_json().to_string(),
+        })
+    }
+
+    /// Representación JSON, para los motores que guardan los arrays como JSON
+    pub fn to_json(&self) -> Value {
+        match self {
+            BindValue::Null => Value::Null,
+            BindValue::Bool(b) => Value::Bool(*b),
+            BindValue::SmallInt(i) => Value::from(*i),
+            BindValue::Int(i) => Value::from(*i),
+            BindValue::BigInt(i) => Value::from(*i),
+            BindValue::Real(f) => Value::from(*f),
+            BindValue::Double(f) => Value::from(*f),
+            BindValue::Decimal(s) => s.parse::<serde_json::Number>().map(Value::Number).unwrap_or_else(|_| Value::String(s.clone())),
+            BindValue::Json(v) => v.clone(),
+            BindValue::Array(items) => Value::Array(items.iter().map(BindValue::to_json).collect()),
+            other => other.as_text().map(Value::String).unwrap_or(Value::Null),
+        }
+    }
+
+    /// Vuelta a `CellValue`, para los caminos que enlazan valores de celda
+    pub fn to_cell(&self) -> CellValue {
+        match self {
+            BindValue::Null => CellValue::Null,
+            BindValue::Bool(b) => CellValue::Bool(*b),
+            BindValue::SmallInt(i) => CellValue::Int(*i as i64),
+            BindValue::Int(i) => CellValue::Int(*i as i64),
+            BindValue::BigInt(i) => CellValue::Int(*i),
+            BindValue::Real(f) => CellValue::Float(*f as f64),
+            BindValue::Double(f) => CellValue::Float(*f),
+            BindValue::Decimal(s) | BindValue::Text(s) => CellValue::String(s.clone()),
+            BindValue::Date(_) => CellValue::Date(self.as_text().unwrap_or_default()),
+            BindValue::Time(_) => CellValue::Time(self.as_text().unwrap_or_default()),
+            BindValue::Timestamp(_) | BindValue::TimestampTz(_) => CellValue::DateTime(self.as_text().unwrap_or_default()),
+            BindValue::Uuid(u) => CellValue::Uuid(u.to_string()),
+            BindValue::Json(v) => CellValue::Json(v.clone()),
+            BindValue::Bytes(bytes) => CellValue::Bytes(bytes.clone()),
+            BindValue::Array(items) => CellValue::Array(items.iter().map(BindValue::to_cell).collect()),
+        }
+    }
+}
+
+fn cell_json(value: &CellValue) -> Value {
+    match value {
+        CellValue::Null => Value::Null,
+        CellValue::Bool(b) => Value::Bool(*b),
+        CellValue::Int(i) => Value::from(*i),
+        CellValue::Float(f) => Value::from(*f),
+        CellValue::Json(v) => v.clone(),
+        CellValue::Array(items) => Value::Array(items.iter().map(cell_json).collect()),
+        CellValue::Bytes(bytes) => Value::from(bytes.clone()),
+        CellValue::String(s)
+        | CellValue::Date(s)
+        | CellValue::Time(s)
+        | CellValue::DateTime(s)
+        | CellValue::Uuid(s) => Value::String(s.clone()),
+    }
+}
+
+fn string(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| format!("{} is not text", value))
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_bool(s: &str) -> Result<bool, String> {
+    match s.trim().to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "on" | "1" => Ok(true),
+        "false" | "f" | "no" | "n" | "off" | "0" => Ok(false),
+        _ => Err(format!("'{}' is not a boolean", s)),
+    }
+}
+
+fn integer(value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Number(n) => n
+            .as_i64()
+            .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64).map(|f| f as i64))
+            .ok_or_else(|| format!("{} is not an integer in range", n)),
+        Value::String(s) => s.trim().parse::<i64>().map_err(|_| format!("'{}' is not an integer in range", s)),
+        // MySQL 8 ya no informa tinyint(1), así que sus booleanos llegan como enteros
+        Value::Bool(b) => Ok(*b as i64),
+        other => Err(format!("{} is not an integer", other)),
+    }
+}
+
+fn float(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(n) => n.as_f64().ok_or_else(|| format!("{} is not a number", n)),
+        Value::String(s) => s.trim().parse::<f64>().map_err(|_| format!("'{}' is not a number", s)),
+        other => Err(format!("{} is not a number", other)),
+    }
+}
+
+/// `[+-]dígitos[.dígitos][e[+-]dígitos]`, lo que aceptan todos los motores como numérico exacto
+fn is_decimal(s: &str) -> bool {
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(at) => (&unsigned[..at], Some(&unsigned[at + 1..])),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let exponent_ok = match exponent {
+        Some(e) => {
+            let digits = e.strip_prefix(['+', '-']).unwrap_or(e);
+            !digits.is_empty() && all_digits(digits)
+        }
+        None => true,
+    };
+    !(whole.is_empty() && fraction.is_empty()) && all_digits(whole) && all_digits(fraction) && exponent_ok
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+}
+
+fn parse_timestamp(s: &str) -> Option<NaiveDateTime> {
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().map(|d| d.and_time(NaiveTime::MIN)))
+}
+
+/// RFC 3339 o el formato de salida de PostgreSQL (`2024-01-31 10:00:00+01`)
+fn parse_timestamp_tz(s: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(&s.replacen(' ', "T", 1))
+        .ok()
+        .or_else(|| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%#z").ok())
+}
+
+/// Hexadecimal con prefijo `\x` o `0x`; cualquier otro texto se guarda como sus bytes UTF-8
+fn parse_bytes(s: &str) -> Result<Vec<u8>, String> {
+    let Some(hex) = s.strip_prefix("\\x").or_else(|| s.strip_prefix("0x")) else {
+        return Ok(s.as_bytes().to_vec());
+    };
+    if hex.len() % 2 != 0 {
+        return Err(format!("'{}' has an odd number of hex digits", s));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|at| u8::from_str_radix(&hex[at..at + 2], 16).map_err(|_| format!("'{}' is not valid hex", s)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn column(data_type: &str, native_type: &str) -> ColumnSchema {
+        ColumnSchema {
+            name: "value".to_string(),
+            data_type: data_type.to_string(),
+            native_type: native_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_unique: false,
+            is_auto_increment: false,
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            comment: None,
+            ordinal_position: 1,
+        }
+    }
+
+    #[test]
+    fn parses_type_names_per_dialect() {
+        use ColumnKind::*;
+        assert_eq!(ColumnKind::parse("tinyint(1)", SqlDialect::MySql), Bool);
+        assert_eq!(ColumnKind::parse("int unsigned", SqlDialect::MySql), BigInt);
+        assert_eq!(ColumnKind::parse("bigint unsigned", SqlDialect::MySql), Decimal);
+        assert_eq!(ColumnKind::parse("float", SqlDialect::MySql), Real);
+        assert_eq!(ColumnKind::parse("float", SqlDialect::Postgres), Double);
+        assert_eq!(ColumnKind::parse("Int8", SqlDialect::ClickHouse), SmallInt);
+        assert_eq!(ColumnKind::parse("int8", SqlDialect::Postgres), BigInt);
+        assert_eq!(ColumnKind::parse("INTEGER", SqlDialect::Sqlite), BigInt);
+        assert_eq!(ColumnKind::parse("real", SqlDialect::Sqlite), Double);
+        assert_eq!(ColumnKind::parse("timestamp with time zone", SqlDialect::Postgres), TimestampTz);
+        assert_eq!(ColumnKind::parse("varchar(20)", SqlDialect::SqlServer), Text);
+        assert_eq!(ColumnKind::parse("geometry", SqlDialect::MySql), Other);
+        // Los tipos propios de PostgreSQL se enlazan como texto y los convierte el cast
+        assert_eq!(ColumnKind::parse("mood", SqlDialect::Postgres), Text);
+    }
+
+    #[test]
+    fn parses_array_types() {
+        let ints = ColumnKind::Array(Box::new(ColumnKind::Int));
+        assert_eq!(ColumnKind::parse("_int4", SqlDialect::Postgres), ints);
+        assert_eq!(ColumnKind::parse("integer[]", SqlDialect::DuckDb), ints);
+        assert_eq!(ColumnKind::parse("Array(Nullable(Int32))", SqlDialect::ClickHouse), ints);
+        // Solo PostgreSQL nombra los arrays con guion bajo
+        assert_eq!(ColumnKind::parse("_int4", SqlDialect::MySql), ColumnKind::Other);
+    }
+
+    #[test]
+    fn prefers_the_native_type_where_it_is_exact() {
+        let bool_column = column("tinyint", "tinyint(1)");
+        assert_eq!(ColumnKind::of(&bool_column, SqlDialect::MySql), ColumnKind::Bool);
+        assert_eq!(ColumnKind::of(&bool_column, SqlDialect::Sqlite), ColumnKind::BigInt);
+        assert_eq!(ColumnKind::of(&column("ARRAY", "_text"), SqlDialect::Postgres), ColumnKind::Array(Box::new(ColumnKind::Text)));
+    }
+
+    #[test]
+    fn converts_editor_values() {
+        use ColumnKind::*;
+        assert_eq!(Bool.convert_json(&json!("yes")), Ok(BindValue::Bool(true)));
+        assert_eq!(Bool.convert_json(&json!(0)), Ok(BindValue::Bool(false)));
+        assert_eq!(SmallInt.convert_json(&json!(" 12 ")), Ok(BindValue::SmallInt(12)));
+        assert_eq!(BigInt.convert_json(&json!(3.0)), Ok(BindValue::BigInt(3)));
+        assert_eq!(Int.convert_json(&json!(true)), Ok(BindValue::Int(1)));
+        assert_eq!(Decimal.convert_json(&json!("-12.50e3")), Ok(BindValue::Decimal("-12.50e3".to_string())));
+        assert_eq!(Text.convert_json(&json!(42)), Ok(BindValue::Text("42".to_string())));
+        assert_eq!(Date.convert_json(&json!("2024-02-29")), Ok(BindValue::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())));
+        assert_eq!(Json.convert_json(&json!("{\"a\": 1}")), Ok(BindValue::Json(json!({"a": 1}))));
+        assert_eq!(Bytes.convert_json(&json!("\\x00ff")), Ok(BindValue::Bytes(vec![0, 255])));
+        assert_eq!(Bytes.convert_json(&json!([1, 2])), Ok(BindValue::Bytes(vec![1, 2])));
+        assert_eq!(
+            Array(Box::new(Int)).convert_json(&json!("[1, \"2\"]")),
+            Ok(BindValue::Array(vec![BindValue::Int(1), BindValue::Int(2)]))
+        );
+    }
+
+    #[test]
+    fn treats_empty_cells_as_null_except_in_text() {
+        assert_eq!(ColumnKind::Int.convert_json(&json!("")), Ok(BindValue::Null));
+        assert_eq!(ColumnKind::Date.convert_json(&json!("")), Ok(BindValue::Null));
+        assert_eq!(ColumnKind::Text.convert_json(&json!("")), Ok(BindValue::Text(String::new())));
+        assert_eq!(ColumnKind::Bool.convert_json(&Value::Null), Ok(BindValue::Null));
+    }
+
+    #[test]
+    fn rejects_values_that_do_not_fit() {
+        assert!(ColumnKind::SmallInt.convert_json(&json!(40000)).is_err());
+        assert!(ColumnKind::Int.convert_json(&json!(1.5)).is_err());
+        assert!(ColumnKind::Real.convert_json(&json!(1e300)).is_err());
+        assert!(ColumnKind::Decimal.convert_json(&json!("1.2.3")).is_err());
+        assert!(ColumnKind::Decimal.convert_json(&json!(".")).is_err());
+        assert!(ColumnKind::Date.convert_json(&json!("31/01/2024")).is_err());
+        assert!(ColumnKind::Uuid.convert_json(&json!("not-a-uuid")).is_err());
+        assert!(ColumnKind::Bytes.convert_json(&json!("\\xabc")).is_err());
+        assert!(ColumnKind::Array(Box::new(ColumnKind::Int)).convert_json(&json!("1,2")).is_err());
+
+        let error = BindValue::from_json(&json!("abc"), &column("integer", "int4"), SqlDialect::Postgres).unwrap_err();
+        assert!(error.to_string().contains("Invalid value for column value (integer)"));
+    }
+
+    #[test]
+    fn parses_timestamps_with_and_without_zone() {
+        let naive = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap().and_hms_opt(10, 0, 0).unwrap();
+        assert_eq!(ColumnKind::Timestamp.convert_json(&json!("2024-01-31T10:00")), Ok(BindValue::Timestamp(naive)));
+        assert_eq!(ColumnKind::Timestamp.convert_json(&json!("2024-01-31 10:00:00+01")), Ok(BindValue::Timestamp(naive)));
+        let zoned = ColumnKind::TimestampTz.convert_json(&json!("2024-01-31 10:00:00+01")).unwrap();
+        assert_eq!(zoned.as_text().as_deref(), Some("2024-01-31T10:00:00+01:00"));
+        // Sin zona queda para que la resuelva el servidor
+        assert_eq!(ColumnKind::TimestampTz.convert_json(&json!("2024-01-31 10:00")), Ok(BindValue::Timestamp(naive)));
+    }
+
+    #[test]
+    fn keeps_typed_cells() {
+        assert_eq!(ColumnKind::Bytes.convert_cell(&CellValue::Bytes(vec![7])), Ok(BindValue::Bytes(vec![7])));
+        assert!(ColumnKind::Text.convert_cell(&CellValue::Bytes(vec![7])).is_err());
+        assert_eq!(ColumnKind::Double.convert_cell(&CellValue::Float(1.5)), Ok(BindValue::Double(1.5)));
+        assert_eq!(
+            ColumnKind::Array(Box::new(ColumnKind::Text)).convert_cell(&CellValue::Array(vec![CellValue::String("a".to_string())])),
+            Ok(BindValue::Array(vec![BindValue::Text("a".to_string())]))
+        );
+    }
+
+    #[test]
+    fn renders_canonical_text_and_json() {
+        assert_eq!(BindValue::Bytes(vec![0, 171]).as_text().as_deref(), Some("\\x00ab"));
+        assert_eq!(BindValue::Null.as_text(), None);
+        assert_eq!(BindValue::Decimal("1.50".to_string()).to_json(), json!(1.50));
+        assert_eq!(BindValue::Decimal("NaN".to_string()).to_json(), json!("NaN"));
+        let array = BindValue::Array(vec![BindValue::Int(1), BindValue::Null]);
+        assert_eq!(array.as_text().as_deref(), Some("[1,null]"));
+        assert!(matches!(array.to_cell(), CellValue::Array(items) if matches!(items[..], [CellValue::Int(1), CellValue::Null])));
+    }
+}
@@ -8,15 +8,22 @@ use uuid::Uuid;
 pub enum DatabaseEngine {
     PostgreSQL,
     MySQL,
+    MariaDB,
     SQLite,
+    SqlServer,
+    DuckDB,
+    ClickHouse,
 }
 
 impl DatabaseEngine {
     pub fn default_port(&self) -> Option<u16> {
         match self {
             DatabaseEngine::PostgreSQL => Some(5432),
-            DatabaseEngine::MySQL => Some(3306),
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB => Some(3306),
             DatabaseEngine::SQLite => None,
+            DatabaseEngine::SqlServer => Some(1433),
+            DatabaseEngine::DuckDB => None,
+            DatabaseEngine::ClickHouse => Some(8123),
         }
     }
 
@@ -24,9 +31,34 @@ impl DatabaseEngine {
         match self {
             DatabaseEngine::PostgreSQL => "PostgreSQL",
             DatabaseEngine::MySQL => "MySQL",
+            DatabaseEngine::MariaDB => "MariaDB",
             DatabaseEngine::SQLite => "SQLite",
+            DatabaseEngine::SqlServer => "SQL Server",
+            DatabaseEngine::DuckDB => "DuckDB",
+            DatabaseEngine::ClickHouse => "ClickHouse",
         }
     }
+
+    /// Motores que abren un fichero local en vez de conectar a un servidor
+    pub fn is_file_based(&self) -> bool {
+        matches!(self, DatabaseEngine::SQLite | DatabaseEngine::DuckDB)
+    }
+}
+
+/// Nivel de TLS exigido al servidor
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    /// Sin TLS
+    Disable,
+    /// TLS si el servidor lo ofrece, sin verificar el certificado
+    Prefer,
+    /// TLS obligatorio, sin verificar el certificado
+    Require,
+    /// TLS obligatorio y certificado firmado por la CA
+    VerifyCa,
+    /// Como VerifyCa y además el nombre del host debe coincidir
+    VerifyFull,
 }
 
 /// Configuración SSL para conexiones
@@ -37,6 +69,36 @@ pub struct SslConfig {
     pub client_cert_path: Option<String>,
     pub client_key_path: Option<String>,
     pub verify_server_cert: bool,
+    /// Modo explícito; si falta se deduce de `enabled` y `verify_server_cert`
+    #[serde(default)]
+    pub mode: Option<SslMode>,
+}
+
+impl SslConfig {
+    /// Modo efectivo de la conexión.
+    /// Sin SSL habilitado se mantiene el comportamiento de siempre (TLS si el servidor lo ofrece)
+    pub fn effective_mode(&self) -> SslMode {
+        match self.mode {
+            Some(mode) => mode,
+            None if !self.enabled => SslMode::Prefer,
+            None if self.verify_server_cert => SslMode::VerifyFull,
+            None => SslMode::Require,
+        }
+    }
+}
+
+/// Estado TLS negociado en una conexión activa
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TlsStatus {
+    pub enabled: bool,
+    pub version: Option<String>,
+    pub cipher: Option<String>,
+}
+
+impl TlsStatus {
+    pub fn plain() -> Self {
+        Self { enabled: false, version: None, cipher: None }
+    }
 }
 
 /// Configuración SSH tunnel
@@ -46,6 +108,12 @@ pub struct SshTunnelConfig {
     pub port: u16,
     pub username: String,
     pub auth_method: SshAuthMethod,
+    /// Fichero known_hosts; por defecto ~/.ssh/known_hosts
+    #[serde(default)]
+    pub known_hosts_path: Option<String>,
+    /// Aceptar y registrar la clave del host si aún no está en known_hosts
+    #[serde(default)]
+    pub accept_unknown_host: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +141,12 @@ pub struct Connection {
     pub ssh_tunnel: Option<SshTunnelConfig>,
     pub color: Option<String>,
     pub read_only: bool,
+    /// Límite por defecto de cada ejecución, en ms; None sin límite
+    #[serde(default)]
+    pub statement_timeout_ms: Option<u64>,
+    /// Conexión de producción: cualquier escritura pide confirmación
+    #[serde(default)]
+    pub production: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_connected_at: Option<DateTime<Utc>>,
@@ -94,6 +168,8 @@ impl Connection {
             ssh_tunnel: None,
             color: None,
             read_only: false,
+            statement_timeout_ms: None,
+            production: false,
             created_at: now,
             updated_at: now,
             last_connected_at: None,
@@ -133,7 +209,7 @@ impl Connection {
                     db
                 )
             }
-            DatabaseEngine::MySQL => {
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB => {
                 // MySQL puede conectar sin database específica
                 let base = format!(
                     "mysql://{}@{}:{}",
@@ -154,6 +230,39 @@ impl Connection {
                     .unwrap_or("database.db");
                 format!("sqlite://{}", path)
             }
+            DatabaseEngine::DuckDB => {
+                let path = self.file_path.as_ref()
+                    .or(self.database.as_ref())
+                    .map(|s| s.as_str())
+                    .unwrap_or(":memory:");
+                format!("duckdb://{}", path)
+            }
+            DatabaseEngine::SqlServer => {
+                let base = format!(
+                    "sqlserver://{}@{}:{}",
+                    self.username.as_deref().unwrap_or("sa"),
+                    self.host.as_deref().unwrap_or("localhost"),
+                    self.port.unwrap_or(1433)
+                );
+                if let Some(db) = &self.database {
+                    format!("{}/{}", base, db)
+                } else {
+                    base
+                }
+            }
+            DatabaseEngine::ClickHouse => {
+                let base = format!(
+                    "clickhouse://{}@{}:{}",
+                    self.username.as_deref().unwrap_or("default"),
+                    self.host.as_deref().unwrap_or("localhost"),
+                    self.port.unwrap_or(8123)
+                );
+                if let Some(db) = &self.database {
+                    format!("{}/{}", base, db)
+                } else {
+                    base
+                }
+            }
         }
     }
     
@@ -161,8 +270,11 @@ impl Connection {
     pub fn get_default_database(&self) -> Option<String> {
         match self.engine {
             DatabaseEngine::PostgreSQL => Some("postgres".to_string()),
-            DatabaseEngine::MySQL => None, // MySQL can connect without a database
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB => None, // MySQL can connect without a database
             DatabaseEngine::SQLite => self.database.clone().or_else(|| self.file_path.clone()),
+            DatabaseEngine::SqlServer => None, // Usa la base por defecto del login
+            DatabaseEngine::DuckDB => self.database.clone().or_else(|| self.file_path.clone()),
+            DatabaseEngine::ClickHouse => None, // Usa la base por defecto del usuario
         }
     }
 }
@@ -180,8 +292,18 @@ pub struct CreateConnectionDto {
     pub password: Option<String>,
     pub file_path: Option<String>,
     pub ssl: Option<SslConfig>,
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+    /// Password SSH o passphrase de la clave privada; se guarda en el CredentialStore
+    #[serde(default)]
+    pub ssh_secret: Option<String>,
     pub color: Option<String>,
     pub read_only: Option<bool>,
+    /// Límite por defecto de cada ejecución, en ms (0 sin límite)
+    #[serde(default)]
+    pub statement_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub production: Option<bool>,
 }
 
 /// DTO para actualizar conexiones
@@ -194,8 +316,20 @@ pub struct UpdateConnectionDto {
     pub username: Option<String>,
     pub password: Option<String>,
     pub ssl: Option<SslConfig>,
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+    #[serde(default)]
+    pub ssh_secret: Option<String>,
+    /// Quita el túnel SSH de la conexión
+    #[serde(default)]
+    pub remove_ssh_tunnel: Option<bool>,
     pub color: Option<String>,
     pub read_only: Option<bool>,
+    /// 0 quita el límite
+    #[serde(default)]
+    pub statement_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub production: Option<bool>,
 }
 
 /// Estado de una conexión activa
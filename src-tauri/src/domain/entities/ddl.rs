@@ -0,0 +1,773 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ColumnSchema, ConstraintType, ForeignKeyAction, ForeignKeyInfo, FunctionInfo, IndexInfo, SequenceInfo, SqlDialect,
+    TableInfo, TriggerEvent, TriggerInfo, TriggerTiming, ViewInfo,
+};
+
+/// Tipo de objeto del que se pide la sentencia `CREATE`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DdlObjectType {
+    Table,
+    View,
+    Index,
+    Function,
+    Trigger,
+    Sequence,
+}
+
+/// Objeto del que se pide la sentencia `CREATE`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DdlObject {
+    pub object_type: DdlObjectType,
+    pub name: String,
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Tabla de un índice o un trigger; sin ella se busca por nombre en el esquema
+    #[serde(default)]
+    pub table: Option<String>,
+}
+
+/// Qué añadir tras la sentencia `CREATE`, si el motor lo guarda
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DdlOptions {
+    /// `COMMENT ON` del objeto y sus columnas
+    #[serde(default)]
+    pub include_comments: bool,
+    /// `GRANT` de los permisos concedidos a otros roles
+    #[serde(default)]
+    pub include_grants: bool,
+    /// `ALTER ... OWNER TO` en PostgreSQL; `DEFINER` en MySQL
+    #[serde(default)]
+    pub include_ownership: bool,
+}
+
+/// `esquema.nombre` con las comillas del motor; sin esquema, solo el nombre
+pub fn qualified_identifier(schema: Option<&str>, name: &str, dialect: SqlDialect) -> String {
+    match schema {
+        Some(schema) => format!("{}.{}", dialect.quote_identifier(schema), dialect.quote_identifier(name)),
+        None => dialect.quote_identifier(name),
+    }
+}
+
+fn column_list(columns: &[String], dialect: SqlDialect) -> String {
+    columns.iter().map(|c| dialect.quote_identifier(c)).collect::<Vec<_>>().join(", ")
+}
+
+/// Tipo de la columna tal como se declara en `CREATE TABLE`
+pub fn column_type(column: &ColumnSchema, dialect: SqlDialect) -> String {
+    if dialect != SqlDialect::Postgres {
+        // MySQL da el tipo completo (`varchar(20)`, `enum(...)`, `int unsigned`); SQLite, el declarado
+        return column.native_type.clone();
+    }
+    // information_schema de PostgreSQL separa el tipo de sus modificadores
+    match column.data_type.as_str() {
+        "ARRAY" => format!("{}[]", column.native_type.trim_start_matches('_')),
+        "USER-DEFINED" => column.native_type.clone(),
+        "character varying" | "character" | "bit" | "bit varying" => match column.max_length {
+            Some(length) => format!("{}({})", column.data_type, length),
+            None => column.data_type.clone(),
+        },
+        "numeric" => match (column.numeric_precision, column.numeric_scale) {
+            (Some(precision), Some(scale)) => format!("numeric({},{})", precision, scale),
+            (Some(precision), None) => format!("numeric({})", precision),
+            _ => "numeric".to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// MySQL guarda sin comillas los valores por defecto de texto y tal cual las expresiones;
+/// los demás motores guardan siempre la expresión
+fn default_expression(default: &str, dialect: SqlDialect) -> String {
+    if dialect != SqlDialect::MySql {
+        return default.to_string();
+    }
+    let upper = default.to_uppercase();
+    let is_expression = default.parse::<f64>().is_ok()
+        || default.starts_with(['\'', '(', '"'])
+        || upper.starts_with("B'")
+        || upper.starts_with("X'")
+        || ["CURRENT_TIMESTAMP", "CURRENT_DATE", "CURRENT_TIME", "LOCALTIME", "NOW(", "NULL"]
+            .iter()
+            .any(|keyword| upper.starts_with(keyword));
+    if is_expression {
+        default.to_string()
+    } else {
+        format!("'{}'", default.replace('\\', "\\\\").replace('\'', "''"))
+    }
+}
+
+fn column_definition(column: &ColumnSchema, dialect: SqlDialect) -> String {
+    let mut definition = dialect.quote_identifier(&column.name);
+    let data_type = column_type(column, dialect);
+    if !data_type.is_empty() {
+        definition.push(' ');
+        definition.push_str(&data_type);
+    }
+    if !column.nullable {
+        definition.push_str(" NOT NULL");
+    }
+    if let Some(default) = column.default_value.as_deref().filter(|d| !d.is_empty()) {
+        definition.push_str(" DEFAULT ");
+        definition.push_str(&default_expression(default, dialect));
+    }
+    if dialect == SqlDialect::MySql && column.is_auto_increment {
+        definition.push_str(" AUTO_INCREMENT");
+    }
+    definition
+}
+
+/// Los `NOT NULL` de PostgreSQL aparecen en information_schema como CHECK con nombre
+/// `<oid>_<oid>_<n>_not_null`; ya van en la definición de la columna
+fn is_not_null_check(name: &str, definition: &str) -> bool {
+    name.ends_with("_not_null") && definition.trim().to_uppercase().ends_with("IS NOT NULL")
+}
+
+/// `CREATE TABLE` con columnas, clave primaria, UNIQUE y CHECK. Las claves foráneas van
+/// dentro solo con `inline_foreign_keys`; si no, se añaden después con [`foreign_key_sql`]
+pub fn create_table_sql(table: &TableInfo, schema: Option<&str>, dialect: SqlDialect, inline_foreign_keys: bool) -> String {
+    let mut lines: Vec<String> = table.columns.iter().map(|c| column_definition(c, dialect)).collect();
+
+    if let Some(pk) = table.primary_key.as_ref().filter(|pk| !pk.columns.is_empty()) {
+        let name = pk.name.as_deref().filter(|_| dialect != SqlDialect::MySql);
+        lines.push(match name {
+            Some(name) => format!("CONSTRAINT {} PRIMARY KEY ({})", dialect.quote_identifier(name), column_list(&pk.columns, dialect)),
+            None => format!("PRIMARY KEY ({})", column_list(&pk.columns, dialect)),
+        });
+    }
+    for constraint in &table.constraints {
+        let name = dialect.quote_identifier(&constraint.name);
+        match constraint.constraint_type {
+            // En MySQL un UNIQUE es un índice y se crea con los demás
+            ConstraintType::Unique if dialect != SqlDialect::MySql && !constraint.columns.is_empty() => {
+                lines.push(format!("CONSTRAINT {} UNIQUE ({})", name, column_list(&constraint.columns, dialect)));
+            }
+            ConstraintType::Check => {
+                if let Some(definition) = constraint.definition.as_deref() {
+                    if !is_not_null_check(&constraint.name, definition) {
+                        let definition = definition.trim();
+                        let definition = if definition.to_uppercase().starts_with("CHECK") {
+                            definition.to_string()
+                        } else {
+                            format!("CHECK ({})", definition)
+                        };
+                        lines.push(format!("CONSTRAINT {} {}", name, definition));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if inline_foreign_keys {
+        for fk in merged_foreign_keys(&table.foreign_keys) {
+            lines.push(foreign_key_clause(&fk, schema, dialect));
+        }
+    }
+
+    format!(
+        "CREATE TABLE {} (\n  {}\n);\n",
+        qualified_identifier(schema, &table.name, dialect),
+        lines.join(",\n  ")
+    )
+}
+
+/// Algunos drivers devuelven una entrada por columna de cada clave foránea: se juntan por nombre
+pub fn merged_foreign_keys(foreign_keys: &[ForeignKeyInfo]) -> Vec<ForeignKeyInfo> {
+    let mut merged: Vec<ForeignKeyInfo> = Vec::new();
+    for fk in foreign_keys {
+        match merged.iter_mut().find(|m| m.name == fk.name && m.referenced_table == fk.referenced_table) {
+            Some(existing) => {
+                for (column, referenced) in fk.columns.iter().zip(&fk.referenced_columns) {
+                    let known = existing.columns.iter().zip(&existing.referenced_columns).any(|(c, r)| c == column && r == referenced);
+                    if !known {
+                        existing.columns.push(column.clone());
+                        existing.referenced_columns.push(referenced.clone());
+                    }
+                }
+            }
+            None => merged.push(fk.clone()),
+        }
+    }
+    merged
+}
+
+fn foreign_key_action(action: &ForeignKeyAction) -> Option<&'static str> {
+    match action {
+        ForeignKeyAction::NoAction => None,
+        ForeignKeyAction::Restrict => Some("RESTRICT"),
+        ForeignKeyAction::Cascade => Some("CASCADE"),
+        ForeignKeyAction::SetNull => Some("SET NULL"),
+        ForeignKeyAction::SetDefault => Some("SET DEFAULT"),
+    }
+}
+
+fn foreign_key_clause(fk: &ForeignKeyInfo, schema: Option<&str>, dialect: SqlDialect) -> String {
+    // SQLite no admite esquema en REFERENCES
+    let referenced_schema = match dialect {
+        SqlDialect::Sqlite => None,
+        _ => schema.and(fk.referenced_schema.as_deref()).or(schema),
+    };
+    let mut clause = format!(
+        "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}",
+        dialect.quote_identifier(&fk.name),
+        column_list(&fk.columns, dialect),
+        qualified_identifier(referenced_schema, &fk.referenced_table, dialect),
+    );
+    // Sin columnas de destino la referencia es a la clave primaria
+    if !fk.referenced_columns.is_empty() {
+        clause.push_str(&format!(" ({})", column_list(&fk.referenced_columns, dialect)));
+    }
+    if let Some(action) = foreign_key_action(&fk.on_delete) {
+        clause.push_str(" ON DELETE ");
+        clause.push_str(action);
+    }
+    if let Some(action) = foreign_key_action(&fk.on_update) {
+        clause.push_str(" ON UPDATE ");
+        clause.push_str(action);
+    }
+    clause
+}
+
+/// `ALTER TABLE ... ADD CONSTRAINT ... FOREIGN KEY`
+pub fn foreign_key_sql(table: &TableInfo, fk: &ForeignKeyInfo, schema: Option<&str>, dialect: SqlDialect) -> String {
+    format!(
+        "ALTER TABLE {} ADD {};\n",
+        qualified_identifier(schema, &table.name, dialect),
+        foreign_key_clause(fk, schema, dialect)
+    )
+}
+
+/// Índices que no crea ya `CREATE TABLE`: ni el de la clave primaria ni los que respaldan
+/// un UNIQUE (en PostgreSQL se llaman como la restricción; en SQLite son `sqlite_autoindex_*`)
+pub fn standalone_indexes(table: &TableInfo, dialect: SqlDialect) -> Vec<IndexInfo> {
+    let constraint_names: HashSet<&str> = table
+        .constraints
+        .iter()
+        .filter(|c| dialect != SqlDialect::MySql && matches!(c.constraint_type, ConstraintType::PrimaryKey | ConstraintType::Unique))
+        .map(|c| c.name.as_str())
+        .collect();
+    let pk_columns = table.primary_key.as_ref().map(|pk| pk.columns.clone()).unwrap_or_default();
+    let mut indexes: Vec<IndexInfo> = table
+        .indexes
+        .iter()
+        .filter(|i| !i.is_primary && !i.columns.is_empty() && !constraint_names.contains(i.name.as_str()))
+        .filter(|i| !(dialect == SqlDialect::Sqlite && i.name.starts_with("sqlite_autoindex_") && i.columns == pk_columns))
+        .cloned()
+        .collect();
+    // MySQL las agrupa sin orden
+    indexes.sort_by(|a, b| a.name.cmp(&b.name));
+    indexes
+}
+
+/// `CREATE [UNIQUE] INDEX`. Los índices automáticos de SQLite se recrean con un nombre propio
+pub fn index_sql(table: &TableInfo, index: &IndexInfo, schema: Option<&str>, dialect: SqlDialect) -> String {
+    let name = if index.name.starts_with("sqlite_autoindex_") {
+        format!("{}_{}_key", table.name, index.columns.join("_"))
+    } else {
+        index.name.clone()
+    };
+    let unique = if index.is_unique { "UNIQUE " } else { "" };
+    let target = qualified_identifier(schema, &table.name, dialect);
+    let columns = column_list(&index.columns, dialect);
+    let index_type = index.index_type.to_lowercase();
+    match dialect {
+        // En PostgreSQL el índice vive en el esquema de la tabla y no se califica
+        SqlDialect::Postgres if index_type != "btree" && !index_type.is_empty() => format!(
+            "CREATE {}INDEX {} ON {} USING {} ({});\n",
+            unique, dialect.quote_identifier(&name), target, index_type, columns
+        ),
+        SqlDialect::MySql if matches!(index_type.as_str(), "fulltext" | "spatial") => format!(
+            "CREATE {} INDEX {} ON {} ({});\n",
+            index_type.to_uppercase(), dialect.quote_identifier(&name), target, columns
+        ),
+        _ => format!("CREATE {}INDEX {} ON {} ({});\n", unique, dialect.quote_identifier(&name), target, columns),
+    }
+}
+
+/// `CREATE VIEW`; `None` si el catálogo no da la definición
+pub fn view_sql(view: &ViewInfo, schema: Option<&str>, dialect: SqlDialect) -> Option<String> {
+    let definition = view.definition.as_deref()?.trim().trim_end_matches(';').trim_end();
+    if definition.is_empty() {
+        return None;
+    }
+    // SQLite guarda la sentencia completa
+    if definition.to_uppercase().starts_with("CREATE") {
+        return Some(format!("{};\n", definition));
+    }
+    let kind = if view.is_materialized { "MATERIALIZED VIEW" } else { "VIEW" };
+    Some(format!("CREATE {} {} AS\n{};\n", kind, qualified_identifier(schema, &view.name, dialect), definition))
+}
+
+/// Definición completa de una función; solo PostgreSQL la da entera (`pg_get_functiondef`)
+pub fn function_sql(function: &FunctionInfo) -> Option<String> {
+    let definition = function.definition.as_deref()?.trim().trim_end_matches(';').trim_end();
+    definition.to_uppercase().starts_with("CREATE").then(|| format!("{};\n", definition))
+}
+
+/// `CREATE SEQUENCE` con sus límites (PostgreSQL)
+pub fn sequence_sql(sequence: &SequenceInfo, schema: Option<&str>, dialect: SqlDialect) -> String {
+    let mut sql = format!("CREATE SEQUENCE {}", qualified_identifier(schema, &sequence.name, dialect));
+    if !sequence.data_type.is_empty() {
+        sql.push_str(&format!(" AS {}", sequence.data_type));
+    }
+    sql.push_str(&format!(" INCREMENT BY {}", sequence.increment));
+    if let Some(min) = sequence.min_value {
+        sql.push_str(&format!(" MINVALUE {}", min));
+    }
+    if let Some(max) = sequence.max_value {
+        sql.push_str(&format!(" MAXVALUE {}", max));
+    }
+    sql.push_str(&format!(" START WITH {};\n", sequence.start_value));
+    sql
+}
+
+/// `CREATE TRIGGER`. PostgreSQL y SQLite dan la sentencia completa; de MySQL solo llega el
+/// cuerpo, así que se reconstruye y se delimita con `DELIMITER` por si lleva `BEGIN ... END`
+pub fn trigger_sql(trigger: &TriggerInfo, schema: Option<&str>, dialect: SqlDialect) -> Option<String> {
+    let definition = trigger.definition.as_deref()?.trim().trim_end_matches(';').trim_end();
+    if definition.is_empty() {
+        return None;
+    }
+    if dialect != SqlDialect::MySql {
+        return definition.to_uppercase().starts_with("CREATE").then(|| format!("{};\n", definition));
+    }
+    let timing = match trigger.timing {
+        TriggerTiming::Before => "BEFORE",
+        TriggerTiming::After => "AFTER",
+        TriggerTiming::InsteadOf => return None,
+    };
+    let event = match trigger.events.first()? {
+        TriggerEvent::Insert => "INSERT",
+        TriggerEvent::Update => "UPDATE",
+        TriggerEvent::Delete => "DELETE",
+        TriggerEvent::Truncate => return None,
+    };
+    Some(format!(
+        "DELIMITER ;;\nCREATE TRIGGER {} {} {} ON {} FOR EACH ROW\n{};;\nDELIMITER ;\n",
+        dialect.quote_identifier(&trigger.name),
+        timing,
+        event,
+        qualified_identifier(schema, &trigger.table_name, dialect),
+        definition
+    ))
+}
+
+/// Orden de creación de las tablas: cada una después de las que referencia. Los ciclos no
+/// tienen orden válido; sus tablas quedan al final, en el orden de entrada
+pub fn table_order(tables: &[TableInfo]) -> Vec<usize> {
+    let dependencies: Vec<Vec<usize>> = tables
+        .iter()
+        .map(|table| {
+            let mut deps: Vec<usize> = table
+                .foreign_keys
+                .iter()
+                .filter_map(|fk| {
+                    tables.iter().position(|t| {
+                        t.name == fk.referenced_table
+                            && (fk.referenced_schema.is_none() || t.schema == fk.referenced_schema)
+                    })
+                })
+                .collect();
+            deps.sort_unstable();
+            deps.dedup();
+            deps
+        })
+        .collect();
+    dependency_order(&dependencies)
+}
+
+/// Orden de creación de las vistas según los nombres de otras vistas que aparecen en su definición
+pub fn view_order(views: &[ViewInfo]) -> Vec<usize> {
+    let dependencies: Vec<Vec<usize>> = views
+        .iter()
+        .enumerate()
+        .map(|(index, view)| {
+            let definition = view.definition.as_deref().unwrap_or_default().to_lowercase();
+            views
+                .iter()
+                .enumerate()
+                .filter(|(other, candidate)| *other != index && mentions(&definition, &candidate.name.to_lowercase()))
+                .map(|(other, _)| other)
+                .collect()
+        })
+        .collect();
+    dependency_order(&dependencies)
+}
+
+/// `name` aparece como palabra completa en `text`
+fn mentions(text: &str, name: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + name.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Orden topológico estable: en cada paso sale el primer elemento sin dependencias pendientes
+fn dependency_order(dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let mut placed = vec![false; dependencies.len()];
+    let mut order = Vec::with_capacity(dependencies.len());
+    while order.len() < dependencies.len() {
+        let next = (0..dependencies.len())
+            .find(|&i| !placed[i] && dependencies[i].iter().all(|&d| d == i || placed[d]));
+        match next {
+            Some(i) => {
+                placed[i] = true;
+                order.push(i);
+            }
+            None => {
+                order.extend((0..dependencies.len()).filter(|&i| !placed[i]));
+                break;
+            }
+        }
+    }
+    order
+}
+
+/// Tablas que forman parte de un ciclo de claves foráneas, por nombre
+pub fn foreign_key_cycles(tables: &[TableInfo]) -> Vec<String> {
+    let order = table_order(tables);
+    let index: HashMap<usize, usize> = order.iter().enumerate().map(|(position, &table)| (table, position)).collect();
+    tables
+        .iter()
+        .enumerate()
+        .filter(|(i, table)| {
+            table.foreign_keys.iter().any(|fk| {
+                tables
+                    .iter()
+                    .position(|t| t.name == fk.referenced_table && (fk.referenced_schema.is_none() || t.schema == fk.referenced_schema))
+                    .is_some_and(|referenced| referenced != *i && index[&referenced] > index[i])
+            })
+        })
+        .map(|(_, table)| table.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ConstraintInfo, PrimaryKeyInfo};
+
+    fn column(name: &str, data_type: &str, native_type: &str) -> ColumnSchema {
+        ColumnSchema {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            native_type: native_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_unique: false,
+            is_auto_increment: false,
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            comment: None,
+            ordinal_position: 0,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnSchema>) -> TableInfo {
+        TableInfo {
+            name: name.to_string(),
+            schema: None,
+            columns,
+            primary_key: None,
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+            triggers: Vec::new(),
+            row_count: None,
+            size_bytes: None,
+            comment: None,
+            storage: None,
+        }
+    }
+
+    fn foreign_key(name: &str, columns: &[&str], referenced_table: &str, referenced_columns: &[&str]) -> ForeignKeyInfo {
+        ForeignKeyInfo {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            referenced_table: referenced_table.to_string(),
+            referenced_schema: None,
+            referenced_columns: referenced_columns.iter().map(|c| c.to_string()).collect(),
+            on_update: ForeignKeyAction::NoAction,
+            on_delete: ForeignKeyAction::NoAction,
+        }
+    }
+
+    fn index(name: &str, columns: &[&str], is_unique: bool, index_type: &str) -> IndexInfo {
+        IndexInfo {
+            name: name.to_string(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            is_unique,
+            is_primary: false,
+            index_type: index_type.to_string(),
+        }
+    }
+
+    fn view(name: &str, definition: Option<&str>) -> ViewInfo {
+        ViewInfo {
+            name: name.to_string(),
+            schema: None,
+            columns: Vec::new(),
+            definition: definition.map(str::to_string),
+            is_materialized: false,
+        }
+    }
+
+    #[test]
+    fn rebuilds_postgres_type_modifiers() {
+        let mut name = column("name", "character varying", "varchar");
+        name.max_length = Some(20);
+        let mut price = column("price", "numeric", "numeric");
+        (price.numeric_precision, price.numeric_scale) = (Some(10), Some(2));
+        let tags = column("tags", "ARRAY", "_int4");
+        assert_eq!(column_type(&name, SqlDialect::Postgres), "character varying(20)");
+        assert_eq!(column_type(&price, SqlDialect::Postgres), "numeric(10,2)");
+        assert_eq!(column_type(&tags, SqlDialect::Postgres), "int4[]");
+        assert_eq!(column_type(&column("mood", "USER-DEFINED", "mood"), SqlDialect::Postgres), "mood");
+        // MySQL ya da el tipo completo
+        assert_eq!(column_type(&column("n", "int", "int unsigned"), SqlDialect::MySql), "int unsigned");
+    }
+
+    #[test]
+    fn creates_postgres_tables_with_their_constraints() {
+        let mut id = column("id", "integer", "int4");
+        id.nullable = false;
+        let mut status = column("status", "text", "text");
+        status.default_value = Some("'new'::text".to_string());
+        let mut users = table("users", vec![id, status]);
+        users.primary_key = Some(PrimaryKeyInfo { name: Some("users_pkey".to_string()), columns: vec!["id".to_string()] });
+        users.constraints = vec![
+            ConstraintInfo {
+                name: "users_status_key".to_string(),
+                constraint_type: ConstraintType::Unique,
+                columns: vec!["status".to_string()],
+                definition: None,
+            },
+            ConstraintInfo {
+                name: "users_status_check".to_string(),
+                constraint_type: ConstraintType::Check,
+                columns: Vec::new(),
+                definition: Some("status <> ''".to_string()),
+            },
+            ConstraintInfo {
+                name: "2200_16384_1_not_null".to_string(),
+                constraint_type: ConstraintType::Check,
+                columns: Vec::new(),
+                definition: Some("id IS NOT NULL".to_string()),
+            },
+        ];
+        assert_eq!(
+            create_table_sql(&users, Some("app"), SqlDialect::Postgres, false),
+            "CREATE TABLE \"app\".\"users\" (\n  \"id\" integer NOT NULL,\n  \"status\" text DEFAULT 'new'::text,\n  \
+             CONSTRAINT \"users_pkey\" PRIMARY KEY (\"id\"),\n  CONSTRAINT \"users_status_key\" UNIQUE (\"status\"),\n  \
+             CONSTRAINT \"users_status_check\" CHECK (status <> '')\n);\n"
+        );
+    }
+
+    #[test]
+    fn quotes_mysql_text_defaults_but_not_expressions() {
+        let mut id = column("id", "int", "int");
+        id.nullable = false;
+        id.is_auto_increment = true;
+        let mut columns = vec![id];
+        for (name, default) in [("label", "it's"), ("created", "CURRENT_TIMESTAMP"), ("total", "0.5"), ("flags", "b'101'")] {
+            let mut c = column(name, "varchar", "varchar(10)");
+            c.default_value = Some(default.to_string());
+            columns.push(c);
+        }
+        let mut t = table("t", columns);
+        t.primary_key = Some(PrimaryKeyInfo { name: Some("PRIMARY".to_string()), columns: vec!["id".to_string()] });
+        assert_eq!(
+            create_table_sql(&t, None, SqlDialect::MySql, false),
+            "CREATE TABLE `t` (\n  `id` int NOT NULL AUTO_INCREMENT,\n  `label` varchar(10) DEFAULT 'it''s',\n  \
+             `created` varchar(10) DEFAULT CURRENT_TIMESTAMP,\n  `total` varchar(10) DEFAULT 0.5,\n  \
+             `flags` varchar(10) DEFAULT b'101',\n  PRIMARY KEY (`id`)\n);\n"
+        );
+    }
+
+    #[test]
+    fn merges_foreign_keys_listed_per_column() {
+        let rows = [
+            foreign_key("fk_order", &["a"], "orders", &["x"]),
+            foreign_key("fk_order", &["b"], "orders", &["y"]),
+            foreign_key("fk_order", &["a"], "orders", &["x"]),
+            foreign_key("fk_user", &["u"], "users", &["id"]),
+        ];
+        let merged = merged_foreign_keys(&rows);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].columns, vec!["a", "b"]);
+        assert_eq!(merged[0].referenced_columns, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn writes_foreign_keys_with_their_actions() {
+        let mut fk = foreign_key("fk_user", &["user_id"], "users", &["id"]);
+        fk.on_delete = ForeignKeyAction::Cascade;
+        fk.on_update = ForeignKeyAction::SetNull;
+        let posts = table("posts", Vec::new());
+        assert_eq!(
+            foreign_key_sql(&posts, &fk, Some("app"), SqlDialect::Postgres),
+            "ALTER TABLE \"app\".\"posts\" ADD CONSTRAINT \"fk_user\" FOREIGN KEY (\"user_id\") \
+             REFERENCES \"app\".\"users\" (\"id\") ON DELETE CASCADE ON UPDATE SET NULL;\n"
+        );
+
+        // SQLite no admite esquema en REFERENCES y las claves van dentro de la tabla
+        let mut posts = table("posts", vec![column("user_id", "INTEGER", "INTEGER")]);
+        posts.foreign_keys = vec![foreign_key("fk_user", &["user_id"], "users", &[])];
+        assert!(create_table_sql(&posts, Some("main"), SqlDialect::Sqlite, true)
+            .contains("CONSTRAINT \"fk_user\" FOREIGN KEY (\"user_id\") REFERENCES \"users\"\n"));
+    }
+
+    #[test]
+    fn skips_indexes_that_create_table_already_makes() {
+        let mut t = table("t", Vec::new());
+        t.primary_key = Some(PrimaryKeyInfo { name: None, columns: vec!["id".to_string()] });
+        t.constraints = vec![ConstraintInfo {
+            name: "t_code_key".to_string(),
+            constraint_type: ConstraintType::Unique,
+            columns: vec!["code".to_string()],
+            definition: None,
+        }];
+        t.indexes = vec![
+            IndexInfo { is_primary: true, ..index("t_pkey", &["id"], true, "btree") },
+            index("t_code_key", &["code"], true, "btree"),
+            index("t_name_idx", &["name"], false, "btree"),
+            index("sqlite_autoindex_t_1", &["id"], true, ""),
+            index("sqlite_autoindex_t_2", &["email"], true, ""),
+            index("a_tags_idx", &["tags"], false, "gin"),
+        ];
+        let names = |dialect| standalone_indexes(&t, dialect).into_iter().map(|i| i.name).collect::<Vec<_>>();
+        assert_eq!(names(SqlDialect::Postgres), vec!["a_tags_idx", "sqlite_autoindex_t_1", "sqlite_autoindex_t_2", "t_name_idx"]);
+        assert_eq!(names(SqlDialect::Sqlite), vec!["a_tags_idx", "sqlite_autoindex_t_2", "t_name_idx"]);
+    }
+
+    #[test]
+    fn writes_indexes_for_each_engine() {
+        let t = table("t", Vec::new());
+        assert_eq!(
+            index_sql(&t, &index("t_tags_idx", &["tags"], false, "GIN"), Some("app"), SqlDialect::Postgres),
+            "CREATE INDEX \"t_tags_idx\" ON \"app\".\"t\" USING gin (\"tags\");\n"
+        );
+        assert_eq!(
+            index_sql(&t, &index("body_ft", &["body"], false, "FULLTEXT"), None, SqlDialect::MySql),
+            "CREATE FULLTEXT INDEX `body_ft` ON `t` (`body`);\n"
+        );
+        assert_eq!(
+            index_sql(&t, &index("sqlite_autoindex_t_2", &["a", "b"], true, ""), None, SqlDialect::Sqlite),
+            "CREATE UNIQUE INDEX \"t_a_b_key\" ON \"t\" (\"a\", \"b\");\n"
+        );
+    }
+
+    #[test]
+    fn writes_views_functions_and_sequences() {
+        assert_eq!(
+            view_sql(&view("active", Some(" SELECT * FROM users WHERE active;")), Some("app"), SqlDialect::Postgres).as_deref(),
+            Some("CREATE VIEW \"app\".\"active\" AS\nSELECT * FROM users WHERE active;\n")
+        );
+        assert_eq!(
+            view_sql(&view("v", Some("CREATE VIEW v AS SELECT 1")), None, SqlDialect::Sqlite).as_deref(),
+            Some("CREATE VIEW v AS SELECT 1;\n")
+        );
+        assert!(view_sql(&view("v", Some("  ")), None, SqlDialect::MySql).is_none());
+
+        let function = |definition: &str| FunctionInfo {
+            name: "f".to_string(),
+            schema: None,
+            return_type: None,
+            parameters: Vec::new(),
+            language: "sql".to_string(),
+            definition: Some(definition.to_string()),
+        };
+        assert_eq!(
+            function_sql(&function("CREATE FUNCTION f() RETURNS int AS $$ SELECT 1 $$ LANGUAGE sql")).as_deref(),
+            Some("CREATE FUNCTION f() RETURNS int AS $$ SELECT 1 $$ LANGUAGE sql;\n")
+        );
+        // Solo el cuerpo no basta para recrearla
+        assert!(function_sql(&function("SELECT 1")).is_none());
+
+        let sequence = SequenceInfo {
+            name: "ids".to_string(),
+            schema: None,
+            data_type: "bigint".to_string(),
+            start_value: 1,
+            increment: 2,
+            min_value: Some(1),
+            max_value: None,
+            current_value: None,
+        };
+        assert_eq!(
+            sequence_sql(&sequence, Some("app"), SqlDialect::Postgres),
+            "CREATE SEQUENCE \"app\".\"ids\" AS bigint INCREMENT BY 2 MINVALUE 1 START WITH 1;\n"
+        );
+    }
+
+    #[test]
+    fn rebuilds_mysql_triggers_from_their_body() {
+        let mut trigger = TriggerInfo {
+            name: "touch".to_string(),
+            table_name: "t".to_string(),
+            schema: None,
+            timing: TriggerTiming::Before,
+            events: vec![TriggerEvent::Update],
+            definition: Some("BEGIN SET NEW.updated = NOW(); END".to_string()),
+            enabled: true,
+        };
+        assert_eq!(
+            trigger_sql(&trigger, None, SqlDialect::MySql).as_deref(),
+            Some("DELIMITER ;;\nCREATE TRIGGER `touch` BEFORE UPDATE ON `t` FOR EACH ROW\nBEGIN SET NEW.updated = NOW(); END;;\nDELIMITER ;\n")
+        );
+        // Los demás motores dan la sentencia completa; sin ella no hay nada que escribir
+        assert!(trigger_sql(&trigger, None, SqlDialect::Postgres).is_none());
+        trigger.definition = Some("CREATE TRIGGER touch BEFORE UPDATE ON t FOR EACH ROW EXECUTE FUNCTION touch();".to_string());
+        assert_eq!(
+            trigger_sql(&trigger, None, SqlDialect::Postgres).as_deref(),
+            Some("CREATE TRIGGER touch BEFORE UPDATE ON t FOR EACH ROW EXECUTE FUNCTION touch();\n")
+        );
+    }
+
+    #[test]
+    fn orders_tables_after_the_ones_they_reference() {
+        let mut comments = table("comments", Vec::new());
+        comments.foreign_keys = vec![foreign_key("fk_post", &["post_id"], "posts", &["id"])];
+        let mut posts = table("posts", Vec::new());
+        posts.foreign_keys = vec![
+            foreign_key("fk_user", &["user_id"], "users", &["id"]),
+            // Una referencia a sí misma no bloquea el orden
+            foreign_key("fk_parent", &["parent_id"], "posts", &["id"]),
+        ];
+        let users = table("users", Vec::new());
+        let tables = [comments, posts, users];
+        assert_eq!(table_order(&tables), vec![2, 1, 0]);
+        assert!(foreign_key_cycles(&tables).is_empty());
+    }
+
+    #[test]
+    fn leaves_foreign_key_cycles_at_the_end() {
+        let mut a = table("a", Vec::new());
+        a.foreign_keys = vec![foreign_key("fk_b", &["b_id"], "b", &["id"])];
+        let mut b = table("b", Vec::new());
+        b.foreign_keys = vec![foreign_key("fk_a", &["a_id"], "a", &["id"])];
+        let tables = [a, b, table("c", Vec::new())];
+        assert_eq!(table_order(&tables), vec![2, 0, 1]);
+        assert_eq!(foreign_key_cycles(&tables), vec!["a"]);
+    }
+
+    #[test]
+    fn orders_views_by_whole_word_mentions() {
+        let views = [
+            view("report", Some("SELECT * FROM active_users JOIN totals USING (id)")),
+            view("active_users", Some("SELECT * FROM accounts WHERE active")),
+            view("totals", Some("SELECT * FROM orders")),
+            // `users` aparece en `report` dentro de `active_users`, pero no como palabra
+            view("users", Some("SELECT 1")),
+        ];
+        assert_eq!(view_order(&views), vec![1, 2, 0, 3]);
+    }
+}
@@ -0,0 +1,145 @@
+use super::risk::{delete_target, lex, qualified_name, update_target, Lexed, Token};
+use super::SqlDialect;
+
+/// Sentencia cuyas filas afectadas se pueden localizar antes de ejecutarla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MutationKind {
+    Update,
+    Delete,
+}
+
+/// Tabla y filtro de un `UPDATE` o `DELETE` sobre una sola tabla, tal como aparecen
+/// en la sentencia, para leer las filas que va a tocar
+#[derive(Debug, Clone)]
+pub struct MutationScope {
+    pub kind: MutationKind,
+    pub schema: Option<String>,
+    pub table: String,
+    /// Tabla con su alias, copiada de la sentencia
+    source: String,
+    /// Condición del WHERE
+    filter: Option<String>,
+    /// `ORDER BY ... LIMIT ...` de MySQL y SQLite, que acota las filas afectadas
+    tail: Option<String>,
+}
+
+impl MutationScope {
+    /// `None` si la sentencia no es un `UPDATE`/`DELETE` de una sola tabla
+    /// (joins, `FROM`/`USING` adicionales, `WHERE CURRENT OF`...)
+    pub fn parse(sql: &str, dialect: SqlDialect) -> Option<Self> {
+        let chars: Vec<char> = sql.chars().collect();
+        let mut tokens = lex(sql, dialect);
+        while tokens.last().is_some_and(|t| t.token == Token::Symbol(';')) {
+            tokens.pop();
+        }
+        let top = |keywords: &[&str], from: usize| {
+            tokens[from..]
+                .iter()
+                .position(|t| t.depth == 0 && t.is_any(keywords))
+                .map(|p| from + p)
+        };
+
+        let first = tokens.first()?;
+        let (kind, start) = if first.is("UPDATE") {
+            (MutationKind::Update, update_target(&tokens))
+        } else if first.is("DELETE") {
+            (MutationKind::Delete, delete_target(&tokens))
+        } else {
+            return None;
+        };
+        let name = qualified_name(&tokens, start);
+        let table = name.last()?.clone();
+        let schema = (name.len() > 1).then(|| name[name.len() - 2].clone());
+
+        let filter_at = top(&["WHERE"], start);
+        let tail_at = top(&["ORDER", "LIMIT"], start);
+        let end_at = top(&["RETURNING", "OUTPUT", "OPTION"], start);
+        let source_end = match kind {
+            MutationKind::Update => top(&["SET"], start)?,
+            MutationKind::Delete => filter_at.or(tail_at).or(end_at).unwrap_or(tokens.len()),
+        };
+        let clause_end = tail_at.or(end_at).unwrap_or(tokens.len());
+
+        let source = &tokens[start..source_end];
+        if source.is_empty() || source.iter().any(is_multi_table) {
+            return None;
+        }
+        // UPDATE ... SET ... FROM otra_tabla
+        if kind == MutationKind::Update && top(&["FROM"], source_end).is_some_and(|f| f < clause_end) {
+            return None;
+        }
+        let filter = match filter_at {
+            Some(w) if tokens.get(w + 1).is_some_and(|t| t.is("CURRENT")) => return None,
+            Some(w) if w + 1 < clause_end => Some(text(&chars, &tokens[w + 1..clause_end])),
+            Some(_) => return None,
+            None => None,
+        };
+        let tail = tail_at.map(|t| text(&chars, &tokens[t..end_at.unwrap_or(tokens.len())]));
+
+        Some(Self { kind, schema, table, source: text(&chars, source), filter, tail })
+    }
+
+    /// Filas que tocará la sentencia, hasta `limit`. SQL Server no tiene `LIMIT` y sus
+    /// `UPDATE`/`DELETE` no llevan cola, así que allí se acota con `TOP`
+    pub fn before_query(&self, limit: usize, dialect: SqlDialect) -> String {
+        let mut sql = match dialect {
+            SqlDialect::SqlServer => format!("SELECT TOP ({}) * FROM {}", limit, self.source),
+            _ => format!("SELECT * FROM {}", self.source),
+        };
+        if let Some(filter) = &self.filter {
+            sql.push_str(&format!(" WHERE {}", filter));
+        }
+        match &self.tail {
+            _ if dialect == SqlDialect::SqlServer => {}
+            Some(tail) if tail.to_uppercase().contains("LIMIT") => sql.push_str(&format!(" {}", tail)),
+            Some(tail) => sql.push_str(&format!(" {} LIMIT {}", tail, limit)),
+            None => sql.push_str(&format!(" LIMIT {}", limit)),
+        }
+        sql
+    }
+
+    /// Filas con las claves dadas, con marcadores `:k{fila}_{columna}`
+    pub fn after_query(&self, key_columns: &[String], rows: usize, dialect: SqlDialect) -> String {
+        let keys: Vec<String> = (0..rows)
+            .map(|row| {
+                let columns: Vec<String> = key_columns
+                    .iter()
+                    .enumerate()
+                    .map(|(col, name)| format!("{} = :k{}_{}", dialect.quote_identifier(name), row, col))
+                    .collect();
+                format!("({})", columns.join(" AND "))
+            })
+            .collect();
+        format!("SELECT * FROM {} WHERE {}", self.source, keys.join(" OR "))
+    }
+}
+
+/// Joins, listas de tablas o un `FROM`/`USING` con más tablas
+fn is_multi_table(token: &Lexed) -> bool {
+    token.depth == 0 && (token.token == Token::Symbol(',') || token.is_any(&["JOIN", "FROM", "USING"]))
+}
+
+/// Texto original desde el primer token hasta el último
+fn text(chars: &[char], tokens: &[Lexed]) -> String {
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => chars[first.start..last.end].iter().collect(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_affected_rows_with_the_dialect_row_limit() {
+        let scope = MutationScope::parse("UPDATE orders SET paid = 1 WHERE total > 10", SqlDialect::Postgres).unwrap();
+        assert_eq!(scope.before_query(50, SqlDialect::Postgres), "SELECT * FROM orders WHERE total > 10 LIMIT 50");
+
+        let scope = MutationScope::parse("DELETE FROM [dbo].[orders] WHERE total > 10", SqlDialect::SqlServer).unwrap();
+        assert_eq!(scope.before_query(50, SqlDialect::SqlServer), "SELECT TOP (50) * FROM [dbo].[orders] WHERE total > 10");
+
+        let scope = MutationScope::parse("DELETE FROM logs ORDER BY id LIMIT 5", SqlDialect::MySql).unwrap();
+        assert_eq!(scope.before_query(50, SqlDialect::MySql), "SELECT * FROM logs ORDER BY id LIMIT 5");
+    }
+}
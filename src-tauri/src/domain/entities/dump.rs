@@ -0,0 +1,355 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{cell_text, split_script, sql_literal, BindValue, CellValue, ScriptMode, SqlDialect};
+
+/// Errores que se guardan en el resumen de una restauración; el resto solo se cuentan
+pub const MAX_RESTORE_ERRORS: usize = 100;
+
+/// Cómo se escriben los datos de las tablas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpDataFormat {
+    /// `INSERT` de varias filas
+    #[default]
+    Insert,
+    /// Bloques `COPY ... FROM stdin` (solo PostgreSQL); bastante más rápidos al restaurar
+    Copy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpOptions {
+    /// Esquema a volcar. Sin él, en PostgreSQL todos los que no son del sistema y en MySQL
+    /// y SQLite la base de la conexión
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Crear tablas, índices, claves foráneas, vistas, funciones, secuencias y triggers
+    #[serde(default = "default_true")]
+    pub include_schema: bool,
+    #[serde(default = "default_true")]
+    pub include_data: bool,
+    #[serde(default)]
+    pub data_format: DumpDataFormat,
+    /// Empezar el fichero borrando los objetos que se van a crear
+    #[serde(default)]
+    pub drop_existing: bool,
+    #[serde(default)]
+    pub rows_per_insert: Option<usize>,
+    /// Filas por lote leído del servidor
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Resultado de un volcado
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DumpSummary {
+    pub dump_id: Uuid,
+    pub path: String,
+    pub schemas: Vec<String>,
+    pub table_count: usize,
+    pub view_count: usize,
+    pub function_count: usize,
+    pub sequence_count: usize,
+    pub trigger_count: usize,
+    pub row_count: u64,
+    pub bytes_written: u64,
+    /// Objetos que no se pudieron reconstruir con lo que da el catálogo
+    pub warnings: Vec<String>,
+    pub execution_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RestoreOptions {
+    /// Qué hacer si falla una sentencia; como en los scripts
+    #[serde(default)]
+    pub mode: ScriptMode,
+    /// Índice de la primera sentencia a ejecutar, para retomar una restauración detenida
+    #[serde(default)]
+    pub start_at: Option<usize>,
+}
+
+/// Sentencia fallida de una restauración
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreError {
+    pub statement_index: usize,
+    /// Línea (desde 1) del fichero donde empieza
+    pub line: usize,
+    /// Inicio de la sentencia, sin los datos de un `COPY`
+    pub sql: String,
+    pub error: String,
+}
+
+/// Resultado de una restauración
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreSummary {
+    pub restore_id: Uuid,
+    pub mode: ScriptMode,
+    pub statement_count: usize,
+    pub start_at: usize,
+    pub executed: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Filas afectadas por las sentencias que lo informan, incluidas las de `COPY`
+    pub affected_rows: u64,
+    /// Primeros [`MAX_RESTORE_ERRORS`] errores
+    pub errors: Vec<RestoreError>,
+    pub stopped: bool,
+    /// Índice desde el que retomar (`start_at`) si se detuvo
+    pub resume_at: Option<usize>,
+    /// En modo transacción, si se confirmó
+    pub committed: Option<bool>,
+    pub execution_time_ms: u64,
+}
+
+/// Sentencia de un volcado. Un `COPY ... FROM stdin` lleva aparte las líneas de datos que le siguen
+#[derive(Debug, Clone)]
+pub struct DumpStatement {
+    pub sql: String,
+    pub line: usize,
+    pub copy_data: Option<String>,
+}
+
+/// Divide un volcado en sentencias. Los bloques `COPY` se reconocen como los escribe el
+/// volcado: la orden sola en su línea, tras una sentencia completa, y los datos hasta `\.`
+pub fn split_dump(text: &str, dialect: SqlDialect) -> Vec<DumpStatement> {
+    let mut statements = Vec::new();
+    let mut pending = String::new();
+    let mut pending_line = 1;
+    let mut lines = text.split_inclusive('\n').enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let complete = pending.trim_end().is_empty() || pending.trim_end().ends_with(';');
+        if dialect == SqlDialect::Postgres && complete && is_copy_header(line) {
+            flush_statements(&mut statements, &pending, pending_line, dialect);
+            pending.clear();
+            let mut data = String::new();
+            let mut consumed = index + 1;
+            for (data_index, data_line) in lines.by_ref() {
+                consumed = data_index + 1;
+                if data_line.trim_end_matches(['\r', '\n']) == "\\." {
+                    break;
+                }
+                data.push_str(data_line);
+            }
+            statements.push(DumpStatement {
+                sql: line.trim().trim_end_matches(';').trim_end().to_string(),
+                line: index + 1,
+                copy_data: Some(data),
+            });
+            pending_line = consumed + 1;
+            continue;
+        }
+        if pending.is_empty() {
+            pending_line = index + 1;
+        }
+        pending.push_str(line);
+    }
+    flush_statements(&mut statements, &pending, pending_line, dialect);
+    statements
+}
+
+fn is_copy_header(line: &str) -> bool {
+    let upper = line.trim().to_uppercase();
+    upper.starts_with("COPY ") && upper.ends_with("FROM STDIN;")
+}
+
+fn flush_statements(statements: &mut Vec<DumpStatement>, text: &str, first_line: usize, dialect: SqlDialect) {
+    statements.extend(split_script(text, dialect).into_iter().map(|s| DumpStatement {
+        sql: s.sql,
+        line: first_line + s.line - 1,
+        copy_data: None,
+    }));
+}
+
+/// `INSERT` de hasta `rows_per_insert` filas cada uno, tras `prefix` (`INSERT INTO t (...) VALUES`)
+pub fn insert_statements(prefix: &str, rows: &[Vec<CellValue>], dialect: SqlDialect, rows_per_insert: usize) -> String {
+    rows.chunks(rows_per_insert.max(1))
+        .map(|chunk| {
+            let tuples: Vec<String> = chunk
+                .iter()
+                .map(|row| {
+                    let values: Vec<String> = row.iter().map(|v| sql_literal(v, dialect)).collect();
+                    format!("  ({})", values.join(", "))
+                })
+                .collect();
+            format!("{}\n{};\n", prefix, tuples.join(",\n"))
+        })
+        .collect()
+}
+
+/// Valor que se escribe en el formato de texto de `COPY` de PostgreSQL: las celdas de un
+/// volcado y los valores ya convertidos de una importación
+pub trait CopyValue: Sized {
+    /// Texto del valor; `None` si es nulo
+    fn copy_text(&self) -> Option<String>;
+
+    /// Elementos del valor, si es un array
+    fn copy_elements(&self) -> Option<&[Self]>;
+}
+
+impl CopyValue for CellValue {
+    fn copy_text(&self) -> Option<String> {
+        Some(match self {
+            CellValue::Null => return None,
+            CellValue::Bool(b) => (if *b { "t" } else { "f" }).to_string(),
+            CellValue::Float(f) if f.is_nan() => "NaN".to_string(),
+            CellValue::Float(f) if f.is_infinite() => (if *f > 0.0 { "Infinity" } else { "-Infinity" }).to_string(),
+            other => cell_text(other)?,
+        })
+    }
+
+    fn copy_elements(&self) -> Option<&[Self]> {
+        match self {
+            CellValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+impl CopyValue for BindValue {
+    fn copy_text(&self) -> Option<String> {
+        match self {
+            BindValue::Real(f) if !f.is_finite() => CellValue::Float(*f as f64).copy_text(),
+            BindValue::Double(f) if !f.is_finite() => CellValue::Float(*f).copy_text(),
+            other => other.as_text(),
+        }
+    }
+
+    fn copy_elements(&self) -> Option<&[Self]> {
+        match self {
+            BindValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Filas en el formato de texto de `COPY` de PostgreSQL: tabuladores entre valores, `\N`
+/// para NULL y barras invertidas para los caracteres de control
+pub fn copy_rows<V: CopyValue>(rows: &[Vec<V>]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        for (i, value) in row.iter().enumerate() {
+            if i > 0 {
+                out.push('\t');
+            }
+            match copy_field(value) {
+                Some(text) => escape_copy(&text, &mut out),
+                None => out.push_str("\\N"),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn copy_field<V: CopyValue>(value: &V) -> Option<String> {
+    match value.copy_elements() {
+        Some(items) => Some(array_literal(items)),
+        None => value.copy_text(),
+    }
+}
+
+/// Literal de array de PostgreSQL (`{"1","2","a b"}`), que sirve tanto en `COPY` como
+/// enlazado a un marcador con cast al tipo del array
+pub fn array_literal<V: CopyValue>(items: &[V]) -> String {
+    let elements: Vec<String> = items
+        .iter()
+        .map(|item| match item.copy_elements() {
+            Some(nested) => array_literal(nested),
+            None => item.copy_text().map(|text| quote_array_element(&text)).unwrap_or_else(|| "NULL".to_string()),
+        })
+        .collect();
+    format!("{{{}}}", elements.join(","))
+}
+
+fn quote_array_element(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn escape_copy(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_statements_with_their_lines() {
+        let statements = split_dump("CREATE TABLE t (a int);\n\nINSERT INTO t VALUES (1);\n", SqlDialect::Postgres);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].sql, "CREATE TABLE t (a int)");
+        assert_eq!(statements[0].line, 1);
+        assert_eq!(statements[1].sql, "INSERT INTO t VALUES (1)");
+        assert_eq!(statements[1].line, 3);
+        assert!(statements.iter().all(|s| s.copy_data.is_none()));
+    }
+
+    #[test]
+    fn keeps_copy_data_apart_until_the_terminator() {
+        let dump = "CREATE TABLE t (a int, b text);\nCOPY t (a, b) FROM stdin;\n1\tx; y\n2\t\\N\n\\.\nSELECT 1;\n";
+        let statements = split_dump(dump, SqlDialect::Postgres);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[1].sql, "COPY t (a, b) FROM stdin");
+        assert_eq!(statements[1].line, 2);
+        assert_eq!(statements[1].copy_data.as_deref(), Some("1\tx; y\n2\t\\N\n"));
+        assert_eq!(statements[2].sql, "SELECT 1");
+        assert_eq!(statements[2].line, 6);
+    }
+
+    #[test]
+    fn accepts_crlf_terminators_and_empty_copy_blocks() {
+        let statements = split_dump("COPY t FROM stdin;\r\n\\.\r\nSELECT 1;\r\n", SqlDialect::Postgres);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].copy_data.as_deref(), Some(""));
+        assert_eq!(statements[1].line, 3);
+    }
+
+    #[test]
+    fn copy_is_only_a_header_after_a_complete_statement() {
+        let function = "CREATE FUNCTION f() RETURNS void AS $$\nCOPY t FROM stdin;\n$$ LANGUAGE sql;\n";
+        let statements = split_dump(function, SqlDialect::Postgres);
+        assert_eq!(statements.len(), 1);
+        assert!(statements[0].copy_data.is_none());
+
+        // Fuera de PostgreSQL no hay bloques COPY
+        let statements = split_dump("COPY t FROM stdin;\nSELECT 1;\n", SqlDialect::MySql);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].copy_data.is_none());
+    }
+
+    #[test]
+    fn copy_rows_escape_control_characters_and_nulls() {
+        let rows = vec![vec![
+            CellValue::String("a\tb\nc\\d".to_string()),
+            CellValue::Null,
+            CellValue::Bool(true),
+            CellValue::Float(f64::NEG_INFINITY),
+        ]];
+        assert_eq!(copy_rows(&rows), "a\\tb\\nc\\\\d\t\\N\tt\t-Infinity\n");
+    }
+
+    #[test]
+    fn dump_cells_and_import_values_share_the_array_literal() {
+        let cells = vec![CellValue::Int(1), CellValue::Null, CellValue::String("a \"b\"".to_string())];
+        let values = vec![BindValue::Int(1), BindValue::Null, BindValue::Text("a \"b\"".to_string())];
+        assert_eq!(array_literal(&cells), r#"{"1",NULL,"a \"b\""}"#);
+        assert_eq!(array_literal(&values), array_literal(&cells));
+        assert_eq!(
+            array_literal(&[CellValue::Array(vec![CellValue::Int(1)]), CellValue::Array(vec![])]),
+            r#"{{"1"},{}}"#
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{CellValue, DatabaseEngine, QueryResult, SqlDialect};
+
+/// Filas por lote leído del servidor si no se indica otro tamaño
+pub const DEFAULT_EXPORT_BATCH: usize = 1_000;
+
+/// Filas por `INSERT` del script SQL si no se indica otro número
+pub const DEFAULT_ROWS_PER_INSERT: usize = 100;
+
+/// Formato del fichero exportado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Ndjson,
+    Sql,
+    Markdown,
+    Sqlite,
+}
+
+/// Qué campos del CSV van entre comillas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvQuoting {
+    /// Solo los que contienen el separador, comillas o saltos de línea
+    #[default]
+    Minimal,
+    All,
+    /// Todos salvo números, booleanos y nulos
+    NonNumeric,
+    /// Ninguno, salvo los que no se podrían leer sin ellas (separador, saltos de línea
+    /// o una comilla al principio)
+    Never,
+}
+
+/// Codificación de los formatos de texto
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    /// UTF-8 con BOM, para que Excel detecte la codificación del CSV
+    Utf8Bom,
+    /// UTF-16 little endian con BOM
+    Utf16Le,
+    /// ISO-8859-1; un carácter que no cabe hace fallar la exportación
+    Latin1,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Separador del CSV; por defecto la coma
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    #[serde(default)]
+    pub quoting: CsvQuoting,
+    #[serde(default)]
+    pub encoding: TextEncoding,
+    /// CSV: escribir los nombres de las columnas en la primera línea
+    #[serde(default = "default_header")]
+    pub header: bool,
+    /// Texto de los nulos en CSV y Markdown; por defecto vacío
+    #[serde(default)]
+    pub null_text: Option<String>,
+    /// Tabla de los `INSERT` del script SQL y de la base SQLite; por defecto `export`
+    #[serde(default)]
+    pub table_name: Option<String>,
+    /// Motor para el que se genera el script SQL; por defecto el de la conexión
+    #[serde(default)]
+    pub target_engine: Option<DatabaseEngine>,
+    #[serde(default)]
+    pub rows_per_insert: Option<usize>,
+    /// Tope de filas a exportar
+    #[serde(default)]
+    pub max_rows: Option<u64>,
+    /// Filas por lote leído del servidor; cada lote se escribe y se informa de una vez
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+fn default_header() -> bool {
+    true
+}
+
+impl ExportOptions {
+    pub fn table_name(&self) -> &str {
+        self.table_name.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or("export")
+    }
+}
+
+/// Resultado de una exportación
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub export_id: Uuid,
+    pub path: String,
+    pub format: ExportFormat,
+    pub row_count: u64,
+    pub bytes_written: u64,
+    /// true si se alcanzó `max_rows` antes de leer todas las filas
+    pub truncated: bool,
+    pub execution_time_ms: u64,
+}
+
+/// Hoja de un libro XLSX: un conjunto de resultados con su nombre. Excel limita el nombre
+/// a 31 caracteres sin `[]:*?/\`; lo que no cumpla se ajusta al escribir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XlsxSheet {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+impl XlsxSheet {
+    pub fn from_result(name: impl Into<String>, result: QueryResult) -> Self {
+        Self {
+            name: name.into(),
+            columns: result.columns.into_iter().map(|c| c.name).collect(),
+            rows: result.rows,
+        }
+    }
+}
+
+/// Texto de una celda en CSV y Markdown; `None` si es nula
+pub fn cell_text(value: &CellValue) -> Option<String> {
+    Some(match value {
+        CellValue::Null => return None,
+        CellValue::Bool(b) => b.to_string(),
+        CellValue::Int(i) => i.to_string(),
+        CellValue::Float(f) => f.to_string(),
+        CellValue::Bytes(bytes) => format!("\\x{}", hex(bytes)),
+        CellValue::Json(v) => v.to_string(),
+        CellValue::Array(_) => cell_json(value).to_string(),
+        CellValue::String(s)
+        | CellValue::Date(s)
+        | CellValue::Time(s)
+        | CellValue::DateTime(s)
+        | CellValue::Uuid(s) => s.clone(),
+    })
+}
+
+/// Valor JSON de una celda; los binarios van como texto hexadecimal `\x...`
+pub fn cell_json(value: &CellValue) -> Value {
+    match value {
+        CellValue::Null => Value::Null,
+        CellValue::Bool(b) => Value::Bool(*b),
+        CellValue::Int(i) => Value::from(*i),
+        // NaN e infinito no existen en JSON
+        CellValue::Float(f) if f.is_finite() => Value::from(*f),
+        CellValue::Float(f) => Value::String(f.to_string()),
+        CellValue::Json(v) => v.clone(),
+        CellValue::Array(items) => Value::Array(items.iter().map(cell_json).collect()),
+        other => cell_text(other).map(Value::String).unwrap_or(Value::Null),
+    }
+}
+
+/// Literal SQL de una celda en el dialecto dado, para los scripts de `INSERT`
+pub fn sql_literal(value: &CellValue, dialect: SqlDialect) -> String {
+    match value {
+        CellValue::Null => "NULL".to_string(),
+        CellValue::Bool(b) => match dialect {
+            SqlDialect::Sqlite | SqlDialect::SqlServer => (if *b { "1" } else { "0" }).to_string(),
+            _ => (if *b { "TRUE" } else { "FALSE" }).to_string(),
+        },
+        CellValue::Int(i) => i.to_string(),
+        CellValue::Float(f) if f.is_finite() => f.to_string(),
+        CellValue::Float(f) => match dialect {
+            SqlDialect::Postgres | SqlDialect::DuckDb => format!("'{}'", postgres_float(*f)),
+            _ => "NULL".to_string(),
+        },
+        CellValue::Bytes(bytes) => match dialect {
+            SqlDialect::Postgres => format!("'\\x{}'::bytea", hex(bytes)),
+            SqlDialect::SqlServer => format!("0x{}", hex(bytes)),
+            SqlDialect::DuckDb | SqlDialect::ClickHouse => format!("unhex('{}')", hex(bytes)),
+            SqlDialect::MySql | SqlDialect::Sqlite => format!("X'{}'", hex(bytes)),
+        },
+        CellValue::Array(items) => match dialect {
+            SqlDialect::Postgres if items.is_empty() => "'{}'".to_string(),
+            SqlDialect::Postgres => {
+                let items: Vec<String> = items.iter().map(|v| sql_literal(v, dialect)).collect();
+                format!("ARRAY[{}]", items.join(", "))
+            }
+            SqlDialect::DuckDb | SqlDialect::ClickHouse => {
+                let items: Vec<String> = items.iter().map(|v| sql_literal(v, dialect)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            _ => string_literal(&cell_json(value).to_string(), dialect),
+        },
+        other => string_literal(&cell_text(other).unwrap_or_default(), dialect),
+    }
+}
+
+/// Cadena entre comillas simples con los escapes del motor
+fn string_literal(text: &str, dialect: SqlDialect) -> String {
+    let mut escaped = text.replace('\'', "''");
+    if dialect.backslash_escapes() {
+        escaped = escaped.replace('\\', "\\\\");
+    }
+    match dialect {
+        // N'...' conserva los caracteres fuera de la página de códigos de la base
+        SqlDialect::SqlServer => format!("N'{}'", escaped),
+        _ => format!("'{}'", escaped),
+    }
+}
+
+fn postgres_float(f: f64) -> &'static str {
+    if f.is_nan() {
+        "NaN"
+    } else if f > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
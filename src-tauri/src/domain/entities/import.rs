@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{BindValue, ColumnSchema, SqlDialect};
+use crate::domain::error::DomainError;
+
+/// Valores por sentencia en los INSERT de varias filas, por debajo del límite de marcadores
+/// de todos los motores (SQL Server admite 2100 y SQLite antiguo 999)
+const MAX_STATEMENT_PARAMS: usize = 999;
+
+/// Errores de fila que se devuelven en el resumen; el resto solo se cuentan
+pub const MAX_REPORTED_IMPORT_ERRORS: usize = 100;
+
+/// Formato del fichero a importar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    /// Array JSON de objetos o de arrays, o un objeto por línea (NDJSON)
+    Json,
+    Xlsx,
+}
+
+/// Qué hacer con una fila que no se puede convertir o que la base de datos rechaza
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportErrorMode {
+    /// Se detiene y deshace la importación
+    #[default]
+    Abort,
+    /// Descarta la fila, la anota en el resumen y sigue
+    Skip,
+}
+
+/// Qué hacer con una fila cuya clave ya existe en la tabla
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateMode {
+    /// La clave repetida es un error más, tratado según `ImportErrorMode`
+    #[default]
+    Abort,
+    /// Conserva la fila existente
+    Skip,
+    /// Sobrescribe la fila existente con la del fichero
+    Upsert,
+}
+
+/// Columna del fichero que se carga en una columna de la tabla
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    /// CSV y XLSX: la primera fila tiene los nombres de las columnas
+    #[serde(default = "default_header")]
+    pub header: bool,
+    /// Separador del CSV; por defecto la coma
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Hoja del XLSX; por defecto la primera
+    #[serde(default)]
+    pub sheet: Option<String>,
+    /// Sin mapeo, cada columna del fichero va a la columna de la tabla con su mismo nombre,
+    /// o por posición si el fichero no tiene cabecera
+    #[serde(default)]
+    pub mapping: Vec<ColumnMapping>,
+    #[serde(default)]
+    pub on_error: ImportErrorMode,
+    #[serde(default)]
+    pub on_duplicate: DuplicateMode,
+    /// Filas por lote; cada lote se escribe y se informa de una vez
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+fn default_header() -> bool {
+    true
+}
+
+/// DTO para importar un fichero en una tabla
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportTableDto {
+    pub connection_id: Uuid,
+    #[serde(default)]
+    pub schema: Option<String>,
+    pub table: String,
+    /// Id de la importación, para seguir su avance; si no se indica se genera uno nuevo
+    #[serde(default)]
+    pub import_id: Option<Uuid>,
+    #[serde(default)]
+    pub confirmation_token: Option<String>,
+}
+
+/// Contenido del fichero ya leído: nombres de columna y una fila de valores por registro
+#[derive(Debug, Clone, Default)]
+pub struct ImportSource {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// Los nombres vienen del fichero; si no, son `column1`, `column2`...
+    pub named: bool,
+}
+
+impl ImportSource {
+    /// Nombres para un fichero sin cabecera
+    pub fn positional_columns(count: usize) -> Vec<String> {
+        (1..=count).map(|i| format!("column{}", i)).collect()
+    }
+
+    /// Primeras `limit` filas, para elegir el mapeo de columnas antes de importar
+    pub fn preview(mut self, limit: usize) -> ImportPreview {
+        let total_rows = self.rows.len();
+        self.rows.truncate(limit);
+        ImportPreview { columns: self.columns, rows: self.rows, total_rows }
+    }
+}
+
+/// Muestra del fichero a importar
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub total_rows: usize,
+}
+
+/// Qué columna del fichero alimenta cada columna de la tabla
+#[derive(Debug, Clone)]
+pub struct ImportPlan {
+    columns: Vec<(usize, ColumnSchema)>,
+    dialect: SqlDialect,
+}
+
+impl ImportPlan {
+    pub fn new(
+        source: &ImportSource,
+        table_columns: &[ColumnSchema],
+        mapping: &[ColumnMapping],
+        dialect: SqlDialect,
+    ) -> Result<Self, DomainError> {
+        let find_target = |name: &str| {
+            table_columns
+                .iter()
+                .find(|c| c.name == name)
+                .or_else(|| table_columns.iter().find(|c| c.name.eq_ignore_ascii_case(name)))
+        };
+
+        let mut columns = Vec::new();
+        if !mapping.is_empty() {
+            for entry in mapping {
+                let index = source
+                    .columns
+                    .iter()
+                    .position(|c| c == &entry.source)
+                    .ok_or_else(|| DomainError::validation(format!("The file has no column {}", entry.source)))?;
+                let target = find_target(&entry.target)
+                    .ok_or_else(|| DomainError::validation(format!("The table has no column {}", entry.target)))?;
+                columns.push((index, target.clone()));
+            }
+        } else if source.named {
+            for (index, name) in source.columns.iter().enumerate() {
+                if let Some(target) = find_target(name) {
+                    columns.push((index, target.clone()));
+                }
+            }
+        } else {
+            let mut ordered = table_columns.to_vec();
+            ordered.sort_by_key(|c| c.ordinal_position);
+            columns = ordered.into_iter().take(source.columns.len()).enumerate().collect();
+        }
+
+        if columns.is_empty() {
+            return Err(DomainError::validation("None of the file's columns match a column of the table"));
+        }
+        for (i, (_, column)) in columns.iter().enumerate() {
+            if columns[..i].iter().any(|(_, other)| other.name == column.name) {
+                return Err(DomainError::validation(format!("Column {} is loaded from more than one file column", column.name)));
+            }
+        }
+        Ok(Self { columns, dialect })
+    }
+
+    /// Columnas de la tabla que se cargan, en el orden de los valores de cada fila
+    pub fn columns(&self) -> Vec<ColumnSchema> {
+        self.columns.iter().map(|(_, column)| column.clone()).collect()
+    }
+
+    /// Fila del fichero convertida al tipo de cada columna; las celdas que faltan son NULL
+    pub fn convert(&self, row: &[Value]) -> Result<Vec<BindValue>, DomainError> {
+        self.columns
+            .iter()
+            .map(|(index, column)| BindValue::from_json(row.get(*index).unwrap_or(&Value::Null), column, self.dialect))
+            .collect()
+    }
+}
+
+/// Tabla destino de una importación, con lo necesario para generar la escritura de cada lote
+#[derive(Debug, Clone)]
+pub struct ImportTarget {
+    pub schema: Option<String>,
+    pub table: String,
+    pub dialect: SqlDialect,
+    /// Columnas que se cargan, en el orden de los valores de cada fila
+    pub columns: Vec<ColumnSchema>,
+    /// Clave primaria, necesaria para sobrescribir duplicados
+    pub key_columns: Vec<String>,
+    pub on_duplicate: DuplicateMode,
+}
+
+impl ImportTarget {
+    pub fn qualified_table(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", self.dialect.quote_identifier(schema), self.dialect.quote_identifier(&self.table)),
+            None => self.dialect.quote_identifier(&self.table),
+        }
+    }
+
+    /// Columnas entre comillas, separadas por comas
+    pub fn column_list(&self) -> String {
+        self.columns
+            .iter()
+            .map(|c| self.dialect.quote_identifier(&c.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Filas por sentencia para no pasar del límite de marcadores
+    pub fn rows_per_statement(&self) -> usize {
+        (MAX_STATEMENT_PARAMS / self.columns.len().max(1)).max(1)
+    }
+
+    /// `INSERT` de `rows` filas; `marker(n)` da el marcador del valor `n` (desde 1, fila a fila)
+    pub fn insert_sql(&self, rows: usize, marker: impl Fn(usize) -> String) -> Result<String, DomainError> {
+        let width = self.columns.len();
+        let values: Vec<String> = (0..rows)
+            .map(|row| {
+                let markers: Vec<String> = (1..=width).map(|col| marker(row * width + col)).collect();
+                format!("({})", markers.join(", "))
+            })
+            .collect();
+        Ok(format!(
+            "INSERT INTO {} ({}) VALUES {}{}",
+            self.qualified_table(),
+            self.column_list(),
+            values.join(", "),
+            self.conflict_clause()?
+        ))
+    }
+
+    /// Cláusula que aplica la política de duplicados al final del `INSERT`
+    pub fn conflict_clause(&self) -> Result<String, DomainError> {
+        let quote = |name: &str| self.dialect.quote_identifier(name);
+        let updated: Vec<&ColumnSchema> = self.columns.iter().filter(|c| !self.key_columns.contains(&c.name)).collect();
+        match (self.on_duplicate, self.dialect) {
+            (DuplicateMode::Abort, _) => Ok(String::new()),
+            (DuplicateMode::Upsert, _) if self.key_columns.is_empty() => {
+                Err(DomainError::validation(format!("{} has no primary key: duplicates cannot be updated", self.table)))
+            }
+            (DuplicateMode::Skip, SqlDialect::Postgres | SqlDialect::Sqlite) => Ok(" ON CONFLICT DO NOTHING".to_string()),
+            (DuplicateMode::Upsert, SqlDialect::Postgres | SqlDialect::Sqlite) if updated.is_empty() => {
+                Ok(" ON CONFLICT DO NOTHING".to_string())
+            }
+            (DuplicateMode::Upsert, SqlDialect::Postgres | SqlDialect::Sqlite) => {
+                let keys: Vec<String> = self.key_columns.iter().map(|k| quote(k)).collect();
+                let sets: Vec<String> = updated.iter().map(|c| format!("{0} = excluded.{0}", quote(&c.name))).collect();
+                Ok(format!(" ON CONFLICT ({}) DO UPDATE SET {}", keys.join(", "), sets.join(", ")))
+            }
+            // MySQL no tiene "no hacer nada": se asigna una columna a sí misma
+            (DuplicateMode::Skip, SqlDialect::MySql) => {
+                Ok(format!(" ON DUPLICATE KEY UPDATE {0} = {0}", quote(&self.columns[0].name)))
+            }
+            (DuplicateMode::Upsert, SqlDialect::MySql) if updated.is_empty() => {
+                Ok(format!(" ON DUPLICATE KEY UPDATE {0} = {0}", quote(&self.columns[0].name)))
+            }
+            (DuplicateMode::Upsert, SqlDialect::MySql) => {
+                let sets: Vec<String> = updated.iter().map(|c| format!("{0} = VALUES({0})", quote(&c.name))).collect();
+                Ok(format!(" ON DUPLICATE KEY UPDATE {}", sets.join(", ")))
+            }
+            _ => Err(DomainError::validation(
+                "Skipping or updating duplicate rows is only supported on PostgreSQL, MySQL, MariaDB and SQLite",
+            )),
+        }
+    }
+}
+
+/// Fila que no se importó
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRowError {
+    /// Número de registro en el fichero, desde 1 y sin contar la cabecera
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl ImportRowError {
+    pub fn new(row: usize, error: DomainError) -> Self {
+        let column = match &error {
+            DomainError::ValidationError { column, .. } => column.clone(),
+            _ => None,
+        };
+        Self { row, column, message: error.to_string() }
+    }
+}
+
+/// Resultado de una importación. El avance se emite como eventos `ImportProgress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub import_id: Uuid,
+    /// Registros leídos del fichero
+    pub total_rows: usize,
+    /// Filas insertadas o actualizadas según el servidor; MySQL cuenta doble las actualizadas
+    pub written_rows: u64,
+    /// Registros descartados por error
+    pub failed_rows: usize,
+    /// Los primeros errores, con la fila y la columna que los causó
+    pub errors: Vec<ImportRowError>,
+    /// Se detuvo en un error sin llegar al final del fichero
+    pub aborted: bool,
+    /// Lo escrito quedó confirmado. Es `false` si se deshizo o si la importación se unió
+    /// a una transacción manual abierta, que sigue pendiente
+    pub committed: bool,
+    pub execution_time_ms: u64,
+}
+
+impl ImportSummary {
+    pub fn new(import_id: Uuid, total_rows: usize) -> Self {
+        Self {
+            import_id,
+            total_rows,
+            written_rows: 0,
+            failed_rows: 0,
+            errors: Vec::new(),
+            aborted: false,
+            committed: false,
+            execution_time_ms: 0,
+        }
+    }
+
+    pub fn record_failure(&mut self, row: usize, error: DomainError) {
+        self.failed_rows += 1;
+        if self.errors.len() < MAX_REPORTED_IMPORT_ERRORS {
+            self.errors.push(ImportRowError::new(row, error));
+        }
+    }
+}
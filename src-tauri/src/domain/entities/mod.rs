@@ -1,11 +1,33 @@
+mod bind_value;
 mod connection;
+mod ddl;
+mod dry_run;
+mod dump;
+mod export;
+mod import;
 mod query;
+mod query_params;
+mod query_plan;
+mod risk;
+mod row_edit;
 mod saved_query;
 mod schema;
+mod script;
 mod workspace;
 
+pub use bind_value::*;
 pub use connection::*;
+pub use ddl::*;
+pub use dry_run::*;
+pub use dump::*;
+pub use export::*;
+pub use import::*;
 pub use query::*;
+pub use query_params::*;
+pub use query_plan::*;
+pub use risk::*;
+pub use row_edit::*;
 pub use saved_query::*;
 pub use schema::*;
+pub use script::*;
 pub use workspace::*;
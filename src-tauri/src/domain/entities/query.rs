@@ -1,7 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use uuid::Uuid;
 
+use super::QueryParams;
+
 /// Resultado de una query SQL
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResult {
@@ -46,6 +49,11 @@ impl QueryResult {
         self.pagination = Some(pagination);
         self
     }
+
+    pub fn with_id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
 }
 
 /// Información de una columna
@@ -103,6 +111,12 @@ pub struct PaginationInfo {
     pub total_pages: Option<u32>,
     pub has_next: bool,
     pub has_previous: bool,
+    /// true si total_rows es una estimación del planificador
+    #[serde(default)]
+    pub total_is_estimate: bool,
+    /// Token para pedir la página siguiente sin repetir la query desde el principio
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 impl PaginationInfo {
@@ -114,6 +128,8 @@ impl PaginationInfo {
             total_pages: None,
             has_next: false,
             has_previous: page > 1,
+            total_is_estimate: false,
+            cursor: None,
         }
     }
 
@@ -123,6 +139,62 @@ impl PaginationInfo {
         self.has_next = self.page < self.total_pages.unwrap_or(1);
         self
     }
+
+    /// Total aproximado; no se usa para calcular has_next
+    pub fn with_estimated_total(mut self, total_rows: u64) -> Self {
+        self.total_rows = Some(total_rows);
+        self.total_pages = Some((total_rows as f64 / self.page_size as f64).ceil() as u32);
+        self.total_is_estimate = true;
+        self
+    }
+
+    pub fn with_has_next(mut self, has_next: bool) -> Self {
+        self.has_next = has_next;
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+}
+
+/// Cómo calcular el total de filas de una query paginada
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountMode {
+    /// No calcular el total
+    None,
+    /// Estimación del planificador (EXPLAIN)
+    #[default]
+    Estimated,
+    /// COUNT(*) sobre la query
+    Exact,
+}
+
+/// Petición de una página de resultados
+#[derive(Debug, Clone)]
+pub struct PageRequest {
+    pub page: u32,
+    pub page_size: u32,
+    /// Token devuelto en PaginationInfo.cursor por la página anterior
+    pub cursor: Option<String>,
+    pub count_mode: CountMode,
+}
+
+impl PageRequest {
+    pub fn new(page: u32, page_size: u32) -> Self {
+        Self {
+            page: page.max(1),
+            page_size: page_size.max(1),
+            cursor: None,
+            count_mode: CountMode::default(),
+        }
+    }
+
+    pub fn offset(&self) -> u64 {
+        (self.page as u64 - 1) * self.page_size as u64
+    }
 }
 
 /// Entrada en el historial de queries
@@ -173,4 +245,233 @@ pub struct ExecuteQueryDto {
     pub query: String,
     pub page: Option<u32>,
     pub page_size: Option<u32>,
+    /// Id de la ejecución; si no se indica se genera uno nuevo
+    pub query_id: Option<Uuid>,
+    /// Cursor de paginación devuelto por la página anterior
+    pub cursor: Option<String>,
+    pub count_mode: Option<CountMode>,
+    /// Valores de los marcadores `:name`, `$n` o `?` de la query
+    #[serde(default)]
+    pub params: Option<QueryParams>,
+    /// Límite de esta ejecución en ms; sustituye al de la conexión (0 sin límite)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Token de ConfirmationRequired con el que se confirma una ejecución peligrosa
+    #[serde(default)]
+    pub confirmation_token: Option<String>,
+}
+
+impl ExecuteQueryDto {
+    pub fn new(connection_id: Uuid, query: String, options: QueryOptions) -> Self {
+        Self {
+            connection_id,
+            query,
+            page: options.page,
+            page_size: options.page_size,
+            query_id: options.query_id,
+            cursor: options.cursor,
+            count_mode: options.count_mode,
+            params: options.params,
+            timeout_ms: options.timeout_ms,
+            confirmation_token: options.confirmation_token,
+        }
+    }
+}
+
+/// Opciones de `execute_query` que envía el frontend; todas opcionales
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueryOptions {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    pub query_id: Option<Uuid>,
+    pub cursor: Option<String>,
+    pub count_mode: Option<CountMode>,
+    pub params: Option<QueryParams>,
+    pub timeout_ms: Option<u64>,
+    pub confirmation_token: Option<String>,
+}
+
+/// Opciones de una ejecución individual en el driver
+#[derive(Debug, Clone)]
+pub struct ExecutionOptions {
+    /// Identificador de la ejecución, usado para cancelarla
+    pub query_id: Uuid,
+    /// Valores de los marcadores de la query, ya en la sintaxis del driver y en orden de enlace
+    pub params: Vec<CellValue>,
+    /// Tiempo máximo de ejecución; el driver lo aplica también en el servidor si el motor lo permite
+    pub timeout: Option<Duration>,
+}
+
+impl ExecutionOptions {
+    pub fn new(query_id: Uuid) -> Self {
+        Self { query_id, params: Vec::new(), timeout: None }
+    }
+
+    pub fn with_params(mut self, params: Vec<CellValue>) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for ExecutionOptions {
+    fn default() -> Self {
+        Self::new(Uuid::new_v4())
+    }
+}
+
+/// Opciones de una ejecución en streaming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamOptions {
+    /// Filas por lote enviado al frontend
+    pub batch_size: usize,
+    /// Tope de filas a leer; al alcanzarlo se detiene el stream
+    pub max_rows: Option<u64>,
+    /// Lotes enviados que el frontend aún no ha confirmado antes de pausar la lectura
+    pub max_pending_batches: usize,
+    /// Lectura sin pérdida para exportaciones y volcados: la query va sin parámetros por el
+    /// protocolo de texto, lo que el grid no sabe tipar conserva el texto del servidor y un
+    /// valor ilegible falla la ejecución en vez de quedar en NULL
+    pub exact_values: bool,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 500,
+            max_rows: None,
+            max_pending_batches: 4,
+            exact_values: false,
+        }
+    }
+}
+
+/// Lote de filas de una ejecución en streaming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowBatch {
+    pub batch_index: usize,
+    /// Solo viene en el primer lote
+    pub columns: Option<Vec<ColumnInfo>>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+/// Resumen de una ejecución en streaming
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryStreamSummary {
+    pub query_id: Uuid,
+    pub query: String,
+    pub columns: Vec<ColumnInfo>,
+    pub row_count: u64,
+    pub batch_count: usize,
+    /// true si el stream se detuvo antes de leer todas las filas (tope o parada del usuario)
+    pub truncated: bool,
+    pub execution_time_ms: u64,
+}
+
+/// Query en ejecución sobre una conexión
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningQueryInfo {
+    pub query_id: Uuid,
+    pub connection_id: Uuid,
+    pub query: String,
+    pub started_at: DateTime<Utc>,
+}
+
+impl RunningQueryInfo {
+    pub fn new(query_id: Uuid, connection_id: Uuid, query: String) -> Self {
+        Self {
+            query_id,
+            connection_id,
+            query,
+            started_at: Utc::now(),
+        }
+    }
+}
+
+/// Nivel de aislamiento de una transacción manual
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Opciones al abrir una transacción manual
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionOptions {
+    /// Sin nivel se usa el que tenga configurado el servidor
+    #[serde(default)]
+    pub isolation_level: Option<IsolationLevel>,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Transacción manual abierta en una conexión
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionSession {
+    pub transaction_id: Uuid,
+    pub connection_id: Uuid,
+    /// Nivel pedido al abrirla; None si se usa el del servidor
+    pub isolation_level: Option<IsolationLevel>,
+    pub read_only: bool,
+    /// Savepoints activos, del más antiguo al más reciente
+    pub savepoints: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    /// Ejecución dueña de la transacción (un script en modo transacción, por ejemplo);
+    /// None si es manual. Mientras tenga dueño, el resto de ejecuciones de la conexión se rechazan
+    #[serde(default)]
+    pub owner: Option<Uuid>,
+}
+
+impl TransactionSession {
+    pub fn new(connection_id: Uuid, options: &TransactionOptions) -> Self {
+        Self {
+            transaction_id: Uuid::new_v4(),
+            connection_id,
+            isolation_level: options.isolation_level,
+            read_only: options.read_only,
+            savepoints: Vec::new(),
+            started_at: Utc::now(),
+            owner: None,
+        }
+    }
+
+    pub fn owned_by(mut self, owner: Uuid) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Segundos desde que se abrió
+    pub fn open_for_secs(&self) -> u64 {
+        (Utc::now() - self.started_at).num_seconds().max(0) as u64
+    }
+}
+
+/// Motivo de un aviso sobre una transacción manual
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionWarningReason {
+    /// Lleva abierta más de lo recomendado y puede estar bloqueando filas
+    LongRunning,
+    /// Se intentó desconectar con cambios sin confirmar
+    UncommittedOnDisconnect,
 }
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::CellValue;
+use crate::domain::error::DomainError;
+
+/// Sintaxis de un marcador de parámetro en el texto SQL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaceholderStyle {
+    /// `:nombre`
+    Named,
+    /// `$1`, `$2`...
+    Numbered,
+    /// `?`
+    Positional,
+}
+
+/// Parámetro que necesita una query para ejecutarse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParameter {
+    /// Nombre sin prefijo; en `$n` y `?` es la posición (desde 1)
+    pub name: String,
+    pub style: PlaceholderStyle,
+    /// Veces que aparece en el texto
+    pub occurrences: usize,
+}
+
+/// Valores de los parámetros: una lista por posición o un objeto por nombre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum QueryParams {
+    Positional(Vec<CellValue>),
+    Named(HashMap<String, CellValue>),
+}
+
+impl QueryParams {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            QueryParams::Positional(values) => values.is_empty(),
+            QueryParams::Named(values) => values.is_empty(),
+        }
+    }
+}
+
+/// Query con los marcadores traducidos a la sintaxis del driver y los valores en orden de enlace
+#[derive(Debug, Clone)]
+pub struct BoundQuery {
+    pub sql: String,
+    pub values: Vec<CellValue>,
+}
+
+impl BoundQuery {
+    /// Sustituye cada marcador por el que genera `placeholder(índice desde 1, valor)`.
+    /// Un parámetro que aparece varias veces se enlaza una vez por aparición.
+    /// Sin valores la query se deja intacta (un `?` puede ser un operador del motor).
+    pub fn bind(
+        sql: &str,
+        params: Option<&QueryParams>,
+        placeholder: impl Fn(usize, &CellValue) -> String,
+    ) -> Result<Self, DomainError> {
+        let params = match params {
+            Some(p) if !p.is_empty() => p,
+            _ => return Ok(Self { sql: sql.to_string(), values: vec![] }),
+        };
+        let found = scan(sql);
+        let parameters = describe(&found)?;
+
+        if let QueryParams::Positional(values) = params {
+            // `$n` toma el valor n-ésimo aunque aparezcan desordenados o con huecos
+            let expected = match parameters.first().map(|p| p.style) {
+                Some(PlaceholderStyle::Numbered) => {
+                    parameters.iter().filter_map(|p| p.name.parse().ok()).max().unwrap_or(0)
+                }
+                _ => parameters.len(),
+            };
+            if values.len() != expected {
+                return Err(DomainError::validation(format!(
+                    "Expected {} parameter values, got {}",
+                    expected,
+                    values.len()
+                )));
+            }
+        }
+
+        let mut out = String::with_capacity(sql.len());
+        let mut values = Vec::with_capacity(found.len());
+        let mut last = 0;
+        for p in &found {
+            let value = match params {
+                QueryParams::Positional(list) => match p.style {
+                    PlaceholderStyle::Numbered => p.name.parse::<usize>().ok().and_then(|n| list.get(n.checked_sub(1)?)),
+                    _ => parameters.iter().position(|q| q.name == p.name).and_then(|i| list.get(i)),
+                },
+                QueryParams::Named(map) => map.get(&p.name),
+            }
+            .ok_or_else(|| DomainError::validation(format!("Missing value for parameter {}", p.label())))?;
+
+            out.push_str(&sql[last..p.start]);
+            out.push_str(&placeholder(values.len() + 1, value));
+            values.push(value.clone());
+            last = p.end;
+        }
+        out.push_str(&sql[last..]);
+
+        Ok(Self { sql: out, values })
+    }
+}
+
+/// Parámetros de la query en orden de primera aparición.
+/// Los operadores JSONB `?|` y `?&`, los casts `::` y las asignaciones `:=` no cuentan.
+pub fn detect_parameters(sql: &str) -> Result<Vec<QueryParameter>, DomainError> {
+    describe(&scan(sql))
+}
+
+fn describe(found: &[Placeholder]) -> Result<Vec<QueryParameter>, DomainError> {
+    if let Some(first) = found.first() {
+        if found.iter().any(|p| p.style != first.style) {
+            return Err(DomainError::validation("Cannot mix :name, $n and ? placeholders in the same query"));
+        }
+    }
+
+    let mut parameters: Vec<QueryParameter> = Vec::new();
+    for p in found {
+        match parameters.iter_mut().find(|q| q.name == p.name) {
+            Some(existing) => existing.occurrences += 1,
+            None => parameters.push(QueryParameter { name: p.name.clone(), style: p.style, occurrences: 1 }),
+        }
+    }
+    Ok(parameters)
+}
+
+/// Marcador encontrado en el texto, con su rango en bytes
+struct Placeholder {
+    start: usize,
+    end: usize,
+    style: PlaceholderStyle,
+    name: String,
+}
+
+impl Placeholder {
+    fn label(&self) -> String {
+        match self.style {
+            PlaceholderStyle::Named => format!(":{}", self.name),
+            PlaceholderStyle::Numbered => format!("${}", self.name),
+            PlaceholderStyle::Positional => format!("?{}", self.name),
+        }
+    }
+}
+
+/// Marcadores fuera de literales, identificadores entre comillas, comentarios y bloques `$tag$`
+fn scan(sql: &str) -> Vec<Placeholder> {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map(|(o, _)| *o).unwrap_or(sql.len());
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut found = Vec::new();
+    let mut positional = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i].1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let prev = i.checked_sub(1).map(|p| chars[p].1);
+        match c {
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i].1 != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i].1 == '*' && matches!(chars.get(i + 1), Some((_, '/')))) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' | '"' | '`' => {
+                i += 1;
+                while i < chars.len() {
+                    if chars[i].1 == c && matches!(chars.get(i + 1), Some((_, n)) if *n == c) {
+                        i += 2;
+                    } else if chars[i].1 == c {
+                        break;
+                    } else {
+                        i += 1;
+                    }
+                }
+                i += 1;
+            }
+            '$' if next.is_some_and(|n| n.is_ascii_digit()) && !prev.is_some_and(is_ident) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i].1.is_ascii_digit() {
+                    i += 1;
+                }
+                found.push(Placeholder {
+                    start: offset(start),
+                    end: offset(i),
+                    style: PlaceholderStyle::Numbered,
+                    name: sql[offset(start + 1)..offset(i)].trim_start_matches('0').to_string(),
+                });
+            }
+            '$' if !prev.is_some_and(is_ident) => {
+                // Bloque `$tag$ ... $tag$` de PostgreSQL
+                let mut j = i + 1;
+                while j < chars.len() && is_ident(chars[j].1) {
+                    j += 1;
+                }
+                if chars.get(j).map(|(_, c)| *c) == Some('$') {
+                    let tag = &sql[offset(i)..offset(j + 1)];
+                    i = match sql[offset(j + 1)..].find(tag) {
+                        Some(pos) => chars.partition_point(|(o, _)| *o < offset(j + 1) + pos + tag.len()),
+                        None => chars.len(),
+                    };
+                } else {
+                    i += 1;
+                }
+            }
+            ':' if next == Some(':') || next == Some('=') => i += 2,
+            ':' if next.is_some_and(|n| n.is_alphabetic() || n == '_') && !prev.is_some_and(|p| is_ident(p) || p == ':') => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && is_ident(chars[i].1) {
+                    i += 1;
+                }
+                found.push(Placeholder {
+                    start: offset(start),
+                    end: offset(i),
+                    style: PlaceholderStyle::Named,
+                    name: sql[offset(start + 1)..offset(i)].to_string(),
+                });
+            }
+            '?' if next == Some('|') || next == Some('&') => i += 2,
+            '?' => {
+                positional += 1;
+                found.push(Placeholder {
+                    start: offset(i),
+                    end: offset(i + 1),
+                    style: PlaceholderStyle::Positional,
+                    name: positional.to_string(),
+                });
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(sql: &str) -> Vec<(String, usize)> {
+        detect_parameters(sql)
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.name, p.occurrences))
+            .collect()
+    }
+
+    fn dollar(index: usize, _: &CellValue) -> String {
+        format!("${}", index)
+    }
+
+    #[test]
+    fn detects_named_parameters_in_order_of_first_use() {
+        assert_eq!(
+            names("SELECT * FROM t WHERE a = :b AND c = :a OR d = :b"),
+            vec![("b".to_string(), 2), ("a".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn ignores_casts_assignments_and_jsonb_operators() {
+        assert!(names("SELECT a::int, data ?| array['x'], data ?& array['y'] FROM t").is_empty());
+        assert!(names("SELECT @n := 1").is_empty());
+        assert!(names("SELECT '10:30'::time").is_empty());
+    }
+
+    #[test]
+    fn ignores_literals_identifiers_comments_and_dollar_blocks() {
+        let sql = "SELECT ':a', \"?\", `:b` -- :c ?\n /* $1 */ FROM t WHERE x = $body$ :d ? $body$ AND y = :e";
+        assert_eq!(names(sql), vec![("e".to_string(), 1)]);
+        assert_eq!(names("SELECT 'it''s :x' , :y"), vec![("y".to_string(), 1)]);
+    }
+
+    #[test]
+    fn numbers_positional_markers_and_strips_leading_zeros() {
+        assert_eq!(names("SELECT ?, ?"), vec![("1".to_string(), 1), ("2".to_string(), 1)]);
+        assert_eq!(names("SELECT $01, $1, $2"), vec![("1".to_string(), 2), ("2".to_string(), 1)]);
+        assert!(names("SELECT price$1 FROM t").is_empty());
+    }
+
+    #[test]
+    fn rejects_mixed_styles() {
+        assert!(detect_parameters("SELECT :a, ?").is_err());
+        assert!(detect_parameters("SELECT $1, :a").is_err());
+    }
+
+    #[test]
+    fn binds_named_values_once_per_occurrence() {
+        let params = QueryParams::Named(HashMap::from([
+            ("id".to_string(), CellValue::Int(7)),
+            ("name".to_string(), CellValue::String("x".into())),
+        ]));
+        let bound = BoundQuery::bind("SELECT :name WHERE id = :id OR parent = :id", Some(&params), dollar).unwrap();
+        assert_eq!(bound.sql, "SELECT $1 WHERE id = $2 OR parent = $3");
+        assert!(matches!(
+            bound.values.as_slice(),
+            [CellValue::String(s), CellValue::Int(7), CellValue::Int(7)] if s == "x"
+        ));
+    }
+
+    #[test]
+    fn binds_numbered_values_by_position() {
+        let params = QueryParams::Positional(vec![CellValue::Int(1), CellValue::Bool(true)]);
+        let bound = BoundQuery::bind("SELECT $2, $1, $2", Some(&params), |_, _| "?".to_string()).unwrap();
+        assert_eq!(bound.sql, "SELECT ?, ?, ?");
+        assert!(matches!(
+            bound.values.as_slice(),
+            [CellValue::Bool(true), CellValue::Int(1), CellValue::Bool(true)]
+        ));
+    }
+
+    #[test]
+    fn reports_missing_or_extra_values() {
+        let named = QueryParams::Named(HashMap::from([("a".to_string(), CellValue::Null)]));
+        let err = BoundQuery::bind("SELECT :a, :b", Some(&named), dollar).unwrap_err();
+        assert!(err.to_string().contains(":b"));
+
+        let positional = QueryParams::Positional(vec![CellValue::Null]);
+        assert!(BoundQuery::bind("SELECT ?, ?", Some(&positional), dollar).is_err());
+        let gap = QueryParams::Positional(vec![CellValue::Null, CellValue::Null]);
+        assert!(BoundQuery::bind("SELECT $1, $3", Some(&gap), dollar).is_err());
+        assert!(BoundQuery::bind("SELECT $0", Some(&positional), dollar).is_err());
+    }
+
+    #[test]
+    fn leaves_the_query_alone_without_values() {
+        let bound = BoundQuery::bind("SELECT data ? 'key' FROM t", None, dollar).unwrap();
+        assert_eq!(bound.sql, "SELECT data ? 'key' FROM t");
+        assert!(bound.values.is_empty());
+
+        let empty = QueryParams::Positional(vec![]);
+        assert_eq!(BoundQuery::bind("SELECT ?", Some(&empty), dollar).unwrap().sql, "SELECT ?");
+    }
+
+    #[test]
+    fn deserializes_lists_and_objects() {
+        let positional: QueryParams = serde_json::from_str(r#"[{"type":"Int","value":1}]"#).unwrap();
+        assert!(matches!(positional, QueryParams::Positional(v) if v.len() == 1));
+        let named: QueryParams = serde_json::from_str(r#"{"a":{"type":"Null"}}"#).unwrap();
+        assert!(matches!(named, QueryParams::Named(m) if m.contains_key("a")));
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Plan de ejecución de una sentencia, normalizado entre motores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlan {
+    pub query: String,
+    /// La sentencia se ejecutó (EXPLAIN ANALYZE) y hay datos reales además de estimaciones
+    pub analyzed: bool,
+    pub root: PlanNode,
+    pub planning_time_ms: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    /// Salida original del motor (JSON o texto)
+    pub raw: String,
+}
+
+/// Nodo del árbol del plan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanNode {
+    pub node_type: String,
+    /// Tabla, vista o CTE sobre la que opera el nodo
+    pub relation: Option<String>,
+    /// Índice, condición o cualquier otro detalle que dé el motor
+    pub detail: Option<String>,
+    pub estimated_rows: Option<f64>,
+    pub actual_rows: Option<f64>,
+    /// Coste total estimado, en las unidades del motor
+    pub estimated_cost: Option<f64>,
+    /// Tiempo real del nodo en ms, por iteración
+    pub actual_time_ms: Option<f64>,
+    pub loops: Option<u64>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    pub fn new(node_type: impl Into<String>) -> Self {
+        Self { node_type: node_type.into(), ..Default::default() }
+    }
+}
@@ -0,0 +1,852 @@
+use serde::{Deserialize, Serialize};
+
+use super::{split_script, SqlDialect};
+use crate::domain::error::DomainError;
+
+/// Gravedad de un riesgo detectado en una sentencia
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Medium,
+    High,
+    Critical,
+}
+
+/// Motivo por el que una sentencia necesita confirmación
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskKind {
+    UpdateWithoutWhere,
+    DeleteWithoutWhere,
+    /// DROP de un objeto, o ALTER que elimina parte de él
+    Drop,
+    Truncate,
+    /// Escritura en un esquema (o base) distinto del activo
+    CrossSchemaWrite,
+    /// Cualquier modificación de datos en una conexión de producción
+    ProductionWrite,
+    /// Cambio de esquema o de permisos en una conexión de producción
+    ProductionSchemaChange,
+}
+
+/// Riesgo detectado en una sentencia
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementRisk {
+    /// Posición de la sentencia en la ejecución (desde 0)
+    pub statement_index: usize,
+    pub sql: String,
+    pub kind: RiskKind,
+    pub level: RiskLevel,
+    /// Objeto afectado, tal como aparece en la sentencia
+    pub target: Option<String>,
+    pub message: String,
+}
+
+/// Contexto de la conexión con el que se evalúan las sentencias
+#[derive(Debug, Clone, Default)]
+pub struct RiskContext {
+    /// Esquema (la base en MySQL) en el que caen los nombres sin calificar
+    pub schema: Option<String>,
+    /// Base activa, para los nombres `base.esquema.tabla`
+    pub database: Option<String>,
+    /// La conexión está marcada como producción
+    pub production: bool,
+}
+
+/// Riesgos de un script, separado en sentencias con las reglas del dialecto
+pub fn assess_script(script: &str, dialect: SqlDialect, context: &RiskContext) -> Vec<StatementRisk> {
+    let mut risks = Vec::new();
+    for (index, statement) in split_script(script, dialect).iter().enumerate() {
+        assess_statement(index, &statement.sql, dialect, context, &mut risks);
+    }
+    risks
+}
+
+/// Riesgos de una lista de sentencias. Cada elemento puede traer a su vez varias
+/// sentencias (MySQL admite multi-statements); todas se atribuyen a su posición en la lista
+pub fn assess_statements<S: AsRef<str>>(statements: &[S], dialect: SqlDialect, context: &RiskContext) -> Vec<StatementRisk> {
+    let mut risks = Vec::new();
+    for (index, sql) in statements.iter().enumerate() {
+        for statement in split_script(sql.as_ref(), dialect) {
+            assess_statement(index, &statement.sql, dialect, context, &mut risks);
+        }
+    }
+    risks
+}
+
+/// Escritura que genera la propia aplicación (edición de filas, importación) sin SQL que analizar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Riesgos de una escritura generada sobre `target` (`[esquema, tabla]` o solo la tabla).
+/// `has_where` indica si las filas van filtradas; solo cuenta en UPDATE y DELETE
+pub fn assess_write(kind: WriteKind, target: &[String], has_where: bool, context: &RiskContext) -> Vec<StatementRisk> {
+    let sql = match kind {
+        WriteKind::Insert => format!("INSERT INTO {}", display(target)),
+        WriteKind::Update => format!("UPDATE {}", display(target)),
+        WriteKind::Delete => format!("DELETE FROM {}", display(target)),
+    };
+    let mut risks = Vec::new();
+    let mut analyzer = Analyzer { index: 0, sql: &sql, context, risks: &mut risks };
+    analyzer.write(kind, target, has_where);
+    analyzer.production_write(0);
+    risks
+}
+
+fn assess_statement(index: usize, sql: &str, dialect: SqlDialect, context: &RiskContext, risks: &mut Vec<StatementRisk>) {
+    let tokens = lex(sql, dialect);
+    let mut analyzer = Analyzer { index, sql, context, risks };
+    analyzer.statement(&tokens, 0);
+}
+
+/// Sentencias que escriben datos o cambian el esquema, los permisos o el modo de la sesión.
+/// Es una comprobación previa: en PostgreSQL, MySQL y SQLite la sesión ya es de solo lectura
+pub fn is_destructive(sql: &str, dialect: SqlDialect) -> bool {
+    const WRITE: [&str; 24] = [
+        "INSERT", "UPDATE", "DELETE", "MERGE", "REPLACE", "UPSERT", "DROP", "TRUNCATE", "ALTER",
+        "CREATE", "GRANT", "REVOKE", "COPY", "RENAME", "COMMENT", "VACUUM", "REINDEX", "CLUSTER",
+        "REFRESH", "LOAD", "IMPORT", "ATTACH", "DETACH", "OPTIMIZE",
+    ];
+    // Sin REPLACE: dentro de una sentencia también es una función de texto
+    const NESTED_DML: [&str; 4] = ["INSERT", "UPDATE", "DELETE", "MERGE"];
+
+    split_script(sql, dialect).iter().any(|statement| {
+        let tokens = lex(&statement.sql, dialect);
+        let Some(first) = tokens.first() else {
+            return false;
+        };
+        let has_dml = || tokens.iter().any(|t| t.is_any(&NESTED_DML));
+        // BEGIN READ WRITE, SET SESSION TRANSACTION READ WRITE, SET "default_transaction_read_only"...
+        let reopens_writes = tokens.windows(2).any(|w| w[0].is("READ") && w[1].is("WRITE"))
+            || tokens.iter().any(|t| {
+                let name = match &t.token {
+                    Token::Word(name) | Token::Quoted(name) => name.to_uppercase(),
+                    _ => return false,
+                };
+                name.ends_with("TRANSACTION_READ_ONLY") || name == "TX_READ_ONLY"
+            });
+        // set_config() cambia cualquier parámetro desde una SELECT, el modo de la sesión incluido:
+        // el nombre va en un literal, así que cuenta como escritura sea cual sea
+        let sets_config = tokens.windows(2).any(|w| w[0].is("SET_CONFIG") && w[1].token == Token::Symbol('('));
+
+        first.is_any(&WRITE)
+            || (first.is("WITH") && has_dml())
+            || (first.is("EXPLAIN") && tokens.iter().any(|t| t.is("ANALYZE")) && has_dml())
+            || reopens_writes
+            || sets_config
+    })
+}
+
+/// Rechaza lo que rompería la garantía de deshacer una simulación: control de transacciones
+/// y, en MySQL, cualquier sentencia que no sea DML (provoca un COMMIT implícito)
+pub fn check_dry_runnable(sql: &str, dialect: SqlDialect) -> Result<(), DomainError> {
+    const TRANSACTION_CONTROL: [&str; 9] =
+        ["BEGIN", "START", "COMMIT", "END", "ROLLBACK", "ABORT", "SAVEPOINT", "RELEASE", "PREPARE"];
+    const MYSQL_SAFE: [&str; 6] = ["SELECT", "WITH", "INSERT", "UPDATE", "DELETE", "REPLACE"];
+
+    for statement in split_script(sql, dialect) {
+        let tokens = lex(&statement.sql, dialect);
+        let Some(first) = tokens.first().and_then(|t| t.word()) else {
+            continue;
+        };
+        let first = first.to_uppercase();
+        let sets_transaction = first == "SET" && tokens.get(1).is_some_and(|t| t.is("TRANSACTION"));
+        if TRANSACTION_CONTROL.contains(&first.as_str()) || sets_transaction {
+            return Err(DomainError::validation(format!("{} cannot be used in a dry run", first)));
+        }
+        if dialect == SqlDialect::MySql && !MYSQL_SAFE.contains(&first.as_str()) {
+            return Err(DomainError::validation(format!(
+                "{} commits implicitly on MySQL and cannot be dry-run",
+                first
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    /// Palabra sin comillas: palabra clave o identificador
+    Word(String),
+    /// Identificador entre comillas, ya sin ellas
+    Quoted(String),
+    Symbol(char),
+    /// Literal de texto o número; solo ocupa una posición
+    Literal,
+}
+
+/// Token con su profundidad de paréntesis. Los propios paréntesis van a la profundidad exterior
+#[derive(Debug, Clone)]
+pub(super) struct Lexed {
+    pub(super) token: Token,
+    pub(super) depth: usize,
+    /// Rango del token en caracteres (no bytes) del texto original
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+impl Lexed {
+    pub(super) fn word(&self) -> Option<&str> {
+        match &self.token {
+            Token::Word(w) => Some(w),
+            _ => None,
+        }
+    }
+
+    pub(super) fn is(&self, keyword: &str) -> bool {
+        self.word().is_some_and(|w| w.eq_ignore_ascii_case(keyword))
+    }
+
+    pub(super) fn is_any(&self, keywords: &[&str]) -> bool {
+        self.word().is_some_and(|w| keywords.iter().any(|k| w.eq_ignore_ascii_case(k)))
+    }
+
+    fn name(&self) -> Option<&str> {
+        match &self.token {
+            Token::Word(w) | Token::Quoted(w) => Some(w),
+            _ => None,
+        }
+    }
+}
+
+pub(super) fn lex(sql: &str, dialect: SqlDialect) -> Vec<Lexed> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens: Vec<Lexed> = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    let push = |tokens: &mut Vec<Lexed>, token: Token, depth: usize| tokens.push(Lexed { token, depth, start: 0, end: 0 });
+
+    while i < chars.len() {
+        let start = i;
+        let lexed = tokens.len();
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '-' if next == Some('-') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' if dialect == SqlDialect::MySql => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                let mut level = 0usize;
+                while i < chars.len() {
+                    if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                        level += 1;
+                        i += 2;
+                    } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        level -= 1;
+                        i += 2;
+                        if level == 0 || !dialect.nested_comments() {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            '\'' => {
+                // E'...' admite escapes con barra también en PostgreSQL y DuckDB
+                let escapes = dialect.backslash_escapes()
+                    || (i > 0 && chars[i - 1].eq_ignore_ascii_case(&'e') && tokens.last().is_some_and(|t| t.is("E")));
+                i += 1;
+                while i < chars.len() {
+                    match chars[i] {
+                        '\\' if escapes => i += 2,
+                        '\'' if chars.get(i + 1) == Some(&'\'') => i += 2,
+                        '\'' => break,
+                        _ => i += 1,
+                    }
+                }
+                i += 1;
+                push(&mut tokens, Token::Literal, depth);
+            }
+            '"' => {
+                let (name, end) = quoted(&chars, i, '"');
+                push(&mut tokens, Token::Quoted(name), depth);
+                i = end;
+            }
+            '`' if dialect.backtick_quotes() => {
+                let (name, end) = quoted(&chars, i, '`');
+                push(&mut tokens, Token::Quoted(name), depth);
+                i = end;
+            }
+            '[' if dialect.bracket_quotes() => {
+                let (name, end) = quoted(&chars, i, ']');
+                push(&mut tokens, Token::Quoted(name), depth);
+                i = end;
+            }
+            '$' if dialect.dollar_quoting() => {
+                let tag_len = chars[i + 1..]
+                    .iter()
+                    .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                    .filter(|&n| chars.get(i + 1 + n) == Some(&'$'));
+                match tag_len {
+                    // $tag$ ... $tag$
+                    Some(n) if !chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) => {
+                        let tag: Vec<char> = chars[i..i + n + 2].to_vec();
+                        i += tag.len();
+                        while i < chars.len() && !chars[i..].starts_with(&tag) {
+                            i += 1;
+                        }
+                        i += tag.len();
+                    }
+                    // $1: parámetro
+                    _ => {
+                        i += 1;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                push(&mut tokens, Token::Literal, depth);
+            }
+            '(' => {
+                push(&mut tokens, Token::Symbol('('), depth);
+                depth += 1;
+                i += 1;
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                push(&mut tokens, Token::Symbol(')'), depth);
+                i += 1;
+            }
+            c if c.is_ascii_digit() => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.') {
+                    i += 1;
+                }
+                push(&mut tokens, Token::Literal, depth);
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '@' || c == '#' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '@' | '#')) {
+                    i += 1;
+                }
+                push(&mut tokens, Token::Word(chars[start..i].iter().collect()), depth);
+            }
+            c => {
+                push(&mut tokens, Token::Symbol(c), depth);
+                i += 1;
+            }
+        }
+        if let Some(token) = tokens.get_mut(lexed) {
+            token.start = start;
+            token.end = i.min(chars.len());
+        }
+    }
+    tokens
+}
+
+/// Identificador entre `open` y su cierre; el cierre duplicado es un escape.
+/// Devuelve el nombre y la posición tras el cierre
+fn quoted(chars: &[char], start: usize, close: char) -> (String, usize) {
+    let mut name = String::new();
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == close {
+            if chars.get(i + 1) == Some(&close) {
+                name.push(close);
+                i += 2;
+                continue;
+            }
+            return (name, i + 1);
+        }
+        name.push(chars[i]);
+        i += 1;
+    }
+    (name, i)
+}
+
+/// Palabras que pueden preceder al objeto en DROP, TRUNCATE y ALTER
+const OBJECT_KIND_WORDS: [&str; 28] = [
+    "TABLE", "VIEW", "MATERIALIZED", "INDEX", "SCHEMA", "DATABASE", "SEQUENCE", "FUNCTION", "PROCEDURE",
+    "TRIGGER", "TYPE", "TEMPORARY", "TEMP", "DICTIONARY", "USER", "ROLE", "EXTENSION", "DOMAIN", "EVENT",
+    "FOREIGN", "UNLOGGED", "CONCURRENTLY", "IF", "EXISTS", "ONLY", "ONLINE", "LOGIN", "SYNONYM",
+];
+
+/// Sentencias que modifican datos
+const DML: [&str; 6] = ["INSERT", "UPDATE", "DELETE", "MERGE", "REPLACE", "UPSERT"];
+
+struct Analyzer<'a> {
+    index: usize,
+    sql: &'a str,
+    context: &'a RiskContext,
+    risks: &'a mut Vec<StatementRisk>,
+}
+
+impl Analyzer<'_> {
+    /// Analiza la sentencia que empieza en `tokens[0]`, cuyo nivel superior está a `depth`
+    fn statement(&mut self, tokens: &[Lexed], depth: usize) {
+        let Some(first) = tokens.first().and_then(|t| t.word()) else {
+            return;
+        };
+        let found = self.risks.len();
+        match first.to_uppercase().as_str() {
+            "WITH" => return self.with_clause(tokens, depth),
+            "EXPLAIN" => return self.explain(tokens, depth),
+            "UPDATE" => {
+                let target = qualified_name(tokens, update_target(tokens));
+                self.write(WriteKind::Update, &target, has_where(tokens, depth));
+            }
+            "DELETE" => {
+                let target = qualified_name(tokens, delete_target(tokens));
+                self.write(WriteKind::Delete, &target, has_where(tokens, depth));
+            }
+            "INSERT" | "REPLACE" | "UPSERT" | "MERGE" => {
+                const MODIFIERS: [&str; 12] = [
+                    "INTO", "LOW_PRIORITY", "DELAYED", "HIGH_PRIORITY", "IGNORE", "OR", "REPLACE", "ROLLBACK",
+                    "ABORT", "FAIL", "OVERWRITE", "TABLE",
+                ];
+                let start = skip_words(tokens, 1, &MODIFIERS);
+                let target = qualified_name(tokens, skip_top(tokens, start, &MODIFIERS));
+                self.write(WriteKind::Insert, &target, false);
+            }
+            "DROP" => {
+                let (kind, start) = object_kind(tokens, 1);
+                let target = qualified_name(tokens, start);
+                let message = format!("DROP {} cannot be undone", object(&kind, &target));
+                self.push(RiskKind::Drop, RiskLevel::Critical, &target, message);
+            }
+            "TRUNCATE" => {
+                let (_, start) = object_kind(tokens, 1);
+                let target = qualified_name(tokens, start);
+                let message = format!("TRUNCATE removes every row of {}", display(&target));
+                self.push(RiskKind::Truncate, RiskLevel::Critical, &target, message);
+            }
+            "ALTER" => {
+                let (kind, start) = object_kind(tokens, 1);
+                let target = qualified_name(tokens, start);
+                let drops = tokens[start..].iter().any(|t| t.depth == depth && t.is("DROP"));
+                if drops {
+                    let message = format!("ALTER {} drops part of the object", object(&kind, &target));
+                    self.push(RiskKind::Drop, RiskLevel::High, &target, message);
+                }
+                self.check_cross_schema(&target);
+                if self.risks.len() == found && self.context.production {
+                    let message = "Changes the schema on a production connection".to_string();
+                    self.push(RiskKind::ProductionSchemaChange, RiskLevel::High, &target, message);
+                }
+                return;
+            }
+            "CREATE" | "GRANT" | "REVOKE" | "RENAME" | "COMMENT" | "DENY" => {
+                if self.context.production {
+                    let message = format!("{} on a production connection", first.to_uppercase());
+                    self.push(RiskKind::ProductionSchemaChange, RiskLevel::High, &[], message);
+                }
+                return;
+            }
+            _ => return,
+        }
+
+        self.production_write(found);
+    }
+
+    /// Cualquier escritura sin otro riesgo (desde `found`) sigue necesitando confirmación en producción
+    fn production_write(&mut self, found: usize) {
+        if self.risks.len() == found && self.context.production {
+            let message = "Modifies data on a production connection".to_string();
+            self.push(RiskKind::ProductionWrite, RiskLevel::Medium, &[], message);
+        }
+    }
+
+    /// `WITH ... AS (DELETE ...) SELECT ...`: se analizan las CTE que escriben y la sentencia principal
+    fn with_clause(&mut self, tokens: &[Lexed], depth: usize) {
+        let mut i = 1;
+        while i < tokens.len() {
+            let t = &tokens[i];
+            if t.depth == depth && t.token == Token::Symbol('(') {
+                let close = tokens[i + 1..]
+                    .iter()
+                    .position(|t| t.depth == depth && t.token == Token::Symbol(')'))
+                    .map_or(tokens.len(), |p| i + 1 + p);
+                let inner = &tokens[i + 1..close];
+                if inner.first().is_some_and(|t| t.is_any(&DML)) {
+                    self.statement(inner, depth + 1);
+                }
+                i = close + 1;
+                continue;
+            }
+            if t.depth == depth && t.is_any(&["SELECT", "INSERT", "UPDATE", "DELETE", "MERGE", "VALUES", "TABLE"]) {
+                if t.is_any(&DML) {
+                    self.statement(&tokens[i..], depth);
+                }
+                return;
+            }
+            i += 1;
+        }
+    }
+
+    /// EXPLAIN ANALYZE ejecuta la sentencia, así que se evalúa como si se ejecutara sin más
+    fn explain(&mut self, tokens: &[Lexed], depth: usize) {
+        let Some(start) = tokens.iter().position(|t| {
+            t.depth == depth && t.is_any(&["SELECT", "WITH", "INSERT", "UPDATE", "DELETE", "MERGE", "REPLACE"])
+        }) else {
+            return;
+        };
+        if tokens[..start].iter().any(|t| t.is("ANALYZE")) {
+            self.statement(&tokens[start..], depth);
+        }
+    }
+
+    fn write(&mut self, kind: WriteKind, target: &[String], has_where: bool) {
+        match kind {
+            WriteKind::Update if !has_where => {
+                let message = format!("UPDATE without WHERE modifies every row of {}", display(target));
+                self.push(RiskKind::UpdateWithoutWhere, RiskLevel::Critical, target, message);
+            }
+            WriteKind::Delete if !has_where => {
+                let message = format!("DELETE without WHERE removes every row of {}", display(target));
+                self.push(RiskKind::DeleteWithoutWhere, RiskLevel::Critical, target, message);
+            }
+            _ => {}
+        }
+        self.check_cross_schema(target);
+    }
+
+    /// Escritura sobre un objeto calificado con otro esquema u otra base que la activa
+    fn check_cross_schema(&mut self, target: &[String]) {
+        let n = target.len();
+        let outside = |part: usize, active: &Option<String>| {
+            n > part && active.as_ref().is_some_and(|a| !a.eq_ignore_ascii_case(&target[n - 1 - part]))
+        };
+        let message = if outside(2, &self.context.database) {
+            format!("Writes to {}, outside the active database", display(target))
+        } else if outside(1, &self.context.schema) {
+            format!(
+                "Writes to {}, outside the active schema {}",
+                display(target),
+                self.context.schema.as_deref().unwrap_or_default()
+            )
+        } else {
+            return;
+        };
+        self.push(RiskKind::CrossSchemaWrite, RiskLevel::High, target, message);
+    }
+
+    fn push(&mut self, kind: RiskKind, level: RiskLevel, target: &[String], message: String) {
+        self.risks.push(StatementRisk {
+            statement_index: self.index,
+            sql: self.sql.to_string(),
+            kind,
+            level,
+            target: (!target.is_empty()).then(|| display(target)),
+            message,
+        });
+    }
+}
+
+/// Posición de la tabla en un `UPDATE` que empieza en `tokens[0]`
+pub(super) fn update_target(tokens: &[Lexed]) -> usize {
+    const MODIFIERS: [&str; 3] = ["ONLY", "LOW_PRIORITY", "IGNORE"];
+    let start = skip_top(tokens, skip_words(tokens, 1, &MODIFIERS), &MODIFIERS);
+    // UPDATE OR REPLACE (SQLite)
+    if tokens.get(start).is_some_and(|t| t.is("OR")) {
+        start + 2
+    } else {
+        start
+    }
+}
+
+/// Posición de la tabla en un `DELETE` que empieza en `tokens[0]`
+pub(super) fn delete_target(tokens: &[Lexed]) -> usize {
+    const MODIFIERS: [&str; 5] = ["LOW_PRIORITY", "QUICK", "IGNORE", "FROM", "ONLY"];
+    skip_top(tokens, skip_words(tokens, 1, &MODIFIERS), &MODIFIERS)
+}
+
+/// WHERE en el nivel superior de la sentencia
+fn has_where(tokens: &[Lexed], depth: usize) -> bool {
+    tokens.iter().any(|t| t.depth == depth && t.is("WHERE"))
+}
+
+/// Avanza sobre las palabras de `words` desde `start`
+fn skip_words(tokens: &[Lexed], mut start: usize, words: &[&str]) -> usize {
+    while tokens.get(start).is_some_and(|t| t.is_any(words)) {
+        start += 1;
+    }
+    start
+}
+
+/// Avanza sobre un `TOP (n) [PERCENT]` de SQL Server y las palabras que lo sigan
+fn skip_top(tokens: &[Lexed], start: usize, words: &[&str]) -> usize {
+    if !tokens.get(start).is_some_and(|t| t.is("TOP")) {
+        return start;
+    }
+    let depth = tokens[start].depth;
+    let mut i = start + 1;
+    if tokens.get(i).is_some_and(|t| t.token == Token::Symbol('(')) {
+        i = tokens[i + 1..]
+            .iter()
+            .position(|t| t.depth == depth && t.token == Token::Symbol(')'))
+            .map_or(tokens.len(), |p| i + 2 + p);
+    } else {
+        i += 1;
+    }
+    skip_words(tokens, skip_words(tokens, i, &["PERCENT"]), words)
+}
+
+/// Tipo de objeto (`MATERIALIZED VIEW`, `TABLE IF EXISTS`...) y posición donde empieza su nombre
+fn object_kind(tokens: &[Lexed], start: usize) -> (String, usize) {
+    let end = skip_words(tokens, start, &OBJECT_KIND_WORDS);
+    let kind = tokens[start..end]
+        .iter()
+        .filter(|t| !t.is_any(&["IF", "EXISTS", "CONCURRENTLY", "ONLY", "ONLINE"]))
+        .filter_map(|t| t.word())
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (kind, end)
+}
+
+/// Nombre calificado (`base.esquema.tabla`) que empieza en `start`; vacío si no hay nombre
+pub(super) fn qualified_name(tokens: &[Lexed], start: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let Some(first) = tokens.get(start).and_then(|t| t.name()) else {
+        return parts;
+    };
+    parts.push(first.to_string());
+    let mut i = start + 1;
+    while tokens.get(i).is_some_and(|t| t.token == Token::Symbol('.')) {
+        match tokens.get(i + 1).and_then(|t| t.name()) {
+            Some(part) => parts.push(part.to_string()),
+            None => break,
+        }
+        i += 2;
+    }
+    parts
+}
+
+/// `TABLE public.users`, o solo el nombre si no se indicó el tipo
+fn object(kind: &str, target: &[String]) -> String {
+    if kind.is_empty() {
+        display(target)
+    } else {
+        format!("{} {}", kind, display(target))
+    }
+}
+
+fn display(target: &[String]) -> String {
+    if target.is_empty() {
+        "the target".to_string()
+    } else {
+        target.join(".")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(sql: &str, dialect: SqlDialect, context: &RiskContext) -> Vec<(RiskKind, Option<String>)> {
+        assess_script(sql, dialect, context).into_iter().map(|r| (r.kind, r.target)).collect()
+    }
+
+    fn in_schema(schema: &str) -> RiskContext {
+        RiskContext { schema: Some(schema.to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn flags_unfiltered_writes_inside_ctes() {
+        let pg = SqlDialect::Postgres;
+        let context = RiskContext::default();
+        assert_eq!(
+            kinds("WITH gone AS (DELETE FROM users RETURNING id) SELECT count(*) FROM gone", pg, &context),
+            vec![(RiskKind::DeleteWithoutWhere, Some("users".to_string()))]
+        );
+        assert_eq!(
+            kinds("WITH ids AS (SELECT id FROM old) UPDATE users SET active = false", pg, &context),
+            vec![(RiskKind::UpdateWithoutWhere, Some("users".to_string()))]
+        );
+        // El WHERE de la subconsulta no filtra la sentencia exterior, y el de la CTE sí filtra la suya
+        assert!(kinds("WITH gone AS (DELETE FROM users WHERE id IN (SELECT id FROM old)) SELECT 1", pg, &context).is_empty());
+        assert_eq!(
+            kinds("UPDATE users SET name = (SELECT name FROM other WHERE id = 1)", pg, &context),
+            vec![(RiskKind::UpdateWithoutWhere, Some("users".to_string()))]
+        );
+    }
+
+    #[test]
+    fn evaluates_explain_analyze_as_an_execution() {
+        let pg = SqlDialect::Postgres;
+        let context = RiskContext::default();
+        assert_eq!(
+            kinds("EXPLAIN ANALYZE DELETE FROM users", pg, &context),
+            vec![(RiskKind::DeleteWithoutWhere, Some("users".to_string()))]
+        );
+        assert_eq!(
+            kinds("EXPLAIN (ANALYZE, BUFFERS) UPDATE users SET a = 1", pg, &context),
+            vec![(RiskKind::UpdateWithoutWhere, Some("users".to_string()))]
+        );
+        assert!(kinds("EXPLAIN DELETE FROM users", pg, &context).is_empty());
+    }
+
+    #[test]
+    fn finds_targets_after_sql_server_top() {
+        let mssql = SqlDialect::SqlServer;
+        let context = RiskContext::default();
+        assert_eq!(
+            kinds("DELETE TOP (10) FROM dbo.users", mssql, &context),
+            vec![(RiskKind::DeleteWithoutWhere, Some("dbo.users".to_string()))]
+        );
+        assert_eq!(
+            kinds("UPDATE TOP (5) PERCENT [users] SET active = 0", mssql, &context),
+            vec![(RiskKind::UpdateWithoutWhere, Some("users".to_string()))]
+        );
+        assert_eq!(
+            kinds("INSERT TOP (1) INTO archive.users SELECT * FROM users", mssql, &in_schema("dbo")),
+            vec![(RiskKind::CrossSchemaWrite, Some("archive.users".to_string()))]
+        );
+    }
+
+    #[test]
+    fn finds_targets_after_sqlite_conflict_clauses() {
+        let sqlite = SqlDialect::Sqlite;
+        let context = RiskContext::default();
+        assert_eq!(
+            kinds("UPDATE OR REPLACE users SET id = id + 1", sqlite, &context),
+            vec![(RiskKind::UpdateWithoutWhere, Some("users".to_string()))]
+        );
+        assert_eq!(
+            kinds("INSERT OR REPLACE INTO aux.users VALUES (1)", sqlite, &in_schema("main")),
+            vec![(RiskKind::CrossSchemaWrite, Some("aux.users".to_string()))]
+        );
+    }
+
+    #[test]
+    fn flags_writes_outside_the_active_schema_or_database() {
+        let pg = SqlDialect::Postgres;
+        let context = RiskContext {
+            schema: Some("public".to_string()),
+            database: Some("app".to_string()),
+            production: false,
+        };
+        assert!(kinds("DELETE FROM users WHERE id = 1", pg, &context).is_empty());
+        assert!(kinds("DELETE FROM PUBLIC.users WHERE id = 1", pg, &context).is_empty());
+        assert_eq!(
+            kinds("DELETE FROM audit.log WHERE id = 1", pg, &context),
+            vec![(RiskKind::CrossSchemaWrite, Some("audit.log".to_string()))]
+        );
+        assert_eq!(
+            kinds("UPDATE other.public.users SET a = 1 WHERE id = 1", pg, &context),
+            vec![(RiskKind::CrossSchemaWrite, Some("other.public.users".to_string()))]
+        );
+        assert_eq!(
+            kinds("INSERT INTO shop.orders VALUES (1)", SqlDialect::MySql, &in_schema("app")),
+            vec![(RiskKind::CrossSchemaWrite, Some("shop.orders".to_string()))]
+        );
+    }
+
+    #[test]
+    fn reads_quoted_identifiers() {
+        let context = in_schema("public");
+        assert_eq!(
+            kinds("DROP TABLE \"Audit Log\".\"Entries\"", SqlDialect::Postgres, &context),
+            vec![(RiskKind::Drop, Some("Audit Log.Entries".to_string()))]
+        );
+        assert_eq!(
+            kinds("TRUNCATE `my``table`", SqlDialect::MySql, &RiskContext::default()),
+            vec![(RiskKind::Truncate, Some("my`table".to_string()))]
+        );
+        assert_eq!(
+            kinds("DELETE FROM [sales].[order items]", SqlDialect::SqlServer, &in_schema("dbo")),
+            vec![
+                (RiskKind::DeleteWithoutWhere, Some("sales.order items".to_string())),
+                (RiskKind::CrossSchemaWrite, Some("sales.order items".to_string())),
+            ]
+        );
+        // Un WHERE entre comillas es un identificador, no una cláusula
+        assert_eq!(
+            kinds("UPDATE t SET \"where\" = 1", SqlDialect::Postgres, &RiskContext::default()),
+            vec![(RiskKind::UpdateWithoutWhere, Some("t".to_string()))]
+        );
+    }
+
+    #[test]
+    fn assesses_generated_writes_without_sql() {
+        let target = vec!["audit".to_string(), "log".to_string()];
+        let risks = assess_write(WriteKind::Delete, &target, false, &in_schema("public"));
+        let found: Vec<RiskKind> = risks.iter().map(|r| r.kind).collect();
+        assert_eq!(found, vec![RiskKind::DeleteWithoutWhere, RiskKind::CrossSchemaWrite]);
+        assert_eq!(risks[0].sql, "DELETE FROM audit.log");
+
+        assert!(assess_write(WriteKind::Update, &["users".to_string()], true, &in_schema("public")).is_empty());
+        let production = RiskContext { production: true, ..in_schema("public") };
+        let risks = assess_write(WriteKind::Insert, &["users".to_string()], false, &production);
+        assert_eq!(risks.iter().map(|r| r.kind).collect::<Vec<_>>(), vec![RiskKind::ProductionWrite]);
+    }
+
+    #[test]
+    fn requires_confirmation_for_any_write_in_production() {
+        let pg = SqlDialect::Postgres;
+        let context = RiskContext { production: true, ..Default::default() };
+        assert_eq!(kinds("UPDATE users SET a = 1 WHERE id = 1", pg, &context), vec![(RiskKind::ProductionWrite, None)]);
+        assert_eq!(kinds("CREATE INDEX ON users (a)", pg, &context), vec![(RiskKind::ProductionSchemaChange, None)]);
+        assert!(kinds("SELECT * FROM users", pg, &context).is_empty());
+    }
+
+    #[test]
+    fn detects_writes_behind_comments_and_in_later_statements() {
+        let pg = SqlDialect::Postgres;
+        assert!(is_destructive("/* nota */ -- otra\n DELETE FROM users WHERE id = 1", pg));
+        assert!(is_destructive("SELECT 1; DROP TABLE users", pg));
+        assert!(is_destructive("WITH gone AS (DELETE FROM users RETURNING id) SELECT * FROM gone", pg));
+        assert!(is_destructive("EXPLAIN ANALYZE UPDATE users SET name = 'x'", pg));
+        assert!(is_destructive("BEGIN READ WRITE", pg));
+        assert!(is_destructive("SET SESSION default_transaction_read_only = off", pg));
+    }
+
+    #[test]
+    fn treats_session_mode_changes_as_writes() {
+        let pg = SqlDialect::Postgres;
+        assert!(is_destructive("SELECT set_config('default_transaction_read_only', 'off', false)", pg));
+        assert!(is_destructive("select pg_catalog.SET_CONFIG('transaction_read_only', 'off', true)", pg));
+        assert!(is_destructive("SELECT Set_Config ('statement_timeout', '0', false)", pg));
+        assert!(is_destructive("set Transaction_Read_Only = off", pg));
+        assert!(is_destructive("SET \"default_transaction_read_only\" TO off", pg));
+        assert!(is_destructive("set session tx_read_only = 0", SqlDialect::MySql));
+        assert!(is_destructive("set session transaction read write", SqlDialect::MySql));
+        assert!(!is_destructive("SELECT current_setting('transaction_read_only')", pg));
+        assert!(!is_destructive("SELECT 'set_config(x)' AS sql", pg));
+    }
+
+    #[test]
+    fn ignores_keywords_inside_literals_identifiers_and_functions() {
+        let pg = SqlDialect::Postgres;
+        assert!(!is_destructive("SELECT 'DELETE FROM users' AS sql", pg));
+        assert!(!is_destructive("SELECT \"update\" FROM audit", pg));
+        assert!(!is_destructive("SELECT $$DROP TABLE users$$", pg));
+        assert!(!is_destructive("EXPLAIN UPDATE users SET name = 'x'", pg));
+        assert!(!is_destructive("WITH t AS (SELECT 1) SELECT replace(name, 'a', 'b') FROM t", pg));
+        assert!(!is_destructive("SELECT `delete` FROM t # DROP TABLE t", SqlDialect::MySql));
+        assert!(!is_destructive("-- DELETE FROM users", pg));
+    }
+
+    #[test]
+    fn rejects_transaction_control_in_dry_runs() {
+        let pg = SqlDialect::Postgres;
+        assert!(check_dry_runnable("UPDATE users SET name = 'x' WHERE id = 1", pg).is_ok());
+        assert!(check_dry_runnable("CREATE TABLE t (id int)", pg).is_ok());
+        assert!(check_dry_runnable("/* antes */ COMMIT", pg).is_err());
+        assert!(check_dry_runnable("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE", pg).is_err());
+        assert!(check_dry_runnable("DELETE FROM t WHERE id = 1; ROLLBACK", pg).is_err());
+        assert!(check_dry_runnable("SELECT 'COMMIT'", pg).is_ok());
+    }
+
+    #[test]
+    fn rejects_implicit_commits_on_mysql() {
+        let mysql = SqlDialect::MySql;
+        assert!(check_dry_runnable("REPLACE INTO t VALUES (1)", mysql).is_ok());
+        assert!(check_dry_runnable("# comentario\nDELETE FROM t WHERE id = 1", mysql).is_ok());
+        assert!(check_dry_runnable("CREATE TABLE t (id int)", mysql).is_err());
+        assert!(check_dry_runnable("TRUNCATE t", mysql).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{BindValue, CellValue, ColumnSchema, SqlDialect};
+use crate::domain::error::DomainError;
+
+/// Cambio de una fila identificada por su clave primaria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowUpdate {
+    /// Valor de cada columna de la clave primaria
+    pub key: HashMap<String, CellValue>,
+    /// Columnas a modificar, con su valor nuevo
+    pub values: HashMap<String, CellValue>,
+    /// Valores leídos antes de editar; si se indican, la fila solo cambia si siguen igual
+    #[serde(default)]
+    pub original: Option<HashMap<String, CellValue>>,
+}
+
+/// Borrado de una fila identificada por su clave primaria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowDelete {
+    pub key: HashMap<String, CellValue>,
+    /// Valores leídos antes de borrar; si se indican, la fila solo se borra si siguen igual
+    #[serde(default)]
+    pub original: Option<HashMap<String, CellValue>>,
+}
+
+/// Tabla de una edición por clave, con sus columnas para convertir cada valor a su tipo
+#[derive(Debug, Clone)]
+pub struct RowEditTarget {
+    pub schema: Option<String>,
+    pub table: String,
+    pub dialect: SqlDialect,
+    pub columns: Vec<ColumnSchema>,
+    pub key_columns: Vec<String>,
+}
+
+/// Sentencia de una edición por clave, con los valores ya convertidos al tipo de su columna
+/// y en orden de enlace
+#[derive(Debug, Clone)]
+pub struct RowEditStatement {
+    pub sql: String,
+    pub values: Vec<BindValue>,
+}
+
+/// Genera el marcador del parámetro `index` (desde 1) con el que se enlaza el valor de la columna
+pub type PlaceholderFn<'a> = Box<dyn Fn(usize, &BindValue, &ColumnSchema) -> String + 'a>;
+
+/// Cómo escribe el motor identificadores y marcadores, para generar las sentencias
+pub struct RowEditSyntax<'a> {
+    pub target: &'a RowEditTarget,
+    /// Tabla ya calificada y entre comillas
+    pub table: String,
+    pub quote: Box<dyn Fn(&str) -> String + 'a>,
+    pub placeholder: PlaceholderFn<'a>,
+}
+
+impl RowEditSyntax<'_> {
+    pub fn update(&self, row: &RowUpdate) -> Result<RowEditStatement, DomainError> {
+        if row.values.is_empty() {
+            return Err(DomainError::validation("A row update needs at least one column to change"));
+        }
+        let mut values = Vec::new();
+        let mut assignments = Vec::with_capacity(row.values.len());
+        for (column, value) in sorted(&row.values) {
+            let marker = self.bind(&mut values, column, value)?;
+            assignments.push(format!("{} = {}", (self.quote)(column), marker));
+        }
+        let filter = self.filter(&row.key, row.original.as_ref(), &mut values)?;
+        let sql = format!("UPDATE {} SET {} WHERE {}", self.table, assignments.join(", "), filter);
+        Ok(RowEditStatement { sql, values })
+    }
+
+    pub fn delete(&self, row: &RowDelete) -> Result<RowEditStatement, DomainError> {
+        let mut values = Vec::new();
+        let filter = self.filter(&row.key, row.original.as_ref(), &mut values)?;
+        let sql = format!("DELETE FROM {} WHERE {}", self.table, filter);
+        Ok(RowEditStatement { sql, values })
+    }
+
+    /// Clave primaria completa y, para la concurrencia optimista, los valores originales
+    fn filter(
+        &self,
+        key: &HashMap<String, CellValue>,
+        original: Option<&HashMap<String, CellValue>>,
+        values: &mut Vec<BindValue>,
+    ) -> Result<String, DomainError> {
+        let key_columns = &self.target.key_columns;
+        let mut conditions = Vec::new();
+        for column in key_columns {
+            let value = key
+                .get(column)
+                .ok_or_else(|| DomainError::validation(format!("Missing value for primary key column {}", column)))?;
+            conditions.push(self.condition(column, value, values)?);
+        }
+        if let Some(unknown) = key.keys().find(|k| !key_columns.contains(k)) {
+            return Err(DomainError::validation(format!("{} is not part of the primary key", unknown)));
+        }
+        for (column, value) in sorted(original.unwrap_or(&HashMap::new())) {
+            // La clave ya está en el filtro y JSON no tiene igualdad en todos los motores
+            if key_columns.contains(column) || matches!(value, CellValue::Json(_)) {
+                continue;
+            }
+            conditions.push(self.condition(column, value, values)?);
+        }
+        Ok(conditions.join(" AND "))
+    }
+
+    fn condition(&self, column: &str, value: &CellValue, values: &mut Vec<BindValue>) -> Result<String, DomainError> {
+        Ok(match value {
+            CellValue::Null => format!("{} IS NULL", (self.quote)(column)),
+            _ => format!("{} = {}", (self.quote)(column), self.bind(values, column, value)?),
+        })
+    }
+
+    /// Convierte el valor al tipo de la columna, también la clave y los originales: leídos
+    /// del grid pueden venir como texto o número y la comparación debe hacerse en su tipo
+    fn bind(&self, values: &mut Vec<BindValue>, column: &str, value: &CellValue) -> Result<String, DomainError> {
+        let schema = self
+            .target
+            .columns
+            .iter()
+            .find(|c| c.name == column)
+            .ok_or_else(|| DomainError::validation(format!("{} is not a column of {}", column, self.target.table)))?;
+        let value = BindValue::from_cell(value, schema, self.target.dialect)?;
+        let marker = (self.placeholder)(values.len() + 1, &value, schema);
+        values.push(value);
+        Ok(marker)
+    }
+}
+
+/// Columnas en orden estable, para que la misma edición genere siempre la misma sentencia
+fn sorted(values: &HashMap<String, CellValue>) -> Vec<(&String, &CellValue)> {
+    let mut entries: Vec<_> = values.iter().collect();
+    entries.sort_by_key(|(column, _)| *column);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, native_type: &str) -> ColumnSchema {
+        ColumnSchema {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            native_type: native_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            is_unique: false,
+            is_auto_increment: false,
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            comment: None,
+            ordinal_position: 0,
+        }
+    }
+
+    /// Tabla con clave compuesta por un NUMERIC y un enum de PostgreSQL
+    fn prices() -> RowEditTarget {
+        RowEditTarget {
+            schema: Some("shop".to_string()),
+            table: "prices".to_string(),
+            dialect: SqlDialect::Postgres,
+            columns: vec![
+                column("amount", "numeric", "numeric"),
+                column("tier", "USER-DEFINED", "tier"),
+                column("label", "text", "text"),
+                column("stock", "integer", "int4"),
+            ],
+            key_columns: vec!["amount".to_string(), "tier".to_string()],
+        }
+    }
+
+    fn syntax(target: &RowEditTarget) -> RowEditSyntax<'_> {
+        RowEditSyntax {
+            target,
+            table: "\"shop\".\"prices\"".to_string(),
+            quote: Box::new(|name| format!("\"{}\"", name)),
+            placeholder: Box::new(|index, _, column| format!("${}::\"{}\"", index, column.native_type)),
+        }
+    }
+
+    fn cells(entries: &[(&str, CellValue)]) -> HashMap<String, CellValue> {
+        entries.iter().map(|(name, value)| (name.to_string(), value.clone())).collect()
+    }
+
+    #[test]
+    fn binds_numeric_and_enum_keys_in_their_column_type() {
+        let target = prices();
+        let row = RowUpdate {
+            key: cells(&[
+                ("amount", CellValue::String("12345678901234567.89".to_string())),
+                ("tier", CellValue::String("gold".to_string())),
+            ]),
+            values: cells(&[("label", CellValue::String("Gold".to_string())), ("stock", CellValue::String("7".to_string()))]),
+            original: Some(cells(&[("stock", CellValue::Int(5)), ("label", CellValue::Null)])),
+        };
+        let statement = syntax(&target).update(&row).unwrap();
+        assert_eq!(
+            statement.sql,
+            "UPDATE \"shop\".\"prices\" SET \"label\" = $1::\"text\", \"stock\" = $2::\"int4\" \
+             WHERE \"amount\" = $3::\"numeric\" AND \"tier\" = $4::\"tier\" AND \"label\" IS NULL AND \"stock\" = $5::\"int4\""
+        );
+        assert_eq!(
+            statement.values,
+            vec![
+                BindValue::Text("Gold".to_string()),
+                BindValue::Int(7),
+                BindValue::Decimal("12345678901234567.89".to_string()),
+                BindValue::Text("gold".to_string()),
+                BindValue::Int(5),
+            ]
+        );
+    }
+
+    #[test]
+    fn deletes_by_numeric_key_read_as_a_number() {
+        let target = prices();
+        let row = RowDelete {
+            key: cells(&[("amount", CellValue::Float(9.5)), ("tier", CellValue::String("basic".to_string()))]),
+            original: None,
+        };
+        let statement = syntax(&target).delete(&row).unwrap();
+        assert_eq!(statement.sql, "DELETE FROM \"shop\".\"prices\" WHERE \"amount\" = $1::\"numeric\" AND \"tier\" = $2::\"tier\"");
+        assert_eq!(statement.values, vec![BindValue::Decimal("9.5".to_string()), BindValue::Text("basic".to_string())]);
+    }
+
+    #[test]
+    fn rejects_incomplete_keys_and_unknown_columns() {
+        let target = prices();
+        let partial = RowDelete { key: cells(&[("amount", CellValue::Int(1))]), original: None };
+        assert!(syntax(&target).delete(&partial).is_err());
+
+        let unknown = RowUpdate {
+            key: cells(&[("amount", CellValue::Int(1)), ("tier", CellValue::String("gold".to_string()))]),
+            values: cells(&[("colour", CellValue::String("red".to_string()))]),
+            original: None,
+        };
+        assert!(syntax(&target).update(&unknown).is_err());
+
+        let invalid = RowUpdate {
+            key: cells(&[("amount", CellValue::Int(1)), ("tier", CellValue::String("gold".to_string()))]),
+            values: cells(&[("stock", CellValue::String("many".to_string()))]),
+            original: None,
+        };
+        assert!(syntax(&target).update(&invalid).is_err());
+    }
+}
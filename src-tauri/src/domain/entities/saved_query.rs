@@ -1,7 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use super::{detect_parameters, CellValue, PlaceholderStyle};
+
 /// Consulta SQL guardada por el usuario
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SavedQuery {
@@ -12,10 +15,25 @@ pub struct SavedQuery {
     pub description: Option<String>,
     pub tags: Vec<String>,
     pub folder_id: Option<Uuid>,
+    /// Parámetros detectados en el SQL, con su definición y el último valor usado
+    #[serde(default)]
+    pub parameters: Vec<SavedQueryParameter>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Parámetro de una consulta guardada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQueryParameter {
+    pub name: String,
+    pub style: PlaceholderStyle,
+    /// Tipo elegido por el usuario para el valor (texto libre, p. ej. "integer" o "date")
+    #[serde(default)]
+    pub data_type: Option<String>,
+    #[serde(default)]
+    pub last_value: Option<CellValue>,
+}
+
 impl SavedQuery {
     pub fn new(connection_id: Uuid, name: String, sql: String) -> Self {
         let now = Utc::now();
@@ -27,6 +45,7 @@ impl SavedQuery {
             description: None,
             tags: Vec::new(),
             folder_id: None,
+            parameters: Vec::new(),
             created_at: now,
             updated_at: now,
         }
@@ -46,6 +65,39 @@ impl SavedQuery {
         self.folder_id = Some(folder_id);
         self
     }
+
+    /// Ajusta los parámetros a los marcadores del SQL actual. Se conservan tipo y
+    /// último valor de los que siguen existiendo, tomando primero los de `definitions`.
+    pub fn sync_parameters(&mut self, definitions: Option<Vec<SavedQueryParameter>>) {
+        let mut known: HashMap<String, SavedQueryParameter> = std::mem::take(&mut self.parameters)
+            .into_iter()
+            .chain(definitions.unwrap_or_default())
+            .map(|p| (p.name.clone(), p))
+            .collect();
+
+        self.parameters = detect_parameters(&self.sql)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|detected| {
+                let previous = known.remove(&detected.name);
+                SavedQueryParameter {
+                    name: detected.name,
+                    style: detected.style,
+                    data_type: previous.as_ref().and_then(|p| p.data_type.clone()),
+                    last_value: previous.and_then(|p| p.last_value),
+                }
+            })
+            .collect();
+    }
+
+    /// Guarda los valores usados en la última ejecución
+    pub fn remember_values(&mut self, values: &HashMap<String, CellValue>) {
+        for parameter in &mut self.parameters {
+            if let Some(value) = values.get(&parameter.name) {
+                parameter.last_value = Some(value.clone());
+            }
+        }
+    }
 }
 
 /// Carpeta para organizar consultas guardadas
@@ -84,6 +136,8 @@ pub struct CreateSavedQueryDto {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub folder_id: Option<Uuid>,
+    #[serde(default)]
+    pub parameters: Option<Vec<SavedQueryParameter>>,
 }
 
 /// DTO para actualizar una consulta guardada
@@ -94,4 +148,6 @@ pub struct UpdateSavedQueryDto {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub folder_id: Option<Uuid>,
+    #[serde(default)]
+    pub parameters: Option<Vec<SavedQueryParameter>>,
 }
@@ -92,6 +92,41 @@ pub struct TableInfo {
     pub row_count: Option<u64>,
     pub size_bytes: Option<u64>,
     pub comment: Option<String>,
+    /// Motor de almacenamiento y particionado, si el driver lo expone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<TableStorageInfo>,
+}
+
+/// Almacenamiento físico de una tabla (motor, claves de particionado y ordenación, partes)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableStorageInfo {
+    pub engine: String,
+    pub partition_key: Option<String>,
+    pub sorting_key: Option<String>,
+    pub primary_key: Option<String>,
+    /// Tabla con historial de versiones (MariaDB `WITH SYSTEM VERSIONING`)
+    pub system_versioned: bool,
+    pub partitions: Vec<PartitionInfo>,
+}
+
+/// Partición de una tabla con sus partes activas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartitionInfo {
+    pub name: String,
+    pub rows: u64,
+    pub bytes_on_disk: u64,
+    pub parts: Vec<PartInfo>,
+}
+
+/// Parte de datos de una tabla MergeTree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartInfo {
+    pub name: String,
+    pub rows: u64,
+    pub bytes_on_disk: u64,
+    /// Nivel de merge: 0 para partes recién insertadas
+    pub level: u32,
+    pub modified_at: Option<String>,
 }
 
 /// Schema de una columna
@@ -0,0 +1,479 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::DatabaseEngine;
+
+/// Reglas léxicas del motor que afectan a dónde termina cada sentencia
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlDialect {
+    Postgres,
+    MySql,
+    SqlServer,
+    Sqlite,
+    DuckDb,
+    ClickHouse,
+}
+
+impl SqlDialect {
+    pub fn from_engine(engine: &DatabaseEngine) -> Self {
+        match engine {
+            DatabaseEngine::PostgreSQL => SqlDialect::Postgres,
+            DatabaseEngine::MySQL | DatabaseEngine::MariaDB => SqlDialect::MySql,
+            DatabaseEngine::SqlServer => SqlDialect::SqlServer,
+            DatabaseEngine::SQLite => SqlDialect::Sqlite,
+            DatabaseEngine::DuckDB => SqlDialect::DuckDb,
+            DatabaseEngine::ClickHouse => SqlDialect::ClickHouse,
+        }
+    }
+
+    /// Bloques `$tag$ ... $tag$`
+    pub(super) fn dollar_quoting(self) -> bool {
+        matches!(self, SqlDialect::Postgres | SqlDialect::DuckDb)
+    }
+
+    /// `/* /* */ */` cierra en el segundo `*/`
+    pub(super) fn nested_comments(self) -> bool {
+        matches!(self, SqlDialect::Postgres | SqlDialect::DuckDb | SqlDialect::SqlServer)
+    }
+
+    /// `\'` escapa la comilla en cualquier literal, no solo en `E'...'`
+    pub(super) fn backslash_escapes(self) -> bool {
+        matches!(self, SqlDialect::MySql | SqlDialect::ClickHouse)
+    }
+
+    pub(super) fn backtick_quotes(self) -> bool {
+        matches!(self, SqlDialect::MySql | SqlDialect::Sqlite | SqlDialect::ClickHouse)
+    }
+
+    pub(super) fn bracket_quotes(self) -> bool {
+        matches!(self, SqlDialect::SqlServer | SqlDialect::Sqlite)
+    }
+
+    /// Identificador entre las comillas del motor
+    pub fn quote_identifier(self, name: &str) -> String {
+        match self {
+            SqlDialect::MySql | SqlDialect::ClickHouse => format!("`{}`", name.replace('`', "``")),
+            SqlDialect::SqlServer => format!("[{}]", name.replace(']', "]]")),
+            _ => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+}
+
+/// Sentencia de un script, con su posición en el texto original
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptStatement {
+    pub sql: String,
+    /// Línea (desde 1) donde empieza
+    pub line: usize,
+    /// Offset en bytes donde empieza
+    pub offset: usize,
+}
+
+/// Qué hacer cuando falla una sentencia del script
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptMode {
+    /// Se detiene en el primer error; lo ya ejecutado se conserva
+    #[default]
+    StopOnError,
+    /// Ejecuta todas las sentencias y reporta cada error
+    ContinueOnError,
+    /// Todo el script en una transacción: se confirma si no hay errores, si no se deshace
+    Transaction,
+}
+
+/// Resumen de la ejecución de un script. Los resultados de cada sentencia se emiten
+/// como eventos según terminan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptRunSummary {
+    pub script_id: Uuid,
+    pub mode: ScriptMode,
+    pub statement_count: usize,
+    pub executed: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Se dejaron sentencias sin ejecutar por un error o una cancelación
+    pub stopped: bool,
+    /// Solo en modo transacción: si se confirmó o se deshizo
+    pub committed: Option<bool>,
+    pub execution_time_ms: u64,
+}
+
+/// Divide un script en sentencias según las reglas del dialecto.
+/// Los separadores dentro de literales, identificadores entre comillas, comentarios y
+/// bloques `$tag$` no cuentan. En MySQL se respetan los cambios con `DELIMITER`, en
+/// SQL Server se separan lotes por líneas `GO` y en SQLite los cuerpos `BEGIN ... END`
+/// de los triggers van enteros. Las sentencias vacías o solo con comentarios se omiten.
+pub fn split_script(script: &str, dialect: SqlDialect) -> Vec<ScriptStatement> {
+    Splitter::new(script, dialect).run()
+}
+
+struct Splitter<'a> {
+    script: &'a str,
+    dialect: SqlDialect,
+    chars: Vec<(usize, char)>,
+    delimiter: String,
+    statements: Vec<ScriptStatement>,
+    /// Primer carácter significativo de la sentencia en curso (los comentarios previos se descartan)
+    content_start: Option<usize>,
+    /// Palabras clave vistas al principio de la sentencia, para reconocer CREATE TRIGGER
+    head: Vec<String>,
+    /// Profundidad de BEGIN/CASE ... END dentro de un trigger de SQLite
+    block_depth: usize,
+    /// Saltos de línea contados hasta `counted_to` (offset en bytes), para no recontar desde el principio
+    counted_lines: usize,
+    counted_to: usize,
+}
+
+impl<'a> Splitter<'a> {
+    fn new(script: &'a str, dialect: SqlDialect) -> Self {
+        Self {
+            script,
+            dialect,
+            chars: script.char_indices().collect(),
+            delimiter: ";".to_string(),
+            statements: Vec::new(),
+            content_start: None,
+            head: Vec::new(),
+            block_depth: 0,
+            counted_lines: 0,
+            counted_to: 0,
+        }
+    }
+
+    fn at(&self, i: usize) -> Option<char> {
+        self.chars.get(i).map(|(_, c)| *c)
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        self.chars.get(i).map(|(o, _)| *o).unwrap_or(self.script.len())
+    }
+
+    fn is_line_start(&self, i: usize) -> bool {
+        i == 0 || self.chars[i - 1].1 == '\n'
+    }
+
+    fn line_end(&self, i: usize) -> usize {
+        (i..self.chars.len()).find(|&j| self.chars[j].1 == '\n').unwrap_or(self.chars.len())
+    }
+
+    fn mark_content(&mut self, i: usize) {
+        self.content_start.get_or_insert(i);
+    }
+
+    /// Cierra la sentencia en curso en el carácter `end` (exclusivo)
+    fn emit(&mut self, end: usize) {
+        if let Some(start) = self.content_start.take() {
+            let begin = self.offset(start);
+            let sql = self.script[begin..self.offset(end)].trim_end();
+            if !sql.is_empty() {
+                self.counted_lines += self.script[self.counted_to..begin].matches('\n').count();
+                self.counted_to = begin;
+                self.statements.push(ScriptStatement {
+                    sql: sql.to_string(),
+                    line: self.counted_lines + 1,
+                    offset: begin,
+                });
+            }
+        }
+        self.head.clear();
+        self.block_depth = 0;
+    }
+
+    fn run(mut self) -> Vec<ScriptStatement> {
+        let mut i = 0;
+        while i < self.chars.len() {
+            let c = self.chars[i].1;
+            let next = self.at(i + 1);
+
+            if self.content_start.is_none() && self.is_line_start(i) {
+                if let Some(after) = self.directive(i) {
+                    i = after;
+                    continue;
+                }
+            }
+            if self.dialect == SqlDialect::SqlServer && self.is_line_start(i) {
+                if let Some(after) = self.go_line(i) {
+                    self.emit(i);
+                    i = after;
+                    continue;
+                }
+            }
+
+            if c.is_whitespace() {
+                i += 1;
+                continue;
+            }
+            if self.dialect != SqlDialect::SqlServer
+                && self.block_depth == 0
+                && self.script[self.offset(i)..].starts_with(self.delimiter.as_str())
+            {
+                self.emit(i);
+                i += self.delimiter.chars().count();
+                continue;
+            }
+
+            i = match c {
+                '-' if next == Some('-') => self.line_end(i),
+                '#' if self.dialect == SqlDialect::MySql => self.line_end(i),
+                '/' if next == Some('*') => {
+                    // `/*! ... */` de MySQL es código que se ejecuta, no un comentario
+                    if self.dialect == SqlDialect::MySql && self.at(i + 2) == Some('!') {
+                        self.mark_content(i);
+                    }
+                    self.skip_block_comment(i)
+                }
+                '\'' => {
+                    self.mark_content(i);
+                    let backslash = self.dialect.backslash_escapes() || self.is_escape_string(i);
+                    self.skip_quoted(i, '\'', backslash)
+                }
+                '"' => {
+                    self.mark_content(i);
+                    self.skip_quoted(i, '"', self.dialect.backslash_escapes())
+                }
+                '`' if self.dialect.backtick_quotes() => {
+                    self.mark_content(i);
+                    self.skip_quoted(i, '`', false)
+                }
+                '[' if self.dialect.bracket_quotes() => {
+                    self.mark_content(i);
+                    self.skip_quoted(i, ']', false)
+                }
+                '$' if self.dialect.dollar_quoting() => {
+                    self.mark_content(i);
+                    self.skip_dollar_quoted(i)
+                }
+                c if c.is_alphabetic() || c == '_' => {
+                    self.mark_content(i);
+                    self.word(i)
+                }
+                _ => {
+                    self.mark_content(i);
+                    i + 1
+                }
+            };
+        }
+        self.emit(self.chars.len());
+        self.statements
+    }
+
+    /// `DELIMITER xx` de MySQL al principio de una sentencia: cambia el separador y no se ejecuta
+    fn directive(&mut self, i: usize) -> Option<usize> {
+        if self.dialect != SqlDialect::MySql {
+            return None;
+        }
+        let end = self.line_end(i);
+        let line = self.script[self.offset(i)..self.offset(end)].trim_start();
+        let keyword = line.get(..9)?;
+        if !keyword.eq_ignore_ascii_case("DELIMITER") || !line[9..].starts_with(char::is_whitespace) {
+            return None;
+        }
+        let delimiter = line[9..].trim();
+        if delimiter.is_empty() {
+            return None;
+        }
+        self.delimiter = delimiter.to_string();
+        Some(end)
+    }
+
+    /// Línea `GO` (o `GO n`) que separa lotes en SQL Server
+    fn go_line(&self, i: usize) -> Option<usize> {
+        let end = self.line_end(i);
+        let line = self.script[self.offset(i)..self.offset(end)].trim();
+        let rest = line.get(..2).filter(|k| k.eq_ignore_ascii_case("GO")).map(|_| line[2..].trim())?;
+        let rest = rest.strip_suffix(';').unwrap_or(rest).trim_end();
+        rest.chars().all(|c| c.is_ascii_digit()).then_some(end)
+    }
+
+    /// `E'...'` de PostgreSQL/DuckDB, donde la barra invertida escapa
+    fn is_escape_string(&self, i: usize) -> bool {
+        self.dialect.dollar_quoting()
+            && matches!(i.checked_sub(1).and_then(|p| self.at(p)), Some('E' | 'e'))
+            && !i.checked_sub(2).and_then(|p| self.at(p)).is_some_and(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    fn skip_block_comment(&self, i: usize) -> usize {
+        let mut depth = 0;
+        let mut j = i;
+        while j < self.chars.len() {
+            match (self.chars[j].1, self.at(j + 1)) {
+                ('/', Some('*')) if depth == 0 || self.dialect.nested_comments() => {
+                    depth += 1;
+                    j += 2;
+                }
+                ('*', Some('/')) => {
+                    depth -= 1;
+                    j += 2;
+                    if depth == 0 {
+                        return j;
+                    }
+                }
+                _ => j += 1,
+            }
+        }
+        j
+    }
+
+    /// Salta un literal o identificador entre comillas; la comilla doblada no lo cierra
+    fn skip_quoted(&self, i: usize, close: char, backslash: bool) -> usize {
+        let mut j = i + 1;
+        while j < self.chars.len() {
+            let c = self.chars[j].1;
+            if (backslash && c == '\\') || (c == close && self.at(j + 1) == Some(close)) {
+                j += 2;
+            } else if c == close {
+                return j + 1;
+            } else {
+                j += 1;
+            }
+        }
+        j
+    }
+
+    /// Salta un bloque `$tag$ ... $tag$`; un `$` que no abre bloque (p. ej. `$1`) se deja pasar
+    fn skip_dollar_quoted(&self, i: usize) -> usize {
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        if i.checked_sub(1).and_then(|p| self.at(p)).is_some_and(is_ident) {
+            return i + 1;
+        }
+        let mut j = i + 1;
+        if self.at(j).is_some_and(|c| c.is_ascii_digit()) {
+            return i + 1;
+        }
+        while self.at(j).is_some_and(is_ident) {
+            j += 1;
+        }
+        if self.at(j) != Some('$') {
+            return i + 1;
+        }
+        let tag = &self.script[self.offset(i)..self.offset(j + 1)];
+        let body = self.offset(j + 1);
+        match self.script[body..].find(tag) {
+            Some(pos) => self.chars.partition_point(|(o, _)| *o < body + pos + tag.len()),
+            None => self.chars.len(),
+        }
+    }
+
+    /// Lee una palabra y sigue los bloques de los triggers de SQLite, cuyo cuerpo lleva `;`
+    fn word(&mut self, i: usize) -> usize {
+        let mut j = i;
+        while self.at(j).is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            j += 1;
+        }
+        if self.dialect != SqlDialect::Sqlite {
+            return j;
+        }
+
+        let word = self.script[self.offset(i)..self.offset(j)].to_ascii_uppercase();
+        let in_trigger = self.head.first().is_some_and(|w| w == "CREATE") && self.head.iter().any(|w| w == "TRIGGER");
+        match word.as_str() {
+            "BEGIN" | "CASE" if in_trigger => self.block_depth += 1,
+            "END" if in_trigger => self.block_depth = self.block_depth.saturating_sub(1),
+            _ => {}
+        }
+        // Basta con la cabecera: CREATE [TEMP] TRIGGER [IF NOT EXISTS] ...
+        if self.head.len() < 4 {
+            self.head.push(word);
+        }
+        j
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sql(script: &str, dialect: SqlDialect) -> Vec<String> {
+        split_script(script, dialect).into_iter().map(|s| s.sql).collect()
+    }
+
+    #[test]
+    fn splits_on_semicolons_and_drops_empty_statements() {
+        assert_eq!(sql("SELECT 1; ;\n SELECT 2;;", SqlDialect::Postgres), vec!["SELECT 1", "SELECT 2"]);
+    }
+
+    #[test]
+    fn reports_line_and_offset_of_each_statement() {
+        let statements = split_script("-- header\nSELECT 1;\n\n  SELECT 2;", SqlDialect::Postgres);
+        assert_eq!(statements[0].sql, "SELECT 1");
+        assert_eq!(statements[0].line, 2);
+        assert_eq!(statements[1].line, 4);
+        assert_eq!(statements[1].offset, 23);
+    }
+
+    #[test]
+    fn ignores_separators_in_comments() {
+        let script = "-- a; b\nSELECT 1 /* ; */;\n/* only a comment; */\nSELECT 2";
+        assert_eq!(sql(script, SqlDialect::Postgres), vec!["SELECT 1 /* ; */", "SELECT 2"]);
+        assert_eq!(sql("# x;\nSELECT 1;", SqlDialect::MySql), vec!["SELECT 1"]);
+    }
+
+    #[test]
+    fn nested_comments_depend_on_the_dialect() {
+        let script = "SELECT 1 /* a /* b */ ; */; SELECT 2";
+        assert_eq!(sql(script, SqlDialect::Postgres), vec!["SELECT 1 /* a /* b */ ; */", "SELECT 2"]);
+        assert_eq!(sql(script, SqlDialect::MySql), vec!["SELECT 1 /* a /* b */", "*/", "SELECT 2"]);
+    }
+
+    #[test]
+    fn keeps_mysql_executable_comments() {
+        assert_eq!(sql("/*!40101 SET NAMES utf8 */;", SqlDialect::MySql), vec!["/*!40101 SET NAMES utf8 */"]);
+    }
+
+    #[test]
+    fn ignores_separators_in_literals_and_identifiers() {
+        assert_eq!(
+            sql(r#"SELECT 'a;''b', "c;d"; SELECT 2"#, SqlDialect::Postgres),
+            vec![r#"SELECT 'a;''b', "c;d""#, "SELECT 2"]
+        );
+        assert_eq!(sql(r"SELECT 'it\'s;'; SELECT 2", SqlDialect::MySql), vec![r"SELECT 'it\'s;'", "SELECT 2"]);
+        assert_eq!(sql(r"SELECT E'\';'; SELECT 2", SqlDialect::Postgres), vec![r"SELECT E'\';'", "SELECT 2"]);
+        assert_eq!(sql("SELECT [a;b] FROM t; SELECT 2", SqlDialect::Sqlite), vec!["SELECT [a;b] FROM t", "SELECT 2"]);
+    }
+
+    #[test]
+    fn keeps_postgres_dollar_quoted_bodies_whole() {
+        let function = "CREATE FUNCTION f() RETURNS int AS $body$ BEGIN RETURN 1; END; $body$ LANGUAGE plpgsql";
+        let script = format!("{};\nDO $$ BEGIN PERFORM 1; END $$;\nSELECT $1", function);
+        assert_eq!(
+            sql(&script, SqlDialect::Postgres),
+            vec![function, "DO $$ BEGIN PERFORM 1; END $$", "SELECT $1"]
+        );
+    }
+
+    #[test]
+    fn follows_mysql_delimiter_changes() {
+        let script = "DELIMITER //\nCREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END//\nDELIMITER ;\nCALL p();";
+        assert_eq!(
+            sql(script, SqlDialect::MySql),
+            vec!["CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END", "CALL p()"]
+        );
+        // Fuera de MySQL DELIMITER no es una directiva
+        assert_eq!(sql("DELIMITER //", SqlDialect::Postgres), vec!["DELIMITER //"]);
+    }
+
+    #[test]
+    fn splits_sql_server_batches_on_go_lines() {
+        let script = "CREATE TABLE t (a int);\nINSERT INTO t VALUES (1);\nGO\nCREATE PROCEDURE p AS SELECT 1;\n  go 2\nSELECT 'GO'";
+        assert_eq!(
+            sql(script, SqlDialect::SqlServer),
+            vec![
+                "CREATE TABLE t (a int);\nINSERT INTO t VALUES (1);",
+                "CREATE PROCEDURE p AS SELECT 1;",
+                "SELECT 'GO'",
+            ]
+        );
+        assert_eq!(sql("SELECT 1\nGOTO x", SqlDialect::SqlServer), vec!["SELECT 1\nGOTO x"]);
+    }
+
+    #[test]
+    fn keeps_sqlite_trigger_bodies_whole() {
+        let trigger = "CREATE TEMP TRIGGER tr AFTER INSERT ON t BEGIN UPDATE t SET a = CASE WHEN 1 THEN 2 END; DELETE FROM u; END";
+        let script = format!("{};\nSELECT 1;", trigger);
+        assert_eq!(sql(&script, SqlDialect::Sqlite), vec![trigger, "SELECT 1"]);
+        // BEGIN fuera de un trigger es una sentencia más
+        assert_eq!(sql("BEGIN; SELECT 1; END;", SqlDialect::Sqlite), vec!["BEGIN", "SELECT 1", "END"]);
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::entities::StatementRisk;
+
 /// Errores del dominio
 #[derive(Debug, Error, Clone, Serialize, Deserialize)]
 pub enum DomainError {
@@ -13,6 +15,9 @@ pub enum DomainError {
     #[error("Authentication failed: {message}")]
     AuthenticationError { message: String },
 
+    #[error("TLS error: {message}")]
+    TlsError { message: String },
+
     #[error("Connection not found: {id}")]
     ConnectionNotFound { id: String },
 
@@ -31,8 +36,13 @@ pub enum DomainError {
     #[error("Resource not found: {resource}")]
     NotFound { resource: String },
 
+    /// `column` indica la columna cuyo valor no se pudo convertir, si el error es de una sola
     #[error("Validation error: {message}")]
-    ValidationError { message: String },
+    ValidationError {
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        column: Option<String>,
+    },
 
     #[error("Credential store error: {message}")]
     CredentialError { message: String },
@@ -49,6 +59,14 @@ pub enum DomainError {
     #[error("Operation cancelled")]
     Cancelled,
 
+    /// La ejecución tiene sentencias peligrosas: se repite con `token` para confirmarla
+    #[error("Confirmation required: {}", .risks.iter().map(|r| r.message.as_str()).collect::<Vec<_>>().join("; "))]
+    ConfirmationRequired { token: String, risks: Vec<StatementRisk> },
+
+    /// Una edición por clave no encontró la fila, o sus valores ya no eran los originales
+    #[error("Row conflict: {message}")]
+    RowConflict { row_index: usize, message: String },
+
     #[error("Internal error: {message}")]
     Internal { message: String },
 }
@@ -71,6 +89,14 @@ impl DomainError {
     pub fn validation(message: impl Into<String>) -> Self {
         Self::ValidationError {
             message: message.into(),
+            column: None,
+        }
+    }
+
+    pub fn invalid_column(column: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::ValidationError {
+            message: message.into(),
+            column: Some(column.into()),
         }
     }
 
@@ -100,6 +126,7 @@ impl DomainError {
             DomainError::ConnectionError { .. } => "CONNECTION_ERROR",
             DomainError::QueryError { .. } => "QUERY_ERROR",
             DomainError::AuthenticationError { .. } => "AUTH_ERROR",
+            DomainError::TlsError { .. } => "TLS_ERROR",
             DomainError::ConnectionNotFound { .. } => "CONNECTION_NOT_FOUND",
             DomainError::DriverNotFound { .. } => "DRIVER_NOT_FOUND",
             DomainError::ConfigurationError { .. } => "CONFIG_ERROR",
@@ -112,6 +139,8 @@ impl DomainError {
             DomainError::IoError { .. } => "IO_ERROR",
             DomainError::PoolExhausted => "POOL_EXHAUSTED",
             DomainError::Cancelled => "CANCELLED",
+            DomainError::ConfirmationRequired { .. } => "CONFIRMATION_REQUIRED",
+            DomainError::RowConflict { .. } => "ROW_CONFLICT",
             DomainError::Internal { .. } => "INTERNAL_ERROR",
         }
     }
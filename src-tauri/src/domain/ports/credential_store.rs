@@ -21,6 +21,15 @@ pub trait CredentialStore: Send + Sync {
 
     /// Actualiza una credencial
     async fn update(&self, connection_id: Uuid, password: &str) -> Result<(), DomainError>;
+
+    /// Guarda el secreto del túnel SSH (password o passphrase de la clave)
+    async fn store_ssh_secret(&self, connection_id: Uuid, secret: &str) -> Result<(), DomainError>;
+
+    /// Obtiene el secreto del túnel SSH
+    async fn retrieve_ssh_secret(&self, connection_id: Uuid) -> Result<Option<String>, DomainError>;
+
+    /// Elimina el secreto del túnel SSH
+    async fn delete_ssh_secret(&self, connection_id: Uuid) -> Result<(), DomainError>;
 }
 
 /// Identificador del servicio para el keychain
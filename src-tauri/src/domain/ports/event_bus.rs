@@ -2,8 +2,9 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::entities::ConnectionStatus;
+use crate::domain::entities::{ConnectionStatus, RowBatch, TransactionWarningReason};
 use crate::domain::error::DomainError;
+use super::StatementResult;
 
 /// Eventos del sistema
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +21,15 @@ pub enum DomainEvent {
     QueryCompleted { connection_id: Uuid, query_id: Uuid, row_count: usize },
     QueryFailed { connection_id: Uuid, query_id: Uuid, error: String },
     QueryCancelled { connection_id: Uuid, query_id: Uuid },
+    QueryStreamBatch { connection_id: Uuid, query_id: Uuid, batch: RowBatch },
+    ScriptStatementCompleted { connection_id: Uuid, script_id: Uuid, result: Box<StatementResult> },
+    ImportProgress { connection_id: Uuid, import_id: Uuid, processed_rows: usize, total_rows: usize, written_rows: u64, failed_rows: usize },
+    ExportProgress { connection_id: Uuid, export_id: Uuid, row_count: u64 },
+    DumpProgress { connection_id: Uuid, dump_id: Uuid, table: String, tables_done: usize, table_count: usize, row_count: u64 },
+    RestoreProgress { connection_id: Uuid, restore_id: Uuid, statement_index: usize, statement_count: usize, success: bool, error: Option<String> },
+
+    // Eventos de transacciones manuales
+    TransactionWarning { connection_id: Uuid, transaction_id: Uuid, reason: TransactionWarningReason, open_for_secs: u64 },
 
     // Eventos de schema
     SchemaRefreshed { connection_id: Uuid },
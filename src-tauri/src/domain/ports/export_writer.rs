@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use crate::domain::entities::{CellValue, ColumnInfo};
+use crate::domain::error::DomainError;
+
+/// Destino de una exportación: recibe las filas por lotes a medida que llegan del servidor
+#[async_trait]
+pub trait ExportWriter: Send {
+    /// Prepara el destino con las columnas del resultado, antes de la primera fila
+    async fn begin(&mut self, columns: &[ColumnInfo]) -> Result<(), DomainError>;
+
+    async fn write_rows(&mut self, rows: &[Vec<CellValue>]) -> Result<(), DomainError>;
+
+    /// Cierra el destino y devuelve los bytes escritos
+    async fn finish(&mut self) -> Result<u64, DomainError>;
+
+    /// Descarta lo escrito tras un error o una cancelación
+    async fn abort(&mut self);
+}
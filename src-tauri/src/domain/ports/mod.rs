@@ -5,10 +5,13 @@ mod credential_store;
 mod event_bus;
 mod saved_query_repository;
 mod workspace_repository;
+mod export_writer;
 
 pub use sql_driver::*;
 pub use connection_repository::*;
 pub use query_history_repository::*;
 pub use credential_store::*;
+pub use event_bus::*;
 pub use saved_query_repository::*;
 pub use workspace_repository::*;
+pub use export_writer::*;
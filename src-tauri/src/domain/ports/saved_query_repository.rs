@@ -1,6 +1,7 @@
-use crate::domain::entities::{CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
+use crate::domain::entities::{CellValue, CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
 use crate::domain::error::DomainError;
 use async_trait::async_trait;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[async_trait]
@@ -17,6 +18,9 @@ pub trait SavedQueryRepository: Send + Sync {
     /// Actualizar una consulta guardada
     async fn update(&self, id: Uuid, dto: UpdateSavedQueryDto) -> Result<SavedQuery, DomainError>;
 
+    /// Guardar los últimos valores usados en los parámetros de una consulta
+    async fn record_parameter_values(&self, id: Uuid, values: HashMap<String, CellValue>) -> Result<SavedQuery, DomainError>;
+
     /// Eliminar una consulta guardada
     async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
 
@@ -1,9 +1,11 @@
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use crate::domain::entities::{
-    ColumnSchema, ConstraintInfo, DatabaseInfo, QueryResult, SchemaInfo, 
-    SequenceInfo, TableInfo, TriggerInfo, ViewInfo, FunctionInfo, IndexInfo,
+    BindValue, CellValue, DdlObject, DdlOptions, ImportTarget, RowDelete, RowEditStatement, RowEditSyntax, RowEditTarget, RowUpdate, ColumnSchema, ConstraintInfo, DatabaseInfo, ExecutionOptions, PageRequest, QueryPlan, QueryResult, QueryStreamSummary,
+    RowBatch, SchemaInfo, SequenceInfo, StreamOptions, TableInfo, TlsStatus, TransactionOptions, TriggerInfo, ViewInfo,
+    FunctionInfo, IndexInfo,
 };
 use crate::domain::error::DomainError;
 
@@ -17,6 +19,17 @@ pub trait SqlDriver: Send + Sync {
     /// Nombre legible del driver
     fn display_name(&self) -> &'static str;
 
+    /// Marcador nativo del parámetro `index` (desde 1) con el que se enlaza `value`.
+    /// Los parámetros de `ExecutionOptions::params` se enlazan en ese orden.
+    fn placeholder(&self, _index: usize, _value: &CellValue) -> String {
+        "?".to_string()
+    }
+
+    /// Identificador entre las comillas del motor
+    fn quote_identifier(&self, name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
     /// Conecta a la base de datos
     async fn connect(&self, connection_string: &str) -> Result<(), DomainError>;
 
@@ -30,16 +43,31 @@ pub trait SqlDriver: Send + Sync {
     async fn test_connection(&self, connection_string: &str) -> Result<(), DomainError>;
 
     /// Ejecuta una query SELECT y retorna resultados
-    async fn execute_query(&self, query: &str) -> Result<QueryResult, DomainError>;
+    async fn execute_query(&self, query: &str, options: &ExecutionOptions) -> Result<QueryResult, DomainError>;
+
+    /// Ejecuta una query enviando las filas por lotes a medida que llegan.
+    /// El canal es acotado: si el consumidor no libera espacio el driver deja de leer,
+    /// y si lo cierra el stream se detiene.
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        options: &ExecutionOptions,
+        stream_options: &StreamOptions,
+        sender: mpsc::Sender<RowBatch>,
+    ) -> Result<QueryStreamSummary, DomainError>;
 
-    /// Ejecuta una query con paginación
+    /// Ejecuta una query con paginación.
+    /// has_next se calcula leyendo una fila de más, no a partir del total.
     async fn execute_query_paginated(
         &self,
         query: &str,
-        page: u32,
-        page_size: u32,
+        page: &PageRequest,
+        options: &ExecutionOptions,
     ) -> Result<QueryResult, DomainError>;
 
+    /// Libera el cursor de paginación indicado (si el motor mantiene estado en el servidor)
+    async fn close_pagination_cursor(&self, cursor: &str) -> Result<(), DomainError>;
+
     /// Inserta una fila en la tabla
     async fn insert_row(
         &self,
@@ -49,14 +77,135 @@ pub trait SqlDriver: Send + Sync {
     ) -> Result<QueryResult, DomainError>;
 
     /// Ejecuta una query de modificación (INSERT, UPDATE, DELETE)
-    async fn execute_statement(&self, statement: &str) -> Result<u64, DomainError>;
+    async fn execute_statement(&self, statement: &str, options: &ExecutionOptions) -> Result<u64, DomainError>;
 
     /// Ejecuta múltiples statements en una transacción
-    async fn execute_multi_statement(&self, statements: Vec<String>) -> Result<Vec<StatementResult>, DomainError>;
+    async fn execute_multi_statement(&self, statements: Vec<String>, options: &ExecutionOptions) -> Result<Vec<StatementResult>, DomainError>;
+
+    /// El motor tiene transacciones manuales con savepoints, así que se puede deshacer una
+    /// parte de lo escrito sin perder el resto (la importación repite así los lotes fallidos)
+    fn supports_transactions(&self) -> bool {
+        false
+    }
+
+    /// Indica si hay una transacción manual abierta
+    async fn in_transaction(&self) -> bool {
+        false
+    }
+
+    /// Abre una transacción manual en una conexión del pool que queda fijada a la sesión:
+    /// las ejecuciones siguientes usan esa conexión hasta el commit o el rollback
+    async fn begin_transaction(&self, _options: &TransactionOptions) -> Result<(), DomainError> {
+        Err(DomainError::validation(format!("{} does not support interactive transactions", self.display_name())))
+    }
+
+    /// Confirma la transacción manual y devuelve la conexión al pool
+    async fn commit_transaction(&self) -> Result<(), DomainError> {
+        Err(DomainError::validation("No transaction is open"))
+    }
+
+    /// Deshace la transacción manual y devuelve la conexión al pool
+    async fn rollback_transaction(&self) -> Result<(), DomainError> {
+        Err(DomainError::validation("No transaction is open"))
+    }
+
+    /// Crea un savepoint en la transacción manual
+    async fn create_savepoint(&self, _name: &str) -> Result<(), DomainError> {
+        Err(DomainError::validation("No transaction is open"))
+    }
+
+    /// Deshace los cambios hechos desde el savepoint, que sigue existiendo
+    async fn rollback_to_savepoint(&self, _name: &str) -> Result<(), DomainError> {
+        Err(DomainError::validation("No transaction is open"))
+    }
+
+    /// Plan de ejecución normalizado. Con `analyze` la sentencia se ejecuta para medir
+    /// filas y tiempos reales, dentro de una transacción que se deshace
+    async fn explain(&self, _sql: &str, _analyze: bool) -> Result<QueryPlan, DomainError> {
+        Err(DomainError::validation(format!("{} does not support EXPLAIN plans", self.display_name())))
+    }
 
     /// Ejecuta un statement dentro de una transacción y retorna filas afectadas
     async fn execute_in_transaction(&self, statement: &str) -> Result<TransactionResult, DomainError>;
 
+    /// Modifica filas por su clave primaria y devuelve las filas afectadas. Si una fila ya no
+    /// existe o sus valores originales cambiaron se detiene con `RowConflict`; la transacción
+    /// que hace el lote todo o nada la abre quien llama
+    async fn update_rows(&self, target: &RowEditTarget, rows: &[RowUpdate], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let statements = {
+            let syntax = self.row_edit_syntax(target);
+            rows.iter().map(|row| syntax.update(row)).collect::<Result<Vec<_>, _>>()?
+        };
+        self.execute_row_edits(statements, options).await
+    }
+
+    /// Borra filas por su clave primaria, con las mismas garantías que `update_rows`
+    async fn delete_rows(&self, target: &RowEditTarget, rows: &[RowDelete], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let statements = {
+            let syntax = self.row_edit_syntax(target);
+            rows.iter().map(|row| syntax.delete(row)).collect::<Result<Vec<_>, _>>()?
+        };
+        self.execute_row_edits(statements, options).await
+    }
+
+    /// Ejecuta en orden las sentencias de una edición por clave. Una sentencia que no toca
+    /// ninguna fila detiene el lote con `RowConflict`
+    async fn execute_row_edits(&self, statements: Vec<RowEditStatement>, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let mut affected_rows = 0;
+        for (row_index, statement) in statements.into_iter().enumerate() {
+            match self.execute_typed(&statement.sql, statement.values, options).await? {
+                0 => {
+                    return Err(DomainError::RowConflict {
+                        row_index,
+                        message: format!("row {} no longer exists or was changed by someone else", row_index + 1),
+                    });
+                }
+                n => affected_rows += n,
+            }
+        }
+        Ok(affected_rows)
+    }
+
+    /// Ejecuta una sentencia con valores ya convertidos al tipo de su columna y devuelve las
+    /// filas afectadas. Por defecto se enlazan como valores de celda
+    async fn execute_typed(&self, statement: &str, values: Vec<BindValue>, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let params = values.iter().map(BindValue::to_cell).collect();
+        self.execute_statement(statement, &options.clone().with_params(params)).await
+    }
+
+    /// Escribe un lote de filas ya convertidas al tipo de cada columna de `target`, con su
+    /// política de duplicados, y devuelve las filas afectadas. Por defecto con INSERT de
+    /// varias filas; no abre transacción, eso lo decide quien importa
+    async fn import_rows(&self, target: &ImportTarget, rows: &[Vec<BindValue>], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let mut affected = 0;
+        for chunk in rows.chunks(target.rows_per_statement()) {
+            let values: Vec<CellValue> = chunk.iter().flatten().map(BindValue::to_cell).collect();
+            let sql = target.insert_sql(chunk.len(), |n| self.placeholder(n, &values[n - 1]))?;
+            affected += self.execute_statement(&sql, &options.clone().with_params(values)).await?;
+        }
+        Ok(affected)
+    }
+
+    /// Ejecuta un `COPY ... FROM STDIN` con sus líneas de datos en el formato de texto de
+    /// COPY y devuelve las filas cargadas
+    async fn copy_from_stdin(&self, _statement: &str, _data: &[u8], _options: &ExecutionOptions) -> Result<u64, DomainError> {
+        Err(DomainError::validation(format!("{} does not support COPY FROM STDIN", self.display_name())))
+    }
+
+    /// Tabla y reglas del motor con las que se generan las sentencias de edición por clave
+    fn row_edit_syntax<'a>(&'a self, target: &'a RowEditTarget) -> RowEditSyntax<'a> {
+        let table = match &target.schema {
+            Some(schema) => format!("{}.{}", self.quote_identifier(schema), self.quote_identifier(&target.table)),
+            None => self.quote_identifier(&target.table),
+        };
+        RowEditSyntax {
+            target,
+            table,
+            quote: Box::new(|name| self.quote_identifier(name)),
+            placeholder: Box::new(|index, value, _| self.placeholder(index, &value.to_cell())),
+        }
+    }
+
     /// Obtiene la lista de bases de datos
     async fn list_databases(&self) -> Result<Vec<String>, DomainError>;
 
@@ -93,14 +242,22 @@ pub trait SqlDriver: Send + Sync {
     /// Obtiene las secuencias
     async fn list_sequences(&self, schema: Option<&str>) -> Result<Vec<SequenceInfo>, DomainError>;
 
-    /// Cancela la query en ejecución
-    async fn cancel_query(&self) -> Result<(), DomainError>;
+    /// Sentencia `CREATE` de un objeto, seguida de comentarios, permisos y propietario si se piden
+    async fn get_object_ddl(&self, _object: &DdlObject, _options: &DdlOptions) -> Result<String, DomainError> {
+        Err(DomainError::validation(format!("{} does not support DDL generation", self.display_name())))
+    }
+
+    /// Cancela la ejecución indicada si sigue en curso
+    async fn cancel_query(&self, query_id: Uuid) -> Result<(), DomainError>;
 
     /// Obtiene sugerencias de autocompletado
     async fn get_completions(&self, prefix: &str, context: CompletionContext) -> Result<Vec<CompletionItem>, DomainError>;
 
     /// Obtiene la versión del servidor
     async fn server_version(&self) -> Result<String, DomainError>;
+
+    /// Estado TLS negociado por la conexión actual
+    async fn tls_status(&self) -> Result<TlsStatus, DomainError>;
 }
 
 /// Contexto para autocompletado
@@ -166,6 +323,31 @@ pub struct StatementResult {
     pub execution_time_ms: u64,
 }
 
+impl StatementResult {
+    /// Resultado a partir de lo que devolvió la sentencia: filas (si produjo un conjunto
+    /// de resultados) y filas afectadas
+    pub fn from_outcome(
+        statement_index: usize,
+        sql: &str,
+        outcome: Result<(Option<QueryResult>, Option<u64>), DomainError>,
+        execution_time_ms: u64,
+    ) -> Self {
+        let (success, result, affected_rows, error) = match outcome {
+            Ok((result, affected)) => (true, result, affected, None),
+            Err(e) => (false, None, None, Some(e.to_string())),
+        };
+        Self {
+            statement_index,
+            sql: sql.to_string(),
+            success,
+            affected_rows,
+            result,
+            error,
+            execution_time_ms,
+        }
+    }
+}
+
 /// Resultado de una transacción
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TransactionResult {
@@ -173,3 +355,28 @@ pub struct TransactionResult {
     pub execution_time_ms: u64,
     pub committed: bool,
 }
+
+
+/// Resultado de una sentencia ejecutada en modo simulación, siempre deshecha
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DryRunStatement {
+    #[serde(flatten)]
+    pub result: StatementResult,
+    /// Muestra de las filas tocadas, si la tabla tiene clave primaria
+    pub sample: Option<RowDiffSample>,
+    /// Por qué no hay muestra
+    pub sample_note: Option<String>,
+}
+
+/// Filas afectadas por una sentencia, leídas antes y después de ejecutarla
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RowDiffSample {
+    pub table: String,
+    pub key_columns: Vec<String>,
+    /// Filas que cumplían el filtro antes de ejecutar la sentencia
+    pub before: QueryResult,
+    /// Las mismas filas, por clave primaria, tras ejecutarla; las borradas ya no aparecen
+    pub after: QueryResult,
+    /// La sentencia afecta a más filas que las de la muestra
+    pub truncated: bool,
+}
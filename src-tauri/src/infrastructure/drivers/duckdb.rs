@@ -0,0 +1,1299 @@
+use async_trait::async_trait;
+use duckdb::arrow::datatypes::DataType;
+use duckdb::types::Value;
+use duckdb::{params_from_iter, AccessMode, Config, Connection as DuckConnection, InterruptHandle, Statement};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Instant;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::domain::{
+    CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
+    ConstraintInfo, ConstraintType, CountMode, DatabaseInfo, DomainError, ExecutionOptions, ForeignKeyAction,
+    ForeignKeyInfo, FunctionInfo, FunctionParameter, IndexInfo, PageRequest, ParameterMode, PrimaryKeyInfo,
+    QueryResult, QueryStreamSummary, RowBatch, SchemaInfo, SequenceInfo, SqlDriver, StreamOptions, TableInfo,
+    TlsStatus, TransactionOptions, TriggerInfo, ViewInfo,
+};
+use super::running::{RunningQueries, RunningQueryGuard};
+use super::stream::RowBatchWriter;
+use super::pagination::{self, KeysetPage, RowTotal, SimpleSelect};
+
+/// Parámetro propio del connection string: abre el fichero en solo lectura
+const READ_ONLY_PARAM: &str = "?access_mode=read_only";
+
+/// Filas en vuelo entre el hilo bloqueante de DuckDB y el stream
+const STREAM_CHANNEL_ROWS: usize = 1024;
+
+/// Mensajes del hilo que lee un resultado en streaming
+enum Streamed {
+    Columns(Vec<ColumnInfo>),
+    Row(Vec<CellValue>),
+}
+
+/// Fila de una consulta de catálogo con acceso por nombre de columna
+struct CatalogRow {
+    values: HashMap<String, Value>,
+}
+
+impl CatalogRow {
+    fn text(&self, column: &str) -> Option<String> {
+        match self.values.get(column)? {
+            Value::Null => None,
+            Value::Text(s) | Value::Enum(s) => Some(s.clone()),
+            other => Some(DuckDbDriver::value_to_string(other)),
+        }
+    }
+
+    fn int(&self, column: &str) -> Option<i64> {
+        match self.values.get(column)? {
+            Value::TinyInt(v) => Some(*v as i64),
+            Value::SmallInt(v) => Some(*v as i64),
+            Value::Int(v) => Some(*v as i64),
+            Value::BigInt(v) => Some(*v),
+            Value::HugeInt(v) => i64::try_from(*v).ok(),
+            Value::UTinyInt(v) => Some(*v as i64),
+            Value::USmallInt(v) => Some(*v as i64),
+            Value::UInt(v) => Some(*v as i64),
+            Value::UBigInt(v) => i64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    fn flag(&self, column: &str) -> bool {
+        matches!(self.values.get(column), Some(Value::Boolean(true)))
+    }
+
+    /// Columna de tipo lista (VARCHAR[]) como vector de textos
+    fn list(&self, column: &str) -> Vec<String> {
+        match self.values.get(column) {
+            Some(Value::List(items)) | Some(Value::Array(items)) => items
+                .iter()
+                .filter(|v| !matches!(v, Value::Null))
+                .map(DuckDbDriver::value_to_string)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Conexión de una ejecución. La de la transacción manual se comparte y las ejecuciones
+/// la usan por turnos
+#[derive(Clone)]
+struct DuckSession {
+    conn: Arc<StdMutex<DuckConnection>>,
+    interrupt: Arc<InterruptHandle>,
+}
+
+impl DuckSession {
+    fn new(conn: DuckConnection) -> Self {
+        let interrupt = conn.interrupt_handle();
+        Self { conn: Arc::new(StdMutex::new(conn)), interrupt }
+    }
+
+    /// Ejecuta trabajo síncrono con la conexión fuera del runtime de tokio
+    async fn run<T, F>(self, f: F) -> Result<T, DomainError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&DuckConnection) -> Result<T, DomainError> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || f(&self.conn.lock().unwrap()))
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?
+    }
+}
+
+pub struct DuckDbDriver {
+    /// Conexión raíz a la base; cada operación usa un clon sobre la misma instancia
+    db: Arc<RwLock<Option<StdMutex<DuckConnection>>>>,
+    /// Handles de interrupción de las queries en ejecución
+    running: RunningQueries<Arc<InterruptHandle>>,
+    /// Conexión fijada mientras dura una transacción manual
+    transaction: Arc<Mutex<Option<DuckSession>>>,
+}
+
+impl DuckDbDriver {
+    pub fn new() -> Self {
+        Self {
+            db: Arc::new(RwLock::new(None)),
+            running: RunningQueries::new(),
+            transaction: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Ruta del fichero a partir de `duckdb:<ruta>` o `duckdb://<ruta>`, y si se abre en solo lectura
+    fn database_path(connection_string: &str) -> (&str, bool) {
+        let path = connection_string
+            .strip_prefix("duckdb://")
+            .or_else(|| connection_string.strip_prefix("duckdb:"))
+            .unwrap_or(connection_string);
+        match path.strip_suffix(READ_ONLY_PARAM) {
+            Some(path) => (path, true),
+            None => (path, false),
+        }
+    }
+
+    /// En solo lectura es el propio DuckDB quien rechaza las escrituras
+    fn open(connection_string: &str) -> Result<DuckConnection, DomainError> {
+        let (path, read_only) = Self::database_path(connection_string);
+        let opened = if read_only {
+            Config::default()
+                .access_mode(AccessMode::ReadOnly)
+                .and_then(|config| DuckConnection::open_with_flags(path, config))
+        } else {
+            DuckConnection::open(path)
+        };
+        opened.map_err(|e| DomainError::connection(e.to_string()))
+    }
+
+    /// Nueva conexión sobre la base abierta. DuckDB admite varias conexiones
+    /// concurrentes a la misma instancia, así que no hace falta un pool.
+    async fn connection(&self) -> Result<DuckConnection, DomainError> {
+        let guard = self.db.read().await;
+        let root = guard.as_ref().ok_or_else(|| DomainError::connection("Not connected"))?;
+        let conn = root.lock().unwrap().try_clone();
+        conn.map_err(|e| DomainError::connection(e.to_string()))
+    }
+
+    /// Conexión de una ejecución, registrada para poder interrumpirla: la de la transacción
+    /// manual si hay una abierta, si no una nueva
+    async fn connection_tracked(&self, query_id: Uuid) -> Result<(DuckSession, RunningQueryGuard<Arc<InterruptHandle>>), DomainError> {
+        let pinned = self.transaction.lock().await.clone();
+        let session = match pinned {
+            Some(session) => session,
+            None => DuckSession::new(self.connection().await?),
+        };
+        let guard = self.running.register(query_id, session.interrupt.clone());
+        Ok((session, guard))
+    }
+
+    /// Ejecuta trabajo síncrono de DuckDB fuera del runtime de tokio
+    async fn blocking<T, F>(conn: DuckConnection, f: F) -> Result<T, DomainError>
+    where
+        T: Send + 'static,
+        F: FnOnce(DuckConnection) -> Result<T, DomainError> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || f(conn))
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?
+    }
+
+    /// Convierte errores de DuckDB distinguiendo las queries interrumpidas
+    fn map_query_error(e: duckdb::Error) -> DomainError {
+        let message = e.to_string();
+        if message.contains("INTERRUPT") {
+            return DomainError::Cancelled;
+        }
+        DomainError::query(message)
+    }
+
+    /// Ejecuta una query con parámetros posicionales y construye el QueryResult
+    async fn fetch_result(&self, query: &str, params: &[serde_json::Value], options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        let (session, _running) = self.connection_tracked(options.query_id).await?;
+        let sql = query.to_string();
+        // Primero los parámetros del usuario y después los que añade la paginación
+        let params: Vec<Value> = options
+            .params
+            .iter()
+            .map(Self::cell_to_value)
+            .chain(params.iter().map(Self::json_to_value))
+            .collect();
+        let start = Instant::now();
+
+        let (columns, data) = session
+            .run(move |conn| Self::run_query(conn, &sql, params).map_err(Self::map_query_error))
+            .await?;
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        Ok(QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time))
+    }
+
+    fn run_query(conn: &DuckConnection, sql: &str, params: Vec<Value>) -> Result<(Vec<ColumnInfo>, Vec<Vec<CellValue>>), duckdb::Error> {
+        let mut stmt = conn.prepare(sql)?;
+        let mut data = Vec::new();
+        {
+            let mut rows = stmt.query(params_from_iter(params))?;
+            while let Some(row) = rows.next()? {
+                data.push(Self::row_values(row)?);
+            }
+        }
+        Ok((Self::column_infos(&stmt), data))
+    }
+
+    /// Filas de una consulta de catálogo
+    async fn fetch_rows(&self, sql: &str, params: Vec<Value>) -> Result<Vec<CatalogRow>, DomainError> {
+        let conn = self.connection().await?;
+        let sql = sql.to_string();
+
+        Self::blocking(conn, move |conn| {
+            let read = || -> Result<Vec<CatalogRow>, duckdb::Error> {
+                let mut stmt = conn.prepare(&sql)?;
+                let mut result = Vec::new();
+                let mut rows = stmt.query(params_from_iter(params))?;
+                while let Some(row) = rows.next()? {
+                    let names = row.as_ref().column_names();
+                    let mut values = HashMap::with_capacity(names.len());
+                    for (idx, name) in names.into_iter().enumerate() {
+                        values.insert(name, row.get::<_, Value>(idx)?);
+                    }
+                    result.push(CatalogRow { values });
+                }
+                Ok(result)
+            };
+            read().map_err(|e| DomainError::query(e.to_string()))
+        })
+        .await
+    }
+
+    fn json_to_value(value: &serde_json::Value) -> Value {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Boolean(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => Value::BigInt(i),
+                None => n.as_f64().map(Value::Double).unwrap_or(Value::Null),
+            },
+            serde_json::Value::String(text) => Value::Text(text.clone()),
+            other => Value::Text(other.to_string()),
+        }
+    }
+
+    /// Valor tipado del usuario; fechas y UUIDs van como texto y DuckDB los convierte al comparar
+    fn cell_to_value(value: &CellValue) -> Value {
+        match value {
+            CellValue::Null => Value::Null,
+            CellValue::Bool(b) => Value::Boolean(*b),
+            CellValue::Int(i) => Value::BigInt(*i),
+            CellValue::Float(f) => Value::Double(*f),
+            CellValue::Bytes(b) => Value::Blob(b.clone()),
+            CellValue::Json(v) => Value::Text(v.to_string()),
+            CellValue::Array(_) => Value::Text(pagination::cell_to_json(value).to_string()),
+            CellValue::String(s)
+            | CellValue::Date(s)
+            | CellValue::Time(s)
+            | CellValue::DateTime(s)
+            | CellValue::Uuid(s) => Value::Text(s.clone()),
+        }
+    }
+
+    /// Clave primaria para paginar por keyset, si la query lee una sola tabla y la proyecta
+    async fn keyset_columns(&self, query: &str) -> Option<Vec<String>> {
+        let select = SimpleSelect::parse(query)?;
+        let keys: Vec<String> = self
+            .get_columns(&select.table, select.schema.as_deref())
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name)
+            .collect();
+        (!keys.is_empty() && select.projects(&keys)).then_some(keys)
+    }
+
+    /// Total de filas según el modo pedido.
+    /// Como en SQLite, al ser una base local se cuenta siempre de forma exacta.
+    async fn count_rows(&self, query: &str, params: &[CellValue], mode: CountMode) -> Result<RowTotal, DomainError> {
+        if mode == CountMode::None {
+            return Ok((None, false));
+        }
+        let params = params.iter().map(Self::cell_to_value).collect();
+        let rows = self
+            .fetch_rows(&format!("SELECT COUNT(*) AS total FROM ({}) AS qx_count", query), params)
+            .await?;
+        let total = rows.first().and_then(|r| r.int("total")).unwrap_or_default();
+        Ok((Some(total as u64), false))
+    }
+
+    /// Columnas del resultado; solo disponibles después de ejecutar la sentencia
+    fn column_infos(stmt: &Statement<'_>) -> Vec<ColumnInfo> {
+        (0..stmt.column_count())
+            .map(|idx| ColumnInfo {
+                name: stmt.column_name(idx).map(|n| n.to_string()).unwrap_or_default(),
+                data_type: Self::type_name(&stmt.column_type(idx)),
+                nullable: true,
+                is_primary_key: false,
+            })
+            .collect()
+    }
+
+    /// Nombre DuckDB del tipo Arrow con el que llega la columna
+    fn type_name(data_type: &DataType) -> String {
+        match data_type {
+            DataType::Null => "NULL".to_string(),
+            DataType::Boolean => "BOOLEAN".to_string(),
+            DataType::Int8 => "TINYINT".to_string(),
+            DataType::Int16 => "SMALLINT".to_string(),
+            DataType::Int32 => "INTEGER".to_string(),
+            DataType::Int64 => "BIGINT".to_string(),
+            DataType::UInt8 => "UTINYINT".to_string(),
+            DataType::UInt16 => "USMALLINT".to_string(),
+            DataType::UInt32 => "UINTEGER".to_string(),
+            DataType::UInt64 => "UBIGINT".to_string(),
+            DataType::Float32 => "FLOAT".to_string(),
+            DataType::Float64 => "DOUBLE".to_string(),
+            DataType::Decimal128(precision, scale) => format!("DECIMAL({},{})", precision, scale),
+            DataType::Utf8 | DataType::LargeUtf8 => "VARCHAR".to_string(),
+            DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => "BLOB".to_string(),
+            DataType::Date32 | DataType::Date64 => "DATE".to_string(),
+            DataType::Time32(_) | DataType::Time64(_) => "TIME".to_string(),
+            DataType::Timestamp(_, Some(_)) => "TIMESTAMP WITH TIME ZONE".to_string(),
+            DataType::Timestamp(_, None) => "TIMESTAMP".to_string(),
+            DataType::Interval(_) | DataType::Duration(_) => "INTERVAL".to_string(),
+            DataType::List(field) | DataType::LargeList(field) => format!("{}[]", Self::type_name(field.data_type())),
+            DataType::FixedSizeList(field, size) => format!("{}[{}]", Self::type_name(field.data_type()), size),
+            DataType::Struct(_) => "STRUCT".to_string(),
+            DataType::Map(_, _) => "MAP".to_string(),
+            DataType::Union(_, _) => "UNION".to_string(),
+            DataType::Dictionary(_, _) => "ENUM".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    fn row_values(row: &duckdb::Row<'_>) -> Result<Vec<CellValue>, duckdb::Error> {
+        (0..row.as_ref().column_count())
+            .map(|idx| row.get::<_, Value>(idx).map(Self::map_value))
+            .collect()
+    }
+
+    fn map_value(value: Value) -> CellValue {
+        match value {
+            Value::Null => CellValue::Null,
+            Value::Boolean(b) => CellValue::Bool(b),
+            Value::TinyInt(v) => CellValue::Int(v as i64),
+            Value::SmallInt(v) => CellValue::Int(v as i64),
+            Value::Int(v) => CellValue::Int(v as i64),
+            Value::BigInt(v) => CellValue::Int(v),
+            Value::UTinyInt(v) => CellValue::Int(v as i64),
+            Value::USmallInt(v) => CellValue::Int(v as i64),
+            Value::UInt(v) => CellValue::Int(v as i64),
+            // HUGEINT y UBIGINT pueden no caber en i64: se pasan como texto para no perder dígitos
+            Value::HugeInt(v) => i64::try_from(v).map(CellValue::Int).unwrap_or_else(|_| CellValue::String(v.to_string())),
+            Value::UBigInt(v) => i64::try_from(v).map(CellValue::Int).unwrap_or_else(|_| CellValue::String(v.to_string())),
+            Value::Float(v) => CellValue::Float(v as f64),
+            Value::Double(v) => CellValue::Float(v),
+            // DECIMAL admite hasta 38 dígitos: como texto se conserva el valor exacto
+            Value::Decimal(d) => CellValue::String(d.to_string()),
+            Value::Text(s) | Value::Enum(s) => CellValue::String(s),
+            Value::Blob(b) => CellValue::Bytes(b),
+            Value::Date32(days) => chrono::DateTime::from_timestamp(days as i64 * 86_400, 0)
+                .map(|d| CellValue::Date(d.date_naive().to_string()))
+                .unwrap_or(CellValue::Null),
+            Value::Time64(unit, v) => {
+                let micros = unit.to_micros(v);
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                    (micros / 1_000_000) as u32,
+                    ((micros % 1_000_000) * 1_000) as u32,
+                )
+                .map(|t| CellValue::Time(t.to_string()))
+                .unwrap_or(CellValue::Null)
+            }
+            Value::Timestamp(unit, v) => chrono::DateTime::from_timestamp_micros(unit.to_micros(v))
+                .map(|d| CellValue::DateTime(d.naive_utc().to_string()))
+                .unwrap_or(CellValue::Null),
+            Value::Interval { months, days, nanos } => CellValue::String(Self::format_interval(months, days, nanos)),
+            Value::List(items) | Value::Array(items) => {
+                CellValue::Array(items.into_iter().map(Self::map_value).collect())
+            }
+            Value::Struct(fields) => CellValue::Json(serde_json::Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, v)| (name.clone(), Self::value_to_json(v)))
+                    .collect(),
+            )),
+            // Las claves de un MAP pueden ser de cualquier tipo: se guardan como pares
+            Value::Map(entries) => CellValue::Json(serde_json::Value::Array(
+                entries
+                    .iter()
+                    .map(|(k, v)| serde_json::json!({ "key": Self::value_to_json(k), "value": Self::value_to_json(v) }))
+                    .collect(),
+            )),
+            Value::Union(inner) => Self::map_value(*inner),
+        }
+    }
+
+    /// Valor anidado (dentro de STRUCT o MAP) como JSON
+    fn value_to_json(value: &Value) -> serde_json::Value {
+        Self::cell_to_json(Self::map_value(value.clone()))
+    }
+
+    fn cell_to_json(cell: CellValue) -> serde_json::Value {
+        match cell {
+            CellValue::Array(items) => serde_json::Value::Array(items.into_iter().map(Self::cell_to_json).collect()),
+            other => pagination::cell_to_json(&other),
+        }
+    }
+
+    fn value_to_string(value: &Value) -> String {
+        match Self::value_to_json(value) {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        }
+    }
+
+    /// INTERVAL con el formato que usa DuckDB al mostrarlo: `1 year 2 months 3 days 04:05:06`
+    fn format_interval(months: i32, days: i32, nanos: i64) -> String {
+        let plural = |n: i64, unit: &str| format!("{} {}{}", n, unit, if n.abs() == 1 { "" } else { "s" });
+        let mut parts = Vec::new();
+
+        let (years, months) = (months / 12, months % 12);
+        if years != 0 {
+            parts.push(plural(years as i64, "year"));
+        }
+        if months != 0 {
+            parts.push(plural(months as i64, "month"));
+        }
+        if days != 0 {
+            parts.push(plural(days as i64, "day"));
+        }
+
+        if nanos != 0 || parts.is_empty() {
+            let sign = if nanos < 0 { "-" } else { "" };
+            let micros = (nanos / 1_000).abs();
+            let secs = micros / 1_000_000;
+            let mut time = format!("{}{:02}:{:02}:{:02}", sign, secs / 3600, (secs / 60) % 60, secs % 60);
+            if micros % 1_000_000 != 0 {
+                time.push_str(&format!(".{:06}", micros % 1_000_000));
+            }
+            parts.push(time);
+        }
+
+        parts.join(" ")
+    }
+
+    fn fk_action(rule: Option<String>) -> ForeignKeyAction {
+        match rule.as_deref() {
+            Some("CASCADE") => ForeignKeyAction::Cascade,
+            Some("SET NULL") => ForeignKeyAction::SetNull,
+            Some("SET DEFAULT") => ForeignKeyAction::SetDefault,
+            Some("RESTRICT") => ForeignKeyAction::Restrict,
+            _ => ForeignKeyAction::NoAction,
+        }
+    }
+}
+
+impl Default for DuckDbDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SqlDriver for DuckDbDriver {
+    fn driver_id(&self) -> &'static str { "duckdb" }
+    fn display_name(&self) -> &'static str { "DuckDB" }
+
+    async fn connect(&self, connection_string: &str) -> Result<(), DomainError> {
+        let connection_string = connection_string.to_string();
+        let conn = tokio::task::spawn_blocking(move || Self::open(&connection_string))
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))??;
+
+        let mut guard = self.db.write().await;
+        *guard = Some(StdMutex::new(conn));
+        Ok(())
+    }
+
+    async fn disconnect(&self) -> Result<(), DomainError> {
+        // La base se cierra cuando terminan las conexiones clonadas que sigan en uso; la de la
+        // transacción manual se suelta y DuckDB deshace lo que no se confirmó
+        self.transaction.lock().await.take();
+        let mut guard = self.db.write().await;
+        guard.take();
+        Ok(())
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.db.read().await.is_some()
+    }
+
+    async fn test_connection(&self, connection_string: &str) -> Result<(), DomainError> {
+        let connection_string = connection_string.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = Self::open(&connection_string)?;
+            conn.execute_batch("SELECT 1")
+                .map_err(|e| DomainError::connection(e.to_string()))
+        })
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?
+    }
+
+    async fn execute_query(&self, query: &str, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        self.fetch_result(query, &[], options).await
+    }
+
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        options: &ExecutionOptions,
+        stream_options: &StreamOptions,
+        sender: mpsc::Sender<RowBatch>,
+    ) -> Result<QueryStreamSummary, DomainError> {
+        let (session, _running) = self.connection_tracked(options.query_id).await?;
+        let mut writer = RowBatchWriter::new(sender, stream_options);
+        let (tx, mut rx) = mpsc::channel::<Streamed>(STREAM_CHANNEL_ROWS);
+
+        // El hilo lee filas mientras el canal acepte; al cerrarlo el receptor, se detiene
+        let sql = query.to_string();
+        let params: Vec<Value> = options.params.iter().map(Self::cell_to_value).collect();
+        let reader = tokio::task::spawn_blocking(move || -> Result<bool, duckdb::Error> {
+            let conn = session.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&sql)?;
+            let mut rows = stmt.query(params_from_iter(params))?;
+            let mut first = true;
+            while let Some(row) = rows.next()? {
+                if first {
+                    first = false;
+                    let columns = Self::column_infos(row.as_ref());
+                    if tx.blocking_send(Streamed::Columns(columns)).is_err() {
+                        return Ok(false);
+                    }
+                }
+                if tx.blocking_send(Streamed::Row(Self::row_values(row)?)).is_err() {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        });
+
+        let mut stopped = false;
+        while let Some(item) = rx.recv().await {
+            match item {
+                Streamed::Columns(columns) => writer.set_columns(columns),
+                Streamed::Row(values) => {
+                    if !writer.push(values).await {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+        }
+        drop(rx);
+
+        let exhausted = reader
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?
+            .map_err(Self::map_query_error)?;
+
+        Ok(writer.finish(options.query_id, query, exhausted && !stopped).await)
+    }
+
+    async fn execute_query_paginated(&self, query: &str, page: &PageRequest, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        let query = pagination::trim_statement(query);
+        if !pagination::is_row_returning(query) {
+            return self.execute_query(query, options).await;
+        }
+
+        let key_columns = self.keyset_columns(query).await;
+        let plan = KeysetPage::new(query, page, key_columns, |c| format!("\"{}\"", c.replace('"', "\"\"")));
+        let result = self.fetch_result(&plan.sql, &plan.params, options).await?;
+        let total = match plan.known_total() {
+            Some(total) => total,
+            None => self.count_rows(query, &options.params, page.count_mode).await?,
+        };
+
+        Ok(plan.finish(result, query, page, total))
+    }
+
+    async fn close_pagination_cursor(&self, _cursor: &str) -> Result<(), DomainError> {
+        // La paginación keyset no deja estado en el servidor
+        Ok(())
+    }
+
+    async fn insert_row(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        values: std::collections::HashMap<String, serde_json::Value>,
+    ) -> Result<QueryResult, DomainError> {
+        let columns_info = self.get_columns(table, schema).await?;
+        let target = format!("\"{}\".\"{}\"", schema.unwrap_or("main").replace('"', "\"\""), table.replace('"', "\"\""));
+
+        // Las columnas vacías con default (p. ej. nextval de una secuencia) se omiten
+        let mut entries = Vec::new();
+        for info in &columns_info {
+            if let Some(val) = values.get(&info.name) {
+                let is_empty = val.is_null() || val.as_str().is_some_and(|s| s.is_empty());
+                if is_empty && info.default_value.is_some() {
+                    continue;
+                }
+                entries.push((info.name.clone(), Self::json_to_value(val)));
+            }
+        }
+
+        let sql = if entries.is_empty() {
+            format!("INSERT INTO {} DEFAULT VALUES RETURNING *", target)
+        } else {
+            let cols = entries.iter().map(|(k, _)| format!("\"{}\"", k.replace('"', "\"\""))).collect::<Vec<_>>().join(", ");
+            let placeholders = vec!["?"; entries.len()].join(", ");
+            format!("INSERT INTO {} ({}) VALUES ({}) RETURNING *", target, cols, placeholders)
+        };
+
+        let conn = self.connection().await?;
+        let params: Vec<Value> = entries.into_iter().map(|(_, v)| v).collect();
+        let query = sql.clone();
+        let (columns, data) = Self::blocking(conn, move |conn| {
+            Self::run_query(&conn, &query, params).map_err(|e| DomainError::query(e.to_string()))
+        })
+        .await?;
+
+        let affected = data.len() as u64;
+        Ok(QueryResult::new(sql, columns, data).with_affected_rows(affected))
+    }
+
+    async fn execute_statement(&self, statement: &str, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (session, _running) = self.connection_tracked(options.query_id).await?;
+        let sql = statement.to_string();
+        let params: Vec<Value> = options.params.iter().map(Self::cell_to_value).collect();
+
+        session
+            .run(move |conn| {
+                conn.execute(&sql, params_from_iter(params))
+                    .map(|affected| affected as u64)
+                    .map_err(Self::map_query_error)
+            })
+            .await
+    }
+
+    async fn execute_multi_statement(&self, statements: Vec<String>, options: &ExecutionOptions) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
+        let mut results = Vec::new();
+
+        for (idx, statement) in statements.iter().enumerate() {
+            let start = Instant::now();
+            let sql = statement.trim();
+
+            if sql.is_empty() {
+                continue;
+            }
+
+            let result = if pagination::may_return_rows(sql) {
+                match self.execute_query(sql, options).await {
+                    Ok(query_result) => crate::domain::StatementResult {
+                        statement_index: idx,
+                        sql: sql.to_string(),
+                        success: true,
+                        affected_rows: None,
+                        result: Some(query_result),
+                        error: None,
+                        execution_time_ms: start.elapsed().as_millis() as u64,
+                    },
+                    Err(e) => crate::domain::StatementResult {
+                        statement_index: idx,
+                        sql: sql.to_string(),
+                        success: false,
+                        affected_rows: None,
+                        result: None,
+                        error: Some(e.to_string()),
+                        execution_time_ms: start.elapsed().as_millis() as u64,
+                    },
+                }
+            } else {
+                match self.execute_statement(sql, options).await {
+                    Ok(affected) => crate::domain::StatementResult {
+                        statement_index: idx,
+                        sql: sql.to_string(),
+                        success: true,
+                        affected_rows: Some(affected),
+                        result: None,
+                        error: None,
+                        execution_time_ms: start.elapsed().as_millis() as u64,
+                    },
+                    Err(e) => crate::domain::StatementResult {
+                        statement_index: idx,
+                        sql: sql.to_string(),
+                        success: false,
+                        affected_rows: None,
+                        result: None,
+                        error: Some(e.to_string()),
+                        execution_time_ms: start.elapsed().as_millis() as u64,
+                    },
+                }
+            };
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Hay transacciones, pero sin savepoints no se puede deshacer solo una parte
+    fn supports_transactions(&self) -> bool {
+        false
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.transaction.lock().await.is_some()
+    }
+
+    /// DuckDB aísla siempre con snapshots, así que no se puede elegir el nivel. No hay
+    /// transacciones de solo lectura: en las conexiones de solo lectura es el fichero el que
+    /// se abre así (una base en memoria no puede, y ahí solo queda la comprobación previa)
+    async fn begin_transaction(&self, options: &TransactionOptions) -> Result<(), DomainError> {
+        if options.isolation_level.is_some() {
+            return Err(DomainError::validation("DuckDB transactions always use snapshot isolation"));
+        }
+        let mut slot = self.transaction.lock().await;
+        if slot.is_some() {
+            return Err(DomainError::validation("A transaction is already open"));
+        }
+        let session = DuckSession::new(self.connection().await?);
+        session
+            .clone()
+            .run(|conn| conn.execute_batch("BEGIN TRANSACTION").map_err(|e| DomainError::query(e.to_string())))
+            .await?;
+        *slot = Some(session);
+        Ok(())
+    }
+
+    /// Si el COMMIT falla por un conflicto, DuckDB ya ha deshecho la transacción
+    async fn commit_transaction(&self) -> Result<(), DomainError> {
+        let session = self
+            .transaction
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| DomainError::validation("No transaction is open"))?;
+        session
+            .run(|conn| {
+                conn.execute_batch("COMMIT")
+                    .map_err(|e| DomainError::query(format!("Failed to commit transaction: {}", e)))
+            })
+            .await
+    }
+
+    async fn rollback_transaction(&self) -> Result<(), DomainError> {
+        let session = self
+            .transaction
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| DomainError::validation("No transaction is open"))?;
+        session
+            .run(|conn| {
+                conn.execute_batch("ROLLBACK")
+                    .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))
+            })
+            .await
+    }
+
+    async fn create_savepoint(&self, _name: &str) -> Result<(), DomainError> {
+        Err(DomainError::validation("DuckDB does not support savepoints"))
+    }
+
+    async fn rollback_to_savepoint(&self, _name: &str) -> Result<(), DomainError> {
+        Err(DomainError::validation("DuckDB does not support savepoints"))
+    }
+
+    async fn execute_in_transaction(&self, statement: &str) -> Result<crate::domain::TransactionResult, DomainError> {
+        let conn = self.connection().await?;
+        let sql = statement.to_string();
+        let start = Instant::now();
+
+        Self::blocking(conn, move |mut conn| {
+            let tx = conn.transaction()
+                .map_err(|e| DomainError::query(format!("Failed to begin transaction: {}", e)))?;
+
+            match tx.execute(&sql, []) {
+                Ok(affected) => {
+                    tx.commit()
+                        .map_err(|e| DomainError::query(format!("Failed to commit transaction: {}", e)))?;
+
+                    Ok(crate::domain::TransactionResult {
+                        affected_rows: affected as u64,
+                        execution_time_ms: start.elapsed().as_millis() as u64,
+                        committed: true,
+                    })
+                }
+                Err(e) => {
+                    tx.rollback()
+                        .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))?;
+
+                    Err(DomainError::query(e.to_string()))
+                }
+            }
+        })
+        .await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<String>, DomainError> {
+        // Además del fichero principal, las bases añadidas con ATTACH
+        let rows = self
+            .fetch_rows(
+                "SELECT database_name FROM duckdb_databases() WHERE NOT internal ORDER BY database_name",
+                Vec::new(),
+            )
+            .await?;
+
+        Ok(rows.iter().filter_map(|r| r.text("database_name")).collect())
+    }
+
+    async fn get_database_info(&self, database: &str) -> Result<DatabaseInfo, DomainError> {
+        let rows = self
+            .fetch_rows(
+                "SELECT CAST(total_blocks * block_size AS BIGINT) AS size_bytes
+                 FROM pragma_database_size() WHERE database_name = ?",
+                vec![Value::Text(database.to_string())],
+            )
+            .await
+            .unwrap_or_default();
+
+        Ok(DatabaseInfo {
+            name: database.to_string(),
+            schemas: self.list_schemas(database).await?,
+            size_bytes: rows.first().and_then(|r| r.int("size_bytes")).map(|s| s as u64),
+            encoding: Some("UTF-8".to_string()),
+        })
+    }
+
+    async fn list_schemas(&self, database: &str) -> Result<Vec<SchemaInfo>, DomainError> {
+        let rows = self
+            .fetch_rows(
+                "SELECT schema_name, internal FROM duckdb_schemas() WHERE database_name = ? ORDER BY schema_name",
+                vec![Value::Text(database.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                let name = r.text("schema_name")?;
+                Some(SchemaInfo {
+                    is_system: r.flag("internal") || name == "information_schema" || name == "pg_catalog",
+                    name,
+                    tables: vec![],
+                    views: vec![],
+                    functions: vec![],
+                    sequences: vec![],
+                })
+            })
+            .collect())
+    }
+
+    async fn list_tables(&self, schema: Option<&str>) -> Result<Vec<TableInfo>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        let rows = self
+            .fetch_rows(
+                "SELECT table_name, estimated_size, comment FROM duckdb_tables()
+                 WHERE database_name = current_database() AND schema_name = ? AND NOT internal
+                 ORDER BY table_name",
+                vec![Value::Text(schema_name.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                Some(TableInfo {
+                    name: r.text("table_name")?,
+                    schema: Some(schema_name.to_string()),
+                    columns: vec![],
+                    primary_key: None,
+                    foreign_keys: vec![],
+                    indexes: vec![],
+                    constraints: vec![],
+                    triggers: vec![],
+                    row_count: r.int("estimated_size").map(|c| c as u64),
+                    size_bytes: None,
+                    comment: r.text("comment"),
+                    storage: None,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_table_info(&self, table: &str, schema: Option<&str>) -> Result<TableInfo, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+        let columns = self.get_columns(table, Some(schema_name)).await?;
+        let indexes = self.list_indexes(table, Some(schema_name)).await?;
+        let constraints = self.list_constraints(table, Some(schema_name)).await?;
+
+        let pk_columns: Vec<String> = columns.iter().filter(|c| c.is_primary_key).map(|c| c.name.clone()).collect();
+        let primary_key = if !pk_columns.is_empty() {
+            let name = constraints
+                .iter()
+                .find(|c| matches!(c.constraint_type, ConstraintType::PrimaryKey))
+                .map(|c| c.name.clone());
+            Some(PrimaryKeyInfo { name, columns: pk_columns })
+        } else {
+            None
+        };
+
+        // Las reglas ON UPDATE/ON DELETE salen de information_schema
+        let fk_rows = self
+            .fetch_rows(
+                "SELECT c.constraint_name, c.constraint_column_names, c.referenced_table,
+                        c.referenced_column_names, rc.update_rule, rc.delete_rule
+                 FROM duckdb_constraints() c
+                 LEFT JOIN information_schema.referential_constraints rc
+                   ON rc.constraint_schema = c.schema_name AND rc.constraint_name = c.constraint_name
+                 WHERE c.database_name = current_database() AND c.schema_name = ? AND c.table_name = ?
+                   AND c.constraint_type = 'FOREIGN KEY'
+                 ORDER BY c.constraint_index",
+                vec![Value::Text(schema_name.to_string()), Value::Text(table.to_string())],
+            )
+            .await
+            .unwrap_or_default();
+
+        let foreign_keys = fk_rows
+            .iter()
+            .map(|r| ForeignKeyInfo {
+                name: r.text("constraint_name").unwrap_or_default(),
+                columns: r.list("constraint_column_names"),
+                referenced_table: r.text("referenced_table").unwrap_or_default(),
+                referenced_schema: Some(schema_name.to_string()),
+                referenced_columns: r.list("referenced_column_names"),
+                on_update: Self::fk_action(r.text("update_rule")),
+                on_delete: Self::fk_action(r.text("delete_rule")),
+            })
+            .collect();
+
+        let stats = self
+            .fetch_rows(
+                "SELECT estimated_size, comment FROM duckdb_tables()
+                 WHERE database_name = current_database() AND schema_name = ? AND table_name = ?",
+                vec![Value::Text(schema_name.to_string()), Value::Text(table.to_string())],
+            )
+            .await
+            .unwrap_or_default();
+        let stats = stats.first();
+
+        Ok(TableInfo {
+            name: table.to_string(),
+            schema: Some(schema_name.to_string()),
+            columns,
+            primary_key,
+            foreign_keys,
+            indexes,
+            constraints,
+            // DuckDB no tiene triggers
+            triggers: vec![],
+            row_count: stats.and_then(|r| r.int("estimated_size")).map(|c| c as u64),
+            size_bytes: None,
+            comment: stats.and_then(|r| r.text("comment")),
+            storage: None,
+        })
+    }
+
+    async fn get_columns(&self, table: &str, schema: Option<&str>) -> Result<Vec<ColumnSchema>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        let rows = self
+            .fetch_rows(
+                "SELECT c.column_name, c.data_type, c.is_nullable, c.column_default, c.column_index,
+                        c.character_maximum_length, c.numeric_precision, c.numeric_scale, c.comment,
+                        EXISTS (SELECT 1 FROM duckdb_constraints() k
+                                WHERE k.database_name = c.database_name AND k.schema_name = c.schema_name
+                                  AND k.table_name = c.table_name AND k.constraint_type = 'PRIMARY KEY'
+                                  AND list_contains(k.constraint_column_names, c.column_name)) AS is_primary_key,
+                        EXISTS (SELECT 1 FROM duckdb_constraints() k
+                                WHERE k.database_name = c.database_name AND k.schema_name = c.schema_name
+                                  AND k.table_name = c.table_name AND k.constraint_type = 'UNIQUE'
+                                  AND list_contains(k.constraint_column_names, c.column_name)) AS is_unique
+                 FROM duckdb_columns() c
+                 WHERE c.database_name = current_database() AND c.schema_name = ? AND c.table_name = ?
+                 ORDER BY c.column_index",
+                vec![Value::Text(schema_name.to_string()), Value::Text(table.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| {
+                let data_type = r.text("data_type").unwrap_or_default();
+                let default_value = r.text("column_default");
+                ColumnSchema {
+                    name: r.text("column_name").unwrap_or_default(),
+                    native_type: data_type.clone(),
+                    nullable: r.flag("is_nullable"),
+                    is_primary_key: r.flag("is_primary_key"),
+                    is_unique: r.flag("is_unique"),
+                    // No hay columnas identity: el autoincremento es un default nextval('seq')
+                    is_auto_increment: default_value.as_deref().is_some_and(|d| d.starts_with("nextval(")),
+                    default_value,
+                    max_length: r.int("character_maximum_length").map(|v| v as u32),
+                    numeric_precision: r.int("numeric_precision").map(|v| v as u32),
+                    numeric_scale: r.int("numeric_scale").map(|v| v as u32),
+                    comment: r.text("comment"),
+                    ordinal_position: r.int("column_index").unwrap_or_default() as u32,
+                    data_type,
+                }
+            })
+            .collect())
+    }
+
+    async fn list_views(&self, schema: Option<&str>) -> Result<Vec<ViewInfo>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        let rows = self
+            .fetch_rows(
+                "SELECT view_name, sql FROM duckdb_views()
+                 WHERE database_name = current_database() AND schema_name = ? AND NOT internal
+                 ORDER BY view_name",
+                vec![Value::Text(schema_name.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                Some(ViewInfo {
+                    name: r.text("view_name")?,
+                    schema: Some(schema_name.to_string()),
+                    columns: vec![],
+                    definition: r.text("sql"),
+                    is_materialized: false,
+                })
+            })
+            .collect())
+    }
+
+    async fn list_indexes(&self, table: &str, schema: Option<&str>) -> Result<Vec<IndexInfo>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        // duckdb_indexes() solo lista los índices creados con CREATE INDEX; las claves
+        // primarias y UNIQUE se añaden desde duckdb_constraints() porque también usan un índice ART
+        let rows = self
+            .fetch_rows(
+                "SELECT index_name, CAST(expressions AS VARCHAR) AS expressions, is_unique, is_primary
+                 FROM duckdb_indexes()
+                 WHERE database_name = current_database() AND schema_name = ? AND table_name = ?
+                 ORDER BY index_name",
+                vec![Value::Text(schema_name.to_string()), Value::Text(table.to_string())],
+            )
+            .await?;
+
+        let mut indexes: Vec<IndexInfo> = rows
+            .iter()
+            .map(|r| IndexInfo {
+                name: r.text("index_name").unwrap_or_default(),
+                // Las expresiones llegan como `[col1, col2]`
+                columns: r
+                    .text("expressions")
+                    .unwrap_or_default()
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split(',')
+                    .map(|c| c.trim().trim_matches('\'').trim_matches('"').to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect(),
+                is_unique: r.flag("is_unique"),
+                is_primary: r.flag("is_primary"),
+                index_type: "art".to_string(),
+            })
+            .collect();
+
+        for constraint in self.list_constraints(table, Some(schema_name)).await? {
+            let is_primary = matches!(constraint.constraint_type, ConstraintType::PrimaryKey);
+            if is_primary || matches!(constraint.constraint_type, ConstraintType::Unique) {
+                indexes.push(IndexInfo {
+                    name: constraint.name,
+                    columns: constraint.columns,
+                    is_unique: true,
+                    is_primary,
+                    index_type: "art".to_string(),
+                });
+            }
+        }
+
+        Ok(indexes)
+    }
+
+    async fn list_constraints(&self, table: &str, schema: Option<&str>) -> Result<Vec<ConstraintInfo>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        let rows = self
+            .fetch_rows(
+                "SELECT constraint_name, constraint_type, constraint_column_names, constraint_text
+                 FROM duckdb_constraints()
+                 WHERE database_name = current_database() AND schema_name = ? AND table_name = ?
+                   AND constraint_type IN ('PRIMARY KEY', 'UNIQUE', 'FOREIGN KEY', 'CHECK')
+                 ORDER BY constraint_index",
+                vec![Value::Text(schema_name.to_string()), Value::Text(table.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| {
+                let constraint_type = match r.text("constraint_type").as_deref() {
+                    Some("PRIMARY KEY") => ConstraintType::PrimaryKey,
+                    Some("UNIQUE") => ConstraintType::Unique,
+                    Some("FOREIGN KEY") => ConstraintType::ForeignKey,
+                    _ => ConstraintType::Check,
+                };
+                ConstraintInfo {
+                    name: r.text("constraint_name").unwrap_or_default(),
+                    constraint_type,
+                    columns: r.list("constraint_column_names"),
+                    definition: r.text("constraint_text"),
+                }
+            })
+            .collect())
+    }
+
+    async fn list_triggers(&self, _table: &str, _schema: Option<&str>) -> Result<Vec<TriggerInfo>, DomainError> {
+        // DuckDB no tiene triggers
+        Ok(vec![])
+    }
+
+    async fn list_functions(&self, schema: Option<&str>) -> Result<Vec<FunctionInfo>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        // Las funciones de usuario en DuckDB son macros (escalares o de tabla)
+        let rows = self
+            .fetch_rows(
+                "SELECT function_name, function_type, return_type, parameters, parameter_types, macro_definition
+                 FROM duckdb_functions()
+                 WHERE database_name = current_database() AND schema_name = ? AND NOT internal
+                 ORDER BY function_name",
+                vec![Value::Text(schema_name.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                let types = r.list("parameter_types");
+                let parameters = r
+                    .list("parameters")
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, name)| FunctionParameter {
+                        name,
+                        data_type: types.get(i).cloned().unwrap_or_default(),
+                        mode: ParameterMode::In,
+                        default_value: None,
+                    })
+                    .collect();
+
+                Some(FunctionInfo {
+                    name: r.text("function_name")?,
+                    schema: Some(schema_name.to_string()),
+                    return_type: if r.text("function_type").as_deref() == Some("table_macro") {
+                        Some("TABLE".to_string())
+                    } else {
+                        r.text("return_type")
+                    },
+                    parameters,
+                    language: "SQL".to_string(),
+                    definition: r.text("macro_definition"),
+                })
+            })
+            .collect())
+    }
+
+    async fn list_sequences(&self, schema: Option<&str>) -> Result<Vec<SequenceInfo>, DomainError> {
+        let schema_name = schema.unwrap_or("main");
+
+        let rows = self
+            .fetch_rows(
+                "SELECT sequence_name, start_value, increment_by, min_value, max_value, last_value
+                 FROM duckdb_sequences()
+                 WHERE database_name = current_database() AND schema_name = ?
+                 ORDER BY sequence_name",
+                vec![Value::Text(schema_name.to_string())],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                Some(SequenceInfo {
+                    name: r.text("sequence_name")?,
+                    schema: Some(schema_name.to_string()),
+                    data_type: "BIGINT".to_string(),
+                    start_value: r.int("start_value").unwrap_or(1),
+                    increment: r.int("increment_by").unwrap_or(1),
+                    min_value: r.int("min_value"),
+                    max_value: r.int("max_value"),
+                    current_value: r.int("last_value"),
+                })
+            })
+            .collect())
+    }
+
+    async fn cancel_query(&self, query_id: Uuid) -> Result<(), DomainError> {
+        if let Some(handle) = self.running.get(&query_id) {
+            handle.interrupt();
+        }
+        Ok(())
+    }
+
+    async fn get_completions(&self, prefix: &str, context: CompletionContext) -> Result<Vec<CompletionItem>, DomainError> {
+        let mut completions = Vec::new();
+        let prefix_lower = prefix.to_lowercase();
+
+        // SQL Keywords, incluidas las lecturas directas de ficheros
+        let keywords = ["SELECT", "FROM", "WHERE", "JOIN", "AND", "OR", "ORDER", "BY", "GROUP", "LIMIT",
+            "QUALIFY", "PIVOT", "UNPIVOT", "DESCRIBE", "SUMMARIZE", "ATTACH", "COPY",
+            "read_parquet", "read_csv", "read_csv_auto", "read_json_auto"];
+
+        for kw in keywords {
+            if kw.to_lowercase().starts_with(&prefix_lower) {
+                completions.push(CompletionItem {
+                    label: kw.to_string(),
+                    kind: CompletionKind::Keyword,
+                    detail: Some("SQL Keyword".to_string()),
+                    insert_text: kw.to_string(),
+                    documentation: None,
+                });
+            }
+        }
+
+        // Tables
+        let schema = context.current_schema.as_deref().unwrap_or("main");
+        let tables = self
+            .fetch_rows(
+                "SELECT table_name FROM duckdb_tables()
+                 WHERE database_name = current_database() AND schema_name = ? AND lower(table_name) LIKE ?
+                 ORDER BY table_name LIMIT 20",
+                vec![Value::Text(schema.to_string()), Value::Text(format!("{}%", prefix_lower))],
+            )
+            .await
+            .unwrap_or_default();
+
+        for row in tables {
+            if let Some(name) = row.text("table_name") {
+                completions.push(CompletionItem {
+                    label: name.clone(),
+                    kind: CompletionKind::Table,
+                    detail: Some(format!("Table in {}", schema)),
+                    insert_text: name,
+                    documentation: None,
+                });
+            }
+        }
+
+        Ok(completions)
+    }
+
+    async fn server_version(&self) -> Result<String, DomainError> {
+        let rows = self.fetch_rows("SELECT version() AS version", Vec::new()).await?;
+        rows.first()
+            .and_then(|r| r.text("version"))
+            .ok_or_else(|| DomainError::query("Empty version"))
+    }
+
+    async fn tls_status(&self) -> Result<TlsStatus, DomainError> {
+        // Fichero local: no hay transporte que cifrar
+        Ok(TlsStatus::plain())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_path_and_access_mode_from_the_connection_string() {
+        assert_eq!(DuckDbDriver::database_path("duckdb:/data/a.duckdb"), ("/data/a.duckdb", false));
+        assert_eq!(DuckDbDriver::database_path("duckdb:///data/a.duckdb"), ("/data/a.duckdb", false));
+        assert_eq!(DuckDbDriver::database_path("duckdb::memory:"), (":memory:", false));
+        assert_eq!(
+            DuckDbDriver::database_path("duckdb:C:/data/a.duckdb?access_mode=read_only"),
+            ("C:/data/a.duckdb", true)
+        );
+        // Un `?` del nombre del fichero no es un parámetro
+        assert_eq!(DuckDbDriver::database_path("duckdb:/data/what?.duckdb"), ("/data/what?.duckdb", false));
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::domain::{DatabaseEngine, DomainError, SqlDriver, SqlDriverFactory};
-use super::{MySqlDriver, PostgresDriver, SqliteDriver};
+use super::{ClickHouseDriver, DuckDbDriver, MariaDbDriver, MySqlDriver, PostgresDriver, SqlServerDriver, SqliteDriver};
 
 /// Factory para crear instancias de drivers SQL
 pub struct DriverFactory {
@@ -15,7 +15,11 @@ impl DriverFactory {
         
         drivers.insert("postgresql".to_string(), Arc::new(PostgresDriver::new()));
         drivers.insert("mysql".to_string(), Arc::new(MySqlDriver::new()));
+        drivers.insert("mariadb".to_string(), Arc::new(MariaDbDriver::new()));
         drivers.insert("sqlite".to_string(), Arc::new(SqliteDriver::new()));
+        drivers.insert("sqlserver".to_string(), Arc::new(SqlServerDriver::new()));
+        drivers.insert("duckdb".to_string(), Arc::new(DuckDbDriver::new()));
+        drivers.insert("clickhouse".to_string(), Arc::new(ClickHouseDriver::new()));
         
         Self { drivers }
     }
@@ -24,7 +28,11 @@ impl DriverFactory {
         let key = match engine {
             DatabaseEngine::PostgreSQL => "postgresql",
             DatabaseEngine::MySQL => "mysql",
+            DatabaseEngine::MariaDB => "mariadb",
             DatabaseEngine::SQLite => "sqlite",
+            DatabaseEngine::SqlServer => "sqlserver",
+            DatabaseEngine::DuckDB => "duckdb",
+            DatabaseEngine::ClickHouse => "clickhouse",
         };
 
         self.drivers
@@ -39,7 +47,11 @@ impl DriverFactory {
         match engine {
             DatabaseEngine::PostgreSQL => Box::new(PostgresDriver::new()),
             DatabaseEngine::MySQL => Box::new(MySqlDriver::new()),
+            DatabaseEngine::MariaDB => Box::new(MariaDbDriver::new()),
             DatabaseEngine::SQLite => Box::new(SqliteDriver::new()),
+            DatabaseEngine::SqlServer => Box::new(SqlServerDriver::new()),
+            DatabaseEngine::DuckDB => Box::new(DuckDbDriver::new()),
+            DatabaseEngine::ClickHouse => Box::new(ClickHouseDriver::new()),
         }
     }
 }
@@ -54,8 +66,12 @@ impl SqlDriverFactory for DriverFactory {
     fn create_driver(&self, engine: &str) -> Result<Box<dyn SqlDriver>, DomainError> {
         match engine.to_lowercase().as_str() {
             "postgresql" | "postgres" => Ok(Box::new(PostgresDriver::new())),
-            "mysql" | "mariadb" => Ok(Box::new(MySqlDriver::new())),
+            "mysql" => Ok(Box::new(MySqlDriver::new())),
+            "mariadb" => Ok(Box::new(MariaDbDriver::new())),
             "sqlite" | "sqlite3" => Ok(Box::new(SqliteDriver::new())),
+            "sqlserver" | "mssql" => Ok(Box::new(SqlServerDriver::new())),
+            "duckdb" => Ok(Box::new(DuckDbDriver::new())),
+            "clickhouse" => Ok(Box::new(ClickHouseDriver::new())),
             _ => Err(DomainError::DriverNotFound {
                 engine: engine.to_string(),
             }),
@@ -63,6 +79,6 @@ impl SqlDriverFactory for DriverFactory {
     }
 
     fn supported_engines(&self) -> Vec<&'static str> {
-        vec!["postgresql", "mysql", "sqlite"]
+        vec!["postgresql", "mysql", "mariadb", "sqlite", "sqlserver", "duckdb", "clickhouse"]
     }
 }
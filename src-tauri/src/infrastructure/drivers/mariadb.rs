@@ -0,0 +1,367 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::domain::{
+    BindValue, ColumnSchema, CompletionContext, CompletionItem, ConstraintInfo, DatabaseInfo, DdlObject, DdlOptions, DomainError, ExecutionOptions,
+    FunctionInfo, ImportTarget, IndexInfo, PageRequest, PartitionInfo, QueryPlan, QueryResult, QueryStreamSummary, RowBatch, SchemaInfo,
+    SequenceInfo, SqlDriver, StatementResult, StreamOptions, TableInfo, TableStorageInfo, TlsStatus,
+    TransactionOptions, TransactionResult, TriggerInfo, ViewInfo,
+};
+use super::pagination;
+use super::MySqlDriver;
+
+/// Driver MariaDB: reutiliza el protocolo y las consultas de MySQL y sustituye
+/// las que dependen del catálogo propio de MariaDB (tablas versionadas, secuencias)
+pub struct MariaDbDriver {
+    mysql: MySqlDriver,
+}
+
+impl MariaDbDriver {
+    pub fn new() -> Self {
+        Self { mysql: MySqlDriver::for_mariadb() }
+    }
+
+    fn db_clause(schema: Option<&str>) -> String {
+        match schema {
+            Some(s) => format!("'{}'", s.replace('\'', "''")),
+            None => "DATABASE()".to_string(),
+        }
+    }
+
+    /// Motor, versionado y particiones de una tabla según information_schema
+    async fn table_storage(&self, table: &str, schema: Option<&str>) -> Result<Option<TableStorageInfo>, DomainError> {
+        let pool = self.mysql.get_pool().await?;
+        let db = Self::db_clause(schema);
+        let table = table.replace('\'', "''");
+
+        let row = sqlx::query(&format!(
+            "SELECT ENGINE, TABLE_TYPE FROM information_schema.TABLES
+             WHERE TABLE_SCHEMA = {} AND TABLE_NAME = '{}'",
+            db, table
+        ))
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| DomainError::query(e.to_string()))?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let engine: Option<String> = row.try_get(0).ok().flatten();
+        let table_type: String = row.try_get(1).unwrap_or_default();
+
+        let rows = sqlx::query(&format!(
+            "SELECT PARTITION_NAME, SUBPARTITION_NAME, PARTITION_EXPRESSION, TABLE_ROWS, DATA_LENGTH, INDEX_LENGTH
+             FROM information_schema.PARTITIONS
+             WHERE TABLE_SCHEMA = {} AND TABLE_NAME = '{}' AND PARTITION_NAME IS NOT NULL
+             ORDER BY PARTITION_ORDINAL_POSITION, SUBPARTITION_ORDINAL_POSITION",
+            db, table
+        ))
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_default();
+
+        let number = |r: &sqlx::mysql::MySqlRow, col: &str| r.try_get::<Option<u64>, _>(col).ok().flatten().unwrap_or(0);
+        let mut partition_key = None;
+        let mut partitions: Vec<PartitionInfo> = Vec::new();
+        for r in &rows {
+            let name: String = r.try_get("PARTITION_NAME").unwrap_or_default();
+            if partition_key.is_none() {
+                partition_key = r.try_get::<Option<String>, _>("PARTITION_EXPRESSION").ok().flatten();
+            }
+            let rows_count = number(r, "TABLE_ROWS");
+            let bytes = number(r, "DATA_LENGTH") + number(r, "INDEX_LENGTH");
+
+            // Las subparticiones se acumulan en su partición
+            match partitions.last_mut() {
+                Some(last) if last.name == name => {
+                    last.rows += rows_count;
+                    last.bytes_on_disk += bytes;
+                }
+                _ => partitions.push(PartitionInfo { name, rows: rows_count, bytes_on_disk: bytes, parts: vec![] }),
+            }
+        }
+
+        Ok(Some(TableStorageInfo {
+            engine: engine.unwrap_or_default(),
+            partition_key,
+            system_versioned: table_type.eq_ignore_ascii_case("SYSTEM VERSIONED"),
+            partitions,
+            ..Default::default()
+        }))
+    }
+}
+
+impl Default for MariaDbDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SqlDriver for MariaDbDriver {
+    fn driver_id(&self) -> &'static str {
+        "mariadb"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "MariaDB"
+    }
+
+    fn quote_identifier(&self, name: &str) -> String {
+        self.mysql.quote_identifier(name)
+    }
+
+    async fn connect(&self, connection_string: &str) -> Result<(), DomainError> {
+        self.mysql.connect(connection_string).await
+    }
+
+    async fn disconnect(&self) -> Result<(), DomainError> {
+        self.mysql.disconnect().await
+    }
+
+    async fn is_connected(&self) -> bool {
+        self.mysql.is_connected().await
+    }
+
+    async fn test_connection(&self, connection_string: &str) -> Result<(), DomainError> {
+        self.mysql.test_connection(connection_string).await
+    }
+
+    async fn execute_query(&self, query: &str, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        self.mysql.execute_query(query, options).await
+    }
+
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        options: &ExecutionOptions,
+        stream_options: &StreamOptions,
+        sender: mpsc::Sender<RowBatch>,
+    ) -> Result<QueryStreamSummary, DomainError> {
+        self.mysql.execute_query_stream(query, options, stream_options, sender).await
+    }
+
+    async fn execute_query_paginated(
+        &self,
+        query: &str,
+        page: &PageRequest,
+        options: &ExecutionOptions,
+    ) -> Result<QueryResult, DomainError> {
+        self.mysql.execute_query_paginated(query, page, options).await
+    }
+
+    async fn close_pagination_cursor(&self, cursor: &str) -> Result<(), DomainError> {
+        self.mysql.close_pagination_cursor(cursor).await
+    }
+
+    async fn insert_row(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        values: HashMap<String, serde_json::Value>,
+    ) -> Result<QueryResult, DomainError> {
+        self.mysql.insert_row(schema, table, values).await
+    }
+
+    async fn import_rows(&self, target: &ImportTarget, rows: &[Vec<BindValue>], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        self.mysql.import_rows(target, rows, options).await
+    }
+
+    async fn execute_statement(&self, statement: &str, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        self.mysql.execute_statement(statement, options).await
+    }
+
+    async fn execute_multi_statement(&self, statements: Vec<String>, options: &ExecutionOptions) -> Result<Vec<StatementResult>, DomainError> {
+        self.mysql.execute_multi_statement(statements, options).await
+    }
+
+    fn supports_transactions(&self) -> bool {
+        self.mysql.supports_transactions()
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.mysql.in_transaction().await
+    }
+
+    async fn begin_transaction(&self, options: &TransactionOptions) -> Result<(), DomainError> {
+        self.mysql.begin_transaction(options).await
+    }
+
+    async fn commit_transaction(&self) -> Result<(), DomainError> {
+        self.mysql.commit_transaction().await
+    }
+
+    async fn rollback_transaction(&self) -> Result<(), DomainError> {
+        self.mysql.rollback_transaction().await
+    }
+
+    async fn create_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.mysql.create_savepoint(name).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.mysql.rollback_to_savepoint(name).await
+    }
+
+    /// MariaDB no tiene EXPLAIN ANALYZE: su equivalente es `ANALYZE FORMAT=JSON`, que también ejecuta
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, DomainError> {
+        let statement = pagination::trim_statement(sql);
+        let explain = if analyze { "ANALYZE FORMAT=JSON" } else { "EXPLAIN FORMAT=JSON" };
+        let raw = self.mysql.explain_output(&format!("{} {}", explain, statement), analyze).await?;
+        let root = MySqlDriver::json_plan(&raw)?;
+        Ok(QueryPlan {
+            query: sql.to_string(),
+            analyzed: analyze,
+            execution_time_ms: if analyze { root.actual_time_ms } else { None },
+            root,
+            planning_time_ms: None,
+            raw,
+        })
+    }
+
+    async fn execute_in_transaction(&self, statement: &str) -> Result<TransactionResult, DomainError> {
+        self.mysql.execute_in_transaction(statement).await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<String>, DomainError> {
+        self.mysql.list_databases().await
+    }
+
+    async fn get_database_info(&self, database: &str) -> Result<DatabaseInfo, DomainError> {
+        self.mysql.get_database_info(database).await
+    }
+
+    async fn list_schemas(&self, database: &str) -> Result<Vec<SchemaInfo>, DomainError> {
+        self.mysql.list_schemas(database).await
+    }
+
+    async fn list_tables(&self, schema: Option<&str>) -> Result<Vec<TableInfo>, DomainError> {
+        let pool = self.mysql.get_pool().await?;
+
+        // Las tablas con WITH SYSTEM VERSIONING tienen su propio TABLE_TYPE
+        let rows = sqlx::query(&format!(
+            "SELECT TABLE_NAME FROM information_schema.TABLES
+             WHERE TABLE_SCHEMA = {} AND TABLE_TYPE IN ('BASE TABLE', 'SYSTEM VERSIONED')
+             ORDER BY TABLE_NAME",
+            Self::db_clause(schema)
+        ))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| DomainError::query(e.to_string()))?;
+
+        Ok(rows
+            .iter()
+            .map(|r| TableInfo {
+                name: r.get::<String, _>(0),
+                schema: schema.map(String::from),
+                columns: vec![],
+                primary_key: None,
+                foreign_keys: vec![],
+                indexes: vec![],
+                constraints: vec![],
+                triggers: vec![],
+                row_count: None,
+                size_bytes: None,
+                comment: None,
+                storage: None,
+            })
+            .collect())
+    }
+
+    async fn get_table_info(&self, table: &str, schema: Option<&str>) -> Result<TableInfo, DomainError> {
+        let mut info = self.mysql.get_table_info(table, schema).await?;
+        info.storage = self.table_storage(table, schema).await?;
+        Ok(info)
+    }
+
+    async fn get_columns(&self, table: &str, schema: Option<&str>) -> Result<Vec<ColumnSchema>, DomainError> {
+        self.mysql.get_columns(table, schema).await
+    }
+
+    async fn list_indexes(&self, table: &str, schema: Option<&str>) -> Result<Vec<IndexInfo>, DomainError> {
+        self.mysql.list_indexes(table, schema).await
+    }
+
+    async fn list_constraints(&self, table: &str, schema: Option<&str>) -> Result<Vec<ConstraintInfo>, DomainError> {
+        self.mysql.list_constraints(table, schema).await
+    }
+
+    async fn list_triggers(&self, table: &str, schema: Option<&str>) -> Result<Vec<TriggerInfo>, DomainError> {
+        self.mysql.list_triggers(table, schema).await
+    }
+
+    async fn list_views(&self, schema: Option<&str>) -> Result<Vec<ViewInfo>, DomainError> {
+        self.mysql.list_views(schema).await
+    }
+
+    async fn list_functions(&self, schema: Option<&str>) -> Result<Vec<FunctionInfo>, DomainError> {
+        self.mysql.list_functions(schema).await
+    }
+
+    async fn list_sequences(&self, schema: Option<&str>) -> Result<Vec<SequenceInfo>, DomainError> {
+        let pool = self.mysql.get_pool().await?;
+
+        // Cada secuencia (MariaDB 10.3+) es una tabla de una fila con su definición
+        let names = sqlx::query(&format!(
+            "SELECT TABLE_SCHEMA, TABLE_NAME FROM information_schema.TABLES
+             WHERE TABLE_SCHEMA = {} AND TABLE_TYPE = 'SEQUENCE'
+             ORDER BY TABLE_NAME",
+            Self::db_clause(schema)
+        ))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| DomainError::query(e.to_string()))?;
+
+        let mut sequences = Vec::with_capacity(names.len());
+        for r in &names {
+            let db: String = r.get(0);
+            let name: String = r.get(1);
+            let row = sqlx::query(&format!(
+                "SELECT start_value, increment, minimum_value, maximum_value, next_not_cached_value FROM `{}`.`{}`",
+                db.replace('`', "``"),
+                name.replace('`', "``")
+            ))
+            .fetch_optional(&pool)
+            .await
+            .ok()
+            .flatten();
+
+            let value = |col: &str| row.as_ref().and_then(|r| r.try_get::<i64, _>(col).ok());
+            sequences.push(SequenceInfo {
+                name,
+                schema: Some(db),
+                data_type: "bigint".to_string(),
+                start_value: value("start_value").unwrap_or(1),
+                increment: value("increment").unwrap_or(1),
+                min_value: value("minimum_value"),
+                max_value: value("maximum_value"),
+                current_value: value("next_not_cached_value"),
+            });
+        }
+
+        Ok(sequences)
+    }
+
+    async fn get_object_ddl(&self, object: &DdlObject, options: &DdlOptions) -> Result<String, DomainError> {
+        self.mysql.get_object_ddl(object, options).await
+    }
+
+    async fn cancel_query(&self, query_id: Uuid) -> Result<(), DomainError> {
+        self.mysql.cancel_query(query_id).await
+    }
+
+    async fn get_completions(&self, prefix: &str, context: CompletionContext) -> Result<Vec<CompletionItem>, DomainError> {
+        self.mysql.get_completions(prefix, context).await
+    }
+
+    async fn server_version(&self) -> Result<String, DomainError> {
+        self.mysql.server_version().await
+    }
+
+    async fn tls_status(&self) -> Result<TlsStatus, DomainError> {
+        self.mysql.tls_status().await
+    }
+}
@@ -1,9 +1,22 @@
 mod postgres;
 mod mysql;
+mod mariadb;
 mod sqlite;
+mod sqlserver;
+mod duckdb;
+mod clickhouse;
 mod factory;
+mod running;
+mod session;
+mod stream;
+mod pagination;
+mod tls;
 
 pub use postgres::PostgresDriver;
 pub use mysql::MySqlDriver;
+pub use mariadb::MariaDbDriver;
 pub use sqlite::SqliteDriver;
+pub use sqlserver::SqlServerDriver;
+pub use self::duckdb::DuckDbDriver;
+pub use clickhouse::ClickHouseDriver;
 pub use factory::DriverFactory;
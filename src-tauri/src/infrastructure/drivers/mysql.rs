@@ -1,32 +1,534 @@
 use async_trait::async_trait;
-use sqlx::{mysql::MySqlPoolOptions, MySqlPool, Row, Column, TypeInfo};
+use sqlx::{mysql::MySqlPoolOptions, Column, Connection, Either, Executor, MySqlConnection, MySqlPool, Row, Statement, TypeInfo, ValueRef};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
 
 use crate::domain::{
-    CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
-    ConstraintInfo, ConstraintType, DatabaseInfo, DomainError, ForeignKeyAction, ForeignKeyInfo, 
-    FunctionInfo, IndexInfo, PaginationInfo, PrimaryKeyInfo, QueryResult, SchemaInfo, 
-    SequenceInfo, SqlDriver, TableInfo, TriggerEvent, TriggerInfo, TriggerTiming, ViewInfo,
+    index_sql, qualified_identifier,
+    BindValue, CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
+    ConstraintInfo, ConstraintType, CountMode, DatabaseInfo, DdlObject, DdlObjectType, DdlOptions, DomainError, ExecutionOptions, ForeignKeyAction,
+    ForeignKeyInfo, FunctionInfo, ImportTarget, IndexInfo, PageRequest, PlanNode, PrimaryKeyInfo, QueryPlan, QueryResult, QueryStreamSummary, RowBatch, SchemaInfo, 
+    SequenceInfo, SqlDialect, SqlDriver, StreamOptions, TableInfo, TlsStatus, TransactionOptions, TriggerEvent, TriggerInfo,
+    TriggerTiming, ViewInfo,
 };
+use super::running::{RunningQueries, RunningQueryGuard};
+use super::session::{BackendState, PinnedSession, SessionConnection};
+use super::stream::RowBatchWriter;
+use super::pagination::{self, KeysetPage, RowTotal, SimpleSelect};
+use super::tls::map_connect_error;
+
+/// ER_QUERY_INTERRUPTED: la query fue detenida con KILL QUERY
+const ER_QUERY_INTERRUPTED: u16 = 1317;
+
+/// ER_QUERY_TIMEOUT: la SELECT superó max_execution_time (MySQL)
+const ER_QUERY_TIMEOUT: u16 = 3024;
+
+/// ER_STATEMENT_TIMEOUT: la sentencia superó max_statement_time (MariaDB)
+const ER_STATEMENT_TIMEOUT: u16 = 1969;
+
+/// ER_CANT_EXECUTE_IN_READ_ONLY_TRANSACTION: escritura en una sesión de solo lectura
+const ER_READ_ONLY_TRANSACTION: u16 = 1792;
+
+/// Parámetro propio del connection string (sqlx lo ignora): abre las sesiones en solo lectura
+const READ_ONLY_PARAM: &str = "read-only=true";
 
 pub struct MySqlDriver {
     pool: Arc<RwLock<Option<MySqlPool>>>,
+    /// Ids de conexión (CONNECTION_ID()) de las queries en ejecución
+    running: RunningQueries<u64>,
+    /// Conexión de la transacción manual en curso
+    session: PinnedSession<sqlx::MySql, u64>,
+    /// El servidor es MariaDB: el límite de ejecución usa max_statement_time
+    mariadb: bool,
 }
 
 impl MySqlDriver {
     pub fn new() -> Self {
         Self {
             pool: Arc::new(RwLock::new(None)),
+            running: RunningQueries::new(),
+            session: PinnedSession::with_reset("SET SESSION max_execution_time = DEFAULT"),
+            mariadb: false,
         }
     }
 
-    async fn get_pool(&self) -> Result<MySqlPool, DomainError> {
+    /// Driver base para MariaDB
+    pub(super) fn for_mariadb() -> Self {
+        Self {
+            session: PinnedSession::with_reset("SET SESSION max_statement_time = DEFAULT"),
+            mariadb: true,
+            ..Self::new()
+        }
+    }
+
+    pub(super) async fn get_pool(&self) -> Result<MySqlPool, DomainError> {
         let guard = self.pool.read().await;
         guard.clone().ok_or_else(|| DomainError::connection("Not connected"))
     }
 
+    /// Adquiere una conexión (la de la transacción manual, si hay una) y registra
+    /// su id para poder cancelarla con KILL QUERY. Fija el límite de ejecución de la sesión
+    /// (max_execution_time en MySQL, que solo afecta a SELECT; max_statement_time en MariaDB).
+    /// El id y el límite ya fijado se recuerdan por conexión: solo se consultan o cambian
+    /// la primera vez o cuando el límite es otro
+    async fn acquire_tracked(&self, options: &ExecutionOptions) -> Result<(SessionConnection<sqlx::MySql, u64>, RunningQueryGuard<u64>), DomainError> {
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.acquire(&pool).await?;
+        let backend = *conn.backend_mut();
+
+        if backend.timeout != Some(options.timeout) {
+            let limit = match (options.timeout, self.mariadb) {
+                (Some(t), false) => format!("SET SESSION max_execution_time = {}", t.as_millis()),
+                (Some(t), true) => format!("SET SESSION max_statement_time = {}", t.as_secs_f64()),
+                (None, false) => "SET SESSION max_execution_time = DEFAULT".to_string(),
+                (None, true) => "SET SESSION max_statement_time = DEFAULT".to_string(),
+            };
+            (&mut *conn).execute(limit.as_str())
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+        }
+
+        let connection_id = match backend.id {
+            Some(id) => id,
+            None => sqlx::query_scalar("SELECT CONNECTION_ID()")
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?,
+        };
+        *conn.backend_mut() = BackendState { id: Some(connection_id), timeout: Some(options.timeout) };
+
+        let guard = self.running.register(options.query_id, connection_id);
+        Ok((conn, guard))
+    }
+
+    /// El connection string pide sesiones de solo lectura
+    fn read_only_requested(connection_string: &str) -> bool {
+        connection_string
+            .split_once('?')
+            .is_some_and(|(_, query)| query.split('&').any(|param| param == READ_ONLY_PARAM))
+    }
+
+    /// Ejecuta una sentencia de control en la conexión de la transacción manual
+    async fn session_execute(&self, sql: &str) -> Result<(), DomainError> {
+        let mut conn = self.session
+            .pinned()
+            .await
+            .ok_or_else(|| DomainError::validation("No transaction is open"))?;
+        sqlx::query(sql)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Convierte errores de sqlx distinguiendo las queries canceladas, las que superaron el límite
+    /// y las escrituras rechazadas por ser la sesión de solo lectura
+    fn map_query_error(e: sqlx::Error) -> DomainError {
+        if let Some(db_err) = e.as_database_error() {
+            if let Some(mysql_err) = db_err.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>() {
+                match mysql_err.number() {
+                    ER_QUERY_INTERRUPTED => return DomainError::Cancelled,
+                    ER_QUERY_TIMEOUT | ER_STATEMENT_TIMEOUT => {
+                        return DomainError::Timeout { operation: mysql_err.message().to_string() };
+                    }
+                    ER_READ_ONLY_TRANSACTION => {
+                        return DomainError::PermissionDenied { resource: mysql_err.message().to_string() };
+                    }
+                    _ => {}
+                }
+            }
+        }
+        DomainError::query(e.to_string())
+    }
+
+    /// Ejecuta una query con parámetros posicionales y construye el QueryResult
+    async fn fetch_result(&self, query: &str, params: &[serde_json::Value], options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let start = Instant::now();
+
+        // Primero los parámetros del usuario y después los que añade la paginación
+        let mut q = Self::bind_all(sqlx::query(query), &options.params);
+        for param in params {
+            q = Self::bind_json(q, param);
+        }
+        let rows = q
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(Self::map_query_error)?;
+
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        let columns: Vec<ColumnInfo> = if !rows.is_empty() {
+            Self::column_infos(rows[0].columns())
+        } else {
+            vec![]
+        };
+
+        let data: Vec<Vec<CellValue>> = rows
+            .iter()
+            .map(|row| {
+                (0..row.columns().len())
+                    .map(|idx| Self::map_mysql_value(row, idx))
+                    .collect()
+            })
+            .collect();
+
+        Ok(QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time))
+    }
+
+    /// Salida en texto de un EXPLAIN. Con `executes` la sentencia llega a ejecutarse
+    /// (EXPLAIN ANALYZE), así que se hace en un ámbito que siempre se deshace
+    pub(super) async fn explain_output(&self, explain: &str, executes: bool) -> Result<String, DomainError> {
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.acquire(&pool).await?;
+        if !executes {
+            let row = sqlx::query(explain).fetch_one(&mut *conn).await.map_err(Self::map_query_error)?;
+            return row.try_get_unchecked::<String, _>(0).map_err(|e| DomainError::query(e.to_string()));
+        }
+
+        let (open, close) = conn.discard_scope();
+        sqlx::query(open).execute(&mut *conn).await.map_err(Self::map_query_error)?;
+        let row = sqlx::query(explain).fetch_one(&mut *conn).await;
+        for statement in close {
+            sqlx::query(statement)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+        }
+        let row = row.map_err(Self::map_query_error)?;
+        row.try_get_unchecked::<String, _>(0).map_err(|e| DomainError::query(e.to_string()))
+    }
+
+    /// Árbol de `EXPLAIN FORMAT=JSON` (MySQL y MariaDB) o de `ANALYZE FORMAT=JSON` (MariaDB)
+    pub(super) fn json_plan(output: &str) -> Result<PlanNode, DomainError> {
+        let plan: serde_json::Value = serde_json::from_str(output).map_err(|e| DomainError::query(e.to_string()))?;
+        plan.get("query_block")
+            .map(|block| Self::json_plan_node("query_block", block))
+            .ok_or_else(|| DomainError::query("EXPLAIN returned no plan"))
+    }
+
+    fn json_plan_node(kind: &str, value: &serde_json::Value) -> PlanNode {
+        let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from);
+        // Los números pueden llegar como texto ("12.50")
+        let number = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+
+        let mut node = PlanNode::new(match (kind, text("access_type")) {
+            ("table", Some(access)) => format!("Table access ({})", access),
+            _ => {
+                let label = kind.replace('_', " ");
+                let mut chars = label.chars();
+                chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+            }
+        });
+        node.relation = text("table_name");
+        let details: Vec<String> = [("key", "Index"), ("attached_condition", "Condition"), ("message", "Note")]
+            .iter()
+            .filter_map(|(key, label)| text(key).map(|value| format!("{}: {}", label, value)))
+            .collect();
+        node.detail = (!details.is_empty()).then(|| details.join("; "));
+        node.estimated_rows = number(value.get("rows_produced_per_join")).or_else(|| number(value.get("rows")));
+        node.estimated_cost = number(value.pointer("/cost_info/prefix_cost"))
+            .or_else(|| number(value.pointer("/cost_info/query_cost")))
+            .or_else(|| number(value.get("cost")));
+        node.actual_rows = number(value.get("r_rows"));
+        node.actual_time_ms = number(value.get("r_total_time_ms"));
+        node.loops = value.get("r_loops").and_then(|v| v.as_u64());
+
+        // Los hijos son los objetos anidados (table, nested_loop, ordering_operation...)
+        // y los elementos de las listas (nested_loop, attached_subqueries...)
+        if let Some(fields) = value.as_object() {
+            for (key, child) in fields {
+                match child {
+                    serde_json::Value::Object(_) if key != "cost_info" && !key.starts_with("r_") => {
+                        node.children.push(Self::json_plan_node(key, child));
+                    }
+                    serde_json::Value::Array(items) => {
+                        for item in items.iter().filter_map(|i| i.as_object()) {
+                            for (item_key, item_value) in item.iter().filter(|(_, v)| v.is_object()) {
+                                node.children.push(Self::json_plan_node(item_key, item_value));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        node
+    }
+
+    /// Árbol de `EXPLAIN ANALYZE` de MySQL (formato TREE): una línea por nodo,
+    /// `-> Nodo  (cost=1.2 rows=3) (actual time=0.01..0.02 rows=3 loops=1)`, sangrada según el nivel
+    fn tree_plan(output: &str) -> PlanNode {
+        fn attach(stack: &mut Vec<(usize, PlanNode)>, roots: &mut Vec<PlanNode>) {
+            if let Some((_, done)) = stack.pop() {
+                match stack.last_mut() {
+                    Some((_, parent)) => parent.children.push(done),
+                    None => roots.push(done),
+                }
+            }
+        }
+
+        let mut stack: Vec<(usize, PlanNode)> = Vec::new();
+        let mut roots = Vec::new();
+        for line in output.lines() {
+            let trimmed = line.trim_start();
+            let Some(body) = trimmed.strip_prefix("-> ") else {
+                continue;
+            };
+            let depth = line.len() - trimmed.len();
+            while stack.last().is_some_and(|(d, _)| *d >= depth) {
+                attach(&mut stack, &mut roots);
+            }
+            stack.push((depth, Self::tree_plan_node(body)));
+        }
+        while !stack.is_empty() {
+            attach(&mut stack, &mut roots);
+        }
+
+        if roots.len() == 1 {
+            roots.remove(0)
+        } else {
+            PlanNode { children: roots, ..PlanNode::new("Query plan") }
+        }
+    }
+
+    fn tree_plan_node(body: &str) -> PlanNode {
+        let (label, metrics) = body.split_once("  (").map_or((body, ""), |(l, m)| (l, m));
+
+        let mut node = match label.split_once(" on ") {
+            // "Index lookup on t1 using idx (a=1)"
+            Some((kind, rest)) => {
+                let (relation, detail) = rest.split_once(' ').map_or((rest, ""), |(r, d)| (r, d));
+                let mut node = PlanNode::new(kind);
+                node.relation = Some(relation.to_string());
+                node.detail = (!detail.is_empty()).then(|| detail.to_string());
+                node
+            }
+            None => match label.split_once(": ") {
+                // "Filter: (t1.a > 1)"
+                Some((kind, detail)) => PlanNode { detail: Some(detail.to_string()), ..PlanNode::new(kind) },
+                None => PlanNode::new(label),
+            },
+        };
+
+        // Métricas: "(cost=... rows=...)" y "(actual time=a..b rows=... loops=...)"
+        let last = |value: &str| value.rsplit("..").next().and_then(|v| v.parse::<f64>().ok());
+        for group in metrics.split(" (") {
+            let actual = group.starts_with("actual");
+            for token in group.trim_end_matches(')').split_whitespace() {
+                let Some((key, value)) = token.split_once('=') else {
+                    continue;
+                };
+                match (actual, key) {
+                    (false, "cost") => node.estimated_cost = last(value),
+                    (false, "rows") => node.estimated_rows = last(value),
+                    (true, "time") => node.actual_time_ms = last(value),
+                    (true, "rows") => node.actual_rows = last(value),
+                    (true, "loops") => node.loops = value.parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+        node
+    }
+
+    fn column_infos(columns: &[sqlx::mysql::MySqlColumn]) -> Vec<ColumnInfo> {
+        columns
+            .iter()
+            .map(|col| ColumnInfo {
+                name: col.name().to_string(),
+                data_type: col.type_info().name().to_string(),
+                nullable: true,
+                is_primary_key: false,
+            })
+            .collect()
+    }
+
+    /// Ejecuta una sentencia suelta. Se prepara antes para saber si produce un conjunto de
+    /// resultados aunque venga vacío; si además modifica datos (`INSERT ... RETURNING` de
+    /// MariaDB) se devuelven tanto las filas como las afectadas. De un CALL con varios
+    /// conjuntos de resultados se conserva el primero.
+    async fn run_statement(&self, sql: &str, options: &ExecutionOptions) -> Result<(Option<QueryResult>, Option<u64>), DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let start = Instant::now();
+        // Hay sentencias que el protocolo de preparadas no admite: se decide por el texto
+        let described = (&mut *conn)
+            .prepare(sql)
+            .await
+            .ok()
+            .map(|statement| Self::column_infos(statement.columns()));
+
+        let mut rows = Vec::new();
+        let mut affected = 0;
+        let mut first_set_done = false;
+        {
+            let query = Self::bind_all(sqlx::query(sql), &options.params);
+            let mut stream = (&mut *conn).fetch_many(query);
+            while let Some(item) = stream.next().await {
+                match item.map_err(Self::map_query_error)? {
+                    Either::Left(done) => {
+                        affected += done.rows_affected();
+                        first_set_done |= !rows.is_empty();
+                    }
+                    Either::Right(row) if !first_set_done => rows.push(row),
+                    Either::Right(_) => {}
+                }
+            }
+        }
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        let returns_rows = !rows.is_empty()
+            || described.as_ref().map_or_else(|| pagination::may_return_rows(sql), |columns| !columns.is_empty());
+        if !returns_rows {
+            return Ok((None, Some(affected)));
+        }
+
+        let columns = match rows.first() {
+            Some(row) => Self::column_infos(row.columns()),
+            None => described.unwrap_or_default(),
+        };
+        let data: Vec<Vec<CellValue>> = rows
+            .iter()
+            .map(|row| (0..row.columns().len()).map(|idx| Self::map_mysql_value(row, idx)).collect())
+            .collect();
+        let mut result = QueryResult::new(sql.to_string(), columns, data).with_execution_time(execution_time);
+        let affected = pagination::is_data_modifying(sql).then_some(affected);
+        if let Some(count) = affected {
+            result = result.with_affected_rows(count);
+        }
+        Ok((Some(result), affected))
+    }
+
+    fn bind_json<'q>(
+        q: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
+        value: &serde_json::Value,
+    ) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+        match value {
+            serde_json::Value::Null => q.bind(None::<String>),
+            serde_json::Value::Bool(b) => q.bind(*b),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => q.bind(i),
+                (None, Some(u)) => q.bind(u),
+                _ => q.bind(n.as_f64()),
+            },
+            serde_json::Value::String(text) => q.bind(text.clone()),
+            other => q.bind(other.to_string()),
+        }
+    }
+
+    fn bind_all<'q>(
+        mut q: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
+        params: &[CellValue],
+    ) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+        for param in params {
+            q = Self::bind_cell(q, param);
+        }
+        q
+    }
+
+    /// Enlaza un valor tipado; MySQL convierte por sí mismo el texto de fechas y horas
+    fn bind_cell<'q>(
+        q: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
+        value: &CellValue,
+    ) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+        match value {
+            CellValue::Null => q.bind(None::<String>),
+            CellValue::Bool(b) => q.bind(*b),
+            CellValue::Int(i) => q.bind(*i),
+            CellValue::Float(f) => q.bind(*f),
+            CellValue::Bytes(b) => q.bind(b.clone()),
+            CellValue::Json(v) => q.bind(v.clone()),
+            CellValue::Array(_) => q.bind(pagination::cell_to_json(value).to_string()),
+            CellValue::String(s)
+            | CellValue::Date(s)
+            | CellValue::Time(s)
+            | CellValue::DateTime(s)
+            | CellValue::Uuid(s) => q.bind(s.clone()),
+        }
+    }
+
+    /// Enlaza un valor ya convertido al tipo de su columna. MySQL no tiene UUID ni arrays:
+    /// el UUID viaja como texto y los arrays como JSON
+    fn bind_value<'q>(
+        q: sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments>,
+        value: BindValue,
+    ) -> sqlx::query::Query<'q, sqlx::MySql, sqlx::mysql::MySqlArguments> {
+        match value {
+            BindValue::Null => q.bind(None::<String>),
+            BindValue::Bool(b) => q.bind(b),
+            BindValue::SmallInt(i) => q.bind(i),
+            BindValue::Int(i) => q.bind(i),
+            BindValue::BigInt(i) => q.bind(i),
+            BindValue::Real(f) => q.bind(f),
+            BindValue::Double(f) => q.bind(f),
+            BindValue::Decimal(s) | BindValue::Text(s) => q.bind(s),
+            BindValue::Date(d) => q.bind(d),
+            BindValue::Time(t) => q.bind(t),
+            BindValue::Timestamp(dt) => q.bind(dt),
+            // La sesión de sqlx trabaja en UTC
+            BindValue::TimestampTz(dt) => q.bind(dt.with_timezone(&chrono::Utc)),
+            BindValue::Uuid(u) => q.bind(u.to_string()),
+            BindValue::Json(v) => q.bind(v),
+            BindValue::Bytes(b) => q.bind(b),
+            BindValue::Array(_) => q.bind(value.to_json()),
+        }
+    }
+
+    /// Clave primaria para paginar por keyset, si la query lee una sola tabla y la proyecta
+    async fn keyset_columns(&self, query: &str) -> Option<Vec<String>> {
+        let select = SimpleSelect::parse(query)?;
+        let keys: Vec<String> = self
+            .get_columns(&select.table, select.schema.as_deref())
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name)
+            .collect();
+        (!keys.is_empty() && select.projects(&keys)).then_some(keys)
+    }
+
+    /// Total de filas según el modo pedido. La estimación sale de EXPLAIN (rows × filtered)
+    /// y, si no se puede obtener, la página se devuelve sin total.
+    async fn count_rows(&self, query: &str, params: &[CellValue], mode: CountMode) -> Result<RowTotal, DomainError> {
+        let pool = self.get_pool().await?;
+        // En la conexión de la transacción manual, si hay una: así el total cuenta las filas
+        // sin confirmar igual que la página
+        let mut conn = self.session.acquire(&pool).await?;
+        match mode {
+            CountMode::None => Ok((None, false)),
+            CountMode::Exact => {
+                let sql = format!("SELECT COUNT(*) FROM ({}) AS qx_count", query);
+                let row = Self::bind_all(sqlx::query(&sql), params)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| DomainError::query(e.to_string()))?;
+                let total: i64 = row.try_get(0).map_err(|e| DomainError::query(e.to_string()))?;
+                Ok((Some(total as u64), false))
+            }
+            CountMode::Estimated => {
+                let sql = format!("EXPLAIN {}", query);
+                let plan = Self::bind_all(sqlx::query(&sql), params)
+                    .fetch_optional(&mut *conn)
+                    .await
+                    .ok()
+                    .flatten();
+                let estimate = plan.and_then(|row| {
+                    let rows = row.try_get::<Option<u64>, _>("rows")
+                        .ok()
+                        .flatten()
+                        .or_else(|| row.try_get::<Option<i64>, _>("rows").ok().flatten().map(|r| r as u64))?;
+                    let filtered = row.try_get::<Option<f32>, _>("filtered").ok().flatten().unwrap_or(100.0);
+                    Some((rows as f64 * filtered as f64 / 100.0).round() as u64)
+                });
+                Ok((estimate, estimate.is_some()))
+            }
+        }
+    }
+
     fn map_mysql_value(row: &sqlx::mysql::MySqlRow, idx: usize) -> CellValue {
         let col = row.column(idx);
         let type_name = col.type_info().name();
@@ -67,6 +569,81 @@ impl MySqlDriver {
                 .unwrap_or(CellValue::Null),
         }
     }
+
+    /// Valor de una lectura exacta, que llega por el protocolo de texto. Solo se tipan los
+    /// valores que el grid representa sin perder nada; DECIMAL, enteros sin signo, fechas,
+    /// TINYINT(1)... se quedan con el texto del servidor, que es lo que acepta al restaurarlos
+    fn exact_mysql_value(row: &sqlx::mysql::MySqlRow, idx: usize) -> Result<CellValue, DomainError> {
+        let raw = row.try_get_raw(idx).map_err(Self::map_query_error)?;
+        if raw.is_null() {
+            return Ok(CellValue::Null);
+        }
+        let col = row.column(idx);
+        let value = match col.type_info().name() {
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => row.try_get::<i64, _>(idx).map(CellValue::Int),
+            "DOUBLE" => row.try_get::<f64, _>(idx).map(CellValue::Float),
+            "JSON" => row.try_get::<serde_json::Value, _>(idx).map(CellValue::Json),
+            "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BINARY" | "VARBINARY" | "BIT" | "GEOMETRY" => {
+                row.try_get_unchecked::<Vec<u8>, _>(idx).map(CellValue::Bytes)
+            }
+            _ => row.try_get_unchecked::<String, _>(idx).map(CellValue::String),
+        };
+        value.map_err(|e| DomainError::query(format!(
+            "Cannot read the value of column {} ({}): {}",
+            col.name(),
+            col.type_info().name(),
+            e
+        )))
+    }
+
+    /// Sentencia de `SHOW CREATE {kind}`, que está en la columna `column`. Va por el protocolo
+    /// de texto porque no todas las variantes de SHOW se pueden preparar
+    async fn show_create(pool: &MySqlPool, kind: &str, target: &str, column: usize) -> Result<String, DomainError> {
+        let row = pool
+            .fetch_one(format!("SHOW CREATE {} {}", kind, target).as_str())
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+        // Sin privilegios sobre el objeto, el servidor deja la definición en NULL
+        Self::text_column(&row, column)
+            .filter(|sql| !sql.is_empty())
+            .ok_or_else(|| DomainError::validation(format!("Not enough privileges to see the definition of {}", target)))
+    }
+
+    /// Texto de una columna de catálogo, que según el servidor llega como cadena o como binario
+    fn text_column(row: &sqlx::mysql::MySqlRow, column: usize) -> Option<String> {
+        row.try_get::<Option<String>, _>(column).ok().flatten().or_else(|| {
+            row.try_get::<Option<Vec<u8>>, _>(column)
+                .ok()
+                .flatten()
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        })
+    }
+
+    /// Quita la cláusula `DEFINER=usuario@host` de una sentencia de `SHOW CREATE`
+    fn strip_definer(sql: &str) -> String {
+        let start = match sql.find(" DEFINER=") {
+            Some(start) => start,
+            None => return sql.to_string(),
+        };
+        let rest = &sql[start + " DEFINER=".len()..];
+        let mut quoted = false;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                if c == '`' || c == '\'' {
+                    quoted = !quoted;
+                }
+                !quoted && c.is_whitespace()
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        format!("{}{}", &sql[..start], &rest[end..])
+    }
+
+    /// Rutinas y triggers llevan `;` dentro de `BEGIN ... END`: se delimitan para el cliente
+    fn delimited(sql: &str) -> String {
+        format!("DELIMITER ;;\n{};;\nDELIMITER ;\n", sql.trim_end().trim_end_matches(';'))
+    }
 }
 
 impl Default for MySqlDriver {
@@ -85,19 +662,37 @@ impl SqlDriver for MySqlDriver {
         "MySQL"
     }
 
+    fn quote_identifier(&self, name: &str) -> String {
+        format!("`{}`", name.replace('`', "``"))
+    }
+
     async fn connect(&self, connection_string: &str) -> Result<(), DomainError> {
+        let read_only = Self::read_only_requested(connection_string);
         let pool = MySqlPoolOptions::new()
             .max_connections(5)
+            .after_connect(move |conn, _| Box::pin(async move {
+                if read_only {
+                    conn.execute("SET SESSION TRANSACTION READ ONLY").await?;
+                }
+                Ok(())
+            }))
             .connect(connection_string)
             .await
-            .map_err(|e| DomainError::connection(e.to_string()))?;
+            .map_err(map_connect_error)?;
 
         let mut guard = self.pool.write().await;
+        self.session.release_warm();
         *guard = Some(pool);
         Ok(())
     }
 
     async fn disconnect(&self) -> Result<(), DomainError> {
+        // La conexión fijada debe volver al pool antes de cerrarlo
+        if let Ok(mut conn) = self.session.take().await {
+            sqlx::query("ROLLBACK").execute(&mut *conn).await.ok();
+        }
+        self.session.release_warm();
+
         let mut guard = self.pool.write().await;
         if let Some(pool) = guard.take() {
             pool.close().await;
@@ -119,7 +714,7 @@ impl SqlDriver for MySqlDriver {
             .max_connections(1)
             .connect(connection_string)
             .await
-            .map_err(|e| DomainError::connection(e.to_string()))?;
+            .map_err(map_connect_error)?;
 
         sqlx::query("SELECT 1")
             .fetch_one(&pool)
@@ -130,66 +725,90 @@ impl SqlDriver for MySqlDriver {
         Ok(())
     }
 
-    async fn execute_query(&self, query: &str) -> Result<QueryResult, DomainError> {
-        let pool = self.get_pool().await?;
-        let start = Instant::now();
-
-        let rows = sqlx::query(query)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| DomainError::query(e.to_string()))?;
-
-        let execution_time = start.elapsed().as_millis() as u64;
+    async fn execute_query(&self, query: &str, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        self.fetch_result(query, &[], options).await
+    }
 
-        let columns: Vec<ColumnInfo> = if !rows.is_empty() {
-            rows[0]
-                .columns()
-                .iter()
-                .map(|col| ColumnInfo {
-                    name: col.name().to_string(),
-                    data_type: col.type_info().name().to_string(),
-                    nullable: true,
-                    is_primary_key: false,
-                })
-                .collect()
-        } else {
-            vec![]
-        };
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        options: &ExecutionOptions,
+        stream_options: &StreamOptions,
+        sender: mpsc::Sender<RowBatch>,
+    ) -> Result<QueryStreamSummary, DomainError> {
+        if stream_options.exact_values && !options.params.is_empty() {
+            return Err(DomainError::validation("Exact reads do not take bound parameters"));
+        }
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let mut writer = RowBatchWriter::new(sender, stream_options);
+        let mut exhausted = true;
+
+        {
+            // Sin argumentos sqlx usa COM_QUERY, cuyos valores llegan como texto
+            let mut rows = if stream_options.exact_values {
+                (&mut *conn).fetch(query)
+            } else {
+                Self::bind_all(sqlx::query(query), &options.params).fetch(&mut *conn)
+            };
+            while let Some(row) = rows.next().await {
+                let row = row.map_err(Self::map_query_error)?;
+                if writer.needs_columns() {
+                    writer.set_columns(row.columns().iter().map(|col| ColumnInfo {
+                        name: col.name().to_string(),
+                        data_type: col.type_info().name().to_string(),
+                        nullable: true,
+                        is_primary_key: false,
+                    }).collect());
+                }
+                let values = if stream_options.exact_values {
+                    (0..row.columns().len())
+                        .map(|idx| Self::exact_mysql_value(&row, idx))
+                        .collect::<Result<_, _>>()?
+                } else {
+                    (0..row.columns().len())
+                        .map(|idx| Self::map_mysql_value(&row, idx))
+                        .collect()
+                };
+                if !writer.push(values).await {
+                    exhausted = false;
+                    break;
+                }
+            }
+        }
 
-        let data: Vec<Vec<CellValue>> = rows
-            .iter()
-            .map(|row| {
-                (0..row.columns().len())
-                    .map(|idx| Self::map_mysql_value(row, idx))
-                    .collect()
-            })
-            .collect();
+        if !exhausted {
+            // Quedan filas sin leer: se cierra la conexión en vez de devolverla al pool
+            conn.close().await.ok();
+        }
 
-        Ok(QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time))
+        Ok(writer.finish(options.query_id, query, exhausted).await)
     }
 
     async fn execute_query_paginated(
         &self,
         query: &str,
-        page: u32,
-        page_size: u32,
+        page: &PageRequest,
+        options: &ExecutionOptions,
     ) -> Result<QueryResult, DomainError> {
-        let offset = (page - 1) * page_size;
-        let paginated_query = format!("{} LIMIT {} OFFSET {}", query.trim_end_matches(';'), page_size, offset);
-
-        let count_query = format!("SELECT COUNT(*) as total FROM ({}) as subquery", query.trim_end_matches(';'));
-        let pool = self.get_pool().await?;
+        let query = pagination::trim_statement(query);
+        if !pagination::is_row_returning(query) {
+            return self.execute_query(query, options).await;
+        }
 
-        let total_rows: i64 = sqlx::query_scalar(&count_query)
-            .fetch_one(&pool)
-            .await
-            .unwrap_or(0);
+        let key_columns = self.keyset_columns(query).await;
+        let plan = KeysetPage::new(query, page, key_columns, |c| format!("`{}`", c.replace('`', "``")));
+        let result = self.fetch_result(&plan.sql, &plan.params, options).await?;
+        let total = match plan.known_total() {
+            Some(total) => total,
+            None => self.count_rows(query, &options.params, page.count_mode).await?,
+        };
 
-        let mut result = self.execute_query(&paginated_query).await?;
-        let pagination = PaginationInfo::new(page, page_size).with_total(total_rows as u64);
-        result = result.with_pagination(pagination);
+        Ok(plan.finish(result, query, page, total))
+    }
 
-        Ok(result)
+    async fn close_pagination_cursor(&self, _cursor: &str) -> Result<(), DomainError> {
+        // La paginación keyset no deja estado en el servidor
+        Ok(())
     }
 
     async fn insert_row(
@@ -231,7 +850,7 @@ impl SqlDriver for MySqlDriver {
              let result = sqlx::query(&sql)
                 .execute(&pool)
                 .await
-                .map_err(|e| DomainError::query(e.to_string()))?;
+                .map_err(Self::map_query_error)?;
                 
              let last_id = result.last_insert_id();
              
@@ -268,44 +887,29 @@ impl SqlDriver for MySqlDriver {
              return Ok(QueryResult::new(sql, vec![], vec![]).with_affected_rows(result.rows_affected()));
         }
 
-        // 3. Construir query dinámica
+        // 3. Convertir cada valor al tipo de su columna antes de tocar la base de datos
+        let mut bound = Vec::with_capacity(valid_entries.len());
+        for (_, val, info) in &valid_entries {
+            bound.push(BindValue::from_json(val, info, SqlDialect::MySql)?);
+        }
+
+        // 4. Construir query dinámica
         let cols_sql = valid_entries.iter().map(|(k,_,_)| format!("`{}`", k)).collect::<Vec<_>>().join(", ");
         let vals_sql = (0..valid_entries.len()).map(|_| "?").collect::<Vec<_>>().join(", ");
         let sql = format!("INSERT INTO {} ({}) VALUES ({})", table, cols_sql, vals_sql);
 
-        // 4. Ejecutar
+        // 5. Ejecutar
         let mut query_builder = sqlx::query(&sql);
-        
-        for (_, val, info) in &valid_entries {
-            let type_name = info.data_type.to_lowercase();
-            
-            if type_name.contains("bool") || type_name.contains("tinyint(1)") {
-                 query_builder = query_builder.bind(val.as_bool());
-            } else if type_name.contains("int") {
-                 if let Some(i) = val.as_i64() {
-                     query_builder = query_builder.bind(i);
-                 } else {
-                     query_builder = query_builder.bind(Option::<i64>::None);
-                 }
-            } else if type_name.contains("float") || type_name.contains("double") || type_name.contains("decimal") {
-                 query_builder = query_builder.bind(val.as_f64());
-            } else {
-                 if val.is_null() {
-                     query_builder = query_builder.bind(Option::<String>::None);
-                 } else if let Some(s) = val.as_str() {
-                     query_builder = query_builder.bind(s);
-                 } else {
-                     query_builder = query_builder.bind(val.to_string());
-                 }
-            }
+        for value in bound {
+            query_builder = Self::bind_value(query_builder, value);
         }
 
         let result = query_builder
             .execute(&pool)
             .await
-            .map_err(|e| DomainError::query(e.to_string()))?;
+            .map_err(Self::map_query_error)?;
 
-        // 5. Intentar recuperar la fila
+        // 6. Intentar recuperar la fila
         let last_id = result.last_insert_id();
         if last_id > 0 {
              let pk_col = columns_info.iter().find(|c| c.is_primary_key || c.is_auto_increment);
@@ -338,19 +942,33 @@ impl SqlDriver for MySqlDriver {
         Ok(QueryResult::new(sql, vec![], vec![]).with_affected_rows(result.rows_affected()))
     }
 
-    async fn execute_statement(&self, statement: &str) -> Result<u64, DomainError> {
-        let pool = self.get_pool().await?;
+    /// INSERT de varias filas por sentencia, con los valores enlazados con su tipo
+    async fn import_rows(&self, target: &ImportTarget, rows: &[Vec<BindValue>], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let mut affected = 0;
+        for chunk in rows.chunks(target.rows_per_statement()) {
+            let sql = target.insert_sql(chunk.len(), |_| "?".to_string())?;
+            let mut query = sqlx::query(&sql);
+            for value in chunk.iter().flatten() {
+                query = Self::bind_value(query, value.clone());
+            }
+            affected += query.execute(&mut *conn).await.map_err(Self::map_query_error)?.rows_affected();
+        }
+        Ok(affected)
+    }
 
-        let result = sqlx::query(statement)
-            .execute(&pool)
+    async fn execute_statement(&self, statement: &str, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+
+        let result = Self::bind_all(sqlx::query(statement), &options.params)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| DomainError::query(e.to_string()))?;
+            .map_err(Self::map_query_error)?;
 
         Ok(result.rows_affected())
     }
 
-    async fn execute_multi_statement(&self, statements: Vec<String>) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
-        let pool = self.get_pool().await?;
+    async fn execute_multi_statement(&self, statements: Vec<String>, options: &ExecutionOptions) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
         let mut results = Vec::new();
 
         for (idx, statement) in statements.iter().enumerate() {
@@ -361,59 +979,94 @@ impl SqlDriver for MySqlDriver {
                 continue;
             }
 
-            let is_select = sql.to_uppercase().trim_start().starts_with("SELECT") ||
-                           sql.to_uppercase().trim_start().starts_with("WITH");
-
-            let result = if is_select {
-                match self.execute_query(sql).await {
-                    Ok(query_result) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: true,
-                        affected_rows: None,
-                        result: Some(query_result),
-                        error: None,
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: false,
-                        affected_rows: None,
-                        result: None,
-                        error: Some(e.to_string()),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                }
-            } else {
-                match sqlx::query(sql).execute(&pool).await {
-                    Ok(exec_result) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: true,
-                        affected_rows: Some(exec_result.rows_affected()),
-                        result: None,
-                        error: None,
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: false,
-                        affected_rows: None,
-                        result: None,
-                        error: Some(e.to_string()),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                }
-            };
-
-            results.push(result);
+            let outcome = self.run_statement(sql, options).await;
+            results.push(crate::domain::StatementResult::from_outcome(
+                idx,
+                sql,
+                outcome,
+                start.elapsed().as_millis() as u64,
+            ));
         }
 
         Ok(results)
     }
 
+    fn supports_transactions(&self) -> bool {
+        true
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.session.is_active().await
+    }
+
+    async fn begin_transaction(&self, options: &TransactionOptions) -> Result<(), DomainError> {
+        if self.session.is_active().await {
+            return Err(DomainError::validation("A transaction is already open"));
+        }
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.checkout(&pool).await?;
+
+        // SET TRANSACTION sin GLOBAL/SESSION solo afecta a la siguiente transacción
+        if let Some(level) = options.isolation_level {
+            sqlx::query(&format!("SET TRANSACTION ISOLATION LEVEL {}", level.as_sql()))
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+        }
+        let start = if options.read_only { "START TRANSACTION READ ONLY" } else { "START TRANSACTION READ WRITE" };
+        sqlx::query(start)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        self.session.pin(conn).await
+    }
+
+    async fn commit_transaction(&self) -> Result<(), DomainError> {
+        let mut conn = self.session.take().await?;
+        sqlx::query("COMMIT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(format!("Failed to commit transaction: {}", e)))?;
+        Ok(())
+    }
+
+    async fn rollback_transaction(&self) -> Result<(), DomainError> {
+        let mut conn = self.session.take().await?;
+        sqlx::query("ROLLBACK")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))?;
+        Ok(())
+    }
+
+    async fn create_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.session_execute(&format!("SAVEPOINT `{}`", name.replace('`', "``"))).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.session_execute(&format!("ROLLBACK TO SAVEPOINT `{}`", name.replace('`', "``"))).await
+    }
+
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, DomainError> {
+        let statement = pagination::trim_statement(sql);
+        let (root, raw) = if analyze {
+            let raw = self.explain_output(&format!("EXPLAIN ANALYZE {}", statement), true).await?;
+            (Self::tree_plan(&raw), raw)
+        } else {
+            let raw = self.explain_output(&format!("EXPLAIN FORMAT=JSON {}", statement), false).await?;
+            (Self::json_plan(&raw)?, raw)
+        };
+        Ok(QueryPlan {
+            query: sql.to_string(),
+            analyzed: analyze,
+            execution_time_ms: if analyze { root.actual_time_ms } else { None },
+            root,
+            planning_time_ms: None,
+            raw,
+        })
+    }
+
     async fn execute_in_transaction(&self, statement: &str) -> Result<crate::domain::TransactionResult, DomainError> {
         let pool = self.get_pool().await?;
         let start = Instant::now();
@@ -443,7 +1096,7 @@ impl SqlDriver for MySqlDriver {
                     .await
                     .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))?;
                 
-                Err(DomainError::query(e.to_string()))
+                Err(Self::map_query_error(e))
             }
         }
     }
@@ -518,6 +1171,7 @@ impl SqlDriver for MySqlDriver {
                 row_count: None,
                 size_bytes: None,
                 comment: None,
+                storage: None,
             })
             .collect())
     }
@@ -587,6 +1241,7 @@ impl SqlDriver for MySqlDriver {
             row_count: None,
             size_bytes: None,
             comment: None,
+            storage: None,
         })
     }
 
@@ -828,7 +1483,127 @@ impl SqlDriver for MySqlDriver {
         Ok(vec![])
     }
 
-    async fn cancel_query(&self) -> Result<(), DomainError> {
+    async fn get_object_ddl(&self, object: &DdlObject, options: &DdlOptions) -> Result<String, DomainError> {
+        let pool = self.get_pool().await?;
+        let dialect = SqlDialect::MySql;
+        let schema = object.schema.as_deref();
+        let target = qualified_identifier(schema, &object.name, dialect);
+        // SHOW CREATE ya lleva los COMMENT de tablas, columnas y rutinas; el propietario es el DEFINER
+        let definer = |sql: String| if options.include_ownership { sql } else { Self::strip_definer(&sql) };
+
+        let mut sql = match object.object_type {
+            DdlObjectType::Table => {
+                let mut sql = format!("{};\n", Self::show_create(&pool, "TABLE", &target, 1).await?);
+                for trigger in self.list_triggers(&object.name, schema).await? {
+                    let name = qualified_identifier(schema, &trigger.name, dialect);
+                    sql.push('\n');
+                    sql.push_str(&Self::delimited(&definer(Self::show_create(&pool, "TRIGGER", &name, 2).await?)));
+                }
+                sql
+            }
+            DdlObjectType::View => format!("{};\n", definer(Self::show_create(&pool, "VIEW", &target, 1).await?)),
+            DdlObjectType::Function => {
+                let kind: String = sqlx::query_scalar(
+                    "SELECT ROUTINE_TYPE FROM information_schema.ROUTINES
+                     WHERE ROUTINE_SCHEMA = COALESCE(?, DATABASE()) AND ROUTINE_NAME = ?
+                     ORDER BY ROUTINE_TYPE
+                     LIMIT 1"
+                )
+                .bind(schema)
+                .bind(&object.name)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?
+                .ok_or_else(|| DomainError::not_found(format!("Function {}", object.name)))?;
+                Self::delimited(&definer(Self::show_create(&pool, &kind, &target, 2).await?))
+            }
+            DdlObjectType::Trigger => Self::delimited(&definer(Self::show_create(&pool, "TRIGGER", &target, 2).await?)),
+            DdlObjectType::Index => {
+                // Los nombres de índice son por tabla: sin ella, se busca la única que lo tenga
+                let tables: Vec<String> = sqlx::query_scalar(
+                    "SELECT DISTINCT TABLE_NAME FROM information_schema.STATISTICS
+                     WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND INDEX_NAME = ?
+                       AND (? IS NULL OR TABLE_NAME = ?)"
+                )
+                .bind(schema)
+                .bind(&object.name)
+                .bind(object.table.as_deref())
+                .bind(object.table.as_deref())
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+                let table_name = match tables.as_slice() {
+                    [table] => table,
+                    [] => return Err(DomainError::not_found(format!("Index {}", object.name))),
+                    _ => {
+                        return Err(DomainError::validation(format!(
+                            "Index {} exists on several tables; specify the table",
+                            object.name
+                        )))
+                    }
+                };
+                let table = self.get_table_info(table_name, schema).await?;
+                let index = table
+                    .indexes
+                    .iter()
+                    .find(|i| i.name == object.name)
+                    .ok_or_else(|| DomainError::not_found(format!("Index {}", object.name)))?;
+                if index.is_primary {
+                    let columns: Vec<String> = index.columns.iter().map(|c| dialect.quote_identifier(c)).collect();
+                    format!(
+                        "ALTER TABLE {} ADD PRIMARY KEY ({});\n",
+                        qualified_identifier(schema, &table.name, dialect),
+                        columns.join(", ")
+                    )
+                } else {
+                    index_sql(&table, index, schema, dialect)
+                }
+            }
+            DdlObjectType::Sequence if self.mariadb => format!("{};\n", Self::show_create(&pool, "SEQUENCE", &target, 1).await?),
+            DdlObjectType::Sequence => return Err(DomainError::validation("MySQL does not support sequences")),
+        };
+
+        if options.include_grants && matches!(object.object_type, DdlObjectType::Table | DdlObjectType::View) {
+            let rows = sqlx::query(
+                "SELECT GRANTEE, GROUP_CONCAT(PRIVILEGE_TYPE ORDER BY PRIVILEGE_TYPE SEPARATOR ', ') AS PRIVILEGES
+                 FROM information_schema.TABLE_PRIVILEGES
+                 WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?
+                 GROUP BY GRANTEE
+                 ORDER BY GRANTEE"
+            )
+            .bind(schema)
+            .bind(&object.name)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+            for r in &rows {
+                // GRANTEE ya viene entre comillas: 'usuario'@'host'
+                if let (Some(grantee), Some(privileges)) = (Self::text_column(r, 0), Self::text_column(r, 1)) {
+                    sql.push_str(&format!("GRANT {} ON {} TO {};\n", privileges, target, grantee));
+                }
+            }
+        }
+        Ok(sql)
+    }
+
+    async fn cancel_query(&self, query_id: Uuid) -> Result<(), DomainError> {
+        let connection_id = match self.running.get(&query_id) {
+            Some(connection_id) => connection_id,
+            None => return Ok(()),
+        };
+
+        // Conexión lateral: el pool puede estar agotado por las propias queries en ejecución
+        let pool = self.get_pool().await?;
+        let mut conn = MySqlConnection::connect_with(&pool.connect_options())
+            .await
+            .map_err(|e| DomainError::connection(e.to_string()))?;
+
+        sqlx::query(&format!("KILL QUERY {}", connection_id))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        conn.close().await.ok();
         Ok(())
     }
 
@@ -865,4 +1640,25 @@ impl SqlDriver for MySqlDriver {
 
         Ok(row.get::<String, _>(0))
     }
+
+    async fn tls_status(&self) -> Result<TlsStatus, DomainError> {
+        let pool = self.get_pool().await?;
+
+        let rows = sqlx::query("SHOW SESSION STATUS WHERE Variable_name IN ('Ssl_version', 'Ssl_cipher')")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        // Sin TLS ambas variables existen pero vienen vacías
+        let value = |name: &str| {
+            rows.iter()
+                .find(|r| r.try_get::<String, _>(0).is_ok_and(|n| n.eq_ignore_ascii_case(name)))
+                .and_then(|r| r.try_get::<String, _>(1).ok())
+                .filter(|v| !v.is_empty())
+        };
+        let version = value("Ssl_version");
+        let cipher = value("Ssl_cipher");
+
+        Ok(TlsStatus { enabled: version.is_some(), version, cipher })
+    }
 }
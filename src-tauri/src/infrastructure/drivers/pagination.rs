@@ -0,0 +1,637 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{CellValue, ColumnInfo, PageRequest, PaginationInfo, QueryResult};
+
+/// Total de filas de una query paginada: (total, es_estimación)
+pub type RowTotal = (Option<u64>, bool);
+
+/// Quita espacios y `;` finales para poder envolver la query en una subconsulta
+pub fn trim_statement(sql: &str) -> &str {
+    sql.trim().trim_end_matches(';').trim_end()
+}
+
+/// true si la sentencia devuelve filas y puede envolverse en `SELECT * FROM (...)`
+pub fn is_row_returning(sql: &str) -> bool {
+    let tokens = tokenize(sql);
+    matches!(
+        tokens.iter().find(|t| !matches!(t, Token::Symbol('('))),
+        Some(Token::Word(w)) if ["SELECT", "WITH", "VALUES", "TABLE"].contains(&w.to_uppercase().as_str())
+    )
+}
+
+/// Palabras del nivel superior de la sentencia, en mayúsculas
+fn keywords(sql: &str) -> Vec<String> {
+    tokenize(sql)
+        .into_iter()
+        .filter_map(|t| match t {
+            Token::Word(w) => Some(w.to_uppercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Aproximación por el texto de si la sentencia produce filas, para cuando el motor
+/// no puede describirla antes de ejecutarla
+pub fn may_return_rows(sql: &str) -> bool {
+    const LEADING: [&str; 9] = ["SHOW", "EXPLAIN", "DESCRIBE", "DESC", "PRAGMA", "CALL", "EXEC", "EXECUTE", "FROM"];
+    let words = keywords(sql);
+    is_row_returning(sql)
+        || words.first().is_some_and(|w| LEADING.contains(&w.as_str()))
+        || words.iter().any(|w| w == "RETURNING" || w == "OUTPUT")
+}
+
+/// true si la sentencia modifica filas (también un WITH que termina en INSERT/UPDATE/DELETE)
+pub fn is_data_modifying(sql: &str) -> bool {
+    const DML: [&str; 5] = ["INSERT", "UPDATE", "DELETE", "MERGE", "REPLACE"];
+    let words = keywords(sql);
+    match words.first().map(String::as_str) {
+        Some("WITH") => words.iter().any(|w| DML.contains(&w.as_str())),
+        Some(first) => DML.contains(&first),
+        None => false,
+    }
+}
+
+/// Posición del ORDER BY final del nivel superior, si la sentencia termina ordenada
+pub fn order_by_position(sql: &str) -> Option<usize> {
+    let tokens = tokenize_with_offsets(sql);
+    let pos = tokens.windows(2).rposition(|w| {
+        matches!((&w[0].1, &w[1].1), (Token::Word(a), Token::Word(b))
+            if a.eq_ignore_ascii_case("ORDER") && b.eq_ignore_ascii_case("BY"))
+    })?;
+    Some(tokens[pos].0)
+}
+
+/// true si la sentencia ya limita sus filas (TOP, OFFSET/FETCH, FOR XML/JSON...)
+/// y no admite otra capa de paginación
+pub fn has_row_limit(sql: &str) -> bool {
+    tokenize(sql).iter().any(|t| {
+        matches!(t, Token::Word(w) if ["TOP", "OFFSET", "FETCH", "FOR", "LIMIT"].contains(&w.to_uppercase().as_str()))
+    })
+}
+
+/// `SELECT <columnas> FROM <tabla> [WHERE ...]` sobre una sola tabla, sin agregaciones ni orden propio
+#[derive(Debug, Clone)]
+pub struct SimpleSelect {
+    pub schema: Option<String>,
+    pub table: String,
+    /// Columnas proyectadas como identificadores simples; None si es `SELECT *`
+    projection: Option<Vec<String>>,
+}
+
+impl SimpleSelect {
+    /// Analiza la query; None si no es una lectura simple de una tabla
+    pub fn parse(sql: &str) -> Option<Self> {
+        const UNSUPPORTED: &[&str] = &[
+            "JOIN", "UNION", "INTERSECT", "EXCEPT", "GROUP", "HAVING", "ORDER", "LIMIT",
+            "OFFSET", "DISTINCT", "WINDOW", "FETCH", "FOR", "INTO", "TOP",
+        ];
+
+        let tokens = tokenize(sql);
+        if !matches!(tokens.first(), Some(Token::Word(w)) if w.eq_ignore_ascii_case("SELECT")) {
+            return None;
+        }
+        if tokens.iter().any(|t| matches!(t, Token::Word(w) if UNSUPPORTED.contains(&w.to_uppercase().as_str()))) {
+            return None;
+        }
+
+        let from_pos = tokens.iter().position(|t| matches!(t, Token::Word(w) if w.eq_ignore_ascii_case("FROM")))?;
+        let projection = parse_projection(&tokens[1..from_pos])?;
+
+        // FROM [schema.]tabla y, como mucho, un WHERE detrás
+        let rest = &tokens[from_pos + 1..];
+        let (schema, table, consumed) = match rest {
+            [a, Token::Symbol('.'), b, ..] => (Some(a.ident()?), b.ident()?, 3),
+            [a, ..] => (None, a.ident()?, 1),
+            [] => return None,
+        };
+        match rest.get(consumed) {
+            None => {}
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case("WHERE") => {}
+            Some(_) => return None,
+        }
+
+        Some(Self { schema, table, projection })
+    }
+
+    /// true si las columnas indicadas llegan al resultado con su nombre original
+    pub fn projects(&self, columns: &[String]) -> bool {
+        match &self.projection {
+            None => true,
+            Some(items) => columns
+                .iter()
+                .all(|c| items.iter().any(|i| i.eq_ignore_ascii_case(c))),
+        }
+    }
+}
+
+fn parse_projection(tokens: &[Token]) -> Option<Option<Vec<String>>> {
+    if let [Token::Symbol('*')] = tokens {
+        return Some(None);
+    }
+
+    let mut items = Vec::new();
+    for item in tokens.split(|t| matches!(t, Token::Symbol(','))) {
+        // Solo nos interesan columnas sin alias ni expresiones: `col` o `tabla.col`
+        match item {
+            [col] => items.extend(col.ident()),
+            [_, Token::Symbol('.'), col] => items.extend(col.ident()),
+            _ => {}
+        }
+    }
+    Some(Some(items))
+}
+
+/// SQL de una página para motores sin cursores de servidor.
+/// Con clave primaria conocida se ordena por ella y, si hay cursor, se sigue desde la última
+/// fila entregada en vez de usar OFFSET. Siempre se lee una fila de más para calcular has_next.
+pub struct KeysetPage {
+    pub sql: String,
+    pub params: Vec<serde_json::Value>,
+    key_columns: Option<Vec<String>>,
+    cursor: Option<KeysetCursor>,
+}
+
+impl KeysetPage {
+    pub fn new(
+        query: &str,
+        page: &PageRequest,
+        key_columns: Option<Vec<String>>,
+        quote: impl Fn(&str) -> String,
+    ) -> Self {
+        let cursor = Self::decode_cursor(page, &key_columns);
+        let limit = page.page_size as u64 + 1;
+
+        let (sql, params) = match (&key_columns, &cursor) {
+            (Some(keys), Some(c)) => {
+                let order = keys.iter().map(|k| quote(k)).collect::<Vec<_>>().join(", ");
+                let placeholders = vec!["?"; keys.len()].join(", ");
+                (
+                    format!(
+                        "SELECT * FROM ({}) AS qx_page WHERE ({}) > ({}) ORDER BY {} LIMIT {}",
+                        query, order, placeholders, order, limit
+                    ),
+                    c.after.clone(),
+                )
+            }
+            (Some(keys), None) => {
+                let order = keys.iter().map(|k| quote(k)).collect::<Vec<_>>().join(", ");
+                (
+                    format!(
+                        "SELECT * FROM ({}) AS qx_page ORDER BY {} LIMIT {} OFFSET {}",
+                        query, order, limit, page.offset()
+                    ),
+                    Vec::new(),
+                )
+            }
+            (None, _) => (
+                format!("SELECT * FROM ({}) AS qx_page LIMIT {} OFFSET {}", query, limit, page.offset()),
+                Vec::new(),
+            ),
+        };
+
+        Self { sql, params, key_columns, cursor }
+    }
+
+    /// Variante para SQL Server: TOP en lugar de LIMIT, OFFSET/FETCH para saltar páginas
+    /// sin cursor y parámetros `@P1..@Pn`. Como no hay comparación de tuplas, la condición
+    /// keyset se expande a `k1 > @P1 OR (k1 = @P1 AND k2 > @P2) ...`.
+    pub fn top(
+        query: &str,
+        page: &PageRequest,
+        key_columns: Option<Vec<String>>,
+        quote: impl Fn(&str) -> String,
+    ) -> Self {
+        let cursor = Self::decode_cursor(page, &key_columns);
+        let limit = page.page_size as u64 + 1;
+        let offset = page.offset();
+
+        let (sql, params) = match (&key_columns, &cursor) {
+            (Some(keys), Some(c)) => {
+                let keys: Vec<String> = keys.iter().map(|k| quote(k)).collect();
+                let seek = (0..keys.len())
+                    .map(|i| {
+                        let mut terms: Vec<String> = (0..i).map(|j| format!("{} = @P{}", keys[j], j + 1)).collect();
+                        terms.push(format!("{} > @P{}", keys[i], i + 1));
+                        format!("({})", terms.join(" AND "))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" OR ");
+                (
+                    format!(
+                        "SELECT TOP ({}) * FROM ({}) AS qx_page WHERE {} ORDER BY {}",
+                        limit, query, seek, keys.join(", ")
+                    ),
+                    c.after.clone(),
+                )
+            }
+            (Some(keys), None) => {
+                let order = keys.iter().map(|k| quote(k)).collect::<Vec<_>>().join(", ");
+                let sql = if offset == 0 {
+                    format!("SELECT TOP ({}) * FROM ({}) AS qx_page ORDER BY {}", limit, query, order)
+                } else {
+                    format!(
+                        "SELECT * FROM ({}) AS qx_page ORDER BY {} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+                        query, order, offset, limit
+                    )
+                };
+                (sql, Vec::new())
+            }
+            // Un ORDER BY no puede ir dentro de una tabla derivada: se pagina la query tal cual
+            (None, _) if order_by_position(query).is_some() => (
+                format!("{} OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", query, offset, limit),
+                Vec::new(),
+            ),
+            (None, _) if offset == 0 => (
+                format!("SELECT TOP ({}) * FROM ({}) AS qx_page", limit, query),
+                Vec::new(),
+            ),
+            (None, _) => (
+                format!(
+                    "SELECT * FROM ({}) AS qx_page ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY",
+                    query, offset, limit
+                ),
+                Vec::new(),
+            ),
+        };
+
+        Self { sql, params, key_columns, cursor }
+    }
+
+    /// Cursor recibido, válido solo si es de esta página y de la misma clave
+    fn decode_cursor(page: &PageRequest, key_columns: &Option<Vec<String>>) -> Option<KeysetCursor> {
+        page.cursor
+            .as_deref()
+            .and_then(KeysetCursor::decode)
+            .filter(|c| {
+                c.page == page.page
+                    && key_columns.as_ref().is_some_and(|keys| keys.len() == c.after.len())
+            })
+    }
+
+    /// Total ya calculado en una página anterior
+    pub fn known_total(&self) -> Option<RowTotal> {
+        self.cursor.as_ref().map(|c| (c.total_rows, c.total_is_estimate))
+    }
+
+    /// Recorta la fila extra y añade la paginación y el cursor de la página siguiente
+    pub fn finish(self, mut result: QueryResult, query: &str, page: &PageRequest, total: RowTotal) -> QueryResult {
+        result.query = query.to_string();
+        let has_next = split_extra_row(&mut result, page.page_size);
+
+        let cursor = match &self.key_columns {
+            Some(keys) if has_next => KeysetCursor::after_last_row(page.page + 1, &result, keys).map(|mut c| {
+                c.total_rows = total.0;
+                c.total_is_estimate = total.1;
+                c.encode()
+            }),
+            _ => None,
+        };
+
+        result.with_pagination(page_info(page, has_next, total, cursor))
+    }
+}
+
+/// PaginationInfo con has_next calculado a partir de la fila extra
+pub fn page_info(page: &PageRequest, has_next: bool, total: RowTotal, cursor: Option<String>) -> PaginationInfo {
+    let info = PaginationInfo::new(page.page, page.page_size);
+    let info = match total {
+        (Some(rows), true) => info.with_estimated_total(rows),
+        (Some(rows), false) => info.with_total(rows),
+        (None, _) => info,
+    };
+    info.with_has_next(has_next).with_cursor(cursor)
+}
+
+/// Posición de un cursor keyset: valores de la clave primaria de la última fila entregada
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeysetCursor {
+    /// Página a la que da acceso el cursor
+    pub page: u32,
+    pub after: Vec<serde_json::Value>,
+    pub total_rows: Option<u64>,
+    #[serde(default)]
+    pub total_is_estimate: bool,
+}
+
+impl KeysetCursor {
+    pub fn encode(&self) -> String {
+        format!("keyset:{}", serde_json::to_string(self).unwrap_or_default())
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        token
+            .strip_prefix("keyset:")
+            .and_then(|json| serde_json::from_str(json).ok())
+    }
+
+    /// Construye el cursor a partir de la última fila de la página
+    pub fn after_last_row(page: u32, result: &QueryResult, key_columns: &[String]) -> Option<Self> {
+        let last = result.rows.last()?;
+        let after = key_columns
+            .iter()
+            .map(|key| {
+                column_index(&result.columns, key).map(|idx| cell_to_json(&last[idx]))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { page, after, total_rows: None, total_is_estimate: false })
+    }
+}
+
+fn column_index(columns: &[ColumnInfo], name: &str) -> Option<usize> {
+    columns.iter().position(|c| c.name.eq_ignore_ascii_case(name))
+}
+
+/// Valor de celda como JSON para poder guardarlo en un cursor y volver a enlazarlo
+pub fn cell_to_json(value: &CellValue) -> serde_json::Value {
+    match value {
+        CellValue::Null => serde_json::Value::Null,
+        CellValue::Bool(b) => serde_json::Value::Bool(*b),
+        CellValue::Int(i) => serde_json::Value::from(*i),
+        CellValue::Float(f) => serde_json::Value::from(*f),
+        CellValue::String(s)
+        | CellValue::Date(s)
+        | CellValue::Time(s)
+        | CellValue::DateTime(s)
+        | CellValue::Uuid(s) => serde_json::Value::String(s.clone()),
+        CellValue::Json(v) => v.clone(),
+        other => serde_json::to_value(other).unwrap_or(serde_json::Value::Null),
+    }
+}
+
+/// Quita la fila extra leída para saber si hay página siguiente
+pub fn split_extra_row(result: &mut QueryResult, page_size: u32) -> bool {
+    let has_next = result.rows.len() > page_size as usize;
+    if has_next {
+        result.rows.truncate(page_size as usize);
+        result.row_count = result.rows.len();
+    }
+    has_next
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(char),
+}
+
+impl Token {
+    fn ident(&self) -> Option<String> {
+        match self {
+            Token::Word(w) => Some(w.clone()),
+            Token::Quoted(q) => Some(q.clone()),
+            Token::Symbol(_) => None,
+        }
+    }
+}
+
+/// Tokens del nivel superior de la sentencia (fuera de paréntesis, literales y comentarios).
+/// Cada grupo entre paréntesis deja un único `Symbol('(')` como marca.
+fn tokenize(sql: &str) -> Vec<Token> {
+    tokenize_with_offsets(sql).into_iter().map(|(_, t)| t).collect()
+}
+
+/// Como `tokenize`, con el offset en bytes donde empieza cada token
+fn tokenize_with_offsets(sql: &str) -> Vec<(usize, Token)> {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        match c {
+            '-' if matches!(chars.get(i + 1), Some((_, '-'))) => {
+                while i < chars.len() && chars[i].1 != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if matches!(chars.get(i + 1), Some((_, '*'))) => {
+                i += 2;
+                while i < chars.len() && !(chars[i].1 == '*' && matches!(chars.get(i + 1), Some((_, '/')))) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            '\'' => {
+                i += 1;
+                while i < chars.len() {
+                    if chars[i].1 == '\'' && matches!(chars.get(i + 1), Some((_, '\''))) {
+                        i += 2;
+                    } else if chars[i].1 == '\'' {
+                        break;
+                    } else {
+                        i += 1;
+                    }
+                }
+                i += 1;
+            }
+            '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && chars[i].1 != close {
+                    i += 1;
+                }
+                if depth == 0 {
+                    tokens.push((offset, Token::Quoted(chars[start..i.min(chars.len())].iter().map(|(_, c)| c).collect())));
+                }
+                i += 1;
+            }
+            '(' => {
+                if depth == 0 {
+                    tokens.push((offset, Token::Symbol('(')));
+                }
+                depth += 1;
+                i += 1;
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_' || chars[i].1 == '$') {
+                    i += 1;
+                }
+                if depth == 0 {
+                    tokens.push((offset, Token::Word(chars[start..i].iter().map(|(_, c)| c).collect())));
+                }
+            }
+            c if c.is_whitespace() => i += 1,
+            c => {
+                if depth == 0 {
+                    tokens.push((offset, Token::Symbol(c)));
+                }
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: "int".to_string(),
+            nullable: false,
+            is_primary_key: name == "id",
+        }
+    }
+
+    fn rows(ids: std::ops::RangeInclusive<i64>) -> QueryResult {
+        QueryResult::new(
+            String::new(),
+            vec![column("id"), column("name")],
+            ids.map(|i| vec![CellValue::Int(i), CellValue::String(format!("n{}", i))]).collect(),
+        )
+    }
+
+    #[test]
+    fn tokenize_skips_comments_literals_and_nested_groups() {
+        let tokens = tokenize("SELECT a, 'x -- y' /* FROM */ FROM (SELECT b FROM t) -- ORDER\n WHERE c");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Word("SELECT".into()),
+                Token::Word("a".into()),
+                Token::Symbol(','),
+                Token::Word("FROM".into()),
+                Token::Symbol('('),
+                Token::Word("WHERE".into()),
+                Token::Word("c".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_identifiers_and_doubled_quotes() {
+        let tokens = tokenize(r#"SELECT "Order", `by`, [top] FROM t WHERE s = 'it''s'"#);
+        assert!(tokens.contains(&Token::Quoted("Order".into())));
+        assert!(tokens.contains(&Token::Quoted("by".into())));
+        assert!(tokens.contains(&Token::Quoted("top".into())));
+        assert_eq!(tokens.last(), Some(&Token::Symbol('=')));
+    }
+
+    #[test]
+    fn tokenize_reports_byte_offsets() {
+        let sql = "SELECT 'ñ' FROM t ORDER BY id";
+        let pos = order_by_position(sql).unwrap();
+        assert_eq!(&sql[pos..], "ORDER BY id");
+        assert_eq!(order_by_position("SELECT * FROM (SELECT 1 ORDER BY 1) x"), None);
+    }
+
+    #[test]
+    fn classifies_statements() {
+        assert!(is_row_returning("with x as (select 1) select * from x"));
+        assert!(!is_row_returning("UPDATE t SET a = 1"));
+        assert!(is_data_modifying("WITH x AS (SELECT 1) DELETE FROM t"));
+        assert!(!is_data_modifying("SELECT 'DELETE' FROM t"));
+        assert!(may_return_rows("DELETE FROM t RETURNING id"));
+        assert!(may_return_rows("SHOW TABLES"));
+        assert!(has_row_limit("SELECT TOP 10 * FROM t"));
+        assert!(!has_row_limit("SELECT * FROM (SELECT * FROM t LIMIT 5) x"));
+        assert_eq!(trim_statement("  SELECT 1 ;; \n"), "SELECT 1");
+    }
+
+    #[test]
+    fn parses_simple_selects() {
+        let select = SimpleSelect::parse("SELECT id, t.name FROM app.users WHERE id > 3").unwrap();
+        assert_eq!(select.schema.as_deref(), Some("app"));
+        assert_eq!(select.table, "users");
+        assert!(select.projects(&["ID".to_string()]));
+        assert!(!select.projects(&["email".to_string()]));
+        assert!(SimpleSelect::parse("SELECT * FROM a JOIN b ON a.id = b.id").is_none());
+        assert!(SimpleSelect::parse("SELECT count(*) FROM t GROUP BY x").is_none());
+    }
+
+    #[test]
+    fn keyset_page_uses_offset_until_a_cursor_exists() {
+        let page = PageRequest::new(2, 10);
+        let keyset = KeysetPage::new("SELECT * FROM t", &page, Some(vec!["id".into()]), |k| format!("\"{}\"", k));
+        assert_eq!(
+            keyset.sql,
+            "SELECT * FROM (SELECT * FROM t) AS qx_page ORDER BY \"id\" LIMIT 11 OFFSET 10"
+        );
+        assert!(keyset.params.is_empty());
+        assert_eq!(keyset.known_total(), None);
+
+        let plain = KeysetPage::new("SELECT * FROM t", &page, None, |k| k.to_string());
+        assert_eq!(plain.sql, "SELECT * FROM (SELECT * FROM t) AS qx_page LIMIT 11 OFFSET 10");
+    }
+
+    #[test]
+    fn keyset_page_seeks_from_the_cursor_and_chains_the_next_one() {
+        let first = PageRequest::new(1, 3);
+        let keys = Some(vec!["id".to_string()]);
+        let result = KeysetPage::new("SELECT * FROM t", &first, keys.clone(), |k| k.to_string())
+            .finish(rows(1..=4), "SELECT * FROM t", &first, (Some(9), true));
+
+        assert_eq!(result.row_count, 3);
+        let info = result.pagination.unwrap();
+        assert!(info.has_next);
+        assert_eq!(info.total_rows, Some(9));
+        assert!(info.total_is_estimate);
+
+        let mut second = PageRequest::new(2, 3);
+        second.cursor = info.cursor;
+        let keyset = KeysetPage::new("SELECT * FROM t", &second, keys, |k| k.to_string());
+        assert_eq!(
+            keyset.sql,
+            "SELECT * FROM (SELECT * FROM t) AS qx_page WHERE (id) > (?) ORDER BY id LIMIT 4"
+        );
+        assert_eq!(keyset.params, vec![serde_json::json!(3)]);
+        assert_eq!(keyset.known_total(), Some((Some(9), true)));
+    }
+
+    #[test]
+    fn keyset_page_ignores_cursors_for_other_pages_or_keys() {
+        let cursor = KeysetCursor { page: 3, after: vec![serde_json::json!(1)], total_rows: None, total_is_estimate: false };
+        let mut page = PageRequest::new(2, 5);
+        page.cursor = Some(cursor.encode());
+        let keyset = KeysetPage::new("SELECT * FROM t", &page, Some(vec!["id".into()]), |k| k.to_string());
+        assert!(keyset.params.is_empty());
+        assert!(keyset.sql.ends_with("OFFSET 5"));
+
+        page.page = 3;
+        let composite = KeysetPage::new("SELECT * FROM t", &page, Some(vec!["a".into(), "b".into()]), |k| k.to_string());
+        assert!(composite.params.is_empty());
+    }
+
+    #[test]
+    fn top_page_expands_the_seek_condition() {
+        let cursor = KeysetCursor {
+            page: 2,
+            after: vec![serde_json::json!(1), serde_json::json!("x")],
+            total_rows: Some(40),
+            total_is_estimate: false,
+        };
+        let mut page = PageRequest::new(2, 20);
+        page.cursor = Some(cursor.encode());
+        let keyset = KeysetPage::top("SELECT * FROM t", &page, Some(vec!["a".into(), "b".into()]), |k| format!("[{}]", k));
+        assert_eq!(
+            keyset.sql,
+            "SELECT TOP (21) * FROM (SELECT * FROM t) AS qx_page \
+             WHERE ([a] > @P1) OR ([a] = @P1 AND [b] > @P2) ORDER BY [a], [b]"
+        );
+
+        let ordered = KeysetPage::top("SELECT * FROM t ORDER BY x", &PageRequest::new(3, 10), None, |k| k.to_string());
+        assert_eq!(ordered.sql, "SELECT * FROM t ORDER BY x OFFSET 20 ROWS FETCH NEXT 11 ROWS ONLY");
+    }
+
+    #[test]
+    fn last_page_has_no_next_cursor() {
+        let page = PageRequest::new(1, 5);
+        let result = KeysetPage::new("SELECT * FROM t", &page, Some(vec!["id".into()]), |k| k.to_string())
+            .finish(rows(1..=2), "SELECT * FROM t", &page, (Some(2), false));
+        let info = result.pagination.unwrap();
+        assert!(!info.has_next);
+        assert_eq!(info.cursor, None);
+        assert_eq!(result.row_count, 2);
+    }
+}
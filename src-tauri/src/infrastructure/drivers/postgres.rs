@@ -1,26 +1,70 @@
 use async_trait::async_trait;
-use sqlx::{postgres::PgPoolOptions, PgPool, Row, Column, TypeInfo};
+use sqlx::{postgres::{PgPoolOptions, PgTypeKind, PgValueFormat}, Column, Connection, Either, Executor, PgConnection, PgPool, Row, Statement, TypeInfo, ValueRef};
 use std::sync::Arc;
 use std::time::Instant;
 use std::collections::HashMap;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
 
 use crate::domain::{
-    CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
-    ConstraintInfo, ConstraintType, DatabaseInfo, DomainError, ForeignKeyAction, ForeignKeyInfo, 
-    FunctionInfo, IndexInfo, PaginationInfo, PrimaryKeyInfo, 
-    QueryResult, SchemaInfo, SequenceInfo, SqlDriver, TableInfo, TriggerEvent, TriggerInfo, 
-    TriggerTiming, ViewInfo,
+    array_literal, copy_rows, create_table_sql, foreign_key_sql, index_sql, merged_foreign_keys, qualified_identifier, sequence_sql, sql_literal,
+    standalone_indexes, trigger_sql, view_sql,
+    BindValue, CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
+    ConstraintInfo, ConstraintType, CountMode, DatabaseInfo, DdlObject, DdlObjectType, DdlOptions, DomainError, DuplicateMode, ExecutionOptions, ForeignKeyAction,
+    ForeignKeyInfo, FunctionInfo, ImportTarget, IndexInfo, PageRequest, PlanNode, PrimaryKeyInfo, 
+    QueryPlan, QueryResult, QueryStreamSummary, RowBatch, RowEditSyntax, RowEditTarget, SchemaInfo, SequenceInfo, SqlDialect, SqlDriver, StreamOptions,
+    TableInfo, TlsStatus, TransactionOptions, TriggerEvent, TriggerInfo, TriggerTiming, ViewInfo,
 };
+use super::running::{RunningQueries, RunningQueryGuard};
+use super::session::{BackendState, PinnedSession, SessionConnection};
+use super::stream::RowBatchWriter;
+use super::pagination::{self, KeysetPage, RowTotal};
+use super::tls::map_connect_error;
+
+/// SQLSTATE que PostgreSQL devuelve cuando una query es cancelada
+const QUERY_CANCELED: &str = "57014";
+
+/// SQLSTATE de una escritura en una transacción de solo lectura
+const READ_ONLY_SQL_TRANSACTION: &str = "25006";
+
+/// Fija statement_timeout al límite en milisegundos ($1) o, si es NULL, al valor de la configuración
+const STATEMENT_TIMEOUT_CONFIG: &str =
+    "set_config('statement_timeout', COALESCE($1, (SELECT reset_val FROM pg_settings WHERE name = 'statement_timeout')), false)";
+
+/// Devuelve statement_timeout al valor de la configuración antes de que la conexión vuelva al pool
+const STATEMENT_TIMEOUT_RESET: &str = "RESET statement_timeout";
+
+/// Cursores de paginación abiertos a la vez; al superarlo se cierra el menos usado
+const MAX_OPEN_CURSORS: usize = 4;
+
+/// Cursor de servidor de una query paginada.
+/// Vive dentro de una transacción propia, que se deshace al soltar el cursor.
+struct PgCursor {
+    tx: sqlx::Transaction<'static, sqlx::Postgres>,
+    query: String,
+    pid: i32,
+    total: RowTotal,
+    last_used: Instant,
+}
 
 pub struct PostgresDriver {
     pool: Arc<RwLock<Option<PgPool>>>,
+    /// PIDs de backend de las queries en ejecución
+    running: RunningQueries<i32>,
+    /// Cursores de paginación por nombre
+    cursors: Arc<Mutex<HashMap<String, PgCursor>>>,
+    /// Conexión de la transacción manual en curso
+    session: PinnedSession<sqlx::Postgres, i32>,
 }
 
 impl PostgresDriver {
     pub fn new() -> Self {
         Self {
             pool: Arc::new(RwLock::new(None)),
+            running: RunningQueries::new(),
+            cursors: Arc::new(Mutex::new(HashMap::new())),
+            session: PinnedSession::with_reset(STATEMENT_TIMEOUT_RESET),
         }
     }
 
@@ -29,6 +73,491 @@ impl PostgresDriver {
         guard.clone().ok_or_else(|| DomainError::connection("Not connected"))
     }
 
+    /// Adquiere una conexión (la de la transacción manual, si hay una) y registra
+    /// su PID de backend para poder cancelarla. Fija statement_timeout al límite de la
+    /// ejecución, o lo devuelve al valor de la configuración si no hay límite.
+    /// El PID y el límite ya fijado se recuerdan por conexión: solo se consultan o cambian
+    /// la primera vez o cuando el límite es otro
+    async fn acquire_tracked(&self, options: &ExecutionOptions) -> Result<(SessionConnection<sqlx::Postgres, i32>, RunningQueryGuard<i32>), DomainError> {
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.acquire(&pool).await?;
+        let backend = *conn.backend_mut();
+
+        let pid = match backend.id {
+            Some(pid) if backend.timeout == Some(options.timeout) => pid,
+            Some(pid) => {
+                sqlx::query(&format!("SELECT {}", STATEMENT_TIMEOUT_CONFIG))
+                    .bind(options.timeout.map(|t| t.as_millis().to_string()))
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| DomainError::query(e.to_string()))?;
+                pid
+            }
+            None => {
+                let (pid, _): (i32, String) = sqlx::query_as(&format!("SELECT pg_backend_pid(), {}", STATEMENT_TIMEOUT_CONFIG))
+                    .bind(options.timeout.map(|t| t.as_millis().to_string()))
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| DomainError::query(e.to_string()))?;
+                pid
+            }
+        };
+        *conn.backend_mut() = BackendState { id: Some(pid), timeout: Some(options.timeout) };
+
+        let guard = self.running.register(options.query_id, pid);
+        Ok((conn, guard))
+    }
+
+    /// Ejecuta una sentencia de control en la conexión de la transacción manual
+    async fn session_execute(&self, sql: &str) -> Result<(), DomainError> {
+        let mut conn = self.session
+            .pinned()
+            .await
+            .ok_or_else(|| DomainError::validation("No transaction is open"))?;
+        sqlx::query(sql)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Convierte errores de sqlx distinguiendo las queries canceladas, las que cortó
+    /// statement_timeout (mismo SQLSTATE, distinto mensaje) y las escrituras en sesiones de solo lectura
+    fn map_query_error(e: sqlx::Error) -> DomainError {
+        if let Some(db_err) = e.as_database_error() {
+            if db_err.code().as_deref() == Some(QUERY_CANCELED) {
+                if db_err.message().contains("statement timeout") {
+                    return DomainError::Timeout { operation: db_err.message().to_string() };
+                }
+                return DomainError::Cancelled;
+            }
+            if db_err.code().as_deref() == Some(READ_ONLY_SQL_TRANSACTION) {
+                return DomainError::PermissionDenied { resource: db_err.message().to_string() };
+            }
+        }
+        DomainError::query(e.to_string())
+    }
+
+    /// Abre un cursor SCROLL para la query en una transacción dedicada
+    async fn declare_cursor(&self, query: &str, count_mode: CountMode, options: &ExecutionOptions) -> Result<(String, PgCursor), DomainError> {
+        let total = self.count_rows(query, &options.params, count_mode).await?;
+
+        let pool = self.get_pool().await?;
+        let mut tx = pool.begin()
+            .await
+            .map_err(|e| DomainError::connection(e.to_string()))?;
+        let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        let name = format!("qx_{}", Uuid::new_v4().simple());
+        let _running = self.running.register(options.query_id, pid);
+        let declare = format!("DECLARE {} SCROLL CURSOR FOR {}", name, query);
+        Self::bind_all(sqlx::query(&declare), &options.params)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_query_error)?;
+
+        Ok((name, PgCursor {
+            tx,
+            query: Self::cursor_key(query, &options.params),
+            pid,
+            total,
+            last_used: Instant::now(),
+        }))
+    }
+
+    /// Guarda un cursor para las siguientes páginas, cerrando el más antiguo si hay demasiados
+    async fn keep_cursor(&self, name: String, cursor: PgCursor) {
+        let mut cursors = self.cursors.lock().await;
+        if cursors.len() >= MAX_OPEN_CURSORS {
+            let oldest = cursors
+                .iter()
+                .min_by_key(|(_, c)| c.last_used)
+                .map(|(n, _)| n.clone());
+            if let Some(evicted) = oldest.and_then(|n| cursors.remove(&n)) {
+                evicted.tx.rollback().await.ok();
+            }
+        }
+        cursors.insert(name, cursor);
+    }
+
+    /// Total de filas según el modo pedido. La estimación sale de EXPLAIN y, si no se
+    /// puede obtener, la página se devuelve sin total.
+    async fn count_rows(&self, query: &str, params: &[CellValue], mode: CountMode) -> Result<RowTotal, DomainError> {
+        let pool = self.get_pool().await?;
+        // En la conexión de la transacción manual, si hay una: así el total cuenta las filas
+        // sin confirmar igual que la página
+        let mut conn = self.session.acquire(&pool).await?;
+        match mode {
+            CountMode::None => Ok((None, false)),
+            CountMode::Exact => {
+                let sql = format!("SELECT COUNT(*) FROM ({}) AS qx_count", query);
+                let row = Self::bind_all(sqlx::query(&sql), params)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .map_err(|e| DomainError::query(e.to_string()))?;
+                let total: i64 = row.try_get(0).map_err(|e| DomainError::query(e.to_string()))?;
+                Ok((Some(total as u64), false))
+            }
+            CountMode::Estimated => {
+                let sql = format!("EXPLAIN (FORMAT JSON) {}", query);
+                let plan: Option<serde_json::Value> = Self::bind_all(sqlx::query(&sql), params)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .ok()
+                    .and_then(|row| row.try_get(0).ok());
+                let estimate = plan
+                    .as_ref()
+                    .and_then(|p| p.get(0)?.get("Plan")?.get("Plan Rows")?.as_f64())
+                    .map(|rows| rows as u64);
+                Ok((estimate, estimate.is_some()))
+            }
+        }
+    }
+
+    /// Clave con la que se reutiliza un cursor: misma query y mismos valores
+    fn cursor_key(query: &str, params: &[CellValue]) -> String {
+        if params.is_empty() {
+            return query.to_string();
+        }
+        format!("{}\n-- {}", query, serde_json::to_string(params).unwrap_or_default())
+    }
+
+    fn bind_all<'q>(
+        mut q: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+        params: &[CellValue],
+    ) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+        for param in params {
+            q = Self::bind_cell(q, param);
+        }
+        q
+    }
+
+    /// Enlaza un valor tipado; fechas, horas y UUID se envían con su tipo si el texto es válido
+    fn bind_cell<'q>(
+        q: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+        value: &CellValue,
+    ) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+        match value {
+            CellValue::Null => q.bind(None::<String>),
+            CellValue::Bool(b) => q.bind(*b),
+            CellValue::Int(i) => q.bind(*i),
+            CellValue::Float(f) => q.bind(*f),
+            CellValue::Bytes(b) => q.bind(b.clone()),
+            CellValue::Json(v) => q.bind(v.clone()),
+            CellValue::Date(s) => match chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+                Ok(d) => q.bind(d),
+                Err(_) => q.bind(s.clone()),
+            },
+            CellValue::Time(s) => match s.parse::<chrono::NaiveTime>() {
+                Ok(t) => q.bind(t),
+                Err(_) => q.bind(s.clone()),
+            },
+            CellValue::DateTime(s) => match chrono::DateTime::parse_from_rfc3339(s) {
+                Ok(dt) => q.bind(dt),
+                Err(_) => match s.replace(' ', "T").parse::<chrono::NaiveDateTime>() {
+                    Ok(dt) => q.bind(dt),
+                    Err(_) => q.bind(s.clone()),
+                },
+            },
+            CellValue::Uuid(s) => match Uuid::parse_str(s) {
+                Ok(u) => q.bind(u),
+                Err(_) => q.bind(s.clone()),
+            },
+            CellValue::String(s) => q.bind(s.clone()),
+            CellValue::Array(items) => {
+                // Arrays homogéneos de enteros, decimales o texto; el resto viaja como JSON
+                if let Some(ints) = items.iter().map(|i| match i { CellValue::Int(v) => Some(*v), _ => None }).collect::<Option<Vec<_>>>() {
+                    q.bind(ints)
+                } else if let Some(floats) = items.iter().map(|i| match i { CellValue::Float(v) => Some(*v), _ => None }).collect::<Option<Vec<_>>>() {
+                    q.bind(floats)
+                } else if let Some(texts) = items.iter().map(|i| match i { CellValue::String(v) => Some(v.clone()), _ => None }).collect::<Option<Vec<_>>>() {
+                    q.bind(texts)
+                } else {
+                    q.bind(pagination::cell_to_json(value))
+                }
+            }
+        }
+    }
+
+    /// Enlaza un valor ya convertido al tipo de su columna; los arrays viajan como literal
+    /// de PostgreSQL y el cast del marcador los lleva al tipo del elemento
+    fn bind_value<'q>(
+        q: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+        value: BindValue,
+    ) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+        match value {
+            BindValue::Null => q.bind(None::<String>),
+            BindValue::Bool(b) => q.bind(b),
+            BindValue::SmallInt(i) => q.bind(i),
+            BindValue::Int(i) => q.bind(i),
+            BindValue::BigInt(i) => q.bind(i),
+            BindValue::Real(f) => q.bind(f),
+            BindValue::Double(f) => q.bind(f),
+            BindValue::Decimal(s) | BindValue::Text(s) => q.bind(s),
+            BindValue::Date(d) => q.bind(d),
+            BindValue::Time(t) => q.bind(t),
+            BindValue::Timestamp(dt) => q.bind(dt),
+            BindValue::TimestampTz(dt) => q.bind(dt),
+            BindValue::Uuid(u) => q.bind(u),
+            BindValue::Json(v) => q.bind(v),
+            BindValue::Bytes(b) => q.bind(b),
+            BindValue::Array(items) => q.bind(array_literal(&items)),
+        }
+    }
+
+    /// Marcador con el cast al tipo de la columna, que resuelve los valores que viajan como
+    /// texto (numeric, enums, arrays...) y los NULL sin tipo
+    fn typed_placeholder(index: usize, column: &ColumnSchema) -> String {
+        match column.native_type.as_str() {
+            "" => format!("${}", index),
+            native => format!("${}::\"{}\"", index, native.replace('"', "\"\"")),
+        }
+    }
+
+    async fn copy_in(conn: &mut PgConnection, statement: &str, data: Vec<u8>) -> Result<u64, DomainError> {
+        let mut copy = conn.copy_in_raw(statement).await.map_err(Self::map_query_error)?;
+        if let Err(e) = copy.send(data).await {
+            copy.abort(e.to_string()).await.ok();
+            return Err(Self::map_query_error(e));
+        }
+        copy.finish().await.map_err(Self::map_query_error)
+    }
+
+    fn column_infos(columns: &[sqlx::postgres::PgColumn]) -> Vec<ColumnInfo> {
+        columns
+            .iter()
+            .map(|col| ColumnInfo {
+                name: col.name().to_string(),
+                data_type: col.type_info().name().to_string(),
+                nullable: true,
+                is_primary_key: false,
+            })
+            .collect()
+    }
+
+    /// Ejecuta una sentencia suelta. Se prepara antes para saber si produce un conjunto de
+    /// resultados aunque venga vacío; si además modifica datos (INSERT ... RETURNING)
+    /// se devuelven tanto las filas como las afectadas.
+    async fn run_statement(&self, sql: &str, options: &ExecutionOptions) -> Result<(Option<QueryResult>, Option<u64>), DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let start = Instant::now();
+        // Lo que no se puede preparar se decide por el texto
+        let described = (&mut *conn)
+            .prepare(sql)
+            .await
+            .ok()
+            .map(|statement| Self::column_infos(statement.columns()));
+
+        let mut rows = Vec::new();
+        let mut affected = 0;
+        {
+            let query = Self::bind_all(sqlx::query(sql), &options.params);
+            let mut stream = (&mut *conn).fetch_many(query);
+            while let Some(item) = stream.next().await {
+                match item.map_err(Self::map_query_error)? {
+                    Either::Left(done) => affected += done.rows_affected(),
+                    Either::Right(row) => rows.push(row),
+                }
+            }
+        }
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        let returns_rows = !rows.is_empty()
+            || described.as_ref().map_or_else(|| pagination::may_return_rows(sql), |columns| !columns.is_empty());
+        if !returns_rows {
+            return Ok((None, Some(affected)));
+        }
+
+        let columns = match rows.first() {
+            Some(row) => Self::column_infos(row.columns()),
+            None => described.unwrap_or_default(),
+        };
+        let data: Vec<Vec<CellValue>> = rows
+            .iter()
+            .map(|row| (0..row.columns().len()).map(|idx| Self::map_pg_value(row, idx)).collect())
+            .collect();
+        let mut result = QueryResult::new(sql.to_string(), columns, data).with_execution_time(execution_time);
+        // En un SELECT el recuento de la etiqueta del servidor son las filas leídas, no afectadas
+        let affected = pagination::is_data_modifying(sql).then_some(affected);
+        if let Some(count) = affected {
+            result = result.with_affected_rows(count);
+        }
+        Ok((Some(result), affected))
+    }
+
+    /// Nodo a partir de un objeto "Plan" de `EXPLAIN (FORMAT JSON)`
+    fn plan_node(plan: &serde_json::Value) -> PlanNode {
+        let text = |key: &str| plan.get(key).and_then(|v| v.as_str()).map(String::from);
+        let number = |key: &str| plan.get(key).and_then(|v| v.as_f64());
+
+        let mut node = PlanNode::new(text("Node Type").unwrap_or_default());
+        node.relation = text("Relation Name").or_else(|| text("CTE Name")).or_else(|| text("Function Name"));
+        let details: Vec<String> = ["Index Name", "Join Type", "Index Cond", "Hash Cond", "Merge Cond", "Join Filter", "Filter"]
+            .iter()
+            .filter_map(|key| text(key).map(|value| format!("{}: {}", key, value)))
+            .collect();
+        node.detail = (!details.is_empty()).then(|| details.join("; "));
+        node.estimated_rows = number("Plan Rows");
+        node.estimated_cost = number("Total Cost");
+        node.actual_rows = number("Actual Rows");
+        node.actual_time_ms = number("Actual Total Time");
+        node.loops = plan.get("Actual Loops").and_then(|v| v.as_u64());
+        node.children = plan
+            .get("Plans")
+            .and_then(|v| v.as_array())
+            .map(|plans| plans.iter().map(Self::plan_node).collect())
+            .unwrap_or_default();
+        node
+    }
+
+    /// Comentarios, propietario y permisos de una relación de `pg_class`. `kind` es como se
+    /// nombra en `COMMENT ON` y `ALTER` (`TABLE`, `VIEW`, `SEQUENCE`, `INDEX`...)
+    async fn relation_extras(pool: &PgPool, target: &str, kind: &str, options: &DdlOptions) -> Result<String, DomainError> {
+        let mut sql = String::new();
+        if options.include_comments {
+            let comment: Option<String> = sqlx::query_scalar("SELECT obj_description($1::regclass, 'pg_class')")
+                .bind(target)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+            if let Some(comment) = comment {
+                sql.push_str(&format!("COMMENT ON {} {} IS {};\n", kind, target, Self::text_literal(&comment)));
+            }
+            if !matches!(kind, "INDEX" | "SEQUENCE") {
+                let rows = sqlx::query(
+                    "SELECT a.attname, col_description(a.attrelid, a.attnum) AS comment
+                     FROM pg_attribute a
+                     WHERE a.attrelid = $1::regclass AND a.attnum > 0 AND NOT a.attisdropped
+                       AND col_description(a.attrelid, a.attnum) IS NOT NULL
+                     ORDER BY a.attnum"
+                )
+                .bind(target)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+                for r in &rows {
+                    sql.push_str(&format!(
+                        "COMMENT ON COLUMN {}.{} IS {};\n",
+                        target,
+                        SqlDialect::Postgres.quote_identifier(&r.get::<String, _>("attname")),
+                        Self::text_literal(&r.get::<String, _>("comment"))
+                    ));
+                }
+            }
+        }
+        // Un índice pertenece a su tabla: no tiene propietario ni permisos propios
+        if kind == "INDEX" {
+            return Ok(sql);
+        }
+        if options.include_ownership {
+            let owner: String = sqlx::query_scalar("SELECT pg_get_userbyid(relowner)::text FROM pg_class WHERE oid = $1::regclass")
+                .bind(target)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+            sql.push_str(&format!("ALTER {} {} OWNER TO {};\n", kind, target, SqlDialect::Postgres.quote_identifier(&owner)));
+        }
+        if options.include_grants {
+            let on = if kind == "SEQUENCE" { format!("SEQUENCE {}", target) } else { format!("TABLE {}", target) };
+            sql.push_str(&Self::grant_statements(
+                pool,
+                "pg_class o, aclexplode(o.relacl) a WHERE o.oid = $1::regclass AND a.grantee <> o.relowner",
+                target,
+                &on,
+            ).await?);
+        }
+        Ok(sql)
+    }
+
+    /// `GRANT` de los privilegios que la ACL de `acl_source` concede a otros roles, uno por rol.
+    /// `key` es el parámetro `$1` de la consulta y `on`, el objeto tal como va tras `ON`
+    async fn grant_statements(pool: &PgPool, acl_source: &str, key: &str, on: &str) -> Result<String, DomainError> {
+        let query = format!(
+            "SELECT CASE WHEN a.grantee = 0 THEN 'PUBLIC' ELSE pg_get_userbyid(a.grantee)::text END AS grantee,
+                    string_agg(a.privilege_type, ', ' ORDER BY a.privilege_type) AS privileges
+             FROM {}
+             GROUP BY a.grantee
+             ORDER BY 1",
+            acl_source
+        );
+        let rows = sqlx::query(&query)
+            .bind(key)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+        Ok(rows
+            .iter()
+            .map(|r| {
+                let grantee: String = r.get("grantee");
+                let grantee = if grantee == "PUBLIC" { grantee } else { SqlDialect::Postgres.quote_identifier(&grantee) };
+                format!("GRANT {} ON {} TO {};\n", r.get::<String, _>("privileges"), on, grantee)
+            })
+            .collect())
+    }
+
+    /// DDL de las funciones y procedimientos con ese nombre, una tras otra si hay sobrecargas
+    async fn function_ddl(pool: &PgPool, schema: &str, name: &str, options: &DdlOptions) -> Result<String, DomainError> {
+        let rows = sqlx::query(
+            "SELECT p.oid::text AS oid, p.prokind::text AS kind,
+                    pg_get_functiondef(p.oid) AS definition,
+                    pg_get_function_identity_arguments(p.oid) AS arguments,
+                    pg_get_userbyid(p.proowner)::text AS owner,
+                    obj_description(p.oid, 'pg_proc') AS comment
+             FROM pg_proc p
+             JOIN pg_namespace n ON p.pronamespace = n.oid
+             WHERE n.nspname = $1 AND p.proname = $2 AND p.prokind IN ('f', 'p')
+             ORDER BY p.oid"
+        )
+        .bind(schema)
+        .bind(name)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| DomainError::query(e.to_string()))?;
+        if rows.is_empty() {
+            return Err(DomainError::not_found(format!("Function {}.{}", schema, name)));
+        }
+
+        let dialect = SqlDialect::Postgres;
+        let mut parts = Vec::new();
+        for r in &rows {
+            let kind = if r.get::<String, _>("kind") == "p" { "PROCEDURE" } else { "FUNCTION" };
+            let signature = format!(
+                "{}({})",
+                qualified_identifier(Some(schema), name, dialect),
+                r.get::<String, _>("arguments")
+            );
+            let mut sql = format!("{};\n", r.get::<String, _>("definition").trim_end());
+            if options.include_comments {
+                if let Some(comment) = r.get::<Option<String>, _>("comment") {
+                    sql.push_str(&format!("COMMENT ON {} {} IS {};\n", kind, signature, Self::text_literal(&comment)));
+                }
+            }
+            if options.include_ownership {
+                sql.push_str(&format!(
+                    "ALTER {} {} OWNER TO {};\n",
+                    kind, signature, dialect.quote_identifier(&r.get::<String, _>("owner"))
+                ));
+            }
+            if options.include_grants {
+                sql.push_str(&Self::grant_statements(
+                    pool,
+                    "pg_proc o, aclexplode(o.proacl) a WHERE o.oid = $1::oid AND a.grantee <> o.proowner",
+                    &r.get::<String, _>("oid"),
+                    &format!("{} {}", kind, signature),
+                ).await?);
+            }
+            parts.push(sql);
+        }
+        Ok(parts.join("\n"))
+    }
+
+    fn text_literal(text: &str) -> String {
+        sql_literal(&CellValue::String(text.to_string()), SqlDialect::Postgres)
+    }
+
     fn map_pg_value(row: &sqlx::postgres::PgRow, idx: usize) -> CellValue {
         let col = row.column(idx);
         let type_name = col.type_info().name();
@@ -47,9 +576,19 @@ impl PostgresDriver {
             "FLOAT4" => row.try_get::<f32, _>(idx)
                 .map(|v| CellValue::Float(v as f64))
                 .unwrap_or(CellValue::Null),
-            "FLOAT8" | "NUMERIC" => row.try_get::<f64, _>(idx)
+            "FLOAT8" => row.try_get::<f64, _>(idx)
                 .map(CellValue::Float)
                 .unwrap_or(CellValue::Null),
+            // Como texto exacto: pasado a f64 perdería dígitos y no serviría como clave
+            "NUMERIC" => row.try_get_raw(idx)
+                .ok()
+                .filter(|raw| !raw.is_null())
+                .and_then(|raw| match raw.format() {
+                    PgValueFormat::Text => raw.as_str().ok().map(str::to_string),
+                    PgValueFormat::Binary => raw.as_bytes().ok().and_then(numeric_text),
+                })
+                .map(CellValue::String)
+                .unwrap_or(CellValue::Null),
             "UUID" => row.try_get::<uuid::Uuid, _>(idx)
                 .map(|v| CellValue::Uuid(v.to_string()))
                 .unwrap_or(CellValue::Null),
@@ -68,11 +607,84 @@ impl PostgresDriver {
             "TIMESTAMP" | "TIMESTAMPTZ" => row.try_get::<chrono::DateTime<chrono::Utc>, _>(idx)
                 .map(|v| CellValue::DateTime(v.to_rfc3339()))
                 .unwrap_or(CellValue::Null),
+            // Los enums llegan como su etiqueta, pero sqlx solo decodifica String desde los tipos de texto
+            _ if matches!(col.type_info().kind(), PgTypeKind::Enum(_)) => row.try_get_unchecked::<String, _>(idx)
+                .map(CellValue::String)
+                .unwrap_or(CellValue::Null),
             _ => row.try_get::<String, _>(idx)
                 .map(CellValue::String)
                 .unwrap_or(CellValue::Null),
         }
     }
+
+    /// Valor de una lectura exacta, que llega por el protocolo de texto. Solo se tipan los
+    /// valores que el grid representa sin perder nada; NUMERIC, fechas, arrays, intervalos,
+    /// enums... se quedan con el texto del servidor, que es lo que acepta al restaurarlos
+    fn exact_pg_value(row: &sqlx::postgres::PgRow, idx: usize) -> Result<CellValue, DomainError> {
+        let raw = row.try_get_raw(idx).map_err(Self::map_query_error)?;
+        if raw.is_null() {
+            return Ok(CellValue::Null);
+        }
+        let col = row.column(idx);
+        let value = match col.type_info().name() {
+            "BOOL" | "INT2" | "INT4" | "INT8" | "FLOAT8" | "UUID" | "JSON" | "JSONB" | "BYTEA" => Self::map_pg_value(row, idx),
+            _ => row.try_get_unchecked::<String, _>(idx)
+                .map(CellValue::String)
+                .unwrap_or(CellValue::Null),
+        };
+        match value {
+            CellValue::Null => Err(DomainError::query(format!(
+                "Cannot read the value of column {} ({})",
+                col.name(),
+                col.type_info().name()
+            ))),
+            value => Ok(value),
+        }
+    }
+}
+
+/// Texto exacto de un NUMERIC en formato binario: dígitos en base 10000, el peso del
+/// primero y la escala con la que se muestra
+fn numeric_text(bytes: &[u8]) -> Option<String> {
+    let words: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+    let (count, weight, sign, scale) = match words.get(..4)? {
+        &[count, weight, sign, scale] => (count as usize, weight as i16 as i64, sign, scale as usize),
+        _ => return None,
+    };
+    let digits = words.get(4..4 + count)?;
+    match sign {
+        0x0000 | 0x4000 => {}
+        0xC000 => return Some("NaN".to_string()),
+        0xD000 => return Some("Infinity".to_string()),
+        0xF000 => return Some("-Infinity".to_string()),
+        _ => return None,
+    }
+    let digit = |index: i64| usize::try_from(index).ok().and_then(|i| digits.get(i)).copied().unwrap_or(0);
+
+    let mut text = String::new();
+    if sign == 0x4000 {
+        text.push('-');
+    }
+    if weight < 0 {
+        text.push('0');
+    } else {
+        text.push_str(&digit(0).to_string());
+        for index in 1..=weight {
+            text.push_str(&format!("{:04}", digit(index)));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit(index)));
+            index += 1;
+        }
+        fraction.truncate(scale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Some(text)
 }
 
 impl Default for PostgresDriver {
@@ -87,6 +699,10 @@ impl SqlDriver for PostgresDriver {
         "postgresql"
     }
 
+    fn placeholder(&self, index: usize, _value: &CellValue) -> String {
+        format!("${}", index)
+    }
+
     fn display_name(&self) -> &'static str {
         "PostgreSQL"
     }
@@ -96,14 +712,24 @@ impl SqlDriver for PostgresDriver {
             .max_connections(5)
             .connect(connection_string)
             .await
-            .map_err(|e| DomainError::connection(e.to_string()))?;
+            .map_err(map_connect_error)?;
 
         let mut guard = self.pool.write().await;
+        self.session.release_warm();
         *guard = Some(pool);
         Ok(())
     }
 
     async fn disconnect(&self) -> Result<(), DomainError> {
+        // Los cursores retienen conexiones del pool; hay que soltarlos antes de cerrarlo
+        for (_, cursor) in self.cursors.lock().await.drain() {
+            cursor.tx.rollback().await.ok();
+        }
+        if let Ok(mut conn) = self.session.take().await {
+            sqlx::query("ROLLBACK").execute(&mut *conn).await.ok();
+        }
+        self.session.release_warm();
+
         let mut guard = self.pool.write().await;
         if let Some(pool) = guard.take() {
             pool.close().await;
@@ -125,7 +751,7 @@ impl SqlDriver for PostgresDriver {
             .max_connections(1)
             .connect(connection_string)
             .await
-            .map_err(|e| DomainError::connection(e.to_string()))?;
+            .map_err(map_connect_error)?;
 
         sqlx::query("SELECT 1")
             .fetch_one(&pool)
@@ -136,28 +762,19 @@ impl SqlDriver for PostgresDriver {
         Ok(())
     }
 
-    async fn execute_query(&self, query: &str) -> Result<QueryResult, DomainError> {
-        let pool = self.get_pool().await?;
+    async fn execute_query(&self, query: &str, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
         let start = Instant::now();
 
-        let rows = sqlx::query(query)
-            .fetch_all(&pool)
+        let rows = Self::bind_all(sqlx::query(query), &options.params)
+            .fetch_all(&mut *conn)
             .await
-            .map_err(|e| DomainError::query(e.to_string()))?;
+            .map_err(Self::map_query_error)?;
 
         let execution_time = start.elapsed().as_millis() as u64;
 
         let columns: Vec<ColumnInfo> = if !rows.is_empty() {
-            rows[0]
-                .columns()
-                .iter()
-                .map(|col| ColumnInfo {
-                    name: col.name().to_string(),
-                    data_type: col.type_info().name().to_string(),
-                    nullable: true,
-                    is_primary_key: false,
-                })
-                .collect()
+            Self::column_infos(rows[0].columns())
         } else {
             vec![]
         };
@@ -174,29 +791,141 @@ impl SqlDriver for PostgresDriver {
         Ok(QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time))
     }
 
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        options: &ExecutionOptions,
+        stream_options: &StreamOptions,
+        sender: mpsc::Sender<RowBatch>,
+    ) -> Result<QueryStreamSummary, DomainError> {
+        if stream_options.exact_values && !options.params.is_empty() {
+            return Err(DomainError::validation("Exact reads do not take bound parameters"));
+        }
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let mut writer = RowBatchWriter::new(sender, stream_options);
+        let mut exhausted = true;
+
+        {
+            // Sin argumentos sqlx usa el protocolo simple, cuyos valores llegan como texto
+            let mut rows = if stream_options.exact_values {
+                (&mut *conn).fetch(query)
+            } else {
+                Self::bind_all(sqlx::query(query), &options.params).fetch(&mut *conn)
+            };
+            while let Some(row) = rows.next().await {
+                let row = row.map_err(Self::map_query_error)?;
+                if writer.needs_columns() {
+                    writer.set_columns(Self::column_infos(row.columns()));
+                }
+                let values = if stream_options.exact_values {
+                    (0..row.columns().len())
+                        .map(|idx| Self::exact_pg_value(&row, idx))
+                        .collect::<Result<_, _>>()?
+                } else {
+                    (0..row.columns().len())
+                        .map(|idx| Self::map_pg_value(&row, idx))
+                        .collect()
+                };
+                if !writer.push(values).await {
+                    exhausted = false;
+                    break;
+                }
+            }
+        }
+
+        if !exhausted {
+            // Quedan filas sin leer: se cierra la conexión en vez de devolverla al pool
+            conn.close().await.ok();
+        }
+
+        Ok(writer.finish(options.query_id, query, exhausted).await)
+    }
+
     async fn execute_query_paginated(
         &self,
         query: &str,
-        page: u32,
-        page_size: u32,
+        page: &PageRequest,
+        options: &ExecutionOptions,
     ) -> Result<QueryResult, DomainError> {
-        let offset = (page - 1) * page_size;
-        let paginated_query = format!("{} LIMIT {} OFFSET {}", query.trim_end_matches(';'), page_size, offset);
+        let query = pagination::trim_statement(query);
+        if !pagination::is_row_returning(query) {
+            return self.execute_query(query, options).await;
+        }
 
-        // Obtener count total
-        let count_query = format!("SELECT COUNT(*) as total FROM ({}) as subquery", query.trim_end_matches(';'));
-        let pool = self.get_pool().await?;
+        // Los cursores viven en su propia transacción y no verían los cambios de la manual:
+        // con una abierta se pagina por OFFSET sobre su conexión
+        if self.session.is_active().await {
+            let plan = KeysetPage::new(query, page, None, |c| format!("\"{}\"", c.replace('"', "\"\"")));
+            let result = self.execute_query(&plan.sql, options).await?;
+            let total = match plan.known_total() {
+                Some(total) => total,
+                None => self.count_rows(query, &options.params, page.count_mode).await?,
+            };
+            return Ok(plan.finish(result, query, page, total));
+        }
 
-        let total_rows: i64 = sqlx::query_scalar(&count_query)
-            .fetch_one(&pool)
-            .await
-            .unwrap_or(0);
+        // Un cursor solo se reutiliza para la misma query que lo abrió
+        let reused = match &page.cursor {
+            Some(name) => {
+                let mut cursors = self.cursors.lock().await;
+                match cursors.get(name) {
+                    Some(c) if c.query == Self::cursor_key(query, &options.params) => cursors.remove(name).map(|c| (name.clone(), c)),
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        let (name, mut cursor) = match reused {
+            Some(existing) => existing,
+            None => self.declare_cursor(query, page.count_mode, options).await?,
+        };
 
-        let mut result = self.execute_query(&paginated_query).await?;
-        let pagination = PaginationInfo::new(page, page_size).with_total(total_rows as u64);
-        result = result.with_pagination(pagination);
+        let start = Instant::now();
+        let rows = {
+            let _running = self.running.register(options.query_id, cursor.pid);
+            sqlx::query(&format!("MOVE ABSOLUTE {} IN {}", page.offset(), name))
+                .execute(&mut *cursor.tx)
+                .await
+                .map_err(Self::map_query_error)?;
+            sqlx::query(&format!("FETCH FORWARD {} FROM {}", page.page_size as u64 + 1, name))
+                .fetch_all(&mut *cursor.tx)
+                .await
+                .map_err(Self::map_query_error)?
+        };
+        let execution_time = start.elapsed().as_millis() as u64;
 
-        Ok(result)
+        let columns = rows.first().map(|r| Self::column_infos(r.columns())).unwrap_or_default();
+        let data: Vec<Vec<CellValue>> = rows
+            .iter()
+            .map(|row| {
+                (0..row.columns().len())
+                    .map(|idx| Self::map_pg_value(row, idx))
+                    .collect()
+            })
+            .collect();
+        let mut result = QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time);
+        let has_next = pagination::split_extra_row(&mut result, page.page_size);
+        let total = cursor.total;
+
+        // Sin más filas el cursor ya no sirve: se cierra con su transacción
+        let next_cursor = if has_next {
+            cursor.last_used = Instant::now();
+            self.keep_cursor(name.clone(), cursor).await;
+            Some(name)
+        } else {
+            cursor.tx.commit().await.ok();
+            None
+        };
+
+        Ok(result.with_pagination(pagination::page_info(page, has_next, total, next_cursor)))
+    }
+
+    async fn close_pagination_cursor(&self, cursor: &str) -> Result<(), DomainError> {
+        let removed = self.cursors.lock().await.remove(cursor);
+        if let Some(c) = removed {
+            c.tx.rollback().await.map_err(|e| DomainError::query(e.to_string()))?;
+        }
+        Ok(())
     }
 
     async fn insert_row(
@@ -240,7 +969,7 @@ impl SqlDriver for PostgresDriver {
              let row = sqlx::query(&sql)
                 .fetch_one(&mut *tx)
                 .await
-                .map_err(|e| DomainError::query(e.to_string()))?;
+                .map_err(Self::map_query_error)?;
                 
              tx.commit().await.map_err(|e| DomainError::query(e.to_string()))?;
              
@@ -261,68 +990,38 @@ impl SqlDriver for PostgresDriver {
             return Ok(QueryResult::new(sql, columns, data));
         }
 
-        // 3. Construir query dinámica
+        // 3. Convertir cada valor al tipo de su columna antes de tocar la base de datos
+        let mut bound = Vec::with_capacity(valid_entries.len());
+        for (_, val, info) in &valid_entries {
+            bound.push(BindValue::from_json(val, info, SqlDialect::Postgres)?);
+        }
+
+        // 4. Construir query dinámica con el cast al tipo de cada columna
         let cols_sql = valid_entries.iter().map(|(k,_,_)| format!("\"{}\"", k)).collect::<Vec<_>>().join(", ");
-        let vals_sql = (1..=valid_entries.len()).map(|i| format!("${}", i)).collect::<Vec<_>>().join(", ");
+        let vals_sql = valid_entries
+            .iter()
+            .enumerate()
+            .map(|(i, (_, _, info))| Self::typed_placeholder(i + 1, info))
+            .collect::<Vec<_>>()
+            .join(", ");
         let sql = format!("INSERT INTO \"{}\".\"{}\" ({}) VALUES ({}) RETURNING *", schema_name, table, cols_sql, vals_sql);
 
-        // 4. Ejecutar en transacción
+        // 5. Ejecutar en transacción
         let mut tx = pool.begin().await.map_err(|e| DomainError::query(e.to_string()))?;
-        
+
         let mut query_builder = sqlx::query(&sql);
-        
-        for (_, val, info) in &valid_entries {
-            let type_name = info.data_type.to_lowercase();
-            
-            // Lógica de binding según tipo
-            if type_name.contains("bool") {
-                 query_builder = query_builder.bind(val.as_bool());
-            } else if type_name.contains("int") || type_name.contains("serial") {
-                 if let Some(i) = val.as_i64() {
-                     if type_name.contains("int2") || type_name.contains("smallint") {
-                         query_builder = query_builder.bind(i as i16);
-                     } else if type_name.contains("int4") || type_name.contains("integer") {
-                         query_builder = query_builder.bind(i as i32);
-                     } else {
-                         query_builder = query_builder.bind(i);
-                     }
-                 } else {
-                     query_builder = query_builder.bind(Option::<i64>::None);
-                 }
-            } else if type_name.contains("float") || type_name.contains("real") || type_name.contains("double") {
-                 query_builder = query_builder.bind(val.as_f64());
-            } else if type_name.contains("json") {
-                 query_builder = query_builder.bind(val);
-            } else if type_name.contains("uuid") {
-                 if let Some(s) = val.as_str() {
-                     if let Ok(u) = uuid::Uuid::parse_str(s) {
-                         query_builder = query_builder.bind(u);
-                     } else {
-                         query_builder = query_builder.bind(Option::<uuid::Uuid>::None);
-                     }
-                 } else {
-                     query_builder = query_builder.bind(Option::<uuid::Uuid>::None);
-                 }
-            } else {
-                 // Text, Varchar, Date, Time, etc. - Bind as string
-                 if val.is_null() {
-                     query_builder = query_builder.bind(Option::<String>::None);
-                 } else if let Some(s) = val.as_str() {
-                     query_builder = query_builder.bind(s);
-                 } else {
-                     query_builder = query_builder.bind(val.to_string());
-                 }
-            }
+        for value in bound {
+            query_builder = Self::bind_value(query_builder, value);
         }
 
         let row = query_builder
             .fetch_one(&mut *tx)
             .await
-            .map_err(|e| DomainError::query(e.to_string()))?;
+            .map_err(Self::map_query_error)?;
 
         tx.commit().await.map_err(|e| DomainError::query(e.to_string()))?;
 
-        // 5. Formatear resultado
+        // 6. Formatear resultado
         let columns: Vec<ColumnInfo> = row.columns().iter().map(|col| ColumnInfo {
             name: col.name().to_string(),
             data_type: col.type_info().name().to_string(),
@@ -339,19 +1038,78 @@ impl SqlDriver for PostgresDriver {
         Ok(QueryResult::new(sql, columns, data))
     }
 
-    async fn execute_statement(&self, statement: &str) -> Result<u64, DomainError> {
-        let pool = self.get_pool().await?;
+    /// Carga el lote con `COPY FROM STDIN`. COPY no admite `ON CONFLICT`, así que para saltar
+    /// o sobrescribir duplicados el lote pasa por una tabla temporal que desaparece con la
+    /// transacción de la importación
+    async fn import_rows(&self, target: &ImportTarget, rows: &[Vec<BindValue>], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let table = target.qualified_table();
+        let columns = target.column_list();
+        let data = copy_rows(rows).into_bytes();
+        if target.on_duplicate == DuplicateMode::Abort {
+            return Self::copy_in(&mut conn, &format!("COPY {} ({}) FROM STDIN", table, columns), data).await;
+        }
 
-        let result = sqlx::query(statement)
-            .execute(&pool)
+        let stage = format!("\"import_{}\"", options.query_id.simple());
+        let prepare = [
+            format!("CREATE TEMP TABLE IF NOT EXISTS {} ON COMMIT DROP AS SELECT {} FROM {} WITH NO DATA", stage, columns, table),
+            format!("TRUNCATE {}", stage),
+        ];
+        for sql in &prepare {
+            sqlx::query(sql).execute(&mut *conn).await.map_err(Self::map_query_error)?;
+        }
+        Self::copy_in(&mut conn, &format!("COPY {} ({}) FROM STDIN", stage, columns), data).await?;
+        let insert = format!("INSERT INTO {} ({}) SELECT {} FROM {}{}", table, columns, columns, stage, target.conflict_clause()?);
+        sqlx::query(&insert)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| DomainError::query(e.to_string()))?;
+            .map(|r| r.rows_affected())
+            .map_err(Self::map_query_error)
+    }
+
+    fn row_edit_syntax<'a>(&'a self, target: &'a RowEditTarget) -> RowEditSyntax<'a> {
+        let table = match &target.schema {
+            Some(schema) => format!("{}.{}", self.quote_identifier(schema), self.quote_identifier(&target.table)),
+            None => self.quote_identifier(&target.table),
+        };
+        RowEditSyntax {
+            target,
+            table,
+            quote: Box::new(|name| self.quote_identifier(name)),
+            placeholder: Box::new(|index, _, column| Self::typed_placeholder(index, column)),
+        }
+    }
+
+    async fn execute_typed(&self, statement: &str, values: Vec<BindValue>, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let mut query = sqlx::query(statement);
+        for value in values {
+            query = Self::bind_value(query, value);
+        }
+        query
+            .execute(&mut *conn)
+            .await
+            .map(|r| r.rows_affected())
+            .map_err(Self::map_query_error)
+    }
+
+    async fn copy_from_stdin(&self, statement: &str, data: &[u8], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        Self::copy_in(&mut conn, statement, data.to_vec()).await
+    }
+
+    async fn execute_statement(&self, statement: &str, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+
+        let result = Self::bind_all(sqlx::query(statement), &options.params)
+            .execute(&mut *conn)
+            .await
+            .map_err(Self::map_query_error)?;
 
         Ok(result.rows_affected())
     }
 
-    async fn execute_multi_statement(&self, statements: Vec<String>) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
-        let pool = self.get_pool().await?;
+    async fn execute_multi_statement(&self, statements: Vec<String>, options: &ExecutionOptions) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
         let mut results = Vec::new();
 
         for (idx, statement) in statements.iter().enumerate() {
@@ -362,60 +1120,116 @@ impl SqlDriver for PostgresDriver {
                 continue;
             }
 
-            // Detectar si es SELECT o statement de modificación
-            let is_select = sql.to_uppercase().trim_start().starts_with("SELECT") ||
-                           sql.to_uppercase().trim_start().starts_with("WITH");
-
-            let result = if is_select {
-                match self.execute_query(sql).await {
-                    Ok(query_result) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: true,
-                        affected_rows: None,
-                        result: Some(query_result),
-                        error: None,
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: false,
-                        affected_rows: None,
-                        result: None,
-                        error: Some(e.to_string()),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                }
-            } else {
-                match sqlx::query(sql).execute(&pool).await {
-                    Ok(exec_result) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: true,
-                        affected_rows: Some(exec_result.rows_affected()),
-                        result: None,
-                        error: None,
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: false,
-                        affected_rows: None,
-                        result: None,
-                        error: Some(e.to_string()),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                }
-            };
-
-            results.push(result);
+            let outcome = self.run_statement(sql, options).await;
+            results.push(crate::domain::StatementResult::from_outcome(
+                idx,
+                sql,
+                outcome,
+                start.elapsed().as_millis() as u64,
+            ));
         }
 
         Ok(results)
     }
 
+    fn supports_transactions(&self) -> bool {
+        true
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.session.is_active().await
+    }
+
+    async fn begin_transaction(&self, options: &TransactionOptions) -> Result<(), DomainError> {
+        if self.session.is_active().await {
+            return Err(DomainError::validation("A transaction is already open"));
+        }
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.checkout(&pool).await?;
+
+        let mut sql = String::from("BEGIN");
+        if let Some(level) = options.isolation_level {
+            sql.push_str(" ISOLATION LEVEL ");
+            sql.push_str(level.as_sql());
+        }
+        sql.push_str(if options.read_only { " READ ONLY" } else { " READ WRITE" });
+        sqlx::query(&sql)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        self.session.pin(conn).await
+    }
+
+    async fn commit_transaction(&self) -> Result<(), DomainError> {
+        let mut conn = self.session.take().await?;
+        sqlx::query("COMMIT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(format!("Failed to commit transaction: {}", e)))?;
+        Ok(())
+    }
+
+    async fn rollback_transaction(&self) -> Result<(), DomainError> {
+        let mut conn = self.session.take().await?;
+        // El ROLLBACK deshace también el statement_timeout fijado dentro de la transacción
+        conn.backend_mut().timeout = None;
+        sqlx::query("ROLLBACK")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))?;
+        Ok(())
+    }
+
+    async fn create_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.session_execute(&format!("SAVEPOINT \"{}\"", name.replace('"', "\"\""))).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        if let Some(mut conn) = self.session.pinned().await {
+            conn.backend_mut().timeout = None;
+        }
+        self.session_execute(&format!("ROLLBACK TO SAVEPOINT \"{}\"", name.replace('"', "\"\""))).await
+    }
+
+    async fn explain(&self, sql: &str, analyze: bool) -> Result<QueryPlan, DomainError> {
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.acquire(&pool).await?;
+        let options = if analyze { "ANALYZE, BUFFERS, FORMAT JSON" } else { "FORMAT JSON" };
+        let explain = format!("EXPLAIN ({}) {}", options, pagination::trim_statement(sql));
+
+        let output: serde_json::Value = if analyze {
+            // ANALYZE ejecuta la sentencia: se hace en un ámbito que siempre se deshace
+            let (open, close) = conn.discard_scope();
+            sqlx::query(open).execute(&mut *conn).await.map_err(Self::map_query_error)?;
+            let output = sqlx::query_scalar(&explain).fetch_one(&mut *conn).await;
+            for statement in close {
+                sqlx::query(statement)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| DomainError::query(e.to_string()))?;
+            }
+            output
+        } else {
+            sqlx::query_scalar(&explain).fetch_one(&mut *conn).await
+        }
+        .map_err(Self::map_query_error)?;
+
+        let entry = output.get(0).cloned().unwrap_or_default();
+        let root = entry
+            .get("Plan")
+            .map(Self::plan_node)
+            .ok_or_else(|| DomainError::query("EXPLAIN returned no plan"))?;
+        Ok(QueryPlan {
+            query: sql.to_string(),
+            analyzed: analyze,
+            root,
+            planning_time_ms: entry.get("Planning Time").and_then(|v| v.as_f64()),
+            execution_time_ms: entry.get("Execution Time").and_then(|v| v.as_f64()),
+            raw: serde_json::to_string_pretty(&output).unwrap_or_default(),
+        })
+    }
+
     async fn execute_in_transaction(&self, statement: &str) -> Result<crate::domain::TransactionResult, DomainError> {
         let pool = self.get_pool().await?;
         let start = Instant::now();
@@ -445,7 +1259,7 @@ impl SqlDriver for PostgresDriver {
                     .await
                     .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))?;
                 
-                Err(DomainError::query(e.to_string()))
+                Err(Self::map_query_error(e))
             }
         }
     }
@@ -531,6 +1345,7 @@ impl SqlDriver for PostgresDriver {
                 row_count: None,
                 size_bytes: None,
                 comment: None,
+                storage: None,
             });
         }
 
@@ -628,6 +1443,7 @@ impl SqlDriver for PostgresDriver {
             row_count,
             size_bytes: None,
             comment: None,
+            storage: None,
         })
     }
 
@@ -912,9 +1728,175 @@ impl SqlDriver for PostgresDriver {
             .collect())
     }
 
-    async fn cancel_query(&self) -> Result<(), DomainError> {
-        // PostgreSQL soporta pg_cancel_backend, pero requiere el PID
-        // Por ahora retornamos Ok
+    async fn get_object_ddl(&self, object: &DdlObject, options: &DdlOptions) -> Result<String, DomainError> {
+        let pool = self.get_pool().await?;
+        let dialect = SqlDialect::Postgres;
+        let schema = object.schema.as_deref().unwrap_or("public");
+        let target = qualified_identifier(Some(schema), &object.name, dialect);
+
+        match object.object_type {
+            DdlObjectType::Table => {
+                let table = self.get_table_info(&object.name, Some(schema)).await?;
+                if table.columns.is_empty() {
+                    return Err(DomainError::not_found(format!("Table {}.{}", schema, object.name)));
+                }
+                let mut sections = vec![create_table_sql(&table, Some(schema), dialect, false)];
+                sections.push(Self::relation_extras(&pool, &target, "TABLE", options).await?);
+                sections.push(
+                    merged_foreign_keys(&table.foreign_keys)
+                        .iter()
+                        .map(|fk| foreign_key_sql(&table, fk, Some(schema), dialect))
+                        .collect(),
+                );
+
+                // pg_get_indexdef conserva expresiones, condiciones y opciones que IndexInfo no recoge
+                let index_rows = sqlx::query(
+                    "SELECT i.relname AS index_name, pg_get_indexdef(i.oid) AS definition
+                     FROM pg_index x
+                     JOIN pg_class i ON i.oid = x.indexrelid
+                     WHERE x.indrelid = $1::regclass"
+                )
+                .bind(&target)
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+                let definitions: HashMap<String, String> = index_rows
+                    .iter()
+                    .map(|r| (r.get("index_name"), r.get("definition")))
+                    .collect();
+                sections.push(
+                    standalone_indexes(&table, dialect)
+                        .iter()
+                        .map(|index| match definitions.get(&index.name) {
+                            Some(definition) => format!("{};\n", definition),
+                            None => index_sql(&table, index, Some(schema), dialect),
+                        })
+                        .collect(),
+                );
+                sections.push(table.triggers.iter().filter_map(|t| trigger_sql(t, Some(schema), dialect)).collect());
+
+                Ok(sections.into_iter().filter(|s| !s.is_empty()).collect::<Vec<_>>().join("\n"))
+            }
+            DdlObjectType::View => {
+                let row = sqlx::query(
+                    "SELECT c.relkind::text AS kind, pg_get_viewdef(c.oid, true) AS definition
+                     FROM pg_class c
+                     JOIN pg_namespace n ON c.relnamespace = n.oid
+                     WHERE n.nspname = $1 AND c.relname = $2 AND c.relkind IN ('v', 'm')"
+                )
+                .bind(schema)
+                .bind(&object.name)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?
+                .ok_or_else(|| DomainError::not_found(format!("View {}.{}", schema, object.name)))?;
+
+                let is_materialized = row.get::<String, _>("kind") == "m";
+                let view = ViewInfo {
+                    name: object.name.clone(),
+                    schema: Some(schema.to_string()),
+                    columns: vec![],
+                    definition: row.get("definition"),
+                    is_materialized,
+                };
+                let mut sql = view_sql(&view, Some(schema), dialect).unwrap_or_default();
+                let kind = if is_materialized { "MATERIALIZED VIEW" } else { "VIEW" };
+                sql.push_str(&Self::relation_extras(&pool, &target, kind, options).await?);
+                Ok(sql)
+            }
+            DdlObjectType::Index => {
+                let definition: String = sqlx::query_scalar(
+                    "SELECT pg_get_indexdef(i.oid)
+                     FROM pg_class i
+                     JOIN pg_namespace n ON i.relnamespace = n.oid
+                     WHERE n.nspname = $1 AND i.relname = $2 AND i.relkind IN ('i', 'I')"
+                )
+                .bind(schema)
+                .bind(&object.name)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?
+                .ok_or_else(|| DomainError::not_found(format!("Index {}.{}", schema, object.name)))?;
+
+                let mut sql = format!("{};\n", definition);
+                sql.push_str(&Self::relation_extras(&pool, &target, "INDEX", options).await?);
+                Ok(sql)
+            }
+            DdlObjectType::Function => Self::function_ddl(&pool, schema, &object.name, options).await,
+            DdlObjectType::Trigger => {
+                let rows = sqlx::query(
+                    "SELECT c.relname AS table_name, pg_get_triggerdef(t.oid) AS definition,
+                            obj_description(t.oid, 'pg_trigger') AS comment
+                     FROM pg_trigger t
+                     JOIN pg_class c ON t.tgrelid = c.oid
+                     JOIN pg_namespace n ON c.relnamespace = n.oid
+                     WHERE n.nspname = $1 AND t.tgname = $2 AND ($3::text IS NULL OR c.relname = $3)
+                       AND NOT t.tgisinternal"
+                )
+                .bind(schema)
+                .bind(&object.name)
+                .bind(object.table.as_deref())
+                .fetch_all(&pool)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+
+                let row = match rows.as_slice() {
+                    [row] => row,
+                    [] => return Err(DomainError::not_found(format!("Trigger {}.{}", schema, object.name))),
+                    _ => {
+                        return Err(DomainError::validation(format!(
+                            "Trigger {} exists on several tables; specify the table",
+                            object.name
+                        )))
+                    }
+                };
+                let mut sql = format!("{};\n", row.get::<String, _>("definition"));
+                if options.include_comments {
+                    if let Some(comment) = row.get::<Option<String>, _>("comment") {
+                        sql.push_str(&format!(
+                            "COMMENT ON TRIGGER {} ON {} IS {};\n",
+                            dialect.quote_identifier(&object.name),
+                            qualified_identifier(Some(schema), &row.get::<String, _>("table_name"), dialect),
+                            Self::text_literal(&comment)
+                        ));
+                    }
+                }
+                Ok(sql)
+            }
+            DdlObjectType::Sequence => {
+                let sequence = self
+                    .list_sequences(Some(schema))
+                    .await?
+                    .into_iter()
+                    .find(|s| s.name == object.name)
+                    .ok_or_else(|| DomainError::not_found(format!("Sequence {}.{}", schema, object.name)))?;
+
+                let mut sql = sequence_sql(&sequence, Some(schema), dialect);
+                sql.push_str(&Self::relation_extras(&pool, &target, "SEQUENCE", options).await?);
+                Ok(sql)
+            }
+        }
+    }
+
+    async fn cancel_query(&self, query_id: Uuid) -> Result<(), DomainError> {
+        let pid = match self.running.get(&query_id) {
+            Some(pid) => pid,
+            None => return Ok(()),
+        };
+
+        // Conexión lateral: el pool puede estar agotado por las propias queries en ejecución
+        let pool = self.get_pool().await?;
+        let mut conn = PgConnection::connect_with(&pool.connect_options())
+            .await
+            .map_err(|e| DomainError::connection(e.to_string()))?;
+
+        sqlx::query("SELECT pg_cancel_backend($1)")
+            .bind(pid)
+            .execute(&mut conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        conn.close().await.ok();
         Ok(())
     }
 
@@ -978,4 +1960,69 @@ impl SqlDriver for PostgresDriver {
 
         Ok(row.get::<String, _>(0))
     }
+
+    async fn tls_status(&self) -> Result<TlsStatus, DomainError> {
+        let pool = self.get_pool().await?;
+
+        let row = sqlx::query("SELECT ssl, version, cipher FROM pg_stat_ssl WHERE pid = pg_backend_pid()")
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        Ok(match row {
+            Some(row) if row.get::<bool, _>(0) => TlsStatus {
+                enabled: true,
+                version: row.get(1),
+                cipher: row.get(2),
+            },
+            _ => TlsStatus::plain(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric(words: &[i16]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn reads_binary_numerics_exactly() {
+        // ndigits, weight, sign, dscale y los dígitos en base 10000
+        assert_eq!(numeric_text(&numeric(&[2, 0, 0, 2, 12, 5000])).as_deref(), Some("12.50"));
+        assert_eq!(numeric_text(&numeric(&[3, 1, 0x4000, 2, 1, 2345, 6700])).as_deref(), Some("-12345.67"));
+        assert_eq!(numeric_text(&numeric(&[1, -2, 0, 6, 1200])).as_deref(), Some("0.000012"));
+        assert_eq!(numeric_text(&numeric(&[1, 2, 0, 0, 7])).as_deref(), Some("700000000"));
+        assert_eq!(numeric_text(&numeric(&[0, 0, 0, 3])).as_deref(), Some("0.000"));
+        assert_eq!(
+            numeric_text(&numeric(&[5, 4, 0, 0, 1234, 5678, 9012, 3456, 7890])).as_deref(),
+            Some("12345678901234567890"),
+        );
+        assert_eq!(numeric_text(&numeric(&[0, 0, 0xC000u16 as i16, 0])).as_deref(), Some("NaN"));
+        assert_eq!(numeric_text(&numeric(&[2, 0, 0])), None);
+    }
+
+    #[test]
+    fn row_edits_cast_each_marker_to_its_column_type() {
+        let column = |name: &str, native_type: &str| ColumnSchema {
+            name: name.to_string(),
+            data_type: String::new(),
+            native_type: native_type.to_string(),
+            nullable: false,
+            default_value: None,
+            is_primary_key: false,
+            is_unique: false,
+            is_auto_increment: false,
+            max_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            comment: None,
+            ordinal_position: 0,
+        };
+        assert_eq!(PostgresDriver::typed_placeholder(1, &column("price", "numeric")), "$1::\"numeric\"");
+        assert_eq!(PostgresDriver::typed_placeholder(2, &column("mood", "my\"mood")), "$2::\"my\"\"mood\"");
+        assert_eq!(PostgresDriver::typed_placeholder(3, &column("raw", "")), "$3");
+    }
 }
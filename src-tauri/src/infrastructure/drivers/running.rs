@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Registro de queries en ejecución de un driver
+/// Cada motor guarda el handle que necesita para cancelar la query en el servidor
+/// (PID de backend en PostgreSQL, id de conexión en MySQL, flag de interrupción en SQLite)
+pub struct RunningQueries<H: Clone> {
+    inner: Arc<Mutex<HashMap<Uuid, H>>>,
+}
+
+impl<H: Clone> RunningQueries<H> {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Registra una ejecución; se elimina del registro al soltar el guard
+    pub fn register(&self, id: Uuid, handle: H) -> RunningQueryGuard<H> {
+        self.inner.lock().unwrap().insert(id, handle);
+        RunningQueryGuard {
+            inner: self.inner.clone(),
+            id,
+        }
+    }
+
+    /// Handle de una ejecución activa
+    pub fn get(&self, id: &Uuid) -> Option<H> {
+        self.inner.lock().unwrap().get(id).cloned()
+    }
+}
+
+impl<H: Clone> Default for RunningQueries<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mantiene una ejecución registrada mientras está vivo
+pub struct RunningQueryGuard<H> {
+    inner: Arc<Mutex<HashMap<Uuid, H>>>,
+    id: Uuid,
+}
+
+impl<H> Drop for RunningQueryGuard<H> {
+    fn drop(&mut self) {
+        if let Ok(mut map) = self.inner.lock() {
+            map.remove(&self.id);
+        }
+    }
+}
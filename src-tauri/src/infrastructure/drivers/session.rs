@@ -0,0 +1,374 @@
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection, Database, Executor, Pool};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::domain::DomainError;
+
+/// Conexiones con estado de sesión conocido que se reservan fuera del pool.
+/// Por debajo del tamaño del pool para no dejar sin conexiones al resto de usos.
+const MAX_WARM_CONNECTIONS: usize = 2;
+
+/// Tiempo que una conexión reservada puede quedarse sin uso antes de volver al pool
+const WARM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Estado de sesión ya aplicado en una conexión física, para no repetir viajes al servidor
+#[derive(Debug, Clone, Copy)]
+pub struct BackendState<Id> {
+    /// Identificador del backend (PID, CONNECTION_ID), una vez consultado
+    pub id: Option<Id>,
+    /// Límite de ejecución fijado en la sesión; None si no se conoce
+    pub timeout: Option<Option<Duration>>,
+}
+
+impl<Id> Default for BackendState<Id> {
+    fn default() -> Self {
+        Self { id: None, timeout: None }
+    }
+}
+
+impl<Id> BackendState<Id> {
+    /// La sesión lleva un límite de ejecución propio que no debe heredar otro uso del pool
+    fn has_limit(&self) -> bool {
+        matches!(self.timeout, Some(Some(_)))
+    }
+}
+
+type ResetFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Sentencia que devuelve el límite de ejecución de la sesión a su valor por defecto
+struct SessionReset<DB: Database> {
+    sql: &'static str,
+    run: fn(PoolConnection<DB>, &'static str) -> ResetFuture,
+}
+
+impl<DB: Database> Clone for SessionReset<DB> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<DB: Database> Copy for SessionReset<DB> {}
+
+/// Ejecuta el reset y deja que la conexión vuelva al pool; si falla, la cierra
+fn run_reset<DB: Database>(mut conn: PoolConnection<DB>, sql: &'static str) -> ResetFuture
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    Box::pin(async move {
+        if conn.execute(sql).await.is_err() {
+            conn.close().await.ok();
+        }
+    })
+}
+
+/// Devuelve al pool una conexión que deja de estar reservada. Si lleva un límite de
+/// ejecución, antes hay que restablecerlo: la tarea devuelta lo hace
+fn release<DB: Database, Id>(
+    conn: PoolConnection<DB>,
+    backend: &BackendState<Id>,
+    reset: Option<SessionReset<DB>>,
+) -> Option<ResetFuture> {
+    match reset {
+        Some(reset) if backend.has_limit() => Some((reset.run)(conn, reset.sql)),
+        _ => None,
+    }
+}
+
+/// Conexiones reservadas de la conexión lógica actual.
+/// La generación cambia al reconectar para no reservar conexiones de un pool ya cerrado.
+struct WarmConnections<DB: Database, Id> {
+    generation: u64,
+    idle: Vec<(PoolConnection<DB>, BackendState<Id>, Instant)>,
+    reset: Option<SessionReset<DB>>,
+}
+
+/// Conexión del pool fijada a la sesión mientras dura una transacción manual.
+/// Cada ejecución la toma en exclusiva; si otra la está usando, espera su turno.
+pub struct PinnedSession<DB: Database, Id: Send + 'static = ()> {
+    slot: Arc<Mutex<Option<TrackedConnection<DB, Id>>>>,
+    warm: Arc<StdMutex<WarmConnections<DB, Id>>>,
+}
+
+impl<DB: Database, Id: Send + 'static> PinnedSession<DB, Id> {
+    pub fn new() -> Self {
+        Self {
+            slot: Arc::new(Mutex::new(None)),
+            warm: Arc::new(StdMutex::new(WarmConnections { generation: 0, idle: Vec::new(), reset: None })),
+        }
+    }
+
+    /// Fija una conexión con la transacción ya abierta
+    pub async fn pin(&self, conn: TrackedConnection<DB, Id>) -> Result<(), DomainError> {
+        let mut slot = self.slot.lock().await;
+        if slot.is_some() {
+            return Err(DomainError::validation("A transaction is already open"));
+        }
+        *slot = Some(conn);
+        Ok(())
+    }
+
+    /// Suelta la conexión fijada para cerrar la transacción
+    pub async fn take(&self) -> Result<TrackedConnection<DB, Id>, DomainError> {
+        self.slot
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| DomainError::validation("No transaction is open"))
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.slot.lock().await.is_some()
+    }
+
+    /// Conexión fijada, si hay una transacción abierta
+    pub async fn pinned(&self) -> Option<SessionConnection<DB, Id>> {
+        let slot = self.slot.clone().lock_owned().await;
+        slot.is_some().then_some(SessionConnection::Pinned(slot))
+    }
+
+    /// Conexión para una ejecución: la fijada si hay transacción, si no una del pool
+    pub async fn acquire(&self, pool: &Pool<DB>) -> Result<SessionConnection<DB, Id>, DomainError> {
+        if let Some(conn) = self.pinned().await {
+            return Ok(conn);
+        }
+        self.checkout(pool).await.map(SessionConnection::Pooled)
+    }
+
+    /// Conexión propia, ajena a la transacción manual: una reservada con su estado
+    /// de sesión si la hay, si no una nueva del pool
+    pub async fn checkout(&self, pool: &Pool<DB>) -> Result<TrackedConnection<DB, Id>, DomainError> {
+        let (warm, generation) = {
+            let mut warm = self.warm.lock().unwrap();
+            let (expired, idle): (Vec<_>, Vec<_>) = std::mem::take(&mut warm.idle)
+                .into_iter()
+                .partition(|(_, _, parked)| parked.elapsed() >= WARM_IDLE_TIMEOUT);
+            warm.idle = idle;
+            for (conn, backend, _) in expired {
+                if let Some(reset) = release(conn, &backend, warm.reset) {
+                    tokio::spawn(reset);
+                }
+            }
+            (warm.idle.pop(), warm.generation)
+        };
+
+        let (conn, backend) = match warm {
+            Some((conn, backend, _)) => (conn, backend),
+            None => {
+                let conn = pool.acquire()
+                    .await
+                    .map_err(|e| DomainError::connection(e.to_string()))?;
+                (conn, BackendState::default())
+            }
+        };
+
+        Ok(TrackedConnection {
+            conn: Some(conn),
+            backend,
+            warm: self.warm.clone(),
+            generation,
+        })
+    }
+
+    /// Devuelve al pool las conexiones reservadas; hay que hacerlo antes de cerrarlo
+    /// o al cambiar de pool, porque el cierre espera a que vuelvan todas
+    pub fn release_warm(&self) {
+        let mut warm = self.warm.lock().unwrap();
+        warm.generation += 1;
+        warm.idle.clear();
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> PinnedSession<DB, Id>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    /// Sesión cuyas conexiones, al volver al pool con un límite de ejecución fijado,
+    /// ejecutan antes `reset` para que no lo hereden otros usos del pool
+    pub fn with_reset(reset: &'static str) -> Self {
+        let session = Self::new();
+        session.warm.lock().unwrap().reset = Some(SessionReset { sql: reset, run: run_reset::<DB> });
+        session
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> Default for PinnedSession<DB, Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Conexión prestada con el estado de sesión que ya se le aplicó.
+/// Al soltarla, si ese estado es conocido y sigue viva, queda reservada para la siguiente ejecución.
+/// Si no cabe entre las reservadas vuelve al pool, con el límite de ejecución ya restablecido.
+pub struct TrackedConnection<DB: Database, Id: Send + 'static> {
+    conn: Option<PoolConnection<DB>>,
+    backend: BackendState<Id>,
+    warm: Arc<StdMutex<WarmConnections<DB, Id>>>,
+    generation: u64,
+}
+
+impl<DB: Database, Id: Send + 'static> TrackedConnection<DB, Id> {
+    pub fn backend_mut(&mut self) -> &mut BackendState<Id> {
+        &mut self.backend
+    }
+
+    /// Cierra la conexión en vez de devolverla al pool
+    pub async fn close(mut self) -> Result<(), sqlx::Error> {
+        match self.conn.take() {
+            Some(conn) => conn.close().await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> Deref for TrackedConnection<DB, Id> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &DB::Connection {
+        self.conn.as_ref().expect("tracked connection")
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> DerefMut for TrackedConnection<DB, Id> {
+    fn deref_mut(&mut self) -> &mut DB::Connection {
+        self.conn.as_mut().expect("tracked connection")
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> Drop for TrackedConnection<DB, Id> {
+    fn drop(&mut self) {
+        let Some(mut conn) = self.conn.take() else { return };
+        if self.backend.id.is_none() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else { return };
+
+        let warm = self.warm.clone();
+        let backend = std::mem::take(&mut self.backend);
+        let generation = self.generation;
+        // Igual que al devolverla al pool: si la ejecución se cortó a medias o el
+        // servidor cayó, el ping falla y la conexión se descarta
+        runtime.spawn(async move {
+            if conn.ping().await.is_err() {
+                return;
+            }
+            let reset = {
+                let mut warm = warm.lock().unwrap();
+                if warm.generation == generation && warm.idle.len() < MAX_WARM_CONNECTIONS {
+                    warm.idle.push((conn, backend, Instant::now()));
+                    return;
+                }
+                release(conn, &backend, warm.reset)
+            };
+            if let Some(reset) = reset {
+                reset.await;
+            }
+        });
+    }
+}
+
+/// Conexión en uso por una ejecución
+pub enum SessionConnection<DB: Database, Id: Send + 'static = ()> {
+    Pooled(TrackedConnection<DB, Id>),
+    Pinned(OwnedMutexGuard<Option<TrackedConnection<DB, Id>>>),
+}
+
+impl<DB: Database, Id: Send + 'static> SessionConnection<DB, Id> {
+    /// Sentencias para envolver algo que debe deshacerse siempre (p. ej. EXPLAIN ANALYZE):
+    /// una transacción propia o, si ya hay una transacción manual, un savepoint
+    pub fn discard_scope(&self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            SessionConnection::Pooled(_) => ("BEGIN", &["ROLLBACK"]),
+            SessionConnection::Pinned(_) => (
+                "SAVEPOINT qx_discard",
+                &["ROLLBACK TO SAVEPOINT qx_discard", "RELEASE SAVEPOINT qx_discard"],
+            ),
+        }
+    }
+
+    pub fn backend_mut(&mut self) -> &mut BackendState<Id> {
+        match self {
+            SessionConnection::Pooled(conn) => conn.backend_mut(),
+            SessionConnection::Pinned(slot) => slot.as_mut().expect("pinned connection").backend_mut(),
+        }
+    }
+
+    /// Cierra la conexión en vez de devolverla al pool.
+    /// La fijada se conserva: cerrarla perdería la transacción.
+    pub async fn close(self) -> Result<(), sqlx::Error> {
+        match self {
+            SessionConnection::Pooled(conn) => conn.close().await,
+            SessionConnection::Pinned(_) => Ok(()),
+        }
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> Deref for SessionConnection<DB, Id> {
+    type Target = DB::Connection;
+
+    fn deref(&self) -> &DB::Connection {
+        match self {
+            SessionConnection::Pooled(conn) => conn,
+            SessionConnection::Pinned(slot) => slot.as_ref().expect("pinned connection"),
+        }
+    }
+}
+
+impl<DB: Database, Id: Send + 'static> DerefMut for SessionConnection<DB, Id> {
+    fn deref_mut(&mut self) -> &mut DB::Connection {
+        match self {
+            SessionConnection::Pooled(conn) => conn,
+            SessionConnection::Pinned(slot) => slot.as_mut().expect("pinned connection"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn busy_timeout(conn: &mut sqlx::SqliteConnection) -> i64 {
+        sqlx::query_scalar("PRAGMA busy_timeout").fetch_one(conn).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn resets_the_session_limit_before_returning_to_the_pool() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        let session = PinnedSession::<sqlx::Sqlite, i32>::with_reset("PRAGMA busy_timeout = 5000");
+
+        let mut conn = session.checkout(&pool).await.unwrap();
+        sqlx::query("PRAGMA busy_timeout = 10").execute(&mut *conn).await.unwrap();
+        *conn.backend_mut() = BackendState { id: Some(1), timeout: Some(Some(Duration::from_millis(10))) };
+        // Con la reserva invalidada, la conexión vuelve al pool en vez de quedar reservada
+        session.release_warm();
+        drop(conn);
+
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(busy_timeout(&mut conn).await, 5000);
+    }
+
+    #[tokio::test]
+    async fn keeps_the_session_limit_while_the_connection_stays_reserved() {
+        let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        let session = PinnedSession::<sqlx::Sqlite, i32>::with_reset("PRAGMA busy_timeout = 5000");
+
+        let mut conn = session.checkout(&pool).await.unwrap();
+        sqlx::query("PRAGMA busy_timeout = 10").execute(&mut *conn).await.unwrap();
+        *conn.backend_mut() = BackendState { id: Some(1), timeout: Some(Some(Duration::from_millis(10))) };
+        drop(conn);
+
+        // La conexión queda reservada tras el ping, en una tarea aparte
+        while session.warm.lock().unwrap().idle.is_empty() {
+            tokio::task::yield_now().await;
+        }
+        let mut conn = session.checkout(&pool).await.unwrap();
+        assert_eq!(conn.backend.id, Some(1));
+        assert_eq!(busy_timeout(&mut conn).await, 10);
+    }
+}
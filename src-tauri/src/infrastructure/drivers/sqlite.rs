@@ -1,24 +1,50 @@
 use async_trait::async_trait;
-use sqlx::{sqlite::SqlitePoolOptions, SqlitePool, Row, Column, TypeInfo};
+use sqlx::{sqlite::SqlitePoolOptions, Column, Either, Executor, Row, SqlitePool, Statement, TypeInfo, ValueRef};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::StreamExt;
+use uuid::Uuid;
 
 use crate::domain::{
-    CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
-    ConstraintInfo, ConstraintType, DatabaseInfo, DomainError, FunctionInfo, IndexInfo, 
-    PaginationInfo, PrimaryKeyInfo, QueryResult, SchemaInfo, SequenceInfo, SqlDriver, 
-    TableInfo, TriggerEvent, TriggerInfo, TriggerTiming, ViewInfo,
+    BindValue, CellValue, ColumnInfo, ColumnSchema, CompletionContext, CompletionItem, CompletionKind,
+    ConstraintInfo, ConstraintType, CountMode, DatabaseInfo, DdlObject, DdlObjectType, DdlOptions, DomainError, ExecutionOptions, ForeignKeyAction, ForeignKeyInfo, FunctionInfo,
+    ImportTarget, IndexInfo, IsolationLevel, PageRequest, PlanNode, PrimaryKeyInfo, QueryPlan, QueryResult, QueryStreamSummary, RowBatch, SchemaInfo, SequenceInfo, SqlDialect, SqlDriver, StreamOptions, 
+    TableInfo, TlsStatus, TransactionOptions, TriggerEvent, TriggerInfo, TriggerTiming, ViewInfo,
 };
+use super::running::{RunningQueries, RunningQueryGuard};
+use super::session::{PinnedSession, SessionConnection};
+use super::stream::RowBatchWriter;
+use super::pagination::{self, KeysetPage, RowTotal, SimpleSelect};
+
+/// SQLITE_INTERRUPT: la ejecución fue interrumpida por el progress handler
+const SQLITE_INTERRUPT: &str = "9";
+
+/// SQLITE_READONLY: escritura sobre una base abierta en solo lectura (código primario;
+/// sqlx devuelve el extendido, que lo lleva en el byte bajo)
+const SQLITE_READONLY: i32 = 8;
+
+/// Instrucciones de la VM de SQLite entre cada comprobación de cancelación
+const PROGRESS_HANDLER_OPS: i32 = 1000;
+
+/// busy_timeout con el que sqlx abre las conexiones, en ms
+const DEFAULT_BUSY_TIMEOUT_MS: u128 = 5000;
 
 pub struct SqliteDriver {
     pool: Arc<RwLock<Option<SqlitePool>>>,
+    /// Flags de interrupción de las queries en ejecución
+    running: RunningQueries<Arc<AtomicBool>>,
+    /// Conexión de la transacción manual en curso
+    session: PinnedSession<sqlx::Sqlite>,
 }
 
 impl SqliteDriver {
     pub fn new() -> Self {
         Self {
             pool: Arc::new(RwLock::new(None)),
+            running: RunningQueries::new(),
+            session: PinnedSession::new(),
         }
     }
 
@@ -27,6 +53,301 @@ impl SqliteDriver {
         guard.clone().ok_or_else(|| DomainError::connection("Not connected"))
     }
 
+    /// Adquiere una conexión e instala un progress handler que interrumpe la
+    /// ejecución en cuanto se marca su flag (equivalente a sqlite3_interrupt) o se
+    /// agota el límite de la ejecución. La espera por bloqueos tampoco pasa de ese límite
+    async fn acquire_tracked(&self, options: &ExecutionOptions) -> Result<(SessionConnection<sqlx::Sqlite>, RunningQueryGuard<Arc<AtomicBool>>), DomainError> {
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.acquire(&pool).await?;
+
+        let busy_timeout = options.timeout
+            .map_or(DEFAULT_BUSY_TIMEOUT_MS, |t| t.as_millis().min(DEFAULT_BUSY_TIMEOUT_MS));
+        sqlx::query(&format!("PRAGMA busy_timeout = {}", busy_timeout))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+
+        let interrupted = Arc::new(AtomicBool::new(false));
+        {
+            // Referencia débil: al terminar la ejecución el registro suelta el flag
+            // y el handler deja de interrumpir las siguientes queries de esta conexión
+            let flag = Arc::downgrade(&interrupted);
+            let deadline = options.timeout.map(|t| Instant::now() + t);
+            let mut handle = conn.lock_handle()
+                .await
+                .map_err(|e| DomainError::connection(e.to_string()))?;
+            handle.set_progress_handler(PROGRESS_HANDLER_OPS, move || match flag.upgrade() {
+                Some(f) => !f.load(Ordering::Relaxed) && deadline.is_none_or(|d| Instant::now() < d),
+                None => true,
+            });
+        }
+
+        let guard = self.running.register(options.query_id, interrupted);
+        Ok((conn, guard))
+    }
+
+    /// Ejecuta una sentencia de control en la conexión de la transacción manual
+    async fn session_execute(&self, sql: &str) -> Result<(), DomainError> {
+        let mut conn = self.session
+            .pinned()
+            .await
+            .ok_or_else(|| DomainError::validation("No transaction is open"))?;
+        sqlx::query(sql)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Convierte errores de sqlx distinguiendo las queries interrumpidas y las escrituras
+    /// sobre una base abierta en solo lectura
+    fn map_query_error(e: sqlx::Error) -> DomainError {
+        if let Some(db_err) = e.as_database_error() {
+            if db_err.code().as_deref() == Some(SQLITE_INTERRUPT) {
+                return DomainError::Cancelled;
+            }
+            let code = db_err.code().and_then(|c| c.parse::<i32>().ok());
+            if code.is_some_and(|c| c & 0xff == SQLITE_READONLY) {
+                return DomainError::PermissionDenied { resource: db_err.message().to_string() };
+            }
+        }
+        DomainError::query(e.to_string())
+    }
+
+    /// Ejecuta una query con parámetros posicionales y construye el QueryResult
+    async fn fetch_result(&self, query: &str, params: &[serde_json::Value], options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let start = Instant::now();
+        // Primero los parámetros del usuario y después los que añade la paginación
+        let mut q = Self::bind_all(sqlx::query(query), &options.params);
+        for param in params {
+            q = Self::bind_json(q, param);
+        }
+        let rows = q.fetch_all(&mut *conn).await
+            .map_err(Self::map_query_error)?;
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        let columns: Vec<ColumnInfo> = if !rows.is_empty() {
+            Self::column_infos(rows[0].columns())
+        } else { vec![] };
+
+        let data: Vec<Vec<CellValue>> = rows.iter().map(|row| {
+            (0..row.columns().len()).map(|idx| Self::map_sqlite_value(row, idx)).collect()
+        }).collect();
+
+        Ok(QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time))
+    }
+
+    /// Nodo de `EXPLAIN QUERY PLAN` a partir de su texto ("SEARCH t USING INDEX idx (a=?)")
+    fn plan_node(detail: &str) -> PlanNode {
+        let mut words = detail.split_whitespace();
+        let kind = words.next().unwrap_or_default();
+        let mut node = PlanNode::new(kind);
+        if matches!(kind, "SCAN" | "SEARCH") {
+            // Versiones anteriores a 3.36 escriben "SCAN TABLE t"
+            node.relation = words.find(|w| *w != "TABLE").map(String::from);
+        }
+        node.detail = Some(detail.to_string());
+        node
+    }
+
+    fn column_infos(columns: &[sqlx::sqlite::SqliteColumn]) -> Vec<ColumnInfo> {
+        columns.iter().map(|col| ColumnInfo {
+            name: col.name().to_string(),
+            data_type: col.type_info().name().to_string(),
+            nullable: true,
+            is_primary_key: false,
+        }).collect()
+    }
+
+    /// Ejecuta una sentencia suelta. Se prepara antes para saber si produce un conjunto de
+    /// resultados aunque venga vacío (PRAGMA, `INSERT ... RETURNING`); si además modifica
+    /// datos se devuelven tanto las filas como las afectadas.
+    async fn run_statement(&self, sql: &str, options: &ExecutionOptions) -> Result<(Option<QueryResult>, Option<u64>), DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let start = Instant::now();
+        let described = (&mut *conn)
+            .prepare(sql)
+            .await
+            .ok()
+            .map(|statement| Self::column_infos(statement.columns()));
+
+        let mut rows = Vec::new();
+        let mut affected = 0;
+        {
+            let query = Self::bind_all(sqlx::query(sql), &options.params);
+            let mut stream = (&mut *conn).fetch_many(query);
+            while let Some(item) = stream.next().await {
+                match item.map_err(Self::map_query_error)? {
+                    Either::Left(done) => affected += done.rows_affected(),
+                    Either::Right(row) => rows.push(row),
+                }
+            }
+        }
+        let execution_time = start.elapsed().as_millis() as u64;
+
+        let returns_rows = !rows.is_empty()
+            || described.as_ref().map_or_else(|| pagination::may_return_rows(sql), |columns| !columns.is_empty());
+        if !returns_rows {
+            return Ok((None, Some(affected)));
+        }
+
+        let columns = match rows.first() {
+            Some(row) => Self::column_infos(row.columns()),
+            None => described.unwrap_or_default(),
+        };
+        let data: Vec<Vec<CellValue>> = rows.iter().map(|row| {
+            (0..row.columns().len()).map(|idx| Self::map_sqlite_value(row, idx)).collect()
+        }).collect();
+        let mut result = QueryResult::new(sql.to_string(), columns, data).with_execution_time(execution_time);
+        let affected = pagination::is_data_modifying(sql).then_some(affected);
+        if let Some(count) = affected {
+            result = result.with_affected_rows(count);
+        }
+        Ok((Some(result), affected))
+    }
+
+    fn bind_json<'q>(
+        q: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+        value: &serde_json::Value,
+    ) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+        match value {
+            serde_json::Value::Null => q.bind(None::<String>),
+            serde_json::Value::Bool(b) => q.bind(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => q.bind(i),
+                None => q.bind(n.as_f64()),
+            },
+            serde_json::Value::String(text) => q.bind(text.clone()),
+            other => q.bind(other.to_string()),
+        }
+    }
+
+    fn bind_all<'q>(
+        mut q: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+        params: &[CellValue],
+    ) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+        for param in params {
+            q = Self::bind_cell(q, param);
+        }
+        q
+    }
+
+    /// Enlaza un valor tipado; SQLite guarda fechas, UUIDs y JSON como texto
+    fn bind_cell<'q>(
+        q: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+        value: &CellValue,
+    ) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+        match value {
+            CellValue::Null => q.bind(None::<String>),
+            CellValue::Bool(b) => q.bind(*b),
+            CellValue::Int(i) => q.bind(*i),
+            CellValue::Float(f) => q.bind(*f),
+            CellValue::Bytes(b) => q.bind(b.clone()),
+            CellValue::Json(v) => q.bind(v.to_string()),
+            CellValue::Array(_) => q.bind(pagination::cell_to_json(value).to_string()),
+            CellValue::String(s)
+            | CellValue::Date(s)
+            | CellValue::Time(s)
+            | CellValue::DateTime(s)
+            | CellValue::Uuid(s) => q.bind(s.clone()),
+        }
+    }
+
+    /// Enlaza un valor ya convertido al tipo declarado de su columna. Fechas, UUID y JSON
+    /// se guardan como texto, que es como los leen las funciones de fecha y JSON de SQLite
+    fn bind_value<'q>(
+        q: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+        value: BindValue,
+    ) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+        match value {
+            BindValue::Null => q.bind(None::<String>),
+            BindValue::Bool(b) => q.bind(b),
+            BindValue::SmallInt(i) => q.bind(i),
+            BindValue::Int(i) => q.bind(i),
+            BindValue::BigInt(i) => q.bind(i),
+            BindValue::Real(f) => q.bind(f),
+            BindValue::Double(f) => q.bind(f),
+            BindValue::Bytes(b) => q.bind(b),
+            BindValue::Array(_) => q.bind(value.to_json().to_string()),
+            other => q.bind(other.as_text()),
+        }
+    }
+
+    /// Clave primaria para paginar por keyset, si la query lee una sola tabla y la proyecta
+    async fn keyset_columns(&self, query: &str) -> Option<Vec<String>> {
+        let select = SimpleSelect::parse(query)?;
+        let keys: Vec<String> = self
+            .get_columns(&select.table, select.schema.as_deref())
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name)
+            .collect();
+        (!keys.is_empty() && select.projects(&keys)).then_some(keys)
+    }
+
+    /// Total de filas según el modo pedido.
+    /// SQLite no expone estimaciones de filas en su plan; al ser una base local se cuenta siempre.
+    async fn count_rows(&self, query: &str, params: &[CellValue], mode: CountMode) -> Result<RowTotal, DomainError> {
+        if mode == CountMode::None {
+            return Ok((None, false));
+        }
+        let pool = self.get_pool().await?;
+        // En la conexión de la transacción manual, si hay una, como la página
+        let mut conn = self.session.acquire(&pool).await?;
+        let sql = format!("SELECT COUNT(*) FROM ({}) AS qx_count", query);
+        let row = Self::bind_all(sqlx::query(&sql), params)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+        let total: i64 = row.try_get(0).map_err(|e| DomainError::query(e.to_string()))?;
+        Ok((Some(total as u64), false))
+    }
+
+    /// Claves foráneas de `PRAGMA foreign_key_list`: una fila por columna, agrupadas por `id`.
+    /// SQLite no les da nombre, así que se nombran por tabla y posición
+    async fn foreign_keys(&self, table: &str) -> Result<Vec<ForeignKeyInfo>, DomainError> {
+        let pool = self.get_pool().await?;
+        let rows = sqlx::query(&format!("PRAGMA foreign_key_list('{}')", table.replace('\'', "''")))
+            .fetch_all(&pool).await.map_err(|e| DomainError::query(e.to_string()))?;
+
+        let mut foreign_keys: Vec<(i64, ForeignKeyInfo)> = Vec::new();
+        for row in rows {
+            let id: i64 = row.get("id");
+            let column: String = row.get("from");
+            // Sin columna de destino la referencia es a la clave primaria
+            let referenced: Option<String> = row.try_get("to").ok().flatten();
+            match foreign_keys.iter_mut().find(|(fk_id, _)| *fk_id == id) {
+                Some((_, fk)) => {
+                    fk.columns.push(column);
+                    fk.referenced_columns.extend(referenced);
+                }
+                None => foreign_keys.push((id, ForeignKeyInfo {
+                    name: format!("fk_{}_{}", table, id),
+                    columns: vec![column],
+                    referenced_table: row.get("table"),
+                    referenced_schema: None,
+                    referenced_columns: referenced.into_iter().collect(),
+                    on_update: Self::fk_action(row.try_get("on_update").ok()),
+                    on_delete: Self::fk_action(row.try_get("on_delete").ok()),
+                })),
+            }
+        }
+        Ok(foreign_keys.into_iter().map(|(_, fk)| fk).collect())
+    }
+
+    fn fk_action(rule: Option<String>) -> ForeignKeyAction {
+        match rule.as_deref() {
+            Some("CASCADE") => ForeignKeyAction::Cascade,
+            Some("SET NULL") => ForeignKeyAction::SetNull,
+            Some("SET DEFAULT") => ForeignKeyAction::SetDefault,
+            Some("RESTRICT") => ForeignKeyAction::Restrict,
+            _ => ForeignKeyAction::NoAction,
+        }
+    }
+
     fn map_sqlite_value(row: &sqlx::sqlite::SqliteRow, idx: usize) -> CellValue {
         let col = row.column(idx);
         let type_name = col.type_info().name();
@@ -46,6 +367,22 @@ impl SqliteDriver {
                 .unwrap_or(CellValue::Null),
         }
     }
+
+    /// Valor de una lectura exacta: se decodifica por la clase de almacenamiento del valor y
+    /// no por el tipo declarado, que en SQLite no obliga (un NUMERIC puede guardar texto)
+    fn exact_sqlite_value(row: &sqlx::sqlite::SqliteRow, idx: usize) -> Result<CellValue, DomainError> {
+        let raw = row.try_get_raw(idx).map_err(Self::map_query_error)?;
+        if raw.is_null() {
+            return Ok(CellValue::Null);
+        }
+        let value = match raw.type_info().name() {
+            "INTEGER" => row.try_get_unchecked::<i64, _>(idx).map(CellValue::Int),
+            "REAL" => row.try_get_unchecked::<f64, _>(idx).map(CellValue::Float),
+            "BLOB" => row.try_get_unchecked::<Vec<u8>, _>(idx).map(CellValue::Bytes),
+            _ => row.try_get_unchecked::<String, _>(idx).map(CellValue::String),
+        };
+        value.map_err(|e| DomainError::query(format!("Cannot read the value of column {}: {}", row.column(idx).name(), e)))
+    }
 }
 
 impl Default for SqliteDriver {
@@ -73,6 +410,11 @@ impl SqlDriver for SqliteDriver {
     }
 
     async fn disconnect(&self) -> Result<(), DomainError> {
+        // La conexión fijada debe volver al pool antes de cerrarlo
+        if let Ok(mut conn) = self.session.take().await {
+            sqlx::query("ROLLBACK").execute(&mut *conn).await.ok();
+        }
+
         let mut guard = self.pool.write().await;
         if let Some(pool) = guard.take() {
             pool.close().await;
@@ -97,39 +439,72 @@ impl SqlDriver for SqliteDriver {
         Ok(())
     }
 
-    async fn execute_query(&self, query: &str) -> Result<QueryResult, DomainError> {
-        let pool = self.get_pool().await?;
-        let start = Instant::now();
-        let rows = sqlx::query(query).fetch_all(&pool).await
-            .map_err(|e| DomainError::query(e.to_string()))?;
-        let execution_time = start.elapsed().as_millis() as u64;
+    async fn execute_query(&self, query: &str, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        self.fetch_result(query, &[], options).await
+    }
 
-        let columns: Vec<ColumnInfo> = if !rows.is_empty() {
-            rows[0].columns().iter().map(|col| ColumnInfo {
-                name: col.name().to_string(),
-                data_type: col.type_info().name().to_string(),
-                nullable: true,
-                is_primary_key: false,
-            }).collect()
-        } else { vec![] };
+    async fn execute_query_stream(
+        &self,
+        query: &str,
+        options: &ExecutionOptions,
+        stream_options: &StreamOptions,
+        sender: mpsc::Sender<RowBatch>,
+    ) -> Result<QueryStreamSummary, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let mut writer = RowBatchWriter::new(sender, stream_options);
+        let mut exhausted = true;
 
-        let data: Vec<Vec<CellValue>> = rows.iter().map(|row| {
-            (0..row.columns().len()).map(|idx| Self::map_sqlite_value(row, idx)).collect()
-        }).collect();
+        {
+            let mut rows = Self::bind_all(sqlx::query(query), &options.params).fetch(&mut *conn);
+            while let Some(row) = rows.next().await {
+                let row = row.map_err(Self::map_query_error)?;
+                if writer.needs_columns() {
+                    writer.set_columns(row.columns().iter().map(|col| ColumnInfo {
+                        name: col.name().to_string(),
+                        data_type: col.type_info().name().to_string(),
+                        nullable: true,
+                        is_primary_key: false,
+                    }).collect());
+                }
+                let values = if stream_options.exact_values {
+                    (0..row.columns().len())
+                        .map(|idx| Self::exact_sqlite_value(&row, idx))
+                        .collect::<Result<_, _>>()?
+                } else {
+                    (0..row.columns().len())
+                        .map(|idx| Self::map_sqlite_value(&row, idx))
+                        .collect()
+                };
+                if !writer.push(values).await {
+                    exhausted = false;
+                    break;
+                }
+            }
+        }
 
-        Ok(QueryResult::new(query.to_string(), columns, data).with_execution_time(execution_time))
+        Ok(writer.finish(options.query_id, query, exhausted).await)
     }
 
+    async fn execute_query_paginated(&self, query: &str, page: &PageRequest, options: &ExecutionOptions) -> Result<QueryResult, DomainError> {
+        let query = pagination::trim_statement(query);
+        if !pagination::is_row_returning(query) {
+            return self.execute_query(query, options).await;
+        }
 
-    async fn execute_query_paginated(&self, query: &str, page: u32, page_size: u32) -> Result<QueryResult, DomainError> {
-        let offset = (page - 1) * page_size;
-        let paginated = format!("{} LIMIT {} OFFSET {}", query.trim_end_matches(';'), page_size, offset);
-        let count_query = format!("SELECT COUNT(*) FROM ({}) as sq", query.trim_end_matches(';'));
-        let pool = self.get_pool().await?;
-        let total: i64 = sqlx::query_scalar(&count_query).fetch_one(&pool).await.unwrap_or(0);
-        let mut result = self.execute_query(&paginated).await?;
-        result = result.with_pagination(PaginationInfo::new(page, page_size).with_total(total as u64));
-        Ok(result)
+        let key_columns = self.keyset_columns(query).await;
+        let plan = KeysetPage::new(query, page, key_columns, |c| format!("\"{}\"", c.replace('"', "\"\"")));
+        let result = self.fetch_result(&plan.sql, &plan.params, options).await?;
+        let total = match plan.known_total() {
+            Some(total) => total,
+            None => self.count_rows(query, &options.params, page.count_mode).await?,
+        };
+
+        Ok(plan.finish(result, query, page, total))
+    }
+
+    async fn close_pagination_cursor(&self, _cursor: &str) -> Result<(), DomainError> {
+        // La paginación keyset no deja estado en el servidor
+        Ok(())
     }
 
     async fn insert_row(
@@ -161,6 +536,12 @@ impl SqlDriver for SqliteDriver {
             }
         }
 
+        // Cada valor se convierte al tipo declarado de su columna antes de tocar la base de datos
+        let mut bound = Vec::with_capacity(valid_entries.len());
+        for (_, val, info) in &valid_entries {
+            bound.push(BindValue::from_json(val, info, SqlDialect::Sqlite)?);
+        }
+
         let sql = if valid_entries.is_empty() {
              format!("INSERT INTO {} DEFAULT VALUES", table)
         } else {
@@ -175,19 +556,8 @@ impl SqlDriver for SqliteDriver {
         let sql_returning = format!("{} RETURNING *", sql);
         
         let mut query_builder = sqlx::query(&sql_returning);
-        for (_, val, _) in &valid_entries {
-             if val.is_null() {
-                 query_builder = query_builder.bind(Option::<String>::None);
-             } else if let Some(b) = val.as_bool() {
-                 query_builder = query_builder.bind(b);
-             } else if let Some(i) = val.as_i64() {
-                 query_builder = query_builder.bind(i);
-             } else if let Some(f) = val.as_f64() {
-                 query_builder = query_builder.bind(f);
-             } else {
-                 let s = val.as_str().map(|s| s.to_string()).unwrap_or_else(|| val.to_string());
-                 query_builder = query_builder.bind(s);
-             }
+        for value in bound.clone() {
+            query_builder = Self::bind_value(query_builder, value);
         }
 
         // Intentar ejecutar con RETURNING
@@ -213,23 +583,12 @@ impl SqlDriver for SqliteDriver {
                 // Fallback si RETURNING falla (versión vieja de SQLite)
                 // Ejecutar INSERT normal y luego SELECT
                 let mut query_builder = sqlx::query(&sql);
-                for (_, val, _) in &valid_entries {
-                     if val.is_null() {
-                         query_builder = query_builder.bind(Option::<String>::None);
-                     } else if let Some(b) = val.as_bool() {
-                         query_builder = query_builder.bind(b);
-                     } else if let Some(i) = val.as_i64() {
-                         query_builder = query_builder.bind(i);
-                     } else if let Some(f) = val.as_f64() {
-                         query_builder = query_builder.bind(f);
-                     } else {
-                         let s = val.as_str().map(|s| s.to_string()).unwrap_or_else(|| val.to_string());
-                         query_builder = query_builder.bind(s);
-                     }
+                for value in bound {
+                    query_builder = Self::bind_value(query_builder, value);
                 }
                 
                 let exec_result = query_builder.execute(&pool).await
-                    .map_err(|e| DomainError::query(e.to_string()))?;
+                    .map_err(Self::map_query_error)?;
                 
                 let last_id = exec_result.last_insert_rowid();
                 
@@ -259,78 +618,156 @@ impl SqlDriver for SqliteDriver {
         }
     }
 
-    async fn execute_statement(&self, statement: &str) -> Result<u64, DomainError> {
-        let pool = self.get_pool().await?;
-        let result = sqlx::query(statement).execute(&pool).await
-            .map_err(|e| DomainError::query(e.to_string()))?;
+    /// INSERT de varias filas por sentencia, con los valores enlazados con su tipo
+    async fn import_rows(&self, target: &ImportTarget, rows: &[Vec<BindValue>], options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let mut affected = 0;
+        for chunk in rows.chunks(target.rows_per_statement()) {
+            let sql = target.insert_sql(chunk.len(), |_| "?".to_string())?;
+            let mut query = sqlx::query(&sql);
+            for value in chunk.iter().flatten() {
+                query = Self::bind_value(query, value.clone());
+            }
+            affected += query.execute(&mut *conn).await.map_err(Self::map_query_error)?.rows_affected();
+        }
+        Ok(affected)
+    }
+
+    async fn execute_statement(&self, statement: &str, options: &ExecutionOptions) -> Result<u64, DomainError> {
+        let (mut conn, _running) = self.acquire_tracked(options).await?;
+        let result = Self::bind_all(sqlx::query(statement), &options.params).execute(&mut *conn).await
+            .map_err(Self::map_query_error)?;
         Ok(result.rows_affected())
     }
 
-    async fn execute_multi_statement(&self, statements: Vec<String>) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
-        let pool = self.get_pool().await?;
+    async fn execute_multi_statement(&self, statements: Vec<String>, options: &ExecutionOptions) -> Result<Vec<crate::domain::StatementResult>, DomainError> {
         let mut results = Vec::new();
 
         for (idx, statement) in statements.iter().enumerate() {
             let start = Instant::now();
             let sql = statement.trim();
-            
+
             if sql.is_empty() {
                 continue;
             }
 
-            let is_select = sql.to_uppercase().trim_start().starts_with("SELECT") ||
-                           sql.to_uppercase().trim_start().starts_with("WITH");
-
-            let result = if is_select {
-                match self.execute_query(sql).await {
-                    Ok(query_result) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: true,
-                        affected_rows: None,
-                        result: Some(query_result),
-                        error: None,
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: false,
-                        affected_rows: None,
-                        result: None,
-                        error: Some(e.to_string()),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                }
-            } else {
-                match sqlx::query(sql).execute(&pool).await {
-                    Ok(exec_result) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: true,
-                        affected_rows: Some(exec_result.rows_affected()),
-                        result: None,
-                        error: None,
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => crate::domain::StatementResult {
-                        statement_index: idx,
-                        sql: sql.to_string(),
-                        success: false,
-                        affected_rows: None,
-                        result: None,
-                        error: Some(e.to_string()),
-                        execution_time_ms: start.elapsed().as_millis() as u64,
-                    },
-                }
-            };
-
-            results.push(result);
+            let outcome = self.run_statement(sql, options).await;
+            results.push(crate::domain::StatementResult::from_outcome(
+                idx,
+                sql,
+                outcome,
+                start.elapsed().as_millis() as u64,
+            ));
         }
 
         Ok(results)
     }
 
+    fn supports_transactions(&self) -> bool {
+        true
+    }
+
+    async fn in_transaction(&self) -> bool {
+        self.session.is_active().await
+    }
+
+    async fn begin_transaction(&self, options: &TransactionOptions) -> Result<(), DomainError> {
+        if options.isolation_level.is_some_and(|l| l != IsolationLevel::Serializable) {
+            return Err(DomainError::validation("SQLite transactions are always serializable"));
+        }
+        if self.session.is_active().await {
+            return Err(DomainError::validation("A transaction is already open"));
+        }
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.checkout(&pool).await?;
+
+        if options.read_only {
+            sqlx::query("PRAGMA query_only = ON")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| DomainError::query(e.to_string()))?;
+        }
+        if let Err(e) = sqlx::query("BEGIN").execute(&mut *conn).await {
+            sqlx::query("PRAGMA query_only = OFF").execute(&mut *conn).await.ok();
+            return Err(DomainError::query(e.to_string()));
+        }
+
+        self.session.pin(conn).await
+    }
+
+    async fn commit_transaction(&self) -> Result<(), DomainError> {
+        let mut conn = self.session.take().await?;
+        if let Err(e) = sqlx::query("COMMIT").execute(&mut *conn).await {
+            // Un COMMIT fallido (p. ej. SQLITE_BUSY) deja la transacción abierta
+            self.session.pin(conn).await?;
+            return Err(DomainError::query(format!("Failed to commit transaction: {}", e)));
+        }
+        // query_only se activa por conexión: hay que quitarlo antes de devolverla al pool
+        sqlx::query("PRAGMA query_only = OFF").execute(&mut *conn).await.ok();
+        Ok(())
+    }
+
+    async fn rollback_transaction(&self) -> Result<(), DomainError> {
+        let mut conn = self.session.take().await?;
+        let result = sqlx::query("ROLLBACK")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)));
+        // query_only se activa por conexión: hay que quitarlo antes de devolverla al pool
+        sqlx::query("PRAGMA query_only = OFF").execute(&mut *conn).await.ok();
+        result.map(|_| ())
+    }
+
+    async fn create_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.session_execute(&format!("SAVEPOINT \"{}\"", name.replace('"', "\"\""))).await
+    }
+
+    async fn rollback_to_savepoint(&self, name: &str) -> Result<(), DomainError> {
+        self.session_execute(&format!("ROLLBACK TO SAVEPOINT \"{}\"", name.replace('"', "\"\""))).await
+    }
+
+    /// SQLite solo estima: no hay EXPLAIN ANALYZE, así que `analyze` se ignora
+    async fn explain(&self, sql: &str, _analyze: bool) -> Result<QueryPlan, DomainError> {
+        let pool = self.get_pool().await?;
+        let mut conn = self.session.acquire(&pool).await?;
+        let rows = sqlx::query(&format!("EXPLAIN QUERY PLAN {}", pagination::trim_statement(sql)))
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(Self::map_query_error)?;
+
+        // Cada fila es (id, parent, notused, detail); se cuelga de su padre en orden
+        let entries: Vec<(i64, i64, String)> = rows
+            .iter()
+            .map(|r| (r.try_get(0).unwrap_or(0), r.try_get(1).unwrap_or(0), r.try_get(3).unwrap_or_default()))
+            .collect();
+        fn children(entries: &[(i64, i64, String)], parent: i64) -> Vec<PlanNode> {
+            entries
+                .iter()
+                .filter(|(_, p, _)| *p == parent)
+                .map(|(id, _, detail)| PlanNode { children: children(entries, *id), ..SqliteDriver::plan_node(detail) })
+                .collect()
+        }
+        let mut roots = children(&entries, 0);
+        let root = if roots.len() == 1 {
+            roots.remove(0)
+        } else {
+            PlanNode { children: roots, ..PlanNode::new("QUERY PLAN") }
+        };
+
+        Ok(QueryPlan {
+            query: sql.to_string(),
+            analyzed: false,
+            root,
+            planning_time_ms: None,
+            execution_time_ms: None,
+            raw: entries
+                .iter()
+                .map(|(id, parent, detail)| format!("{}|{}|{}", id, parent, detail))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        })
+    }
+
     async fn execute_in_transaction(&self, statement: &str) -> Result<crate::domain::TransactionResult, DomainError> {
         let pool = self.get_pool().await?;
         let start = Instant::now();
@@ -360,7 +797,7 @@ impl SqlDriver for SqliteDriver {
                     .await
                     .map_err(|e| DomainError::query(format!("Failed to rollback transaction: {}", e)))?;
                 
-                Err(DomainError::query(e.to_string()))
+                Err(Self::map_query_error(e))
             }
         }
     }
@@ -398,7 +835,7 @@ impl SqlDriver for SqliteDriver {
             schema: Some("main".to_string()),
             columns: vec![], primary_key: None, foreign_keys: vec![], indexes: vec![],
             constraints: vec![], triggers: vec![],
-            row_count: None, size_bytes: None, comment: None,
+            row_count: None, size_bytes: None, comment: None, storage: None,
         }).collect())
     }
 
@@ -408,14 +845,15 @@ impl SqlDriver for SqliteDriver {
         let indexes = self.list_indexes(table, None).await?;
         let constraints = self.list_constraints(table, None).await?;
         let triggers = self.list_triggers(table, None).await?;
+        let foreign_keys = self.foreign_keys(table).await?;
         
         let pk_cols: Vec<String> = columns.iter().filter(|c| c.is_primary_key).map(|c| c.name.clone()).collect();
         let primary_key = if !pk_cols.is_empty() { Some(PrimaryKeyInfo { name: None, columns: pk_cols }) } else { None };
 
         Ok(TableInfo {
             name: table.to_string(), schema: Some("main".to_string()), columns, primary_key,
-            foreign_keys: vec![], indexes, constraints, triggers,
-            row_count: None, size_bytes: None, comment: None,
+            foreign_keys, indexes, constraints, triggers,
+            row_count: None, size_bytes: None, comment: None, storage: None,
         })
     }
 
@@ -427,7 +865,8 @@ impl SqlDriver for SqliteDriver {
             name: r.get("name"), data_type: r.get("type"), native_type: r.get("type"),
             nullable: r.get::<i32, _>("notnull") == 0,
             default_value: r.try_get("dflt_value").ok(),
-            is_primary_key: r.get::<i32, _>("pk") == 1,
+            // `pk` es la posición en la clave primaria, 0 si no forma parte
+            is_primary_key: r.get::<i32, _>("pk") > 0,
             is_unique: false, is_auto_increment: false,
             max_length: None, numeric_precision: None, numeric_scale: None, comment: None,
             ordinal_position: r.get::<i32, _>("cid") as u32,
@@ -536,7 +975,52 @@ impl SqlDriver for SqliteDriver {
         Ok(vec![])
     }
 
-    async fn cancel_query(&self) -> Result<(), DomainError> { Ok(()) }
+    async fn get_object_ddl(&self, object: &DdlObject, _options: &DdlOptions) -> Result<String, DomainError> {
+        // SQLite no guarda comentarios, permisos ni propietarios: solo la sentencia original
+        let kind = match object.object_type {
+            DdlObjectType::Table => "table",
+            DdlObjectType::View => "view",
+            DdlObjectType::Index => "index",
+            DdlObjectType::Trigger => "trigger",
+            DdlObjectType::Function => return Err(DomainError::validation("SQLite does not support stored functions")),
+            DdlObjectType::Sequence => return Err(DomainError::validation("SQLite does not support sequences")),
+        };
+        let pool = self.get_pool().await?;
+        let row = sqlx::query("SELECT sql FROM sqlite_master WHERE type = ? AND name = ?")
+            .bind(kind)
+            .bind(&object.name)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?
+            .ok_or_else(|| DomainError::not_found(format!("{} {}", kind, object.name)))?;
+        // Los índices automáticos de PRIMARY KEY y UNIQUE no tienen sentencia propia
+        let sql: String = row.try_get::<Option<String>, _>("sql").ok().flatten().ok_or_else(|| {
+            DomainError::validation(format!("{} is created automatically by a constraint of its table", object.name))
+        })?;
+        let mut statements = vec![format!("{};\n", sql)];
+
+        // CREATE TABLE no incluye los índices ni los triggers de la tabla
+        if object.object_type == DdlObjectType::Table {
+            let rows = sqlx::query(
+                "SELECT sql FROM sqlite_master
+                 WHERE tbl_name = ? AND type IN ('index', 'trigger') AND sql IS NOT NULL
+                 ORDER BY type, name"
+            )
+            .bind(&object.name)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| DomainError::query(e.to_string()))?;
+            statements.extend(rows.iter().map(|r| format!("{};\n", r.get::<String, _>("sql"))));
+        }
+        Ok(statements.join("\n"))
+    }
+
+    async fn cancel_query(&self, query_id: Uuid) -> Result<(), DomainError> {
+        if let Some(interrupted) = self.running.get(&query_id) {
+            interrupted.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
 
     async fn get_completions(&self, prefix: &str, _ctx: CompletionContext) -> Result<Vec<CompletionItem>, DomainError> {
         let keywords = ["SELECT", "FROM", "WHERE", "JOIN", "AND", "OR", "ORDER", "BY", "LIMIT"];
@@ -553,4 +1037,9 @@ impl SqlDriver for SqliteDriver {
             .map_err(|e| DomainError::query(e.to_string()))?;
         Ok(row.get::<String, _>(0))
     }
+
+    async fn tls_status(&self) -> Result<TlsStatus, DomainError> {
+        // Fichero local: no hay transporte que cifrar
+        Ok(TlsStatus::plain())
+    }
 }
@@ -0,0 +1,104 @@
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use crate::domain::{CellValue, ColumnInfo, QueryStreamSummary, RowBatch, StreamOptions};
+
+/// Agrupa filas en lotes y los envía por el canal del stream
+/// El envío espera si el canal está lleno, de modo que el driver deja de leer
+/// filas del servidor hasta que el consumidor libera espacio
+pub struct RowBatchWriter {
+    sender: mpsc::Sender<RowBatch>,
+    batch_size: usize,
+    max_rows: Option<u64>,
+    columns: Option<Vec<ColumnInfo>>,
+    pending: Vec<Vec<CellValue>>,
+    batch_count: usize,
+    row_count: u64,
+    truncated: bool,
+    closed: bool,
+    start: Instant,
+}
+
+impl RowBatchWriter {
+    pub fn new(sender: mpsc::Sender<RowBatch>, options: &StreamOptions) -> Self {
+        let batch_size = options.batch_size.max(1);
+        Self {
+            sender,
+            batch_size,
+            max_rows: options.max_rows,
+            columns: None,
+            pending: Vec::with_capacity(batch_size),
+            batch_count: 0,
+            row_count: 0,
+            truncated: false,
+            closed: false,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn needs_columns(&self) -> bool {
+        self.columns.is_none()
+    }
+
+    pub fn set_columns(&mut self, columns: Vec<ColumnInfo>) {
+        self.columns = Some(columns);
+    }
+
+    /// Añade una fila; devuelve false si el stream debe detenerse
+    /// (tope de filas alcanzado o consumidor cerrado)
+    pub async fn push(&mut self, row: Vec<CellValue>) -> bool {
+        if matches!(self.max_rows, Some(max) if self.row_count >= max) {
+            self.truncated = true;
+            return false;
+        }
+
+        self.pending.push(row);
+        self.row_count += 1;
+
+        if self.pending.len() >= self.batch_size {
+            return self.flush().await;
+        }
+        true
+    }
+
+    async fn flush(&mut self) -> bool {
+        if self.pending.is_empty() && self.batch_count > 0 {
+            return true;
+        }
+
+        let batch = RowBatch {
+            batch_index: self.batch_count,
+            columns: if self.batch_count == 0 { Some(self.columns.clone().unwrap_or_default()) } else { None },
+            rows: std::mem::replace(&mut self.pending, Vec::with_capacity(self.batch_size)),
+        };
+        self.batch_count += 1;
+
+        if self.sender.send(batch).await.is_err() {
+            self.closed = true;
+            self.truncated = true;
+            return false;
+        }
+        true
+    }
+
+    /// Envía el último lote pendiente y construye el resumen
+    /// `exhausted` indica si se leyeron todas las filas del servidor
+    pub async fn finish(mut self, query_id: uuid::Uuid, query: &str, exhausted: bool) -> QueryStreamSummary {
+        if !self.closed {
+            self.flush().await;
+        }
+        if !exhausted {
+            self.truncated = true;
+        }
+
+        QueryStreamSummary {
+            query_id,
+            query: query.to_string(),
+            columns: self.columns.unwrap_or_default(),
+            row_count: self.row_count,
+            batch_count: self.batch_count,
+            truncated: self.truncated,
+            execution_time_ms: self.start.elapsed().as_millis() as u64,
+        }
+    }
+}
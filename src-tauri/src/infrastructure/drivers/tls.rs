@@ -0,0 +1,26 @@
+use crate::domain::DomainError;
+
+/// Convierte un error al abrir la conexión distinguiendo los fallos de TLS
+/// (certificado no válido, CA desconocida, servidor sin TLS) del resto
+pub fn map_connect_error(e: sqlx::Error) -> DomainError {
+    match &e {
+        sqlx::Error::Tls(inner) => DomainError::TlsError { message: inner.to_string() },
+        // rustls informa de los fallos del handshake como InvalidData
+        sqlx::Error::Io(io) if io.kind() == std::io::ErrorKind::InvalidData => {
+            DomainError::TlsError { message: io.to_string() }
+        }
+        // Fichero de certificado o clave que no existe o no se puede leer
+        sqlx::Error::Io(io) if io.kind() == std::io::ErrorKind::NotFound => {
+            DomainError::TlsError { message: format!("cannot read certificate: {}", io) }
+        }
+        sqlx::Error::Configuration(inner) if is_tls_message(&inner.to_string()) => {
+            DomainError::TlsError { message: inner.to_string() }
+        }
+        _ => DomainError::connection(e.to_string()),
+    }
+}
+
+fn is_tls_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["ssl", "tls", "certificate"].iter().any(|k| message.contains(k))
+}
@@ -0,0 +1,3 @@
+mod tauri_event_bus;
+
+pub use tauri_event_bus::{TauriEventBus, DOMAIN_EVENT_CHANNEL};
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use crate::domain::{DomainError, DomainEvent, EventBus, EventHandler};
+
+/// Canal por el que el frontend recibe los eventos de dominio
+pub const DOMAIN_EVENT_CHANNEL: &str = "domain-event";
+
+/// Implementación de EventBus que reenvía los eventos al frontend vía Tauri
+pub struct TauriEventBus {
+    app_handle: AppHandle,
+    handlers: RwLock<HashMap<Uuid, EventHandler>>,
+}
+
+impl TauriEventBus {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            app_handle,
+            handlers: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl EventBus for TauriEventBus {
+    async fn publish(&self, event: DomainEvent) -> Result<(), DomainError> {
+        if let Ok(handlers) = self.handlers.read() {
+            for handler in handlers.values() {
+                handler(event.clone());
+            }
+        }
+
+        self.app_handle
+            .emit_all(DOMAIN_EVENT_CHANNEL, event)
+            .map_err(|e| DomainError::internal(e.to_string()))
+    }
+
+    fn subscribe(&self, handler: EventHandler) -> Uuid {
+        let id = Uuid::new_v4();
+        if let Ok(mut handlers) = self.handlers.write() {
+            handlers.insert(id, handler);
+        }
+        id
+    }
+
+    fn unsubscribe(&self, subscription_id: Uuid) {
+        if let Ok(mut handlers) = self.handlers.write() {
+            handlers.remove(&subscription_id);
+        }
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.handlers.read().map(|h| h.len()).unwrap_or(0)
+    }
+}
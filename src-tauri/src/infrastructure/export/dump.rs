@@ -0,0 +1,45 @@
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::domain::DomainError;
+use super::partial_path;
+
+/// Fichero SQL de un volcado. Como las exportaciones, se escribe en `<destino>.part` y solo
+/// sustituye al destino cuando está completo
+pub struct DumpFileWriter {
+    path: PathBuf,
+    partial: PathBuf,
+    file: Option<BufWriter<File>>,
+    bytes_written: u64,
+}
+
+impl DumpFileWriter {
+    pub async fn create(path: &Path) -> Result<Self, DomainError> {
+        let partial = partial_path(path);
+        let file = BufWriter::new(File::create(&partial).await?);
+        Ok(Self { path: path.to_path_buf(), partial, file: Some(file), bytes_written: 0 })
+    }
+
+    pub async fn write(&mut self, text: &str) -> Result<(), DomainError> {
+        let file = self.file.as_mut().ok_or_else(|| DomainError::internal("Dump file is not open"))?;
+        file.write_all(text.as_bytes()).await?;
+        self.bytes_written += text.len() as u64;
+        Ok(())
+    }
+
+    /// Cierra el fichero, lo mueve al destino y devuelve los bytes escritos
+    pub async fn finish(&mut self) -> Result<u64, DomainError> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+            file.into_inner().sync_all().await?;
+        }
+        fs::rename(&self.partial, &self.path).await?;
+        Ok(self.bytes_written)
+    }
+
+    pub async fn abort(&mut self) {
+        self.file = None;
+        fs::remove_file(&self.partial).await.ok();
+    }
+}
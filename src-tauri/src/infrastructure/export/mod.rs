@@ -0,0 +1,49 @@
+mod dump;
+mod sqlite;
+mod text;
+mod xlsx;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::domain::{ColumnInfo, ExportFormat, ExportOptions, ExportWriter, SqlDialect};
+
+pub use dump::DumpFileWriter;
+pub use xlsx::write_xlsx;
+pub(crate) use xlsx::column_letters;
+
+/// Crea el escritor del formato pedido. `dialect` es el de la conexión de origen
+pub fn create_export_writer(path: &Path, options: &ExportOptions, dialect: SqlDialect) -> Box<dyn ExportWriter> {
+    match options.format {
+        ExportFormat::Sqlite => Box::new(sqlite::SqliteExportWriter::new(path, options, dialect)),
+        _ => {
+            let target = options.target_engine.as_ref().map(SqlDialect::from_engine).unwrap_or(dialect);
+            Box::new(text::TextExportWriter::new(path, options, target))
+        }
+    }
+}
+
+/// Fichero en el que se escribe hasta terminar; así un error o una cancelación no dejan
+/// a medias el destino, que puede existir de antes
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Nombres de columna sin repetir (`id`, `id_2`...), para claves JSON y columnas de tabla
+fn unique_names(columns: &[ColumnInfo]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns
+        .iter()
+        .map(|c| {
+            let mut name = c.name.clone();
+            let mut n = 1;
+            while !seen.insert(name.to_lowercase()) {
+                n += 1;
+                name = format!("{}_{}", c.name, n);
+            }
+            name
+        })
+        .collect()
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions};
+use sqlx::{Connection, Sqlite, SqliteConnection};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::domain::{cell_text, CellValue, ColumnInfo, ColumnKind, DomainError, ExportOptions, ExportWriter, SqlDialect};
+use super::{partial_path, unique_names};
+
+/// Marcadores por sentencia que admite cualquier versión de SQLite
+const SQLITE_MAX_PARAMS: usize = 999;
+
+/// Base de datos SQLite nueva con una tabla con el resultado
+pub struct SqliteExportWriter {
+    path: PathBuf,
+    partial: PathBuf,
+    table: String,
+    /// Dialecto de la conexión de origen, para deducir el tipo de cada columna
+    source: SqlDialect,
+    conn: Option<SqliteConnection>,
+    insert_prefix: String,
+    column_count: usize,
+}
+
+impl SqliteExportWriter {
+    pub fn new(path: &Path, options: &ExportOptions, source: SqlDialect) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            partial: partial_path(path),
+            table: SqlDialect::Sqlite.quote_identifier(options.table_name()),
+            source,
+            conn: None,
+            insert_prefix: String::new(),
+            column_count: 0,
+        }
+    }
+
+    /// Afinidad de SQLite más cercana al tipo de origen
+    fn affinity(&self, column: &ColumnInfo) -> &'static str {
+        match ColumnKind::parse(&column.data_type, self.source) {
+            ColumnKind::Bool | ColumnKind::SmallInt | ColumnKind::Int | ColumnKind::BigInt => "INTEGER",
+            ColumnKind::Real | ColumnKind::Double => "REAL",
+            ColumnKind::Decimal => "NUMERIC",
+            ColumnKind::Bytes => "BLOB",
+            _ => "TEXT",
+        }
+    }
+
+    fn bind<'q>(
+        q: sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>>,
+        value: &CellValue,
+    ) -> sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>> {
+        match value {
+            CellValue::Null => q.bind(None::<String>),
+            CellValue::Bool(b) => q.bind(*b),
+            CellValue::Int(i) => q.bind(*i),
+            CellValue::Float(f) => q.bind(*f),
+            CellValue::Bytes(bytes) => q.bind(bytes.clone()),
+            other => q.bind(cell_text(other)),
+        }
+    }
+
+    fn conn(&mut self) -> Result<&mut SqliteConnection, DomainError> {
+        self.conn.as_mut().ok_or_else(|| DomainError::internal("Export database is not open"))
+    }
+}
+
+#[async_trait]
+impl ExportWriter for SqliteExportWriter {
+    async fn begin(&mut self, columns: &[ColumnInfo]) -> Result<(), DomainError> {
+        // Restos de una exportación anterior interrumpida
+        fs::remove_file(&self.partial).await.ok();
+        let options = SqliteConnectOptions::new().filename(&self.partial).create_if_missing(true);
+        let mut conn = SqliteConnection::connect_with(&options).await.map_err(io_error)?;
+
+        let names: Vec<String> = unique_names(columns).iter().map(|n| SqlDialect::Sqlite.quote_identifier(n)).collect();
+        let definitions: Vec<String> = names
+            .iter()
+            .zip(columns)
+            .map(|(name, column)| format!("{} {}", name, self.affinity(column)))
+            .collect();
+        let create = format!("CREATE TABLE {} ({})", self.table, definitions.join(", "));
+        sqlx::query(&create).execute(&mut conn).await.map_err(io_error)?;
+        // Todas las filas en una transacción: sin ella SQLite sincroniza el disco en cada INSERT
+        sqlx::query("BEGIN").execute(&mut conn).await.map_err(io_error)?;
+
+        self.insert_prefix = format!("INSERT INTO {} ({}) VALUES ", self.table, names.join(", "));
+        self.column_count = columns.len();
+        self.conn = Some(conn);
+        Ok(())
+    }
+
+    async fn write_rows(&mut self, rows: &[Vec<CellValue>]) -> Result<(), DomainError> {
+        let per_statement = (SQLITE_MAX_PARAMS / self.column_count.max(1)).max(1);
+        let tuple = format!("({})", vec!["?"; self.column_count].join(", "));
+        for chunk in rows.chunks(per_statement) {
+            let sql = format!("{}{}", self.insert_prefix, vec![tuple.as_str(); chunk.len()].join(", "));
+            let mut q = sqlx::query(&sql);
+            for value in chunk.iter().flatten() {
+                q = Self::bind(q, value);
+            }
+            q.execute(self.conn()?).await.map_err(io_error)?;
+        }
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> Result<u64, DomainError> {
+        let mut conn = self.conn.take().ok_or_else(|| DomainError::internal("Export database is not open"))?;
+        sqlx::query("COMMIT").execute(&mut conn).await.map_err(io_error)?;
+        conn.close().await.map_err(io_error)?;
+        fs::rename(&self.partial, &self.path).await?;
+        Ok(fs::metadata(&self.path).await?.len())
+    }
+
+    async fn abort(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            conn.close().await.ok();
+        }
+        fs::remove_file(&self.partial).await.ok();
+    }
+}
+
+/// Los fallos de la base de destino son de escritura del fichero, no de la conexión de origen
+fn io_error(err: sqlx::Error) -> DomainError {
+    DomainError::IoError { message: err.to_string() }
+}
@@ -0,0 +1,406 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::domain::{
+    cell_json, cell_text, insert_statements, CellValue, ColumnInfo, CsvQuoting, DomainError, ExportFormat, ExportOptions,
+    ExportWriter, SqlDialect, TextEncoding, DEFAULT_ROWS_PER_INSERT,
+};
+use super::{partial_path, unique_names};
+
+/// Filas por `VALUES` que admite SQL Server
+const SQL_SERVER_MAX_ROWS_PER_INSERT: usize = 1000;
+
+/// Convierte a texto la cabecera, cada lote de filas y el cierre de un formato
+trait TextFormat: Send {
+    fn header(&mut self, columns: &[ColumnInfo]) -> String;
+    fn rows(&mut self, rows: &[Vec<CellValue>]) -> String;
+    fn footer(&mut self) -> String {
+        String::new()
+    }
+}
+
+/// Formatos de texto: se escriben en `<destino>.part` y se mueven al destino al terminar
+pub struct TextExportWriter {
+    path: PathBuf,
+    partial: PathBuf,
+    encoding: TextEncoding,
+    format: Box<dyn TextFormat>,
+    file: Option<BufWriter<File>>,
+    bytes_written: u64,
+}
+
+impl TextExportWriter {
+    pub fn new(path: &Path, options: &ExportOptions, dialect: SqlDialect) -> Self {
+        let null_text = options.null_text.clone().unwrap_or_default();
+        let format: Box<dyn TextFormat> = match options.format {
+            ExportFormat::Json => Box::new(JsonFormat::new(false)),
+            ExportFormat::Ndjson => Box::new(JsonFormat::new(true)),
+            ExportFormat::Sql => Box::new(SqlFormat::new(options, dialect)),
+            ExportFormat::Markdown => Box::new(MarkdownFormat { null_text }),
+            _ => Box::new(CsvFormat {
+                delimiter: options.delimiter.unwrap_or(','),
+                quoting: options.quoting,
+                header: options.header,
+                null_text,
+            }),
+        };
+        Self {
+            path: path.to_path_buf(),
+            partial: partial_path(path),
+            encoding: options.encoding,
+            format,
+            file: None,
+            bytes_written: 0,
+        }
+    }
+
+    async fn write(&mut self, text: &str) -> Result<(), DomainError> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let bytes = encode(text, self.encoding)?;
+        let file = self.file.as_mut().ok_or_else(|| DomainError::internal("Export file is not open"))?;
+        file.write_all(&bytes).await?;
+        self.bytes_written += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExportWriter for TextExportWriter {
+    async fn begin(&mut self, columns: &[ColumnInfo]) -> Result<(), DomainError> {
+        let mut file = BufWriter::new(File::create(&self.partial).await?);
+        let bom = bom(self.encoding);
+        file.write_all(bom).await?;
+        self.bytes_written = bom.len() as u64;
+        self.file = Some(file);
+        let header = self.format.header(columns);
+        self.write(&header).await
+    }
+
+    async fn write_rows(&mut self, rows: &[Vec<CellValue>]) -> Result<(), DomainError> {
+        let text = self.format.rows(rows);
+        self.write(&text).await
+    }
+
+    async fn finish(&mut self) -> Result<u64, DomainError> {
+        let footer = self.format.footer();
+        self.write(&footer).await?;
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+            file.into_inner().sync_all().await?;
+        }
+        fs::rename(&self.partial, &self.path).await?;
+        Ok(self.bytes_written)
+    }
+
+    async fn abort(&mut self) {
+        self.file = None;
+        fs::remove_file(&self.partial).await.ok();
+    }
+}
+
+fn bom(encoding: TextEncoding) -> &'static [u8] {
+    match encoding {
+        TextEncoding::Utf8Bom => b"\xEF\xBB\xBF",
+        TextEncoding::Utf16Le => b"\xFF\xFE",
+        _ => b"",
+    }
+}
+
+/// Un carácter que no cabe en Latin-1 detiene la exportación: sustituirlo perdería datos sin avisar
+fn encode(text: &str, encoding: TextEncoding) -> Result<Vec<u8>, DomainError> {
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => Ok(text.as_bytes().to_vec()),
+        TextEncoding::Utf16Le => Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+        TextEncoding::Latin1 => text
+            .chars()
+            .map(|c| {
+                u8::try_from(u32::from(c)).map_err(|_| {
+                    DomainError::validation(format!(
+                        "'{}' (U+{:04X}) cannot be encoded as Latin-1: export as UTF-8 instead",
+                        c,
+                        u32::from(c)
+                    ))
+                })
+            })
+            .collect(),
+    }
+}
+
+/// CSV en el estilo de RFC 4180. Los nulos nunca van entre comillas, así que con la
+/// política mínima un texto vacío (`""`) se distingue de un nulo
+struct CsvFormat {
+    delimiter: char,
+    quoting: CsvQuoting,
+    header: bool,
+    null_text: String,
+}
+
+impl CsvFormat {
+    fn field(&self, text: &str, numeric: bool) -> String {
+        let quote = match self.quoting {
+            CsvQuoting::All => true,
+            CsvQuoting::NonNumeric => !numeric,
+            // Sin comillas, un separador o un salto de línea partiría la fila
+            CsvQuoting::Never => text.contains([self.delimiter, '\n', '\r']) || text.starts_with('"'),
+            CsvQuoting::Minimal => {
+                text.is_empty() || text.contains([self.delimiter, '"', '\n', '\r'])
+            }
+        };
+        if quote {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn line(&self, fields: Vec<String>) -> String {
+        let mut line = fields.join(&self.delimiter.to_string());
+        line.push('\n');
+        line
+    }
+}
+
+impl TextFormat for CsvFormat {
+    fn header(&mut self, columns: &[ColumnInfo]) -> String {
+        if !self.header {
+            return String::new();
+        }
+        self.line(columns.iter().map(|c| self.field(&c.name, false)).collect())
+    }
+
+    fn rows(&mut self, rows: &[Vec<CellValue>]) -> String {
+        rows.iter()
+            .map(|row| {
+                let fields = row
+                    .iter()
+                    .map(|value| match cell_text(value) {
+                        None => self.null_text.clone(),
+                        Some(text) => {
+                            let numeric = matches!(value, CellValue::Int(_) | CellValue::Float(_) | CellValue::Bool(_));
+                            self.field(&text, numeric)
+                        }
+                    })
+                    .collect();
+                self.line(fields)
+            })
+            .collect()
+    }
+}
+
+/// Un objeto por fila con las columnas en su orden, en un array JSON o uno por línea (NDJSON)
+struct JsonFormat {
+    lines: bool,
+    keys: Vec<String>,
+    first: bool,
+}
+
+impl JsonFormat {
+    fn new(lines: bool) -> Self {
+        Self { lines, keys: Vec::new(), first: true }
+    }
+
+    fn object(&self, row: &[CellValue]) -> String {
+        let members: Vec<String> = self
+            .keys
+            .iter()
+            .zip(row)
+            .map(|(key, value)| format!("{}:{}", key, cell_json(value)))
+            .collect();
+        format!("{{{}}}", members.join(","))
+    }
+}
+
+impl TextFormat for JsonFormat {
+    fn header(&mut self, columns: &[ColumnInfo]) -> String {
+        self.keys = unique_names(columns).iter().map(|name| Value::from(name.as_str()).to_string()).collect();
+        if self.lines { String::new() } else { "[".to_string() }
+    }
+
+    fn rows(&mut self, rows: &[Vec<CellValue>]) -> String {
+        let mut text = String::new();
+        for row in rows {
+            if self.lines {
+                text.push_str(&self.object(row));
+                text.push('\n');
+            } else {
+                text.push_str(if self.first { "\n  " } else { ",\n  " });
+                text.push_str(&self.object(row));
+            }
+            self.first = false;
+        }
+        text
+    }
+
+    fn footer(&mut self) -> String {
+        if self.lines { String::new() } else { "\n]\n".to_string() }
+    }
+}
+
+/// Script de `INSERT` de varias filas en el dialecto de destino
+struct SqlFormat {
+    dialect: SqlDialect,
+    table: String,
+    rows_per_insert: usize,
+    prefix: String,
+}
+
+impl SqlFormat {
+    fn new(options: &ExportOptions, dialect: SqlDialect) -> Self {
+        // `esquema.tabla` se cita parte a parte
+        let table = options
+            .table_name()
+            .split('.')
+            .map(|part| dialect.quote_identifier(part.trim()))
+            .collect::<Vec<_>>()
+            .join(".");
+        let mut rows_per_insert = options.rows_per_insert.unwrap_or(DEFAULT_ROWS_PER_INSERT).max(1);
+        if dialect == SqlDialect::SqlServer {
+            rows_per_insert = rows_per_insert.min(SQL_SERVER_MAX_ROWS_PER_INSERT);
+        }
+        Self { dialect, table, rows_per_insert, prefix: String::new() }
+    }
+}
+
+impl TextFormat for SqlFormat {
+    fn header(&mut self, columns: &[ColumnInfo]) -> String {
+        let names: Vec<String> = columns.iter().map(|c| self.dialect.quote_identifier(&c.name)).collect();
+        self.prefix = format!("INSERT INTO {} ({}) VALUES", self.table, names.join(", "));
+        String::new()
+    }
+
+    fn rows(&mut self, rows: &[Vec<CellValue>]) -> String {
+        insert_statements(&self.prefix, rows, self.dialect, self.rows_per_insert)
+    }
+}
+
+/// Tabla de Markdown (GFM); siempre lleva cabecera, que el formato exige
+struct MarkdownFormat {
+    null_text: String,
+}
+
+impl MarkdownFormat {
+    fn line(cells: impl Iterator<Item = String>) -> String {
+        let cells: Vec<String> = cells.collect();
+        format!("| {} |\n", cells.join(" | "))
+    }
+
+    fn escape(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace(['\n', '\r'], "<br>")
+    }
+}
+
+impl TextFormat for MarkdownFormat {
+    fn header(&mut self, columns: &[ColumnInfo]) -> String {
+        let names = Self::line(columns.iter().map(|c| Self::escape(&c.name)));
+        let rule = Self::line(columns.iter().map(|_| "---".to_string()));
+        names + &rule
+    }
+
+    fn rows(&mut self, rows: &[Vec<CellValue>]) -> String {
+        rows.iter()
+            .map(|row| {
+                Self::line(row.iter().map(|value| match cell_text(value) {
+                    Some(text) => Self::escape(&text),
+                    None => Self::escape(&self.null_text),
+                }))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn csv(quoting: CsvQuoting) -> CsvFormat {
+        CsvFormat { delimiter: ';', quoting, header: true, null_text: "NULL".to_string() }
+    }
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo { name: name.to_string(), data_type: "text".to_string(), nullable: true, is_primary_key: false }
+    }
+
+    #[test]
+    fn quotes_only_what_each_policy_asks_for() {
+        let row = vec![vec![CellValue::Int(1), CellValue::String("a;b".into()), CellValue::String(String::new()), CellValue::Null]];
+        assert_eq!(csv(CsvQuoting::Minimal).rows(&row), "1;\"a;b\";\"\";NULL\n");
+        assert_eq!(csv(CsvQuoting::All).rows(&row), "\"1\";\"a;b\";\"\";NULL\n");
+        assert_eq!(csv(CsvQuoting::NonNumeric).rows(&row), "1;\"a;b\";\"\";NULL\n");
+        assert_eq!(csv(CsvQuoting::Minimal).header(&[column("id"), column("say \"hi\"")]), "id;\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn never_quoting_still_quotes_fields_that_would_break_the_row() {
+        let mut format = csv(CsvQuoting::Never);
+        let rows = vec![vec![
+            CellValue::String("plain \"text\"".into()),
+            CellValue::String("a;b".into()),
+            CellValue::String("two\nlines".into()),
+            CellValue::String("\"quoted\"".into()),
+        ]];
+        assert_eq!(format.rows(&rows), "plain \"text\";\"a;b\";\"two\nlines\";\"\"\"quoted\"\"\"\n");
+    }
+
+    #[test]
+    fn rejects_characters_outside_latin1() {
+        assert_eq!(encode("año", TextEncoding::Latin1).unwrap(), b"a\xF1o");
+        let error = encode("price: 5 €", TextEncoding::Latin1).unwrap_err().to_string();
+        assert!(error.contains("'€' (U+20AC)"), "{}", error);
+        assert_eq!(encode("€", TextEncoding::Utf16Le).unwrap(), b"\xAC\x20");
+    }
+
+    #[tokio::test]
+    async fn writes_the_file_only_when_finished() {
+        let path = std::env::temp_dir().join(format!("export-{}.csv", uuid::Uuid::new_v4()));
+        let options: ExportOptions = serde_json::from_value(json!({ "format": "csv", "encoding": "utf8_bom" })).unwrap();
+        let mut writer = TextExportWriter::new(&path, &options, SqlDialect::Postgres);
+        writer.begin(&[column("name")]).await.unwrap();
+        writer.write_rows(&[vec![CellValue::String("ñ".into())]]).await.unwrap();
+        assert!(!path.exists());
+
+        let bytes = writer.finish().await.unwrap();
+        let written = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(written, "\u{feff}name\nñ\n".as_bytes());
+        assert_eq!(bytes, written.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn discards_the_partial_file_when_a_character_cannot_be_encoded() {
+        let path = std::env::temp_dir().join(format!("export-{}.csv", uuid::Uuid::new_v4()));
+        let options: ExportOptions = serde_json::from_value(json!({ "format": "csv", "encoding": "latin1" })).unwrap();
+        let mut writer = TextExportWriter::new(&path, &options, SqlDialect::Postgres);
+        writer.begin(&[column("name")]).await.unwrap();
+        assert!(writer.write_rows(&[vec![CellValue::String("日本".into())]]).await.is_err());
+
+        writer.abort().await;
+        assert!(!path.exists());
+        assert!(!partial_path(&path).exists());
+    }
+
+    #[test]
+    fn caps_sql_server_inserts_at_its_row_limit() {
+        let options: ExportOptions =
+            serde_json::from_value(json!({ "format": "sql", "table_name": "dbo.orders", "rows_per_insert": 5000 })).unwrap();
+        let rows: Vec<Vec<CellValue>> = (0..2500).map(|i| vec![CellValue::Int(i)]).collect();
+
+        let mut format = SqlFormat::new(&options, SqlDialect::SqlServer);
+        assert_eq!(format.rows_per_insert, SQL_SERVER_MAX_ROWS_PER_INSERT);
+        format.header(&[column("id")]);
+        let script = format.rows(&rows);
+        assert_eq!(script.matches("INSERT INTO [dbo].[orders] ([id]) VALUES").count(), 3);
+
+        let mut format = SqlFormat::new(&options, SqlDialect::Postgres);
+        format.header(&[column("id")]);
+        assert_eq!(format.rows(&rows).matches("INSERT INTO").count(), 1);
+    }
+}
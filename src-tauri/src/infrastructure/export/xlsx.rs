@@ -0,0 +1,308 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use umya_spreadsheet::{self as excel, Hyperlink, Pane, PaneStateValues, PaneValues, SheetView, Worksheet};
+
+use crate::domain::{cell_text, CellValue, DomainError, XlsxSheet};
+
+/// Caracteres que admite una celda de Excel
+const MAX_CELL_CHARS: usize = 32_767;
+
+/// Longitud máxima del nombre de una hoja
+const MAX_SHEET_NAME_CHARS: usize = 31;
+
+/// Anchura de las columnas, en caracteres
+const MIN_COLUMN_WIDTH: usize = 8;
+const MAX_COLUMN_WIDTH: usize = 60;
+
+/// Excel guarda los números en coma flotante: por encima de 15 dígitos un entero cambia,
+/// así que esos se escriben como texto
+const MAX_EXACT_INTEGER: u64 = 999_999_999_999_999;
+
+const DATE_FORMAT: &str = "yyyy-mm-dd";
+const DATETIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+const TIME_FORMAT: &str = "hh:mm:ss";
+
+/// Color de los enlaces del tema por defecto de Excel
+const HYPERLINK_COLOR: &str = "FF0563C1";
+
+/// Escribe un libro con una hoja por conjunto de resultados. Cada celda lleva su tipo:
+/// números, booleanos, fechas con formato de fecha y enlaces para las URL
+pub fn write_xlsx(path: &Path, sheets: &[XlsxSheet]) -> Result<(), DomainError> {
+    let mut book = excel::new_file_empty_worksheet();
+    let mut used = HashSet::new();
+    for (index, data) in sheets.iter().enumerate() {
+        let name = sheet_name(&data.name, index, &mut used);
+        let sheet = book.new_sheet(name).map_err(DomainError::internal)?;
+        write_sheet(sheet, data);
+    }
+    // Un libro sin hojas no se puede abrir
+    if sheets.is_empty() {
+        book.new_sheet("Sheet1").map_err(DomainError::internal)?;
+    }
+    excel::writer::xlsx::write(&book, path).map_err(|e| DomainError::IoError { message: e.to_string() })
+}
+
+/// Letras de la columna `col` (desde 1): A, B, ..., Z, AA...
+pub(crate) fn column_letters(mut col: u32) -> String {
+    let mut letters = String::new();
+    while col > 0 {
+        let rem = (col - 1) % 26;
+        letters.insert(0, (b'A' + rem as u8) as char);
+        col = (col - 1) / 26;
+    }
+    letters
+}
+
+fn coordinate(col: usize, row: usize) -> String {
+    format!("{}{}", column_letters(col as u32 + 1), row)
+}
+
+/// Nombre válido y único en el libro; Excel no distingue mayúsculas en los nombres de hoja
+fn sheet_name(name: &str, index: usize, used: &mut HashSet<String>) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\') { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('\'').to_string();
+    let base = if cleaned.is_empty() { format!("Sheet{}", index + 1) } else { cleaned };
+
+    let mut candidate: String = base.chars().take(MAX_SHEET_NAME_CHARS).collect();
+    let mut n = 1;
+    while !used.insert(candidate.to_lowercase()) {
+        n += 1;
+        let suffix = format!(" ({})", n);
+        let keep = MAX_SHEET_NAME_CHARS - suffix.chars().count();
+        candidate = base.chars().take(keep).collect::<String>() + &suffix;
+    }
+    candidate
+}
+
+/// Cabecera en negrita e inmovilizada y columnas ajustadas a su contenido más largo
+fn write_sheet(sheet: &mut Worksheet, data: &XlsxSheet) {
+    let mut widths: Vec<usize> = data.columns.iter().map(|c| c.chars().count()).collect();
+    for (col, name) in data.columns.iter().enumerate() {
+        let cell = sheet.get_cell_mut(coordinate(col, 1));
+        cell.set_value_string(name.clone());
+        cell.get_style_mut().get_font_mut().set_bold(true);
+    }
+
+    for (row, values) in data.rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            let width = write_cell(sheet, coordinate(col, row + 2), value);
+            if col >= widths.len() {
+                widths.resize(col + 1, 0);
+            }
+            widths[col] = widths[col].max(width);
+        }
+    }
+
+    for (col, width) in widths.into_iter().enumerate() {
+        let width = (width + 2).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        sheet
+            .get_column_dimension_mut(&column_letters(col as u32 + 1))
+            .set_width(width as f64);
+    }
+
+    if !data.columns.is_empty() {
+        freeze_header(sheet);
+    }
+}
+
+/// Valor de una celda de Excel con su tipo
+#[derive(Debug, PartialEq)]
+enum TypedCell {
+    Empty,
+    Bool(bool),
+    /// Número con su formato, si lo necesita (fechas y horas)
+    Number(f64, Option<&'static str>),
+    Text(String),
+    Link(String),
+}
+
+/// Tipo con el que se escribe cada valor: los enteros que Excel no guarda exactos, los
+/// números no finitos y las fechas sin número de serie fiable quedan como texto
+fn typed_cell(value: &CellValue) -> TypedCell {
+    if let Some((serial, format)) = date_serial(value) {
+        return TypedCell::Number(serial, Some(format));
+    }
+    match value {
+        CellValue::Null => TypedCell::Empty,
+        CellValue::Bool(b) => TypedCell::Bool(*b),
+        CellValue::Int(i) if i.unsigned_abs() <= MAX_EXACT_INTEGER => TypedCell::Number(*i as f64, None),
+        CellValue::Float(f) if f.is_finite() => TypedCell::Number(*f, None),
+        other => {
+            let mut text = cell_text(other).unwrap_or_default();
+            if text.chars().count() > MAX_CELL_CHARS {
+                text = text.chars().take(MAX_CELL_CHARS).collect();
+            }
+            if matches!(other, CellValue::String(_)) && is_url(&text) {
+                TypedCell::Link(text)
+            } else {
+                TypedCell::Text(text)
+            }
+        }
+    }
+}
+
+/// Escribe la celda con su tipo y devuelve los caracteres que ocupa
+fn write_cell(sheet: &mut Worksheet, coordinate: String, value: &CellValue) -> usize {
+    let cell = sheet.get_cell_mut(coordinate);
+    match typed_cell(value) {
+        TypedCell::Empty => 0,
+        TypedCell::Bool(b) => {
+            cell.set_value_bool(b);
+            5
+        }
+        TypedCell::Number(serial, Some(format)) => {
+            cell.set_value_number(serial);
+            cell.get_style_mut().get_number_format_mut().set_format_code(format);
+            format.len()
+        }
+        TypedCell::Number(number, None) => {
+            cell.set_value_number(number);
+            number.to_string().len()
+        }
+        TypedCell::Text(text) => {
+            let width = text_width(&text);
+            cell.set_value_string(text);
+            width
+        }
+        TypedCell::Link(url) => {
+            let width = text_width(&url);
+            let mut link = Hyperlink::default();
+            link.set_url(url.clone());
+            cell.set_hyperlink(link);
+            let font = cell.get_style_mut().get_font_mut();
+            font.set_underline("single");
+            font.get_color_mut().set_argb(HYPERLINK_COLOR);
+            cell.set_value_string(url);
+            width
+        }
+    }
+}
+
+/// Caracteres de la línea más larga
+fn text_width(text: &str) -> usize {
+    text.lines().map(|line| line.chars().count()).max().unwrap_or(0)
+}
+
+fn is_url(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    ["http://", "https://", "ftp://", "mailto:"].iter().any(|scheme| lower.starts_with(scheme))
+        && !text.contains(char::is_whitespace)
+}
+
+/// Fechas y horas como número de serie de Excel con su formato. Las fechas anteriores al
+/// 1 de marzo de 1900 no tienen un número fiable y se quedan como texto
+fn date_serial(value: &CellValue) -> Option<(f64, &'static str)> {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let first = NaiveDate::from_ymd_opt(1900, 3, 1)?.and_hms_opt(0, 0, 0)?;
+    let (moment, format) = match value {
+        CellValue::Date(s) => (NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?, DATE_FORMAT),
+        CellValue::DateTime(s) => (parse_datetime(s.trim())?, DATETIME_FORMAT),
+        CellValue::Time(s) => {
+            let time = parse_time(s.trim())?;
+            let seconds = time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1e9;
+            return Some((seconds / 86_400.0, TIME_FORMAT));
+        }
+        _ => return None,
+    };
+    if moment < first {
+        return None;
+    }
+    let elapsed = moment - epoch;
+    let seconds = elapsed.num_seconds() as f64 + elapsed.subsec_nanos() as f64 / 1e9;
+    Some((seconds / 86_400.0, format))
+}
+
+/// Excel no tiene zonas horarias: se conserva la hora tal como la mostró el servidor
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    if let Ok(moment) = DateTime::parse_from_rfc3339(s) {
+        return Some(moment.naive_local());
+    }
+    if let Ok(moment) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(moment.naive_local());
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    ["%H:%M:%S%.f", "%H:%M"].iter().find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+}
+
+fn freeze_header(sheet: &mut Worksheet) {
+    let mut pane = Pane::default();
+    pane.set_vertical_split(1.0);
+    pane.set_top_left_cell("A2");
+    pane.set_active_pane(PaneValues::BottomLeft);
+    pane.set_state(PaneStateValues::Frozen);
+
+    let views = sheet.get_sheets_views_mut().get_sheet_view_list_mut();
+    if views.is_empty() {
+        views.push(SheetView::default());
+    }
+    views[0].set_pane(pane);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_numbers_booleans_and_links() {
+        assert_eq!(typed_cell(&CellValue::Null), TypedCell::Empty);
+        assert_eq!(typed_cell(&CellValue::Bool(true)), TypedCell::Bool(true));
+        assert_eq!(typed_cell(&CellValue::Int(-42)), TypedCell::Number(-42.0, None));
+        assert_eq!(typed_cell(&CellValue::Float(1.5)), TypedCell::Number(1.5, None));
+        assert_eq!(
+            typed_cell(&CellValue::String("https://example.com/a".into())),
+            TypedCell::Link("https://example.com/a".into())
+        );
+        assert_eq!(
+            typed_cell(&CellValue::String("see https://example.com".into())),
+            TypedCell::Text("see https://example.com".into())
+        );
+    }
+
+    #[test]
+    fn keeps_values_excel_would_alter_as_text() {
+        assert_eq!(typed_cell(&CellValue::Int(999_999_999_999_999)), TypedCell::Number(999_999_999_999_999.0, None));
+        assert_eq!(typed_cell(&CellValue::Int(1_234_567_890_123_456)), TypedCell::Text("1234567890123456".into()));
+        assert!(matches!(typed_cell(&CellValue::Float(f64::NAN)), TypedCell::Text(_)));
+        assert_eq!(typed_cell(&CellValue::Date("1900-02-28".into())), TypedCell::Text("1900-02-28".into()));
+
+        let long = "x".repeat(MAX_CELL_CHARS + 10);
+        match typed_cell(&CellValue::String(long)) {
+            TypedCell::Text(text) => assert_eq!(text.chars().count(), MAX_CELL_CHARS),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn writes_dates_and_times_as_serials_with_their_format() {
+        assert_eq!(typed_cell(&CellValue::Date("2024-01-01".into())), TypedCell::Number(45292.0, Some(DATE_FORMAT)));
+        assert_eq!(
+            typed_cell(&CellValue::DateTime("2024-01-01T12:00:00+02:00".into())),
+            TypedCell::Number(45292.5, Some(DATETIME_FORMAT))
+        );
+        assert_eq!(typed_cell(&CellValue::Time("18:00".into())), TypedCell::Number(0.75, Some(TIME_FORMAT)));
+        assert_eq!(typed_cell(&CellValue::DateTime("not a date".into())), TypedCell::Text("not a date".into()));
+    }
+
+    #[test]
+    fn names_sheets_validly_and_uniquely() {
+        let mut used = HashSet::new();
+        assert_eq!(sheet_name("sales/2024?", 0, &mut used), "sales_2024_");
+        assert_eq!(sheet_name("SALES/2024?", 1, &mut used), "SALES_2024_ (2)");
+        assert_eq!(sheet_name("  ", 2, &mut used), "Sheet3");
+        let long = sheet_name(&"a".repeat(40), 3, &mut used);
+        assert_eq!(long.chars().count(), MAX_SHEET_NAME_CHARS);
+        let again = sheet_name(&"a".repeat(40), 4, &mut used);
+        assert_eq!(again, format!("{} (2)", "a".repeat(27)));
+        assert_eq!([column_letters(1), column_letters(26), column_letters(27), column_letters(703)], ["A", "Z", "AA", "AAA"]);
+    }
+}
@@ -0,0 +1,87 @@
+use serde_json::Value;
+
+use crate::domain::ImportSource;
+
+/// CSV según RFC 4180: comillas dobles para los campos con separadores o saltos de línea
+/// y `""` para una comilla dentro de ellos. Como en `COPY` de PostgreSQL, un campo vacío
+/// sin comillas es NULL y `""` es texto vacío. Las líneas en blanco se ignoran
+pub fn parse(text: &str, delimiter: char, header: bool) -> ImportSource {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    let end_field = |field: &mut String, quoted: &mut bool, record: &mut Vec<Value>| {
+        let value = if field.is_empty() && !*quoted { Value::Null } else { Value::String(std::mem::take(field)) };
+        record.push(value);
+        *quoted = false;
+    };
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            c if c == delimiter => end_field(&mut field, &mut quoted, &mut record),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                end_field(&mut field, &mut quoted, &mut record);
+                push_record(&mut records, std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || quoted || !record.is_empty() {
+        end_field(&mut field, &mut quoted, &mut record);
+        push_record(&mut records, record);
+    }
+    super::from_records(records, header)
+}
+
+fn push_record(records: &mut Vec<Vec<Value>>, record: Vec<Value>) {
+    if !(record.len() == 1 && record[0].is_null()) {
+        records.push(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_quoted_fields_with_delimiters_quotes_and_newlines() {
+        let source = parse("id;note\r\n1;\"a;b\"\r\n2;\"say \"\"hi\"\"\nthere\"\r\n", ';', true);
+        assert_eq!(source.columns, vec!["id", "note"]);
+        assert_eq!(source.rows, vec![
+            vec![json!("1"), json!("a;b")],
+            vec![json!("2"), json!("say \"hi\"\nthere")],
+        ]);
+        assert!(source.named);
+    }
+
+    #[test]
+    fn tells_null_from_empty_text_and_skips_blank_lines() {
+        let source = parse("1,,\"\"\n\n2,x,y", ',', false);
+        assert_eq!(source.columns, vec!["column1", "column2", "column3"]);
+        assert_eq!(source.rows, vec![
+            vec![json!("1"), Value::Null, json!("")],
+            vec![json!("2"), json!("x"), json!("y")],
+        ]);
+        assert!(!source.named);
+    }
+}
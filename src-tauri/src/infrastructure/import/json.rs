@@ -0,0 +1,80 @@
+use serde_json::Value;
+
+use crate::domain::{DomainError, ImportSource};
+
+/// Array JSON de objetos o de arrays, o NDJSON con un registro por línea. Con objetos las
+/// columnas son todas las claves que aparecen; con arrays, la cabecera si se pidió
+pub fn parse(text: &str, header: bool) -> Result<ImportSource, DomainError> {
+    let trimmed = text.trim();
+    let records = match serde_json::from_str::<Value>(trimmed) {
+        Ok(Value::Array(items)) => items,
+        // Un solo objeto, o varios registros por línea
+        _ => trimmed
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| DomainError::validation(format!("Line {} is not valid JSON: {}", i + 1, e)))
+            })
+            .collect::<Result<Vec<Value>, _>>()?,
+    };
+
+    if records.iter().all(Value::is_object) {
+        let mut columns: Vec<String> = Vec::new();
+        for record in &records {
+            for key in record.as_object().into_iter().flat_map(|o| o.keys()) {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+        let rows = records
+            .iter()
+            .map(|record| columns.iter().map(|c| record.get(c).cloned().unwrap_or(Value::Null)).collect())
+            .collect();
+        return Ok(ImportSource { columns, rows, named: true });
+    }
+
+    let rows = records
+        .into_iter()
+        .map(|record| match record {
+            Value::Array(values) => Ok(values),
+            _ => Err(DomainError::validation("Every JSON record must be an object, or every one an array")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(super::from_records(rows, header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collects_every_key_of_an_array_of_objects() {
+        let source = parse(r#"[{"id": 1, "name": "a"}, {"id": 2, "active": true}]"#, false).unwrap();
+        assert_eq!(source.columns, vec!["id", "name", "active"]);
+        assert_eq!(source.rows, vec![
+            vec![json!(1), json!("a"), Value::Null],
+            vec![json!(2), Value::Null, json!(true)],
+        ]);
+        assert!(source.named);
+    }
+
+    #[test]
+    fn reads_ndjson_and_arrays_with_a_header() {
+        let source = parse("{\"id\": 1}\n\n{\"id\": 2}\n", false).unwrap();
+        assert_eq!(source.rows, vec![vec![json!(1)], vec![json!(2)]]);
+
+        let source = parse(r#"[["id", "name"], [1, "a"]]"#, true).unwrap();
+        assert_eq!(source.columns, vec!["id", "name"]);
+        assert_eq!(source.rows, vec![vec![json!(1), json!("a")]]);
+    }
+
+    #[test]
+    fn rejects_mixed_records_and_invalid_lines() {
+        assert!(parse(r#"[{"id": 1}, [2]]"#, false).is_err());
+        let error = parse("{\"id\": 1}\n{oops}", false).unwrap_err().to_string();
+        assert!(error.contains("Line 2"), "{}", error);
+    }
+}
@@ -0,0 +1,41 @@
+mod csv;
+mod json;
+mod xlsx;
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::domain::{DomainError, ImportFormat, ImportOptions, ImportSource};
+
+/// Lee el fichero a importar según su formato
+pub fn read_import_source(path: &Path, options: &ImportOptions) -> Result<ImportSource, DomainError> {
+    match options.format {
+        ImportFormat::Csv => Ok(csv::parse(&read_text(path)?, options.delimiter.unwrap_or(','), options.header)),
+        ImportFormat::Json => json::parse(&read_text(path)?, options.header),
+        ImportFormat::Xlsx => xlsx::read(path, options.sheet.as_deref(), options.header),
+    }
+}
+
+fn read_text(path: &Path) -> Result<String, DomainError> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(text.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(text))
+}
+
+/// Separa la cabecera, si la hay, de los registros de una tabla leída del fichero
+fn from_records(mut records: Vec<Vec<Value>>, header: bool) -> ImportSource {
+    let columns = if header && !records.is_empty() {
+        records
+            .remove(0)
+            .into_iter()
+            .map(|name| match name {
+                Value::String(s) => s.trim().to_string(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            })
+            .collect()
+    } else {
+        ImportSource::positional_columns(records.iter().map(Vec::len).max().unwrap_or(0))
+    };
+    ImportSource { columns, rows: records, named: header }
+}
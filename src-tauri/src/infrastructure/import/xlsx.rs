@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate};
+use serde_json::Value;
+use umya_spreadsheet::{self as excel, Cell};
+
+use crate::domain::{DomainError, ImportSource};
+use crate::infrastructure::export::column_letters;
+
+/// Lee una hoja del libro; por defecto la primera. Las filas vacías se ignoran
+pub fn read(path: &Path, sheet: Option<&str>, header: bool) -> Result<ImportSource, DomainError> {
+    let book = excel::reader::xlsx::read(path)
+        .map_err(|e| DomainError::validation(format!("Could not read the workbook: {}", e)))?;
+    let sheet = match sheet {
+        Some(name) => book
+            .get_sheet_by_name(name)
+            .map_err(|_| DomainError::validation(format!("The workbook has no sheet {}", name)))?,
+        None => book
+            .get_sheet(&0)
+            .map_err(|_| DomainError::validation("The workbook has no sheets"))?,
+    };
+
+    let (columns, rows) = sheet.get_highest_column_and_row();
+    let mut records: Vec<Vec<Value>> = (1..=rows)
+        .map(|row| {
+            (1..=columns)
+                .map(|col| {
+                    sheet
+                        .get_cell(format!("{}{}", column_letters(col), row))
+                        .map(cell_value)
+                        .unwrap_or(Value::Null)
+                })
+                .collect()
+        })
+        .collect();
+    records.retain(|record| record.iter().any(|v| !v.is_null()));
+    Ok(super::from_records(records, header))
+}
+
+/// Texto de la celda. Excel guarda fechas y horas como número de días con un formato de
+/// fecha: esas se devuelven en ISO para que encajen en columnas de fecha
+fn cell_value(cell: &Cell) -> Value {
+    let text = cell.get_value().to_string();
+    if text.is_empty() {
+        return Value::Null;
+    }
+    let format = cell
+        .get_style()
+        .get_number_format()
+        .map(|f| f.get_format_code().to_lowercase())
+        .unwrap_or_default();
+    match text.parse::<f64>() {
+        Ok(serial) => date_text(serial, &format).map(Value::String).unwrap_or(Value::String(text)),
+        Err(_) => Value::String(text),
+    }
+}
+
+fn date_text(serial: f64, format: &str) -> Option<String> {
+    // Lo que va entre comillas o corchetes es texto literal, colores o configuración regional
+    let mut code = String::new();
+    let mut skip = None;
+    for c in format.chars() {
+        match (skip, c) {
+            (None, '"') => skip = Some('"'),
+            (None, '[') => skip = Some(']'),
+            (Some(end), c) if c == end => skip = None,
+            (None, c) => code.push(c),
+            _ => {}
+        }
+    }
+    let has_date = code.contains('y') || code.contains('d');
+    let has_time = code.contains('h') || code.contains('s');
+    if !has_date && !has_time {
+        return None;
+    }
+
+    // Día 0 de Excel, contando su 29 de febrero de 1900 inexistente
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let seconds = (serial * 86_400.0).round() as i64;
+    let moment = epoch.checked_add_signed(Duration::seconds(seconds))?;
+    Some(match (has_date, has_time) {
+        (true, false) => moment.format("%Y-%m-%d").to_string(),
+        (false, true) => moment.format("%H:%M:%S").to_string(),
+        _ => moment.format("%Y-%m-%d %H:%M:%S").to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_serials_with_date_and_time_formats() {
+        assert_eq!(date_text(45292.0, "yyyy-mm-dd").as_deref(), Some("2024-01-01"));
+        assert_eq!(date_text(0.75, "hh:mm:ss").as_deref(), Some("18:00:00"));
+        assert_eq!(date_text(45292.5, "dd/mm/yyyy hh:mm").as_deref(), Some("2024-01-01 12:00:00"));
+        // Antes del 1 de marzo de 1900 Excel cuenta un 29 de febrero que no existió
+        assert_eq!(date_text(61.0, "d-mmm-yy").as_deref(), Some("1900-03-01"));
+    }
+
+    #[test]
+    fn keeps_plain_numbers_and_ignores_literal_text_in_formats() {
+        assert_eq!(date_text(1234.5, "0.00"), None);
+        assert_eq!(date_text(1234.5, "#,##0 \"days\""), None);
+        assert_eq!(date_text(1234.5, "[red]0.0"), None);
+    }
+}
@@ -1,4 +1,12 @@
 pub mod drivers;
+pub mod events;
+pub mod export;
+pub mod import;
 pub mod repositories;
+pub mod tunnel;
 
+pub use events::*;
+pub use export::*;
+pub use import::*;
 pub use repositories::*;
+pub use tunnel::*;
@@ -93,7 +93,11 @@ impl ConnectionRepository for FileConnectionRepository {
         let port = dto.port.or_else(|| match dto.engine {
             crate::domain::DatabaseEngine::PostgreSQL => Some(5432),
             crate::domain::DatabaseEngine::MySQL => Some(3306),
+            crate::domain::DatabaseEngine::MariaDB => Some(3306),
             crate::domain::DatabaseEngine::SQLite => None,
+            crate::domain::DatabaseEngine::SqlServer => Some(1433),
+            crate::domain::DatabaseEngine::DuckDB => None,
+            crate::domain::DatabaseEngine::ClickHouse => Some(8123),
         });
         
         let connection = Connection {
@@ -106,9 +110,11 @@ impl ConnectionRepository for FileConnectionRepository {
             username: dto.username,
             file_path: dto.file_path,
             ssl: dto.ssl.unwrap_or_default(),
-            ssh_tunnel: None,
+            ssh_tunnel: dto.ssh_tunnel,
             color: dto.color,
             read_only: dto.read_only.unwrap_or(false),
+            statement_timeout_ms: dto.statement_timeout_ms.filter(|ms| *ms > 0),
+            production: dto.production.unwrap_or(false),
             created_at: now,
             updated_at: now,
             last_connected_at: None,
@@ -137,8 +143,12 @@ impl ConnectionRepository for FileConnectionRepository {
         if let Some(database) = dto.database { conn.database = Some(database); }
         if let Some(username) = dto.username { conn.username = Some(username); }
         if let Some(ssl) = dto.ssl { conn.ssl = ssl; }
+        if let Some(tunnel) = dto.ssh_tunnel { conn.ssh_tunnel = Some(tunnel); }
+        if dto.remove_ssh_tunnel == Some(true) { conn.ssh_tunnel = None; }
         if let Some(color) = dto.color { conn.color = Some(color); }
         if let Some(ro) = dto.read_only { conn.read_only = ro; }
+        if let Some(ms) = dto.statement_timeout_ms { conn.statement_timeout_ms = (ms > 0).then_some(ms); }
+        if let Some(production) = dto.production { conn.production = production; }
         conn.updated_at = Utc::now();
 
         let updated = conn.clone();
@@ -185,8 +195,12 @@ impl ConnectionRepository for FileConnectionRepository {
             password: None,
             file_path: original.file_path,
             ssl: Some(original.ssl),
+            ssh_tunnel: original.ssh_tunnel,
+            ssh_secret: None,
             color: original.color,
             read_only: Some(original.read_only),
+            statement_timeout_ms: original.statement_timeout_ms,
+            production: Some(original.production),
         };
         self.create(dto).await
     }
@@ -1,8 +1,9 @@
-use crate::domain::entities::{CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
+use crate::domain::entities::{CellValue, CreateSavedQueryDto, QueryFolder, SavedQuery, UpdateSavedQueryDto};
 use crate::domain::error::DomainError;
 use crate::domain::ports::SavedQueryRepository;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::fs;
 use tokio::sync::RwLock;
@@ -126,6 +127,7 @@ impl SavedQueryRepository for FileSavedQueryRepository {
         if let Some(folder_id) = dto.folder_id {
             query = query.with_folder(folder_id);
         }
+        query.sync_parameters(dto.parameters);
 
         let mut cache = self.cache.write().await;
         cache.queries.push(query.clone());
@@ -159,6 +161,7 @@ impl SavedQueryRepository for FileSavedQueryRepository {
         if let Some(folder_id) = dto.folder_id {
             query.folder_id = Some(folder_id);
         }
+        query.sync_parameters(dto.parameters);
 
         query.updated_at = chrono::Utc::now();
         let updated = query.clone();
@@ -168,6 +171,28 @@ impl SavedQueryRepository for FileSavedQueryRepository {
         Ok(updated)
     }
 
+    async fn record_parameter_values(
+        &self,
+        id: Uuid,
+        values: HashMap<String, CellValue>,
+    ) -> Result<SavedQuery, DomainError> {
+        let mut cache = self.cache.write().await;
+
+        let query = cache
+            .queries
+            .iter_mut()
+            .find(|q| q.id == id)
+            .ok_or_else(|| DomainError::not_found(format!("Saved query {}", id)))?;
+
+        // Ejecutar la consulta no cuenta como modificación: updated_at no cambia
+        query.remember_values(&values);
+        let updated = query.clone();
+        drop(cache);
+
+        self.save_to_disk().await?;
+        Ok(updated)
+    }
+
     async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
         let mut cache = self.cache.write().await;
         cache.queries.retain(|q| q.id != id);
@@ -16,6 +16,12 @@ impl KeychainCredentialStore {
         Entry::new(KEYCHAIN_SERVICE, &connection_id.to_string())
             .map_err(|e| DomainError::CredentialError { message: e.to_string() })
     }
+
+    /// Entrada separada para el secreto SSH, así no pisa el password de la base de datos
+    fn get_ssh_entry(&self, connection_id: Uuid) -> Result<Entry, DomainError> {
+        Entry::new(KEYCHAIN_SERVICE, &format!("{}:ssh", connection_id))
+            .map_err(|e| DomainError::CredentialError { message: e.to_string() })
+    }
 }
 
 impl Default for KeychainCredentialStore {
@@ -63,4 +69,29 @@ impl CredentialStore for KeychainCredentialStore {
     async fn update(&self, connection_id: Uuid, password: &str) -> Result<(), DomainError> {
         self.store(connection_id, password).await
     }
+
+    async fn store_ssh_secret(&self, connection_id: Uuid, secret: &str) -> Result<(), DomainError> {
+        let entry = self.get_ssh_entry(connection_id)?;
+        entry
+            .set_password(secret)
+            .map_err(|e| DomainError::CredentialError { message: e.to_string() })
+    }
+
+    async fn retrieve_ssh_secret(&self, connection_id: Uuid) -> Result<Option<String>, DomainError> {
+        let entry = self.get_ssh_entry(connection_id)?;
+        match entry.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(DomainError::CredentialError { message: e.to_string() }),
+        }
+    }
+
+    async fn delete_ssh_secret(&self, connection_id: Uuid) -> Result<(), DomainError> {
+        let entry = self.get_ssh_entry(connection_id)?;
+        match entry.delete_password() {
+            Ok(()) => Ok(()),
+            Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(DomainError::CredentialError { message: e.to_string() }),
+        }
+    }
 }
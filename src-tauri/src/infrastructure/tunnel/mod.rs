@@ -0,0 +1,3 @@
+mod ssh_tunnel;
+
+pub use ssh_tunnel::SshTunnel;
@@ -0,0 +1,590 @@
+use polling::{Event, Poller};
+use ssh2::{BlockDirections, Channel, CheckResult, HashType, HostKeyType, KnownHostFileKind, Session};
+use std::collections::BTreeMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::domain::{DomainError, SshAuthMethod, SshTunnelConfig};
+
+/// Tiempo máximo para establecer la conexión TCP con el servidor SSH
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Claves del poller para el listener y el socket SSH; las conexiones usan las siguientes
+const LISTENER_KEY: usize = 0;
+const SESSION_KEY: usize = 1;
+
+/// Túnel SSH con reenvío de puerto local.
+/// Escucha en 127.0.0.1 en un puerto libre y reenvía cada conexión al host remoto
+/// a través del servidor SSH. Se cierra al llamar a `close` o al soltarlo.
+pub struct SshTunnel {
+    local_port: u16,
+    shutdown: Arc<AtomicBool>,
+    poller: Arc<Poller>,
+}
+
+impl SshTunnel {
+    /// Abre la sesión SSH (verificando el host en known_hosts) y empieza a escuchar
+    pub async fn open(
+        config: SshTunnelConfig,
+        secret: Option<String>,
+        remote_host: String,
+        remote_port: u16,
+    ) -> Result<Self, DomainError> {
+        tokio::task::spawn_blocking(move || {
+            Self::open_blocking(&config, secret.as_deref(), remote_host, remote_port)
+        })
+        .await
+        .map_err(|e| DomainError::internal(e.to_string()))?
+    }
+
+    /// Puerto local al que debe conectarse el driver
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    /// Deja de aceptar conexiones y cierra las que estén abiertas
+    pub fn close(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        self.poller.notify().ok();
+    }
+
+    fn open_blocking(
+        config: &SshTunnelConfig,
+        secret: Option<&str>,
+        remote_host: String,
+        remote_port: u16,
+    ) -> Result<Self, DomainError> {
+        let (session, socket) = Self::connect_session(config, secret)?;
+
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .map_err(|e| DomainError::connection(format!("SSH tunnel: cannot bind local port: {}", e)))?;
+        let local_port = listener
+            .local_addr()
+            .map_err(|e| DomainError::connection(e.to_string()))?
+            .port();
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| DomainError::connection(e.to_string()))?;
+
+        // A partir de aquí todos los canales comparten la sesión sin bloquearla
+        session.set_blocking(false);
+
+        let poller = Arc::new(Poller::new().map_err(|e| DomainError::internal(e.to_string()))?);
+        poller
+            .add(&listener, Event::readable(LISTENER_KEY))
+            .and_then(|_| poller.add(&socket, Event::readable(SESSION_KEY)))
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let forwarder = Forwarder {
+            listener,
+            session,
+            socket,
+            poller: poller.clone(),
+            shutdown: shutdown.clone(),
+            remote_host,
+            remote_port,
+            local_port,
+            connections: BTreeMap::new(),
+            next_key: SESSION_KEY + 1,
+        };
+        thread::Builder::new()
+            .name(format!("ssh-tunnel-{}", local_port))
+            .spawn(move || forwarder.run())
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+
+        tracing::info!("SSH tunnel listening on 127.0.0.1:{}", local_port);
+        Ok(Self { local_port, shutdown, poller })
+    }
+
+    /// Sesión autenticada y una copia de su socket para esperar en él sin pasar por libssh2
+    fn connect_session(
+        config: &SshTunnelConfig,
+        secret: Option<&str>,
+    ) -> Result<(Session, TcpStream), DomainError> {
+        let addr = (config.host.as_str(), config.port)
+            .to_socket_addrs()
+            .map_err(|e| DomainError::connection(format!("SSH host {}: {}", config.host, e)))?
+            .next()
+            .ok_or_else(|| DomainError::connection(format!("SSH host {} not found", config.host)))?;
+        let tcp = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+            .map_err(|e| DomainError::connection(format!("SSH {}:{}: {}", config.host, config.port, e)))?;
+
+        let socket = tcp.try_clone().map_err(|e| DomainError::connection(e.to_string()))?;
+
+        let mut session = Session::new().map_err(|e| DomainError::connection(e.to_string()))?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(CONNECT_TIMEOUT.as_millis() as u32);
+        session
+            .handshake()
+            .map_err(|e| DomainError::connection(format!("SSH handshake failed: {}", e)))?;
+
+        Self::verify_host_key(&session, config)?;
+
+        match &config.auth_method {
+            SshAuthMethod::Password => {
+                session.userauth_password(&config.username, secret.unwrap_or_default())
+            }
+            SshAuthMethod::PrivateKey { key_path } => {
+                session.userauth_pubkey_file(&config.username, None, &expand_home(key_path), secret)
+            }
+        }
+        .map_err(|e| DomainError::AuthenticationError { message: format!("SSH: {}", e) })?;
+
+        if !session.authenticated() {
+            return Err(DomainError::AuthenticationError {
+                message: "SSH: authentication rejected".to_string(),
+            });
+        }
+
+        session.set_timeout(0);
+        Ok((session, socket))
+    }
+
+    /// Comprueba la clave del servidor contra known_hosts
+    fn verify_host_key(session: &Session, config: &SshTunnelConfig) -> Result<(), DomainError> {
+        let (key, key_type) = session
+            .host_key()
+            .ok_or_else(|| DomainError::connection("SSH server did not send a host key"))?;
+        let fingerprint = session
+            .host_key_hash(HashType::Sha256)
+            .map(|hash| hash.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"))
+            .unwrap_or_default();
+
+        let path = config
+            .known_hosts_path
+            .as_deref()
+            .map(expand_home)
+            .or_else(default_known_hosts)
+            .ok_or_else(|| DomainError::ConfigurationError {
+                message: "SSH: cannot locate known_hosts file".to_string(),
+            })?;
+
+        let mut known_hosts = session
+            .known_hosts()
+            .map_err(|e| DomainError::connection(e.to_string()))?;
+        if path.exists() {
+            known_hosts
+                .read_file(&path, KnownHostFileKind::OpenSSH)
+                .map_err(|e| DomainError::ConfigurationError {
+                    message: format!("SSH: cannot read {}: {}", path.display(), e),
+                })?;
+        }
+
+        match known_hosts.check_port(&config.host, config.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::NotFound if config.accept_unknown_host => {
+                let entry = if config.port == 22 {
+                    config.host.clone()
+                } else {
+                    format!("[{}]:{}", config.host, config.port)
+                };
+                Self::append_known_host(session, &path, &entry, key, key_type)
+                    .map_err(|e| DomainError::ConfigurationError {
+                        message: format!("SSH: cannot update {}: {}", path.display(), e),
+                    })?;
+                tracing::info!("SSH host {} added to {} (SHA256 {})", entry, path.display(), fingerprint);
+                Ok(())
+            }
+            CheckResult::NotFound => Err(DomainError::AuthenticationError {
+                message: format!(
+                    "SSH host {} is not in {} (SHA256 fingerprint {})",
+                    config.host,
+                    path.display(),
+                    fingerprint
+                ),
+            }),
+            CheckResult::Mismatch => Err(DomainError::AuthenticationError {
+                message: format!(
+                    "SSH host key for {} does not match {} (got SHA256 {}); refusing to connect",
+                    config.host,
+                    path.display(),
+                    fingerprint
+                ),
+            }),
+            CheckResult::Failure => Err(DomainError::connection("SSH: host key check failed")),
+        }
+    }
+
+    /// Añade una línea a known_hosts sin reescribir las entradas existentes
+    fn append_known_host(
+        session: &Session,
+        path: &Path,
+        entry: &str,
+        key: &[u8],
+        key_type: HostKeyType,
+    ) -> Result<(), String> {
+        let mut single = session.known_hosts().map_err(|e| e.to_string())?;
+        single
+            .add(entry, key, "added by QueryX", key_type.into())
+            .map_err(|e| e.to_string())?;
+        let host = single
+            .hosts()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .next()
+            .ok_or("empty known_hosts entry")?;
+        let line = single
+            .write_string(&host, KnownHostFileKind::OpenSSH)
+            .map_err(|e| e.to_string())?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line.trim_end()).map_err(|e| e.to_string())
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Bucle del túnel: un solo hilo acepta conexiones y mueve los datos de todas ellas,
+/// bloqueado en el poller hasta que algún socket está listo o se cierra el túnel
+struct Forwarder {
+    listener: TcpListener,
+    session: Session,
+    socket: TcpStream,
+    poller: Arc<Poller>,
+    shutdown: Arc<AtomicBool>,
+    remote_host: String,
+    remote_port: u16,
+    local_port: u16,
+    connections: BTreeMap<usize, Forward>,
+    next_key: usize,
+}
+
+impl Forwarder {
+    fn run(mut self) {
+        let mut buf = vec![0u8; 32 * 1024];
+        let mut events = Vec::new();
+
+        while !self.shutdown.load(Ordering::Relaxed) {
+            if let Err(e) = self.step(&mut buf, &mut events) {
+                tracing::error!("SSH tunnel on 127.0.0.1:{} failed: {}", self.local_port, e);
+                break;
+            }
+        }
+
+        for forward in self.connections.values_mut() {
+            forward.close(&self.poller);
+        }
+        self.session.disconnect(None, "tunnel closed", None).ok();
+        tracing::info!("SSH tunnel on 127.0.0.1:{} closed", self.local_port);
+    }
+
+    /// Atiende todo lo que esté listo y espera al siguiente evento
+    fn step(&mut self, buf: &mut [u8], events: &mut Vec<Event>) -> std::io::Result<()> {
+        self.accept()?;
+        self.pump(buf);
+        self.rearm()?;
+        events.clear();
+        self.poller.wait(events, None)?;
+        Ok(())
+    }
+
+    /// Acepta las conexiones locales pendientes; el canal se abre al moverlas
+    fn accept(&mut self) -> std::io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(true)?;
+                    let key = self.next_key;
+                    self.next_key += 1;
+                    self.poller.add(&stream, Event::none(key))?;
+                    self.connections.insert(key, Forward::new(stream));
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Mueve datos en todas las conexiones hasta que ninguna puede avanzar sin esperar.
+    /// Leer un canal puede dejar en libssh2 datos de otros, así que se repasan todas cada vez.
+    /// libssh2 solo lleva una apertura de canal a la vez: se abren por orden de llegada.
+    fn pump(&mut self, buf: &mut [u8]) {
+        loop {
+            let mut progress = false;
+            let mut finished = Vec::new();
+            let mut may_open = true;
+
+            for (key, forward) in self.connections.iter_mut() {
+                if forward.channel.is_none() && !std::mem::take(&mut may_open) {
+                    continue;
+                }
+                match forward.pump(&self.session, &self.remote_host, self.remote_port, self.local_port, buf) {
+                    Ok(Pump::Idle) => {}
+                    Ok(Pump::Progress) => progress = true,
+                    Ok(Pump::Done) => finished.push(*key),
+                    Err(e) => {
+                        tracing::warn!("SSH tunnel connection closed: {}", e);
+                        finished.push(*key);
+                    }
+                }
+            }
+            for key in finished {
+                if let Some(mut forward) = self.connections.remove(&key) {
+                    forward.close(&self.poller);
+                }
+            }
+
+            if !progress {
+                return;
+            }
+        }
+    }
+
+    /// Vuelve a armar el poller con lo que espera cada socket.
+    /// El socket SSH solo se lee cuando alguna conexión puede aceptar datos del servidor,
+    /// para no girar en vacío mientras los clientes locales no leen.
+    fn rearm(&self) -> std::io::Result<()> {
+        self.poller.modify(&self.listener, Event::readable(LISTENER_KEY))?;
+
+        let mut session_interest = Event::none(SESSION_KEY);
+        session_interest.readable = self.connections.values().any(Forward::waits_on_session);
+        session_interest.writable =
+            matches!(self.session.block_directions(), BlockDirections::Outbound | BlockDirections::Both);
+        self.poller.modify(&self.socket, session_interest)?;
+
+        for (key, forward) in &self.connections {
+            self.poller.modify(&forward.stream, forward.interest(*key))?;
+        }
+        Ok(())
+    }
+}
+
+enum Pump {
+    Idle,
+    Progress,
+    Done,
+}
+
+/// Conexión local reenviada por un canal direct-tcpip, con lo leído de cada lado
+/// que aún no se ha podido escribir en el otro
+struct Forward {
+    stream: TcpStream,
+    channel: Option<Channel>,
+    to_remote: Vec<u8>,
+    to_local: Vec<u8>,
+    local_eof: bool,
+}
+
+impl Forward {
+    fn new(stream: TcpStream) -> Self {
+        Self { stream, channel: None, to_remote: Vec::new(), to_local: Vec::new(), local_eof: false }
+    }
+
+    /// Un paso sin bloquear en cada sentido. Termina cuando cualquiera de los dos extremos
+    /// cierra y lo que envió ya ha llegado al otro.
+    fn pump(
+        &mut self,
+        session: &Session,
+        remote_host: &str,
+        remote_port: u16,
+        local_port: u16,
+        buf: &mut [u8],
+    ) -> std::io::Result<Pump> {
+        let channel = match &mut self.channel {
+            Some(channel) => channel,
+            None => match session.channel_direct_tcpip(remote_host, remote_port, Some(("127.0.0.1", local_port))) {
+                Ok(channel) => self.channel.insert(channel),
+                Err(e) => return would_block(e.into()).map(|_| Pump::Idle),
+            },
+        };
+        let mut progress = false;
+
+        if self.to_remote.is_empty() && !self.local_eof {
+            match self.stream.read(buf) {
+                Ok(0) => self.local_eof = true,
+                Ok(n) => {
+                    self.to_remote.extend_from_slice(&buf[..n]);
+                    progress = true;
+                }
+                Err(e) => would_block(e)?,
+            }
+        }
+        progress |= write_pending(channel, &mut self.to_remote)?;
+
+        if self.to_local.is_empty() {
+            match channel.read(buf) {
+                Ok(0) => {}
+                Ok(n) => {
+                    self.to_local.extend_from_slice(&buf[..n]);
+                    progress = true;
+                }
+                Err(e) => would_block(e)?,
+            }
+        }
+        progress |= write_pending(&mut self.stream, &mut self.to_local)?;
+
+        if (self.local_eof && self.to_remote.is_empty()) || (channel.eof() && self.to_local.is_empty()) {
+            return Ok(Pump::Done);
+        }
+        Ok(if progress { Pump::Progress } else { Pump::Idle })
+    }
+
+    /// true si la conexión está esperando algo del servidor SSH: el canal, datos
+    /// o que se abra la ventana para seguir enviando
+    fn waits_on_session(&self) -> bool {
+        self.channel.is_none() || self.to_local.is_empty() || !self.to_remote.is_empty()
+    }
+
+    fn interest(&self, key: usize) -> Event {
+        let mut event = Event::none(key);
+        event.readable = self.channel.is_some() && self.to_remote.is_empty() && !self.local_eof;
+        event.writable = !self.to_local.is_empty();
+        event
+    }
+
+    fn close(&mut self, poller: &Poller) {
+        poller.delete(&self.stream).ok();
+        if let Some(channel) = &mut self.channel {
+            channel.close().ok();
+        }
+    }
+}
+
+/// Escribe lo que admita el destino sin bloquear y lo quita de `pending`; true si escribió algo
+fn write_pending(writer: &mut impl Write, pending: &mut Vec<u8>) -> std::io::Result<bool> {
+    let mut written = 0;
+    while written < pending.len() {
+        match writer.write(&pending[written..]) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Ok(n) => written += n,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(e) => return Err(e),
+        }
+    }
+    pending.drain(..written);
+    Ok(written > 0)
+}
+
+fn would_block(e: std::io::Error) -> std::io::Result<()> {
+    if e.kind() == ErrorKind::WouldBlock {
+        Ok(())
+    } else {
+        Err(e)
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn default_known_hosts() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".ssh").join("known_hosts"))
+}
+
+/// Expande `~/` al directorio del usuario
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => Path::new(path).to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Destino que acepta como mucho `capacity` bytes y después se bloquearía
+    struct Limited {
+        written: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.capacity - self.written.len()).min(3);
+            if n == 0 {
+                return Err(ErrorKind::WouldBlock.into());
+            }
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn config(port: u16) -> SshTunnelConfig {
+        SshTunnelConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            username: "tester".to_string(),
+            auth_method: SshAuthMethod::Password,
+            known_hosts_path: None,
+            accept_unknown_host: false,
+        }
+    }
+
+    #[test]
+    fn keeps_what_the_destination_could_not_take() {
+        let mut writer = Limited { written: Vec::new(), capacity: 7 };
+        let mut pending = b"0123456789".to_vec();
+        assert!(write_pending(&mut writer, &mut pending).unwrap());
+        assert_eq!(writer.written, b"0123456");
+        assert_eq!(pending, b"789");
+
+        // Sin hueco no escribe nada, pero tampoco es un error
+        assert!(!write_pending(&mut writer, &mut pending).unwrap());
+        assert_eq!(pending, b"789");
+    }
+
+    #[test]
+    fn treats_a_zero_write_as_a_closed_destination() {
+        let mut pending = b"data".to_vec();
+        let error = write_pending(&mut &mut [0u8; 0][..], &mut pending).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::WriteZero);
+        assert!(would_block(ErrorKind::WouldBlock.into()).is_ok());
+        assert!(would_block(ErrorKind::ConnectionReset.into()).is_err());
+    }
+
+    #[test]
+    fn expands_the_home_directory_only_at_the_start() {
+        if let Some(home) = home_dir() {
+            assert_eq!(expand_home("~/.ssh/id_ed25519"), home.join(".ssh/id_ed25519"));
+            assert_eq!(default_known_hosts(), Some(home.join(".ssh").join("known_hosts")));
+        }
+        assert_eq!(expand_home("/etc/ssh/key"), PathBuf::from("/etc/ssh/key"));
+        assert_eq!(expand_home("keys/~/id"), PathBuf::from("keys/~/id"));
+    }
+
+    #[tokio::test]
+    async fn reports_an_unreachable_ssh_server() {
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let error = SshTunnel::open(config(port), Some("secret".into()), "db".into(), 5432).await.err().unwrap();
+        assert!(matches!(error, DomainError::ConnectionError { .. }), "{:?}", error);
+        assert!(error.to_string().contains(&format!("SSH 127.0.0.1:{}", port)), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn fails_the_handshake_with_a_server_that_does_not_speak_ssh() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").ok();
+        });
+
+        let error = SshTunnel::open(config(port), None, "db".into(), 5432).await.err().unwrap();
+        server.join().unwrap();
+        assert!(error.to_string().contains("SSH handshake failed"), "{}", error);
+    }
+}
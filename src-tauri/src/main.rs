@@ -13,9 +13,9 @@ use std::sync::{Arc, Mutex};
 use tauri::Manager;
 use tokio::sync::OnceCell;
 
-use application::{ConnectionUseCase, QueryUseCase, SavedQueryUseCase, SchemaUseCase, WorkspaceUseCase};
+use application::{ConnectionUseCase, DumpUseCase, ExportUseCase, ImportUseCase, QueryUseCase, SavedQueryUseCase, SchemaUseCase, WorkspaceUseCase};
 use commands::*;
-use infrastructure::{FileSavedQueryRepository, FileWorkspaceRepository, KeychainCredentialStore};
+use infrastructure::{FileSavedQueryRepository, FileWorkspaceRepository, KeychainCredentialStore, TauriEventBus};
 
 static CONNECTION_REPO: OnceCell<Arc<dyn domain::ConnectionRepository>> = OnceCell::const_new();
 static HISTORY_REPO: OnceCell<Arc<dyn domain::QueryHistoryRepository>> = OnceCell::const_new();
@@ -204,9 +204,31 @@ fn main() {
                 credential_store,
             ));
 
+            let event_bus = Arc::new(TauriEventBus::new(app_handle.clone()))
+                as Arc<dyn domain::EventBus>;
+
             let query_use_case = Arc::new(QueryUseCase::new_lazy(
                 connection_use_case.clone(),
                 data_dir_clone,
+                event_bus.clone(),
+            ));
+
+            let import_use_case = Arc::new(ImportUseCase::new(
+                connection_use_case.clone(),
+                query_use_case.clone(),
+                event_bus.clone(),
+            ));
+
+            let export_use_case = Arc::new(ExportUseCase::new(
+                connection_use_case.clone(),
+                query_use_case.clone(),
+                event_bus.clone(),
+            ));
+
+            let dump_use_case = Arc::new(DumpUseCase::new(
+                connection_use_case.clone(),
+                query_use_case.clone(),
+                event_bus,
             ));
 
             let schema_use_case = Arc::new(SchemaUseCase::new(connection_use_case.clone()));
@@ -238,6 +260,9 @@ fn main() {
             // Registrar estados
             app.manage(connection_use_case);
             app.manage(query_use_case);
+            app.manage(import_use_case);
+            app.manage(export_use_case);
+            app.manage(dump_use_case);
             app.manage(schema_use_case);
             app.manage(saved_query_use_case);
             app.manage(workspace_use_case);
@@ -255,18 +280,36 @@ fn main() {
             connect,
             disconnect,
             get_connection_status,
+            get_connection_tls,
             change_database,
             change_schema,
             get_active_context,
             // Query commands
             execute_query,
+            execute_query_stream,
+            ack_query_stream_batch,
             insert_row,
+            update_rows,
+            delete_rows,
             execute_statement,
             execute_multi_statement,
+            dry_run_statements,
+            split_sql_script,
+            run_script,
+            explain_query,
             execute_in_transaction,
+            begin_transaction,
+            commit_transaction,
+            rollback_transaction,
+            create_savepoint,
+            rollback_to_savepoint,
+            get_transaction_status,
             get_query_history,
             search_query_history,
             cancel_query,
+            list_running_queries,
+            close_query_cursor,
+            detect_query_parameters,
             // Schema commands
             list_databases,
             get_database_info,
@@ -280,12 +323,14 @@ fn main() {
             list_views,
             list_functions,
             list_sequences,
+            get_object_ddl,
             get_server_version,
             // Saved query commands
             get_saved_queries,
             get_saved_query,
             create_saved_query,
             update_saved_query,
+            record_saved_query_parameters,
             delete_saved_query,
             find_saved_queries_by_tags,
             get_query_folders,
@@ -298,6 +343,15 @@ fn main() {
             get_all_workspaces,
             // Export commands
             export_results_xlsx,
+            export_xlsx_workbook,
+            export_statement_results_xlsx,
+            export_query,
+            // Import commands
+            preview_import_file,
+            import_table_data,
+            // Dump commands
+            dump_database,
+            restore_dump,
             // App commands
             get_launch_file,
         ])
@@ -38,6 +38,7 @@ export function CommandPalette() {
     if (!name) return '';
     switch (eng) {
       case 'mysql':
+      case 'mariadb':
         return `\`${name}\``;
       case 'postgresql':
         return `"${name}"`;
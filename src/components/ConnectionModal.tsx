@@ -7,15 +7,21 @@ import type { DatabaseEngine, CreateConnectionDto } from '../domain/types';
 
 const engines: { value: DatabaseEngine; label: string; icon: string }[] = [
   { value: 'postgresql', label: 'PostgreSQL', icon: '🐘' },
-  { value: 'mysql', label: 'MySQL / MariaDB', icon: '🐬' },
+  { value: 'mysql', label: 'MySQL', icon: '🐬' },
+  { value: 'mariadb', label: 'MariaDB', icon: '🦭' },
   { value: 'sqlite', label: 'SQLite', icon: '📦' },
+  { value: 'duckdb', label: 'DuckDB', icon: '🦆' },
+  { value: 'clickhouse', label: 'ClickHouse', icon: '🟨' },
 ];
 
 const defaultPorts: Record<DatabaseEngine, number> = {
   postgresql: 5432,
   mysql: 3306,
+  mariadb: 3306,
   sqlite: 0,
   sqlserver: 1433,
+  duckdb: 0,
+  clickhouse: 8123,
 };
 
 export function ConnectionModal() {
@@ -43,12 +49,12 @@ export function ConnectionModal() {
   const [isSaving, setIsSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const isSQLite = form.engine === 'sqlite';
+  const isFileBased = form.engine === 'sqlite' || form.engine === 'duckdb';
 
   const getPayload = (): CreateConnectionDto => {
     // Sanitización explícita campo por campo para asegurar tipos primitivos
     const safeName = String(form.name || '');
-    const safeEngine = (['postgresql', 'mysql', 'sqlite', 'sqlserver'].includes(form.engine) ? form.engine : 'postgresql') as DatabaseEngine;
+    const safeEngine = (['postgresql', 'mysql', 'mariadb', 'sqlite', 'sqlserver', 'duckdb', 'clickhouse'].includes(form.engine) ? form.engine : 'postgresql') as DatabaseEngine;
     const safeHost = String(form.host || 'localhost');
     const safePort = Number(form.port) || defaultPorts[safeEngine];
     const safeDatabase = form.database ? String(form.database) : undefined;
@@ -59,12 +65,12 @@ export function ConnectionModal() {
     const rawPayload = {
       name: safeName,
       engine: safeEngine,
-      database: isSQLite ? safeFilePath : safeDatabase,
-      host: isSQLite ? undefined : safeHost,
-      port: isSQLite ? undefined : safePort,
-      username: isSQLite ? undefined : safeUsername,
-      password: isSQLite ? undefined : safePassword,
-      file_path: isSQLite ? safeFilePath : undefined,
+      database: isFileBased ? safeFilePath : safeDatabase,
+      host: isFileBased ? undefined : safeHost,
+      port: isFileBased ? undefined : safePort,
+      username: isFileBased ? undefined : safeUsername,
+      password: isFileBased ? undefined : safePassword,
+      file_path: isFileBased ? safeFilePath : undefined,
       read_only: form.read_only ?? false,
     };
     
@@ -225,8 +231,8 @@ export function ConnectionModal() {
             />
           </div>
 
-          {isSQLite ? (
-            /* SQLite: File Path */
+          {isFileBased ? (
+            /* SQLite / DuckDB: File Path */
             <div>
               <label className="label">Database File</label>
               <input
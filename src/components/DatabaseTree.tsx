@@ -51,6 +51,7 @@ const getCellValue = (cell: CellValue | undefined): any => {
 function quoteIdentifier(name: string, engine: DatabaseEngine): string {
   switch (engine) {
     case 'mysql':
+    case 'mariadb':
       return `\`${name}\``;
     case 'postgresql':
       return `"${name}"`;
@@ -845,6 +845,7 @@ export function ResultsTable({ tab, hideMenu }: ResultsTableProps) {
   const quoteIdentifier = useCallback((name: string) => {
     switch (engine) {
       case 'mysql':
+      case 'mariadb':
         return `\`${name}\``;
       case 'postgresql':
       case 'sqlite':
@@ -924,7 +925,7 @@ export function ResultsTable({ tab, hideMenu }: ResultsTableProps) {
   }, [result, buildWhereClause, queryAnalysis, formatTableName, query, executeQuery, tab.id]);
 
   const handlePageChange = (page: number) => {
-    executeQuery(tab.id, undefined, page, result?.pagination?.page_size || 50);
+    executeQuery(tab.id, undefined, page, result?.pagination?.page_size || 50, result?.pagination?.cursor);
   };
   
   const handleExportCsv = useCallback(async () => {
@@ -1621,7 +1622,7 @@ export function ResultsTable({ tab, hideMenu }: ResultsTableProps) {
         <div className="flex items-center justify-between px-3 py-1.5 border-t border-dark-border/30 bg-dark-surface/50 backdrop-blur-sm">
           <span className="text-xs text-dark-muted/70">
             Page {result.pagination.page} of {result.pagination.total_pages || '?'}
-            {result.pagination.total_rows && ` (${result.pagination.total_rows} total)`}
+            {result.pagination.total_rows && ` (${result.pagination.total_is_estimate ? '~' : ''}${result.pagination.total_rows} total)`}
           </span>
           <div className="flex items-center gap-1">
             <button
@@ -1,5 +1,14 @@
 // Tipos para consultas guardadas
 
+import type { CellValue, PlaceholderStyle } from './types';
+
+export interface SavedQueryParameter {
+  name: string;
+  style: PlaceholderStyle;
+  data_type?: string;
+  last_value?: CellValue;
+}
+
 export interface SavedQuery {
   id: string;
   connection_id: string;
@@ -8,6 +17,7 @@ export interface SavedQuery {
   description?: string;
   tags: string[];
   folder_id?: string;
+  parameters: SavedQueryParameter[];
   created_at: string;
   updated_at: string;
 }
@@ -27,6 +37,7 @@ export interface CreateSavedQueryDto {
   description?: string;
   tags?: string[];
   folder_id?: string;
+  parameters?: SavedQueryParameter[];
 }
 
 export interface UpdateSavedQueryDto {
@@ -35,4 +46,5 @@ export interface UpdateSavedQueryDto {
   description?: string;
   tags?: string[];
   folder_id?: string;
+  parameters?: SavedQueryParameter[];
 }
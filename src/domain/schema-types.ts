@@ -28,6 +28,31 @@ export interface TableInfo {
   row_count?: number;
   size_bytes?: number;
   comment?: string;
+  storage?: TableStorageInfo;
+}
+
+export interface TableStorageInfo {
+  engine: string;
+  partition_key?: string | null;
+  sorting_key?: string | null;
+  primary_key?: string | null;
+  system_versioned: boolean;
+  partitions: PartitionInfo[];
+}
+
+export interface PartitionInfo {
+  name: string;
+  rows: number;
+  bytes_on_disk: number;
+  parts: PartInfo[];
+}
+
+export interface PartInfo {
+  name: string;
+  rows: number;
+  bytes_on_disk: number;
+  level: number;
+  modified_at?: string | null;
 }
 
 export interface ColumnSchema {
@@ -137,3 +162,20 @@ export interface FunctionParameter {
   mode: 'In' | 'Out' | 'InOut';
   default_value?: string;
 }
+
+export type DdlObjectType = 'table' | 'view' | 'index' | 'function' | 'trigger' | 'sequence';
+
+export interface DdlObject {
+  object_type: DdlObjectType;
+  name: string;
+  schema?: string;
+  /** Tabla de un índice o un trigger; sin ella se busca por nombre en el esquema */
+  table?: string;
+}
+
+export interface DdlOptions {
+  include_comments?: boolean;
+  include_grants?: boolean;
+  /** `ALTER ... OWNER TO` en PostgreSQL; `DEFINER` en MySQL */
+  include_ownership?: boolean;
+}
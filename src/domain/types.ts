@@ -1,6 +1,15 @@
 // Tipos del dominio - Espejo de las entidades Rust
 
-export type DatabaseEngine = 'postgresql' | 'mysql' | 'sqlite' | 'sqlserver';
+export type DatabaseEngine =
+  | 'postgresql'
+  | 'mysql'
+  | 'mariadb'
+  | 'sqlite'
+  | 'sqlserver'
+  | 'duckdb'
+  | 'clickhouse';
+
+export type SslMode = 'disable' | 'prefer' | 'require' | 'verify-ca' | 'verify-full';
 
 export interface SslConfig {
   enabled: boolean;
@@ -8,6 +17,26 @@ export interface SslConfig {
   client_cert_path?: string;
   client_key_path?: string;
   verify_server_cert: boolean;
+  mode?: SslMode | null;
+}
+
+export interface TlsStatus {
+  enabled: boolean;
+  version?: string | null;
+  cipher?: string | null;
+}
+
+export type SshAuthMethod =
+  | { type: 'Password' }
+  | { type: 'PrivateKey'; key_path: string };
+
+export interface SshTunnelConfig {
+  host: string;
+  port: number;
+  username: string;
+  auth_method: SshAuthMethod;
+  known_hosts_path?: string;
+  accept_unknown_host?: boolean;
 }
 
 export interface Connection {
@@ -20,8 +49,11 @@ export interface Connection {
   username?: string;
   file_path?: string;
   ssl: SslConfig;
+  ssh_tunnel?: SshTunnelConfig | null;
   color?: string;
   read_only?: boolean;
+  statement_timeout_ms?: number | null;
+  production?: boolean;
   created_at: string;
   updated_at: string;
   last_connected_at?: string;
@@ -37,8 +69,12 @@ export interface CreateConnectionDto {
   password?: string;
   file_path?: string;
   ssl?: SslConfig;
+  ssh_tunnel?: SshTunnelConfig;
+  ssh_secret?: string;
   color?: string;
   read_only?: boolean;
+  statement_timeout_ms?: number;
+  production?: boolean;
 }
 
 export interface UpdateConnectionDto {
@@ -49,8 +85,13 @@ export interface UpdateConnectionDto {
   username?: string;
   password?: string;
   ssl?: SslConfig;
+  ssh_tunnel?: SshTunnelConfig;
+  ssh_secret?: string;
+  remove_ssh_tunnel?: boolean;
   color?: string;
   read_only?: boolean;
+  statement_timeout_ms?: number; // 0 removes the limit
+  production?: boolean;
 }
 
 export type ConnectionStatus = 
@@ -85,6 +126,40 @@ export type CellValue =
   | { type: 'Uuid'; value: string }
   | { type: 'Array'; value: CellValue[] };
 
+export type PlaceholderStyle = 'named' | 'numbered' | 'positional';
+
+export interface QueryParameter {
+  name: string;
+  style: PlaceholderStyle;
+  occurrences: number;
+}
+
+// Valores por posición ($1/? en orden) o por nombre (:name)
+export type QueryParams = CellValue[] | Record<string, CellValue>;
+
+// Grid edits keyed by primary key; with `original`, a changed row fails with ROW_CONFLICT
+export interface RowUpdate {
+  key: Record<string, CellValue>;
+  values: Record<string, CellValue>;
+  original?: Record<string, CellValue>;
+}
+
+export interface RowDelete {
+  key: Record<string, CellValue>;
+  original?: Record<string, CellValue>;
+}
+
+// Values that do not fit their column reject the insert/update before it runs
+export interface ValidationErrorPayload {
+  ValidationError: { message: string; column?: string };
+}
+
+export interface TransactionResult {
+  affected_rows: number;
+  execution_time_ms: number;
+  committed: boolean; // false when the rows joined an already open transaction
+}
+
 export interface PaginationInfo {
   page: number;
   page_size: number;
@@ -92,6 +167,8 @@ export interface PaginationInfo {
   total_pages?: number;
   has_next: boolean;
   has_previous: boolean;
+  total_is_estimate?: boolean;
+  cursor?: string | null;
 }
 
 export interface QueryResult {
@@ -116,3 +193,307 @@ export interface QueryHistoryEntry {
   success: boolean;
   error_message?: string;
 }
+
+export interface RunningQueryInfo {
+  query_id: string;
+  connection_id: string;
+  query: string;
+  started_at: string;
+}
+
+export type IsolationLevel = 'read_uncommitted' | 'read_committed' | 'repeatable_read' | 'serializable';
+
+export interface TransactionOptions {
+  isolation_level?: IsolationLevel;
+  read_only?: boolean;
+}
+
+export interface TransactionSession {
+  transaction_id: string;
+  connection_id: string;
+  isolation_level: IsolationLevel | null;
+  read_only: boolean;
+  savepoints: string[];
+  started_at: string;
+  /** Ejecución dueña de la transacción (script, simulación...); null si es manual */
+  owner?: string | null;
+}
+
+export type TransactionWarningReason = 'long_running' | 'uncommitted_on_disconnect';
+
+export interface PlanNode {
+  node_type: string;
+  relation: string | null;
+  detail: string | null;
+  estimated_rows: number | null;
+  actual_rows: number | null;
+  estimated_cost: number | null;
+  actual_time_ms: number | null;
+  loops: number | null;
+  children: PlanNode[];
+}
+
+export interface QueryPlan {
+  query: string;
+  analyzed: boolean;
+  root: PlanNode;
+  planning_time_ms: number | null;
+  execution_time_ms: number | null;
+  raw: string;
+}
+
+export interface ScriptStatement {
+  sql: string;
+  line: number;
+  offset: number;
+}
+
+export type ScriptMode = 'stop_on_error' | 'continue_on_error' | 'transaction';
+
+export type RiskLevel = 'medium' | 'high' | 'critical';
+
+export type RiskKind =
+  | 'update_without_where'
+  | 'delete_without_where'
+  | 'drop'
+  | 'truncate'
+  | 'cross_schema_write'
+  | 'production_write'
+  | 'production_schema_change';
+
+// Rejected with { ConfirmationRequired: { token, risks } }; re-run passing the token to confirm
+export interface StatementRisk {
+  statement_index: number;
+  sql: string;
+  kind: RiskKind;
+  level: RiskLevel;
+  target: string | null;
+  message: string;
+}
+
+export interface StatementResult {
+  statement_index: number;
+  sql: string;
+  success: boolean;
+  affected_rows: number | null;
+  result: QueryResult | null;
+  error: string | null;
+  execution_time_ms: number;
+}
+
+export interface RowDiffSample {
+  table: string;
+  key_columns: string[];
+  before: QueryResult;
+  after: QueryResult; // deleted rows are missing here
+  truncated: boolean;
+}
+
+// Always rolled back; sample_note explains a missing sample
+export interface DryRunStatement extends StatementResult {
+  sample: RowDiffSample | null;
+  sample_note: string | null;
+}
+
+export interface ScriptRunSummary {
+  script_id: string;
+  mode: ScriptMode;
+  statement_count: number;
+  executed: number;
+  succeeded: number;
+  failed: number;
+  stopped: boolean;
+  committed: boolean | null;
+  execution_time_ms: number;
+}
+
+export interface RowBatch {
+  batch_index: number;
+  columns: ColumnInfo[] | null;
+  rows: CellValue[][];
+}
+
+export interface QueryStreamSummary {
+  query_id: string;
+  query: string;
+  columns: ColumnInfo[];
+  row_count: number;
+  batch_count: number;
+  truncated: boolean;
+  execution_time_ms: number;
+}
+
+export type ImportFormat = 'csv' | 'json' | 'xlsx';
+
+export type ImportErrorMode = 'abort' | 'skip';
+
+export type DuplicateMode = 'abort' | 'skip' | 'upsert'; // upsert needs a primary key
+
+export interface ColumnMapping {
+  source: string;
+  target: string;
+}
+
+// Without mapping, file columns go to same-named table columns (or by position without header)
+export interface ImportOptions {
+  format: ImportFormat;
+  header?: boolean; // default true
+  delimiter?: string;
+  sheet?: string;
+  mapping?: ColumnMapping[];
+  on_error?: ImportErrorMode;
+  on_duplicate?: DuplicateMode;
+  batch_size?: number;
+}
+
+export interface ImportPreview {
+  columns: string[];
+  rows: unknown[][];
+  total_rows: number;
+}
+
+export interface ImportRowError {
+  row: number; // 1-based, header excluded
+  column: string | null;
+  message: string;
+}
+
+export interface ImportSummary {
+  import_id: string;
+  total_rows: number;
+  written_rows: number;
+  failed_rows: number;
+  errors: ImportRowError[]; // first 100 only
+  aborted: boolean;
+  committed: boolean; // false when rolled back or joined an open transaction
+  execution_time_ms: number;
+}
+
+// Payload of the ImportProgress domain event, sent after each batch
+export interface ImportProgress {
+  connection_id: string;
+  import_id: string;
+  processed_rows: number;
+  total_rows: number;
+  written_rows: number;
+  failed_rows: number;
+}
+
+export type ExportFormat = 'csv' | 'json' | 'ndjson' | 'sql' | 'markdown' | 'sqlite';
+
+export type CsvQuoting = 'minimal' | 'all' | 'non_numeric' | 'never';
+
+export type TextEncoding = 'utf8' | 'utf8_bom' | 'utf16_le' | 'latin1';
+
+export interface ExportOptions {
+  format: ExportFormat;
+  delimiter?: string;
+  quoting?: CsvQuoting; // nulls are never quoted
+  encoding?: TextEncoding;
+  header?: boolean; // CSV only, default true
+  null_text?: string;
+  table_name?: string; // SQL INSERT and SQLite, default "export"
+  target_engine?: DatabaseEngine; // SQL INSERT dialect, default the connection's
+  rows_per_insert?: number;
+  max_rows?: number;
+  batch_size?: number;
+}
+
+export interface ExportSummary {
+  export_id: string;
+  path: string;
+  format: ExportFormat;
+  row_count: number;
+  bytes_written: number;
+  truncated: boolean; // max_rows reached
+  execution_time_ms: number;
+}
+
+// Payload of the ExportProgress domain event; cancel with queryApi.cancel(connectionId, exportId)
+export interface ExportProgress {
+  connection_id: string;
+  export_id: string;
+  row_count: number;
+}
+
+// One sheet per result set; names are made valid (31 chars, no []:*?/\) and unique
+export interface XlsxSheet {
+  name: string;
+  columns: string[];
+  rows: CellValue[][];
+}
+
+export type DumpDataFormat = 'insert' | 'copy'; // copy is PostgreSQL only
+
+export interface DumpOptions {
+  schema?: string; // default: every non-system schema (PostgreSQL) or the connection's database
+  include_schema?: boolean; // default true
+  include_data?: boolean; // default true
+  data_format?: DumpDataFormat;
+  drop_existing?: boolean; // start with DROP ... IF EXISTS
+  rows_per_insert?: number;
+  batch_size?: number;
+}
+
+export interface DumpSummary {
+  dump_id: string;
+  path: string;
+  schemas: string[];
+  table_count: number;
+  view_count: number;
+  function_count: number;
+  sequence_count: number;
+  trigger_count: number;
+  row_count: number;
+  bytes_written: number;
+  warnings: string[]; // objects the catalog could not rebuild
+  execution_time_ms: number;
+}
+
+// Payload of the DumpProgress domain event; cancel with queryApi.cancel(connectionId, dumpId)
+export interface DumpProgress {
+  connection_id: string;
+  dump_id: string;
+  table: string;
+  tables_done: number;
+  table_count: number;
+  row_count: number;
+}
+
+export interface RestoreOptions {
+  mode?: ScriptMode;
+  start_at?: number; // statement index to resume from
+}
+
+export interface RestoreError {
+  statement_index: number;
+  line: number;
+  sql: string;
+  error: string;
+}
+
+export interface RestoreSummary {
+  restore_id: string;
+  mode: ScriptMode;
+  statement_count: number;
+  start_at: number;
+  executed: number;
+  succeeded: number;
+  failed: number;
+  affected_rows: number;
+  errors: RestoreError[]; // first 100
+  stopped: boolean;
+  resume_at: number | null; // pass as start_at to resume
+  committed: boolean | null;
+  execution_time_ms: number;
+}
+
+// Payload of the RestoreProgress domain event, sent after each statement
+export interface RestoreProgress {
+  connection_id: string;
+  restore_id: string;
+  statement_index: number;
+  statement_count: number;
+  success: boolean;
+  error: string | null;
+}
@@ -12,6 +12,7 @@ export class AdminAdapterFactory {
       case 'postgresql':
         return new PostgresAdminAdapter();
       case 'mysql':
+      case 'mariadb':
         return new MySQLAdminAdapter();
       case 'sqlite':
         return new SqliteAdminAdapter();
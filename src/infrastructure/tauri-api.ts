@@ -1,16 +1,45 @@
 import { invoke } from '@tauri-apps/api/tauri';
 import type {
+  CellValue,
   Connection,
   ConnectionStatus,
   CreateConnectionDto,
+  DryRunStatement,
+  DumpOptions,
+  DumpSummary,
+  ExportOptions,
+  ExportSummary,
+  ImportOptions,
+  ImportPreview,
+  ImportSummary,
   QueryHistoryEntry,
+  QueryParameter,
+  QueryParams,
+  QueryPlan,
   QueryResult,
+  QueryStreamSummary,
+  RestoreOptions,
+  RestoreSummary,
+  RowDelete,
+  RowUpdate,
+  RunningQueryInfo,
+  ScriptMode,
+  ScriptRunSummary,
+  ScriptStatement,
+  StatementResult,
+  TlsStatus,
+  TransactionOptions,
+  TransactionResult,
+  TransactionSession,
   UpdateConnectionDto,
+  XlsxSheet,
 } from '../domain/types';
 import type {
   ColumnSchema,
   ConstraintInfo,
   DatabaseInfo,
+  DdlObject,
+  DdlOptions,
   FunctionInfo,
   IndexInfo,
   SchemaInfo,
@@ -38,11 +67,14 @@ export const connectionApi = {
   
   connect: (id: string) => invoke<void>('connect', { id }),
   
-  disconnect: (id: string) => invoke<void>('disconnect', { id }),
+  disconnect: (id: string, force?: boolean) => invoke<void>('disconnect', { id, force }),
   
   getStatus: (id: string) => 
     invoke<ConnectionStatus>('get_connection_status', { id }),
 
+  getTlsStatus: (id: string) =>
+    invoke<TlsStatus>('get_connection_tls', { id }),
+
   changeDatabase: (id: string, database: string) =>
     invoke<void>('change_database', { id, database }),
 
@@ -59,23 +91,51 @@ export const queryApi = {
     connectionId: string,
     query: string,
     page?: number,
-    pageSize?: number
+    pageSize?: number,
+    cursor?: string | null,
+    params?: QueryParams,
+    timeoutMs?: number,
+    confirmationToken?: string
   ) =>
     invoke<QueryResult>('execute_query', {
       connectionId,
       query,
-      page,
-      pageSize,
+      options: {
+        page,
+        page_size: pageSize,
+        cursor,
+        params,
+        timeout_ms: timeoutMs,
+        confirmation_token: confirmationToken,
+      },
     }),
 
-  executeStatement: (connectionId: string, statement: string) =>
-    invoke<number>('execute_statement', { connectionId, statement }),
+  detectParameters: (query: string) =>
+    invoke<QueryParameter[]>('detect_query_parameters', { query }),
+
+  closeCursor: (connectionId: string, cursor: string) =>
+    invoke<void>('close_query_cursor', { connectionId, cursor }),
+
+  executeStatement: (connectionId: string, statement: string, params?: QueryParams, confirmationToken?: string) =>
+    invoke<number>('execute_statement', { connectionId, statement, params, confirmationToken }),
+
+  executeMultiStatement: (connectionId: string, statements: string[], confirmationToken?: string) =>
+    invoke<any[]>('execute_multi_statement', { connectionId, statements, confirmationToken }),
 
-  executeMultiStatement: (connectionId: string, statements: string[]) =>
-    invoke<any[]>('execute_multi_statement', { connectionId, statements }),
+  dryRun: (connectionId: string, statements: string[], params?: QueryParams, queryId?: string, confirmationToken?: string) =>
+    invoke<DryRunStatement[]>('dry_run_statements', { connectionId, statements, params, queryId, confirmationToken }),
 
-  executeInTransaction: (connectionId: string, statement: string) =>
-    invoke<{ affected_rows: number; execution_time_ms: number; committed: boolean }>('execute_in_transaction', { connectionId, statement }),
+  splitScript: (connectionId: string, script: string) =>
+    invoke<ScriptStatement[]>('split_sql_script', { connectionId, script }),
+
+  runScript: (connectionId: string, script: string, mode?: ScriptMode, scriptId?: string, confirmationToken?: string) =>
+    invoke<ScriptRunSummary>('run_script', { connectionId, script, mode, scriptId, confirmationToken }),
+
+  explain: (connectionId: string, sql: string, analyze = false, confirmationToken?: string) =>
+    invoke<QueryPlan>('explain_query', { connectionId, sql, analyze, confirmationToken }),
+
+  executeInTransaction: (connectionId: string, statement: string, confirmationToken?: string) =>
+    invoke<{ affected_rows: number; execution_time_ms: number; committed: boolean }>('execute_in_transaction', { connectionId, statement, confirmationToken }),
 
   getHistory: (connectionId: string, limit?: number) =>
     invoke<QueryHistoryEntry[]>('get_query_history', { connectionId, limit }),
@@ -83,21 +143,82 @@ export const queryApi = {
   searchHistory: (query: string, limit?: number) =>
     invoke<QueryHistoryEntry[]>('search_query_history', { query, limit }),
 
-  cancel: (connectionId: string) =>
-    invoke<void>('cancel_query', { connectionId }),
+  cancel: (connectionId: string, queryId?: string) =>
+    invoke<void>('cancel_query', { connectionId, queryId }),
+
+  listRunning: (connectionId: string) =>
+    invoke<RunningQueryInfo[]>('list_running_queries', { connectionId }),
+
+  beginTransaction: (connectionId: string, options?: TransactionOptions) =>
+    invoke<TransactionSession>('begin_transaction', { connectionId, options }),
+
+  commitTransaction: (connectionId: string) =>
+    invoke<void>('commit_transaction', { connectionId }),
+
+  rollbackTransaction: (connectionId: string) =>
+    invoke<void>('rollback_transaction', { connectionId }),
+
+  createSavepoint: (connectionId: string, name: string) =>
+    invoke<TransactionSession>('create_savepoint', { connectionId, name }),
+
+  rollbackToSavepoint: (connectionId: string, name: string) =>
+    invoke<TransactionSession>('rollback_to_savepoint', { connectionId, name }),
+
+  getTransaction: (connectionId: string) =>
+    invoke<TransactionSession | null>('get_transaction_status', { connectionId }),
+
+  executeStream: (
+    connectionId: string,
+    query: string,
+    options: { queryId?: string; batchSize?: number; maxRows?: number; maxPendingBatches?: number; confirmationToken?: string } = {}
+  ) =>
+    invoke<QueryStreamSummary>('execute_query_stream', {
+      connectionId,
+      query,
+      queryId: options.queryId,
+      options: {
+        batch_size: options.batchSize,
+        max_rows: options.maxRows,
+        max_pending_batches: options.maxPendingBatches,
+      },
+      confirmationToken: options.confirmationToken,
+    }),
+
+  ackStreamBatch: (queryId: string) =>
+    invoke<void>('ack_query_stream_batch', { queryId }),
 
   insertRow: (
     connectionId: string,
     schema: string | null,
     table: string,
-    values: Record<string, any>
+    values: Record<string, any>,
+    confirmationToken?: string
   ) =>
     invoke<QueryResult>('insert_row', {
       connectionId,
       schema,
       table,
       values,
+      confirmationToken,
     }),
+
+  updateRows: (
+    connectionId: string,
+    schema: string | null,
+    table: string,
+    rows: RowUpdate[],
+    confirmationToken?: string
+  ) =>
+    invoke<TransactionResult>('update_rows', { connectionId, schema, table, rows, confirmationToken }),
+
+  deleteRows: (
+    connectionId: string,
+    schema: string | null,
+    table: string,
+    rows: RowDelete[],
+    confirmationToken?: string
+  ) =>
+    invoke<TransactionResult>('delete_rows', { connectionId, schema, table, rows, confirmationToken }),
 };
 
 // Schema API
@@ -146,6 +267,9 @@ export const schemaApi = {
   listSequences: (connId: string, schema?: string) =>
     invoke<SequenceInfo[]>('list_sequences', { connectionId: connId, schema }),
 
+  getObjectDdl: (connId: string, object: DdlObject, options?: DdlOptions) =>
+    invoke<string>('get_object_ddl', { connectionId: connId, object, options }),
+
   getServerVersion: (connId: string) =>
     invoke<string>('get_server_version', { connectionId: connId }),
 };
@@ -171,6 +295,9 @@ export const savedQueryApi = {
   update: (id: string, dto: UpdateSavedQueryDto) =>
     invoke<SavedQuery>('update_saved_query', { id, dto }),
 
+  recordParameters: (id: string, values: Record<string, CellValue>) =>
+    invoke<SavedQuery>('record_saved_query_parameters', { id, values }),
+
   delete: (id: string) =>
     invoke<void>('delete_saved_query', { id }),
 
@@ -208,4 +335,75 @@ export const workspaceApi = {
 export const exportApi = {
   exportXlsx: (columns: string[], rows: any[][], path: string) =>
     invoke<void>('export_results_xlsx', { columns, rows, path }),
+
+  exportXlsxWorkbook: (sheets: XlsxSheet[], path: string) =>
+    invoke<void>('export_xlsx_workbook', { sheets, path }),
+
+  exportStatementResultsXlsx: (results: StatementResult[], path: string) =>
+    invoke<void>('export_statement_results_xlsx', { results, path }),
+
+  exportQuery: (
+    connectionId: string,
+    query: string,
+    path: string,
+    options: ExportOptions,
+    exportId?: string,
+    confirmationToken?: string
+  ) =>
+    invoke<ExportSummary>('export_query', {
+      connectionId,
+      query,
+      path,
+      options,
+      exportId,
+      confirmationToken,
+    }),
+};
+
+// Import API
+export const importApi = {
+  previewFile: (path: string, options: ImportOptions, limit?: number) =>
+    invoke<ImportPreview>('preview_import_file', { path, options, limit }),
+
+  importTableData: (
+    connectionId: string,
+    schema: string | null,
+    table: string,
+    path: string,
+    options: ImportOptions,
+    importId?: string,
+    confirmationToken?: string
+  ) =>
+    invoke<ImportSummary>('import_table_data', {
+      dto: {
+        connection_id: connectionId,
+        schema,
+        table,
+        import_id: importId,
+        confirmation_token: confirmationToken,
+      },
+      path,
+      options,
+    }),
+};
+
+// Dump API
+export const dumpApi = {
+  dumpDatabase: (connectionId: string, path: string, options: DumpOptions, dumpId?: string) =>
+    invoke<DumpSummary>('dump_database', { connectionId, path, options, dumpId }),
+
+  restoreDump: (
+    connectionId: string,
+    path: string,
+    options?: RestoreOptions,
+    restoreId?: string,
+    confirmationToken?: string
+  ) =>
+    invoke<RestoreSummary>('restore_dump', {
+      connectionId,
+      path,
+      options,
+      restoreId,
+      confirmationToken,
+    }),
 };
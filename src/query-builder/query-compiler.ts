@@ -160,6 +160,7 @@ export class QueryToSqlCompiler {
       case 'postgresql':
         return `"${name}"`;
       case 'mysql':
+      case 'mariadb':
         return `\`${name}\``;
       case 'sqlite':
         return `"${name}"`;
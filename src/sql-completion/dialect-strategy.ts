@@ -159,6 +159,7 @@ export function getDialectStrategy(engine: DatabaseEngine): SqlDialectStrategy {
     case 'postgresql':
       return new PostgresDialectStrategy();
     case 'mysql':
+    case 'mariadb':
       return new MySqlDialectStrategy();
     case 'sqlite':
       return new SqliteDialectStrategy();
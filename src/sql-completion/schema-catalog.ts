@@ -187,7 +187,7 @@ export class SchemaCatalog {
     if (this.engine === 'postgresql' && table.schema) {
       return `${table.schema}.${table.name}`;
     }
-    if (this.engine === 'mysql' && table.database) {
+    if ((this.engine === 'mysql' || this.engine === 'mariadb') && table.database) {
       return `${table.database}.${table.name}`;
     }
     return table.name;
@@ -67,7 +67,7 @@ interface QueryState {
   setActiveTab: (tabId: string) => void;
   updateQuery: (tabId: string, query: string) => void;
   updateTabTitle: (tabId: string, title: string) => void;
-  executeQuery: (tabId: string, specificQuery?: string, page?: number, pageSize?: number) => Promise<void>;
+  executeQuery: (tabId: string, specificQuery?: string, page?: number, pageSize?: number, cursor?: string | null) => Promise<void>;
   executeMultiStatement: (tabId: string) => Promise<void>;
   cancelQuery: (tabId: string) => Promise<void>;
   loadHistory: (connectionId: string) => Promise<void>;
@@ -153,7 +153,7 @@ export const useQueryStore = create<QueryState>((set, get) => ({
     }));
   },
 
-  executeQuery: async (tabId, specificQuery, page, pageSize) => {
+  executeQuery: async (tabId, specificQuery, page, pageSize, cursor) => {
     const tab = get().tabs.find((t) => t.id === tabId);
     if (!tab) return;
     if (tab.isExecuting) return;
@@ -284,7 +284,8 @@ export const useQueryStore = create<QueryState>((set, get) => ({
         tab.connectionId,
         queryToExecute,
         page,
-        pageSize
+        pageSize,
+        cursor
       );
       set((state) => ({
         tabs: state.tabs.map((t) =>
@@ -32,7 +32,7 @@ function sqlLiteral(value: any, _engine: DatabaseEngine): string {
 function quoteIdentifier(name: string, engine: DatabaseEngine): string {
   if (!name) return '""';
   const n = name.replace(/^["`]/, '').replace(/["`]$/, '');
-  if (engine === 'mysql') return `\`${n}\``;
+  if (engine === 'mysql' || engine === 'mariadb') return `\`${n}\``;
   return `"${n}"`;
 }
 